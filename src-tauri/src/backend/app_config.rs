@@ -21,6 +21,20 @@ pub fn set_property(key: &str, value: &str) -> Result<(), String> {
     update_config(key, value)
 }
 
+pub fn get_all() -> Result<BTreeMap<String, String>, String> {
+    load_config()
+}
+
+/// 整体替换配置并立即落盘
+pub fn replace_all(map: BTreeMap<String, String>) -> Result<(), String> {
+    save_config(&map)?;
+    let mut guard = CONFIG_CACHE
+        .write()
+        .map_err(|_| "Config lock failed".to_string())?;
+    *guard = Some(map);
+    Ok(())
+}
+
 pub fn flush() -> Result<(), String> {
     let map = {
         let guard = CONFIG_CACHE
@@ -0,0 +1,219 @@
+use crate::backend::app_config;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const AUDIT_ENABLED_KEY: &str = "audit_enabled";
+const AUDIT_SCRUB_KEY: &str = "audit_scrub_secrets";
+const AUDIT_PATH_KEY: &str = "audit_log_path";
+
+// 多个连接并发执行时保证每条记录整行写入
+static AUDIT_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+// 单引号或双引号字符串字面量（含 \ 转义与重复引号）
+const STRING_LITERAL: &str = r#"(?:'(?:[^'\\]|\\.|'')*'|"(?:[^"\\]|\\.|"")*")"#;
+
+// 口令/密钥字面量：IDENTIFIED BY '...'、PASSWORD('...')、SET PASSWORD FOR u = '...'、password = '...' 等
+static SECRET_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
+    [
+        r"(?i)(\bIDENTIFIED\s+(?:WITH\s+\S+\s+)?(?:BY|AS)\s+)",
+        r"(?i)(\bPASSWORD\s*(?:\(\s*|=\s*))",
+        r"(?i)(\bSET\s+PASSWORD\s+FOR\s+\S+?\s*=\s*)",
+        r"(?i)(\b\w*(?:password|passwd|pwd|secret|token)\w*`?\s*=\s*)",
+    ]
+    .iter()
+    .map(|prefix| Regex::new(&format!("{prefix}{STRING_LITERAL}")).expect("valid secret pattern"))
+    .collect()
+});
+
+fn is_enabled() -> bool {
+    flag(AUDIT_ENABLED_KEY, false)
+}
+
+/// 追加一条审计记录（制表符分隔：时间、用户、库、结果、语句）。
+/// 未开启 `audit_enabled` 时直接返回；写入失败不影响语句执行结果
+pub fn log_statement(user: &str, schema: Option<&str>, sql: &str, error: Option<&str>) {
+    if !is_enabled() {
+        return;
+    }
+    let result = audit_path().and_then(|path| {
+        let line = format_line(user, schema, sql, error, flag(AUDIT_SCRUB_KEY, true));
+        append_line(&path, &line)
+    });
+    if let Err(err) = result {
+        eprintln!("[WARN] Audit log write failed: {err}");
+    }
+}
+
+fn format_line(
+    user: &str,
+    schema: Option<&str>,
+    sql: &str,
+    error: Option<&str>,
+    scrub: bool,
+) -> String {
+    let sql = if scrub {
+        scrub_secrets(sql.trim())
+    } else {
+        sql.trim().to_string()
+    };
+    let outcome = match error {
+        Some(err) => format!("ERROR: {}", single_line(err)),
+        None => "OK".to_string(),
+    };
+    format!(
+        "{}\t{}\t{}\t{}\t{}\n",
+        chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+        user,
+        schema.unwrap_or(""),
+        outcome,
+        single_line(&sql)
+    )
+}
+
+/// 将口令类字面量替换为 '***'
+pub fn scrub_secrets(sql: &str) -> String {
+    SECRET_PATTERNS
+        .iter()
+        .fold(sql.to_string(), |text, pattern| {
+            pattern.replace_all(&text, "${1}'***'").into_owned()
+        })
+}
+
+fn append_line(path: &Path, line: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {e}"))?;
+    }
+    let _guard = AUDIT_LOCK
+        .lock()
+        .map_err(|_| "Audit lock failed".to_string())?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open file: {e}"))?;
+    file.write_all(line.as_bytes())
+        .map_err(|e| format!("Failed to write file: {e}"))
+}
+
+// 语句中的换行/制表符转义，保证一条记录一行
+fn single_line(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('\r', "\\r")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
+
+fn flag(key: &str, default_value: bool) -> bool {
+    app_config::get_property(key, if default_value { "true" } else { "false" })
+        .map(|value| matches!(value.trim(), "true" | "1" | "yes"))
+        .unwrap_or(default_value)
+}
+
+fn audit_path() -> Result<PathBuf, String> {
+    let configured = app_config::get_property(AUDIT_PATH_KEY, "")?;
+    if !configured.trim().is_empty() {
+        return Ok(PathBuf::from(configured.trim()));
+    }
+    let home = home_dir().ok_or_else(|| "Failed to resolve home directory".to_string())?;
+    Ok(home.join(".dbworkbench").join("audit.log"))
+}
+
+fn home_dir() -> Option<PathBuf> {
+    if let Some(value) = env::var_os("USERPROFILE") {
+        return Some(PathBuf::from(value));
+    }
+    if let Some(value) = env::var_os("HOME") {
+        return Some(PathBuf::from(value));
+    }
+    env::current_dir().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrub_secrets_masks_password_literals() {
+        assert_eq!(
+            scrub_secrets("CREATE USER 'bob'@'%' IDENTIFIED BY 's3cr''et'"),
+            "CREATE USER 'bob'@'%' IDENTIFIED BY '***'"
+        );
+        assert_eq!(
+            scrub_secrets(
+                "ALTER USER bob IDENTIFIED WITH mysql_native_password BY 'pw' PASSWORD EXPIRE"
+            ),
+            "ALTER USER bob IDENTIFIED WITH mysql_native_password BY '***' PASSWORD EXPIRE"
+        );
+        assert_eq!(
+            scrub_secrets("SET PASSWORD='hunter2'"),
+            "SET PASSWORD='***'"
+        );
+        assert_eq!(
+            scrub_secrets("SET PASSWORD FOR bob = PASSWORD('old')"),
+            "SET PASSWORD FOR bob = PASSWORD('***')"
+        );
+        assert_eq!(
+            scrub_secrets("UPDATE users SET api_token = 'abc\\'d' WHERE id = 1"),
+            "UPDATE users SET api_token = '***' WHERE id = 1"
+        );
+        assert_eq!(
+            scrub_secrets("SELECT name FROM users WHERE id = '1'"),
+            "SELECT name FROM users WHERE id = '1'"
+        );
+        assert_eq!(
+            scrub_secrets("SET PASSWORD FOR 'bob'@'%' = 'pw'"),
+            "SET PASSWORD FOR 'bob'@'%' = '***'"
+        );
+        assert_eq!(
+            scrub_secrets("CREATE USER bob IDENTIFIED BY \"p\\\"w\""),
+            "CREATE USER bob IDENTIFIED BY '***'"
+        );
+    }
+
+    #[test]
+    fn executed_statements_append_one_line_each() {
+        let path = std::env::temp_dir().join(format!("dbw_audit_{}.log", std::process::id()));
+        let _ = fs::remove_file(&path);
+        for (sql, error) in [
+            ("ALTER USER bob IDENTIFIED BY 'pw'", None),
+            (
+                "SELECT *\nFROM missing",
+                Some("ERROR 1146 (42S02): Table doesn't exist"),
+            ),
+        ] {
+            append_line(&path, &format_line("root", Some("shop"), sql, error, true)).unwrap();
+        }
+        let content = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let fields: Vec<Vec<&str>> = content
+            .lines()
+            .map(|line| line.split('\t').skip(1).collect())
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                vec!["root", "shop", "OK", "ALTER USER bob IDENTIFIED BY '***'"],
+                vec![
+                    "root",
+                    "shop",
+                    "ERROR: ERROR 1146 (42S02): Table doesn't exist",
+                    "SELECT *\\nFROM missing"
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn single_line_escapes_control_characters() {
+        assert_eq!(
+            single_line("SELECT 1\r\n\tFROM t -- a\\b"),
+            "SELECT 1\\r\\n\\tFROM t -- a\\\\b"
+        );
+    }
+}
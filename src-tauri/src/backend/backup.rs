@@ -19,6 +19,7 @@ use std::thread;
 use std::time::{Duration, Instant};
 
 use crate::backend::pool;
+use crate::backend::sqlutils;
 
 #[derive(Clone)]
 struct DumpServerInfo {
@@ -47,7 +48,7 @@ struct ColumnCharsetMeta {
     collation: Option<String>,
 }
 
-#[derive(Deserialize, Clone, Default)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct BackupOptions {
     pub include_structure: bool,
     pub include_data: bool,
@@ -61,7 +62,7 @@ pub struct BackupOptions {
     pub insert_batch_size: Option<usize>,
 }
 
-#[derive(Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct BackupRequest {
     pub conn: ConnectionProfile,
     pub schema: String,
@@ -85,6 +86,9 @@ pub struct RestoreRequest {
     pub continue_on_error: bool,
     #[serde(default)]
     pub use_transaction: bool,
+    /// 只检查文件（空文件、编码、末尾语句截断），不执行
+    #[serde(default)]
+    pub verify_only: bool,
 }
 
 #[derive(Deserialize)]
@@ -97,7 +101,7 @@ pub struct IncrementalRequest {
     pub mysqlbinlog_path: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 pub struct ScheduleRequest {
     pub schedule_id: String,
     pub cron: String,
@@ -113,6 +117,15 @@ pub struct BackupResult {
 #[derive(Serialize)]
 pub struct RestoreResult {
     pub duration_ms: u64,
+    pub statement_count: usize,
+    pub verified_only: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct RestoreProgress {
+    pub executed: usize,
+    pub total: usize,
+    pub percentage: u8,
 }
 
 #[derive(Serialize)]
@@ -121,9 +134,17 @@ pub struct IncrementalResult {
     pub duration_ms: u64,
 }
 
+#[derive(Serialize)]
+pub struct BinlogStatus {
+    pub file: String,
+    pub position: u64,
+    pub gtid_executed: Option<String>,
+}
+
 #[derive(Clone)]
 struct ScheduleTask {
     id: String,
+    cron: String,
     schedule: Schedule,
     backup: BackupRequest,
     next_run: Arc<Mutex<DateTime<Local>>>,
@@ -145,14 +166,36 @@ pub fn backup_execute(req: BackupRequest) -> Result<BackupResult, String> {
     })
 }
 
-pub fn restore_execute(req: RestoreRequest) -> Result<RestoreResult, String> {
+/// 执行前先拆分整个文件，执行时按语句数回调进度（百分比变化时才回调）。
+/// 末尾语句缺少分隔符只在 verify_only 时报错，实际恢复照常执行该语句
+pub fn restore_execute_with_progress<F>(
+    req: RestoreRequest,
+    on_progress: F,
+) -> Result<RestoreResult, String>
+where
+    F: FnMut(&RestoreProgress),
+{
     let start = Instant::now();
-    if req.create_schema {
-        create_schema(&req)?;
+    let input_path = PathBuf::from(&req.input_path);
+    if !input_path.exists() {
+        return Err("SQL file not found".to_string());
+    }
+    let sql_text = read_sql_file(&input_path)?;
+    let statements = redirect_to_target_schema(
+        verify_sql_dump(&sql_text, req.verify_only)?,
+        &req.target_schema,
+    );
+
+    if !req.verify_only {
+        if req.create_schema {
+            create_schema(&req)?;
+        }
+        run_sql_restore(&req, &statements, on_progress)?;
     }
-    run_sql_restore(&req)?;
     Ok(RestoreResult {
         duration_ms: start.elapsed().as_millis() as u64,
+        statement_count: statements.len(),
+        verified_only: req.verify_only,
     })
 }
 
@@ -161,6 +204,60 @@ pub fn incremental_backup(req: IncrementalRequest) -> Result<IncrementalResult,
     Err("Incremental backup via mysqlbinlog has been deprecated. Use full SQL backup profiles instead.".to_string())
 }
 
+pub fn get_binlog_status(conn: &ConnectionProfile) -> Result<BinlogStatus, String> {
+    pool::with_temp_connection(conn, |conn| {
+        let log_bin: Option<i64> = conn
+            .query_first("SELECT @@GLOBAL.log_bin")
+            .map_err(|e| format!("Query log_bin failed: {e}"))?;
+        if log_bin.unwrap_or(0) == 0 {
+            return Err(BINLOG_DISABLED_MESSAGE.to_string());
+        }
+
+        // MySQL 8.4 removed SHOW MASTER STATUS in favour of SHOW BINARY LOG STATUS.
+        let row = match conn.query_first::<mysql::Row, _>("SHOW MASTER STATUS") {
+            Ok(row) => row,
+            Err(_) => conn
+                .query_first::<mysql::Row, _>("SHOW BINARY LOG STATUS")
+                .map_err(|e| format!("Read binlog status failed: {e}"))?,
+        };
+        let row = row.ok_or_else(|| BINLOG_DISABLED_MESSAGE.to_string())?;
+
+        // MariaDB has no gtid_executed; fall back to the status row in that case.
+        let gtid_executed = conn
+            .query_first::<Option<String>, _>("SELECT @@GLOBAL.gtid_executed")
+            .ok()
+            .flatten()
+            .flatten();
+
+        map_binlog_status_row(&row, gtid_executed)
+    })
+}
+
+const BINLOG_DISABLED_MESSAGE: &str =
+    "Binary logging is disabled on this server (log_bin = OFF); enable it to record a recovery point";
+
+fn map_binlog_status_row(
+    row: &mysql::Row,
+    gtid_executed: Option<String>,
+) -> Result<BinlogStatus, String> {
+    let file = row_get_string(row, &["File"])
+        .filter(|v| !v.trim().is_empty())
+        .ok_or_else(|| BINLOG_DISABLED_MESSAGE.to_string())?;
+    let position = row
+        .get::<u64, _>("Position")
+        .ok_or_else(|| "Binlog status is missing the Position column".to_string())?;
+    let gtid_executed = gtid_executed
+        .or_else(|| row_get_string(row, &["Executed_Gtid_Set"]))
+        .map(|v| v.replace('\n', ""))
+        .filter(|v| !v.trim().is_empty());
+
+    Ok(BinlogStatus {
+        file,
+        position,
+        gtid_executed,
+    })
+}
+
 pub fn schedule_add(req: ScheduleRequest) -> Result<bool, String> {
     let schedule = Schedule::from_str(&req.cron).map_err(|e| format!("Invalid cron: {e}"))?;
     let next = schedule
@@ -170,6 +267,7 @@ pub fn schedule_add(req: ScheduleRequest) -> Result<bool, String> {
 
     let task = ScheduleTask {
         id: req.schedule_id.clone(),
+        cron: req.cron,
         schedule,
         backup: req.backup,
         next_run: Arc::new(Mutex::new(next)),
@@ -196,6 +294,22 @@ pub fn schedule_list() -> Result<Vec<String>, String> {
     Ok(tasks.keys().cloned().collect())
 }
 
+/// 导出当前所有计划任务（含原始 cron 表达式），用于配置迁移
+pub fn schedule_snapshot() -> Vec<ScheduleRequest> {
+    let scheduler = ensure_scheduler();
+    let tasks = scheduler.tasks.lock().unwrap();
+    let mut requests: Vec<ScheduleRequest> = tasks
+        .values()
+        .map(|task| ScheduleRequest {
+            schedule_id: task.id.clone(),
+            cron: task.cron.clone(),
+            backup: task.backup.clone(),
+        })
+        .collect();
+    requests.sort_by(|a, b| a.schedule_id.cmp(&b.schedule_id));
+    requests
+}
+
 fn ensure_scheduler() -> Arc<Scheduler> {
     SCHEDULER
         .get_or_init(|| {
@@ -342,18 +456,36 @@ fn create_schema(req: &RestoreRequest) -> Result<(), String> {
     })
 }
 
-fn run_sql_restore(req: &RestoreRequest) -> Result<(), String> {
-    let input_path = PathBuf::from(&req.input_path);
-    if !input_path.exists() {
-        return Err("SQL file not found".to_string());
+// 检查空文件；reject_unterminated 时还检查末尾未以分隔符结束的语句（通常是文件被截断）
+fn verify_sql_dump(sql_text: &str, reject_unterminated: bool) -> Result<Vec<String>, String> {
+    if sql_text.trim().is_empty() {
+        return Err("SQL file is empty".to_string());
     }
-
-    let sql_text = read_sql_file(&input_path)?;
-    let statements = split_sql_statements(&sql_text);
+    let (statements, truncated) = split_sql_statements_with_tail(sql_text);
     if statements.is_empty() {
         return Err("No executable SQL statements found".to_string());
     }
+    if truncated && reject_unterminated {
+        let tail = statements.last().map(|s| s.as_str()).unwrap_or_default();
+        let preview: String = tail.chars().take(80).collect();
+        return Err(format!(
+            "SQL file appears truncated: final statement #{} has no terminator: {}",
+            statements.len(),
+            preview
+        ));
+    }
+    Ok(statements)
+}
 
+fn run_sql_restore<F>(
+    req: &RestoreRequest,
+    statements: &[String],
+    mut on_progress: F,
+) -> Result<(), String>
+where
+    F: FnMut(&RestoreProgress),
+{
+    let total = statements.len();
     pool::with_temp_connection_database(&req.conn, Some(&req.target_schema), |conn| {
         if req.use_transaction {
             conn.query_drop("START TRANSACTION")
@@ -361,8 +493,22 @@ fn run_sql_restore(req: &RestoreRequest) -> Result<(), String> {
         }
 
         let mut first_error: Option<String> = None;
-
-        for statement in statements.iter().map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        let mut last_percentage: Option<u8> = None;
+
+        for (index, statement) in statements.iter().enumerate() {
+            let percentage = ((index + 1) * 100 / total.max(1)) as u8;
+            if last_percentage != Some(percentage) {
+                last_percentage = Some(percentage);
+                on_progress(&RestoreProgress {
+                    executed: index + 1,
+                    total,
+                    percentage,
+                });
+            }
+            let statement = statement.trim();
+            if statement.is_empty() {
+                continue;
+            }
             // Use text protocol for restore SQL to support statements not available in prepared mode.
             let exec_result = conn.query_drop(statement);
             if let Err(err) = exec_result {
@@ -1265,8 +1411,96 @@ fn escape_identifier(identifier: &str) -> String {
     identifier.replace('`', "``")
 }
 
+// 把转储中针对源库的 USE / CREATE DATABASE / `源库`.`表` 引用改写到目标库
+fn redirect_to_target_schema(statements: Vec<String>, target: &str) -> Vec<String> {
+    let source = statements.iter().find_map(|statement| {
+        parse_use_schema(statement)
+            .or_else(|| parse_create_database(statement).map(|(name, _)| name))
+    });
+    let source = match source {
+        Some(source) if source != target => source,
+        _ => return statements,
+    };
+
+    statements
+        .into_iter()
+        .map(|statement| {
+            if parse_use_schema(&statement).is_some_and(|name| name == source) {
+                return format!("USE `{}`", escape_identifier(target));
+            }
+            if let Some((name, options)) = parse_create_database(&statement) {
+                if name == source {
+                    return format!(
+                        "CREATE DATABASE IF NOT EXISTS `{}`{}",
+                        escape_identifier(target),
+                        options
+                    );
+                }
+            }
+            sqlutils::replace_schema_qualifier(&statement, &source, target)
+        })
+        .collect()
+}
+
+fn parse_use_schema(statement: &str) -> Option<String> {
+    let rest = strip_keywords(statement.trim(), &["USE"])?;
+    let (name, tail) = parse_schema_identifier(rest)?;
+    tail.trim().is_empty().then_some(name)
+}
+
+// 返回库名与其后的字符集等选项
+fn parse_create_database(statement: &str) -> Option<(String, &str)> {
+    let trimmed = statement.trim();
+    let rest = strip_keywords(trimmed, &["CREATE", "DATABASE"])
+        .or_else(|| strip_keywords(trimmed, &["CREATE", "SCHEMA"]))?;
+    let rest = strip_keywords(rest, &["IF", "NOT", "EXISTS"]).unwrap_or(rest);
+    parse_schema_identifier(rest)
+}
+
+fn strip_keywords<'a>(text: &'a str, keywords: &[&str]) -> Option<&'a str> {
+    let mut rest = text;
+    for keyword in keywords {
+        let head = rest.get(..keyword.len())?;
+        if !head.eq_ignore_ascii_case(keyword) {
+            return None;
+        }
+        let tail = &rest[keyword.len()..];
+        if !tail.starts_with(char::is_whitespace) {
+            return None;
+        }
+        rest = tail.trim_start();
+    }
+    Some(rest)
+}
+
+fn parse_schema_identifier(text: &str) -> Option<(String, &str)> {
+    if let Some(quoted) = text.strip_prefix('`') {
+        let mut name = String::new();
+        let mut chars = quoted.char_indices().peekable();
+        while let Some((idx, ch)) = chars.next() {
+            if ch == '`' {
+                if chars.peek().map(|(_, next)| *next) == Some('`') {
+                    name.push('`');
+                    chars.next();
+                    continue;
+                }
+                return Some((name, &quoted[idx + 1..]));
+            }
+            name.push(ch);
+        }
+        return None;
+    }
+    let end = text
+        .find(|c: char| c.is_whitespace() || c == ';')
+        .unwrap_or(text.len());
+    if end == 0 {
+        return None;
+    }
+    Some((text[..end].to_string(), &text[end..]))
+}
+
 fn read_sql_file(path: &PathBuf) -> Result<String, String> {
-    let mut content = String::new();
+    let mut content = Vec::new();
     if path
         .extension()
         .and_then(|v| v.to_str())
@@ -1275,17 +1509,23 @@ fn read_sql_file(path: &PathBuf) -> Result<String, String> {
         let file = File::open(path).map_err(|e| format!("Open SQL file failed: {e}"))?;
         let mut decoder = GzDecoder::new(file);
         decoder
-            .read_to_string(&mut content)
+            .read_to_end(&mut content)
             .map_err(|e| format!("Read gzip SQL failed: {e}"))?;
     } else {
         let mut file = File::open(path).map_err(|e| format!("Open SQL file failed: {e}"))?;
-        file.read_to_string(&mut content)
+        file.read_to_end(&mut content)
             .map_err(|e| format!("Read SQL file failed: {e}"))?;
     }
-    Ok(content)
+    String::from_utf8(content).map_err(|e| {
+        format!(
+            "SQL file is not valid UTF-8 (invalid byte at offset {})",
+            e.utf8_error().valid_up_to()
+        )
+    })
 }
 
-fn split_sql_statements(sql: &str) -> Vec<String> {
+// 第二个返回值表示最后一条语句没有以分隔符结束
+fn split_sql_statements_with_tail(sql: &str) -> (Vec<String>, bool) {
     let mut statements: Vec<String> = Vec::new();
     let mut delimiter = ";".to_string();
     let mut current = String::new();
@@ -1347,11 +1587,12 @@ fn split_sql_statements(sql: &str) -> Vec<String> {
     }
 
     let trailing = current.trim();
-    if !trailing.is_empty() {
+    let truncated = !trailing.is_empty();
+    if truncated {
         statements.push(trailing.to_string());
     }
 
-    statements
+    (statements, truncated)
 }
 
 fn is_compound_create_statement(statement: &str) -> bool {
@@ -1368,3 +1609,120 @@ fn ends_with_compound_terminator(statement: &str) -> bool {
     let upper = statement.trim_end().to_ascii_uppercase();
     upper.ends_with("END;")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::test_support::{row, text};
+    use mysql::Value;
+
+    #[test]
+    fn maps_binlog_status_row() {
+        let row = row(
+            &["File", "Position", "Executed_Gtid_Set"],
+            vec![
+                text("binlog.000042"),
+                Value::UInt(1547),
+                text("3e11fa47-71ca-11e1-9e33-c80aa9429562:1-5,\n4f22fb58:1-2"),
+            ],
+        );
+        let status = map_binlog_status_row(&row, None).unwrap();
+        assert_eq!(status.file, "binlog.000042");
+        assert_eq!(status.position, 1547);
+        assert_eq!(
+            status.gtid_executed.as_deref(),
+            Some("3e11fa47-71ca-11e1-9e33-c80aa9429562:1-5,4f22fb58:1-2")
+        );
+    }
+
+    #[test]
+    fn gtid_override_takes_precedence() {
+        let row = row(
+            &["File", "Position", "Executed_Gtid_Set"],
+            vec![text("binlog.000001"), Value::UInt(4), text("")],
+        );
+        let status = map_binlog_status_row(&row, Some("uuid:1-9\n".to_string())).unwrap();
+        assert_eq!(status.gtid_executed.as_deref(), Some("uuid:1-9"));
+
+        let status = map_binlog_status_row(&row, None).unwrap();
+        assert_eq!(status.gtid_executed, None);
+    }
+
+    #[test]
+    fn empty_file_means_binlog_disabled() {
+        let row = row(&["File", "Position"], vec![text(""), Value::UInt(0)]);
+        assert_eq!(
+            map_binlog_status_row(&row, None).err().as_deref(),
+            Some(BINLOG_DISABLED_MESSAGE)
+        );
+    }
+
+    #[test]
+    fn counts_statements_across_delimiter_blocks() {
+        let dump = "-- header\n\
+                    CREATE TABLE t (id INT);\n\
+                    INSERT INTO t VALUES (1), (2);\n\
+                    DELIMITER ;;\n\
+                    CREATE PROCEDURE p() BEGIN SELECT 1; END;;\n\
+                    DELIMITER ;\n\
+                    /* trailing comment */\n";
+        let (statements, truncated) = split_sql_statements_with_tail(dump);
+        assert_eq!(statements.len(), 3);
+        assert!(!truncated);
+        assert_eq!(verify_sql_dump(dump, true).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn verify_rejects_truncated_dump() {
+        let dump = "CREATE TABLE t (id INT);\nINSERT INTO t VALUES (1), (2";
+        let (statements, truncated) = split_sql_statements_with_tail(dump);
+        assert_eq!(statements.len(), 2);
+        assert!(truncated);
+        assert_eq!(
+            verify_sql_dump(dump, true).unwrap_err(),
+            "SQL file appears truncated: final statement #2 has no terminator: INSERT INTO t VALUES (1), (2"
+        );
+        assert_eq!(
+            verify_sql_dump("  \n", false).unwrap_err(),
+            "SQL file is empty"
+        );
+    }
+
+    #[test]
+    fn restore_accepts_final_statement_without_terminator() {
+        let dump = "CREATE TABLE t (id INT);\nINSERT INTO t VALUES (1)";
+        assert_eq!(
+            verify_sql_dump(dump, false).unwrap(),
+            ["CREATE TABLE t (id INT)", "INSERT INTO t VALUES (1)"]
+        );
+    }
+
+    #[test]
+    fn redirects_dump_statements_to_target_schema() {
+        let statements = vec![
+            "CREATE DATABASE IF NOT EXISTS `shop` DEFAULT CHARACTER SET utf8mb4".to_string(),
+            "USE `shop`".to_string(),
+            "INSERT INTO `shop`.`notes` VALUES (1, 'see `shop`.`orders`')".to_string(),
+            "CREATE VIEW `shop`.`v` AS SELECT * FROM `shop`.`orders` /* `shop`.x */".to_string(),
+        ];
+        let redirected = redirect_to_target_schema(statements, "shop_copy");
+        assert_eq!(
+            redirected,
+            vec![
+                "CREATE DATABASE IF NOT EXISTS `shop_copy` DEFAULT CHARACTER SET utf8mb4",
+                "USE `shop_copy`",
+                "INSERT INTO `shop_copy`.`notes` VALUES (1, 'see `shop`.`orders`')",
+                "CREATE VIEW `shop_copy`.`v` AS SELECT * FROM `shop_copy`.`orders` /* `shop`.x */",
+            ]
+        );
+    }
+
+    #[test]
+    fn redirect_is_noop_without_source_schema() {
+        let statements = vec!["INSERT INTO `shop`.`t` VALUES (1)".to_string()];
+        assert_eq!(
+            redirect_to_target_schema(statements.clone(), "other"),
+            statements
+        );
+    }
+}
@@ -0,0 +1,420 @@
+use crate::backend::app_config;
+use crate::backend::backup::{self, ScheduleRequest};
+use crate::backend::config;
+use crate::backend::favorites;
+use crate::backend::models::{ConnectionProfile, FavoriteItem};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::num::NonZeroU32;
+use std::path::Path;
+
+// 迁移包格式版本，结构不兼容时递增
+const BUNDLE_VERSION: u32 = 1;
+
+// 文件布局：魔数 | PBKDF2 盐 | AES-GCM nonce | 密文（gzip 压缩的 JSON）+ 认证标签
+const BUNDLE_MAGIC: &[u8; 8] = b"DBWBNDL1";
+const SALT_LEN: usize = 16;
+const PBKDF2_ITERATIONS: u32 = 210_000;
+
+#[derive(Serialize, Deserialize)]
+struct ConfigBundle {
+    version: u32,
+    created_at: String,
+    connections: Vec<ConnectionProfile>,
+    favorites: Vec<FavoriteItem>,
+    app_config: BTreeMap<String, String>,
+    schedules: Vec<ScheduleRequest>,
+}
+
+#[derive(Serialize)]
+pub struct BundleImportResult {
+    pub version: u32,
+    pub connections: usize,
+    pub favorites: usize,
+    pub settings: usize,
+    pub schedules: usize,
+}
+
+/// 将连接、收藏、应用配置和计划任务打包为单个加密文件，密钥由 passphrase 经 PBKDF2 派生。
+/// 连接按本地存储的原样导出（`${VAR}` 模板保持未展开）；
+/// `include_passwords` 为 false 时清空连接和备份计划中的密码，迁移后需重新填写
+pub fn export_bundle(path: &Path, passphrase: &str, include_passwords: bool) -> Result<(), String> {
+    let connections = config::load_connections()?
+        .iter()
+        .map(config::restore_env_templates)
+        .collect();
+    let mut bundle = ConfigBundle {
+        version: BUNDLE_VERSION,
+        created_at: chrono::Local::now().to_rfc3339(),
+        connections,
+        favorites: favorites::get_all()?,
+        app_config: app_config::get_all()?,
+        schedules: backup::schedule_snapshot(),
+    };
+    if !include_passwords {
+        clear_passwords(&mut bundle);
+    }
+    write_bundle(path, passphrase, &bundle)
+}
+
+fn clear_passwords(bundle: &mut ConfigBundle) {
+    for profile in &mut bundle.connections {
+        profile.password.clear();
+    }
+    for schedule in &mut bundle.schedules {
+        schedule.backup.conn.password.clear();
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<LessSafeKey, String> {
+    if passphrase.is_empty() {
+        return Err("Bundle passphrase must not be empty".to_string());
+    }
+    let mut key = [0u8; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        NonZeroU32::new(PBKDF2_ITERATIONS).expect("iterations are non-zero"),
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    let key = UnboundKey::new(&AES_256_GCM, &key).map_err(|_| "Invalid bundle key".to_string())?;
+    Ok(LessSafeKey::new(key))
+}
+
+fn write_bundle(path: &Path, passphrase: &str, bundle: &ConfigBundle) -> Result<(), String> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    serde_json::to_writer(&mut encoder, bundle)
+        .map_err(|e| format!("Failed to serialize bundle: {e}"))?;
+    let mut payload = encoder
+        .finish()
+        .map_err(|e| format!("Failed to compress bundle: {e}"))?;
+
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut salt)
+        .and_then(|_| rng.fill(&mut nonce))
+        .map_err(|_| "Failed to generate random bytes".to_string())?;
+    derive_key(passphrase, &salt)?
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(BUNDLE_MAGIC),
+            &mut payload,
+        )
+        .map_err(|_| "Failed to encrypt bundle".to_string())?;
+
+    let mut file =
+        std::fs::File::create(path).map_err(|e| format!("Failed to create file: {e}"))?;
+    file.write_all(BUNDLE_MAGIC)
+        .and_then(|_| file.write_all(&salt))
+        .and_then(|_| file.write_all(&nonce))
+        .and_then(|_| file.write_all(&payload))
+        .and_then(|_| file.flush())
+        .map_err(|e| format!("Failed to write file: {e}"))
+}
+
+fn read_bundle(path: &Path, passphrase: &str) -> Result<ConfigBundle, String> {
+    let content = std::fs::read(path).map_err(|e| format!("Failed to open file: {e}"))?;
+    let header_len = BUNDLE_MAGIC.len() + SALT_LEN + NONCE_LEN;
+    if content.len() < header_len || !content.starts_with(BUNDLE_MAGIC) {
+        return Err("Invalid config bundle: not an encrypted bundle file".to_string());
+    }
+    let (salt, rest) = content[BUNDLE_MAGIC.len()..].split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce)
+        .map_err(|_| "Invalid config bundle: bad nonce".to_string())?;
+    let mut ciphertext = ciphertext.to_vec();
+    let payload = derive_key(passphrase, salt)?
+        .open_in_place(nonce, Aad::from(BUNDLE_MAGIC), &mut ciphertext)
+        .map_err(|_| "Failed to decrypt bundle: wrong passphrase or corrupted file".to_string())?;
+
+    let mut json = Vec::new();
+    GzDecoder::new(&payload[..])
+        .read_to_end(&mut json)
+        .map_err(|e| format!("Invalid config bundle: {e}"))?;
+    let bundle: ConfigBundle =
+        serde_json::from_slice(&json).map_err(|e| format!("Invalid config bundle: {e}"))?;
+    if bundle.version > BUNDLE_VERSION {
+        return Err(format!(
+            "Config bundle version {} is newer than supported version {}",
+            bundle.version, BUNDLE_VERSION
+        ));
+    }
+    Ok(bundle)
+}
+
+/// 用导出时的 passphrase 解密并恢复迁移包。merge 为 true 时按连接名/收藏 id/配置键合并，
+/// 同名以包内为准；为 false 时整体替换现有数据
+pub fn import_bundle(
+    path: &Path,
+    passphrase: &str,
+    merge: bool,
+) -> Result<BundleImportResult, String> {
+    let bundle = read_bundle(path, passphrase)?;
+
+    let result = BundleImportResult {
+        version: bundle.version,
+        connections: bundle.connections.len(),
+        favorites: bundle.favorites.len(),
+        settings: bundle.app_config.len(),
+        schedules: bundle.schedules.len(),
+    };
+
+    let connections = if merge {
+        let existing = config::load_connections()?
+            .iter()
+            .map(config::restore_env_templates)
+            .collect();
+        merge_connections(existing, bundle.connections)
+    } else {
+        bundle.connections
+    };
+    config::save_connections(&connections)?;
+
+    let favorite_items = if merge {
+        merge_favorites(favorites::get_all()?, bundle.favorites)
+    } else {
+        bundle.favorites
+    };
+    favorites::replace_all(&favorite_items)?;
+
+    let settings = if merge {
+        let mut existing = app_config::get_all()?;
+        existing.extend(bundle.app_config);
+        existing
+    } else {
+        bundle.app_config
+    };
+    app_config::replace_all(settings)?;
+
+    let existing_schedules = if merge {
+        backup::schedule_snapshot()
+    } else {
+        for schedule_id in backup::schedule_list()? {
+            backup::schedule_remove(&schedule_id)?;
+        }
+        Vec::new()
+    };
+    for schedule in merge_schedule_passwords(existing_schedules, bundle.schedules) {
+        backup::schedule_add(schedule)?;
+    }
+
+    Ok(result)
+}
+
+fn merge_connections(
+    mut existing: Vec<ConnectionProfile>,
+    incoming: Vec<ConnectionProfile>,
+) -> Vec<ConnectionProfile> {
+    for mut profile in incoming {
+        match existing.iter_mut().find(|p| p.name == profile.name) {
+            Some(slot) => {
+                // 不含密码的迁移包不覆盖本机已保存的密码
+                if profile.password.is_empty() {
+                    profile.password = std::mem::take(&mut slot.password);
+                }
+                *slot = profile;
+            }
+            None => existing.push(profile),
+        }
+    }
+    existing
+}
+
+// 与连接相同：包内密码为空时沿用本机同 id 计划的密码
+fn merge_schedule_passwords(
+    existing: Vec<ScheduleRequest>,
+    incoming: Vec<ScheduleRequest>,
+) -> Vec<ScheduleRequest> {
+    incoming
+        .into_iter()
+        .map(|mut schedule| {
+            if schedule.backup.conn.password.is_empty() {
+                if let Some(local) = existing
+                    .iter()
+                    .find(|e| e.schedule_id == schedule.schedule_id)
+                {
+                    schedule.backup.conn.password = local.backup.conn.password.clone();
+                }
+            }
+            schedule
+        })
+        .collect()
+}
+
+fn merge_favorites(
+    mut existing: Vec<FavoriteItem>,
+    incoming: Vec<FavoriteItem>,
+) -> Vec<FavoriteItem> {
+    for item in incoming {
+        match existing
+            .iter_mut()
+            .find(|e| e.id.is_some() && e.id == item.id)
+        {
+            Some(slot) => *slot = item,
+            None => existing.push(item),
+        }
+    }
+    existing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::test_support::{profile, temp_path};
+    use serde_json::json;
+
+    fn favorite(id: &str, name: &str) -> FavoriteItem {
+        serde_json::from_value(json!({
+            "id": id,
+            "name": name,
+            "description": null,
+            "type": "SQL_QUERY",
+            "content": "SELECT 1",
+            "createdTime": 1,
+            "lastUsedTime": 2,
+            "usageCount": 3,
+        }))
+        .unwrap()
+    }
+
+    fn schedule(id: &str, conn: &ConnectionProfile) -> ScheduleRequest {
+        serde_json::from_value(json!({
+            "schedule_id": id,
+            "cron": "0 3 * * *",
+            "backup": {
+                "conn": conn,
+                "schema": "shop",
+                "output_path": "/backups/shop.sql.gz",
+                "options": {
+                    "include_structure": true,
+                    "include_data": true,
+                    "include_views": false,
+                    "include_routines": false,
+                    "include_triggers": false,
+                    "add_drop_table": true,
+                    "use_transaction": true,
+                    "compress_output": true,
+                    "compression_level": 6,
+                    "insert_batch_size": 500,
+                },
+            },
+        }))
+        .unwrap()
+    }
+
+    fn populated_bundle() -> ConfigBundle {
+        let mut local = profile("db.internal", 3307, "app", "s3cret-pw");
+        local.name = Some("prod".to_string());
+        local.init_sql = vec!["SET time_zone = '+00:00'".to_string()];
+        ConfigBundle {
+            version: BUNDLE_VERSION,
+            created_at: "2024-01-01T00:00:00+00:00".to_string(),
+            schedules: vec![schedule("nightly", &local)],
+            connections: vec![local],
+            favorites: vec![favorite("fav-1", "daily report")],
+            app_config: BTreeMap::from([("theme".to_string(), "dark".to_string())]),
+        }
+    }
+
+    #[test]
+    fn populated_bundle_round_trips_through_encrypted_file() {
+        let bundle = populated_bundle();
+        let path = temp_path("bundle.dbwb");
+        write_bundle(&path, "correct horse", &bundle).unwrap();
+        let raw = std::fs::read(&path).unwrap();
+        let restored = read_bundle(&path, "correct horse");
+        let wrong = read_bundle(&path, "battery staple");
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            serde_json::to_value(restored.unwrap()).unwrap(),
+            serde_json::to_value(&bundle).unwrap()
+        );
+        assert!(raw.starts_with(BUNDLE_MAGIC));
+        assert!(!raw.windows(9).any(|w| w == b"s3cret-pw"));
+        assert_eq!(
+            wrong.err().as_deref(),
+            Some("Failed to decrypt bundle: wrong passphrase or corrupted file")
+        );
+    }
+
+    #[test]
+    fn empty_passphrase_and_plain_files_are_rejected() {
+        let path = temp_path("plain.dbwb");
+        let written = write_bundle(&path, "", &populated_bundle());
+        std::fs::write(&path, b"{\"version\":1}").unwrap();
+        let plain = read_bundle(&path, "pw");
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            written.err().as_deref(),
+            Some("Bundle passphrase must not be empty")
+        );
+        assert_eq!(
+            plain.err().as_deref(),
+            Some("Invalid config bundle: not an encrypted bundle file")
+        );
+    }
+
+    #[test]
+    fn clearing_passwords_covers_schedule_connections() {
+        let mut bundle = populated_bundle();
+        clear_passwords(&mut bundle);
+
+        assert_eq!(bundle.connections[0].password, "");
+        assert_eq!(bundle.schedules[0].backup.conn.password, "");
+
+        let mut saved = profile("db.internal", 3307, "app", "local-pw");
+        saved.name = Some("prod".to_string());
+        let merged = merge_schedule_passwords(vec![schedule("nightly", &saved)], bundle.schedules);
+        assert_eq!(merged[0].backup.conn.password, "local-pw");
+    }
+
+    #[test]
+    fn rejects_bundle_from_newer_version() {
+        let bundle = ConfigBundle {
+            version: BUNDLE_VERSION + 1,
+            created_at: String::new(),
+            connections: Vec::new(),
+            favorites: Vec::new(),
+            app_config: BTreeMap::new(),
+            schedules: Vec::new(),
+        };
+        let path = temp_path("future.dbwb");
+        write_bundle(&path, "pw", &bundle).unwrap();
+        let restored = read_bundle(&path, "pw");
+        let _ = std::fs::remove_file(&path);
+
+        assert!(restored.err().unwrap().contains("newer than supported"));
+    }
+    #[test]
+    fn merge_keeps_local_password_and_replaces_matching_favorites() {
+        let mut saved = profile("old-host", 3306, "app", "secret");
+        saved.name = Some("prod".to_string());
+        let mut incoming = profile("new-host", 3306, "app", "");
+        incoming.name = Some("prod".to_string());
+        let mut other = profile("dev-host", 3306, "dev", "dev");
+        other.name = Some("dev".to_string());
+
+        let merged = merge_connections(vec![saved], vec![incoming, other]);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].host, "new-host");
+        assert_eq!(merged[0].password, "secret");
+
+        let favorites = merge_favorites(
+            vec![favorite("fav-1", "old"), favorite("fav-2", "kept")],
+            vec![favorite("fav-1", "new"), favorite("fav-3", "added")],
+        );
+        let names: Vec<_> = favorites.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["new", "kept", "added"]);
+    }
+}
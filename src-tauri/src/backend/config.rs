@@ -1,16 +1,43 @@
-use crate::backend::models::ConnectionProfile;
-use std::collections::HashMap;
+use crate::backend::models::{ConnectionProfile, ProfileRef};
+use crate::backend::pool;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::fs;
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+const TEST_ALL_MAX_PARALLEL: usize = 8;
+
+/// 字段中引用的环境变量未设置时的处理方式
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MissingEnvVar {
+    #[default]
+    Error,
+    Empty,
+    /// 保留未展开的 `${VAR}`，只影响引用了它的连接
+    Keep,
+}
+
+#[derive(Serialize)]
+pub struct ConnectionTestStatus {
+    pub name: String,
+    pub ok: bool,
+    #[serde(rename = "latencyMs")]
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
 
 pub fn load_connections() -> Result<Vec<ConnectionProfile>, String> {
     let path = default_store_path()?;
     if !path.exists() {
         return Ok(Vec::new());
     }
-    load_profiles_from_properties(&path)
+    // 某个连接引用的变量未设置时不能让其它连接也无法加载
+    load_profiles_from_properties(&path, MissingEnvVar::Keep)
 }
 
 pub fn save_connections(profiles: &[ConnectionProfile]) -> Result<(), String> {
@@ -19,7 +46,84 @@ pub fn save_connections(profiles: &[ConnectionProfile]) -> Result<(), String> {
     save_profiles_to_properties(&path, profiles)
 }
 
-pub fn import_connections(path: &Path) -> Result<Vec<ConnectionProfile>, String> {
+/// 按名称查找已保存的连接
+pub fn resolve(name: &str) -> Result<ConnectionProfile, String> {
+    find_named(load_connections()?, name)
+}
+
+fn find_named(profiles: Vec<ConnectionProfile>, name: &str) -> Result<ConnectionProfile, String> {
+    profiles
+        .into_iter()
+        .find(|profile| profile.name.as_deref() == Some(name))
+        .ok_or_else(|| format!("Saved connection not found: {name}"))
+}
+
+pub fn resolve_ref(profile: ProfileRef) -> Result<ConnectionProfile, String> {
+    match profile {
+        ProfileRef::Inline(profile) => Ok(*profile),
+        ProfileRef::Named(name) => resolve(&name),
+    }
+}
+
+/// 并发测试所有连接，结果顺序与输入一致
+pub fn test_all(profiles: Vec<ConnectionProfile>) -> Vec<ConnectionTestStatus> {
+    let runtime = pool::runtime();
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(TEST_ALL_MAX_PARALLEL));
+
+    runtime.block_on(async {
+        let handles: Vec<_> = profiles
+            .into_iter()
+            .map(|profile| {
+                let semaphore = Arc::clone(&semaphore);
+                let name = profile
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("{}:{}", profile.host, profile.port));
+                let handle = runtime.spawn(async move {
+                    let _permit = semaphore.acquire_owned().await;
+                    tokio::task::spawn_blocking(move || {
+                        let start = Instant::now();
+                        let result = pool::test_connection(&profile);
+                        (result, start.elapsed().as_millis() as u64)
+                    })
+                    .await
+                });
+                (name, handle)
+            })
+            .collect();
+
+        let mut statuses = Vec::with_capacity(handles.len());
+        for (name, handle) in handles {
+            let status = match handle.await {
+                Ok(Ok((Ok(_), latency_ms))) => ConnectionTestStatus {
+                    name,
+                    ok: true,
+                    latency_ms,
+                    error: None,
+                },
+                Ok(Ok((Err(err), latency_ms))) => ConnectionTestStatus {
+                    name,
+                    ok: false,
+                    latency_ms,
+                    error: Some(err),
+                },
+                Ok(Err(err)) | Err(err) => ConnectionTestStatus {
+                    name,
+                    ok: false,
+                    latency_ms: 0,
+                    error: Some(format!("Test task failed: {err}")),
+                },
+            };
+            statuses.push(status);
+        }
+        statuses
+    })
+}
+
+pub fn import_connections(
+    path: &Path,
+    missing_env: MissingEnvVar,
+) -> Result<Vec<ConnectionProfile>, String> {
     let ext = path
         .extension()
         .and_then(|e| e.to_str())
@@ -27,8 +131,8 @@ pub fn import_connections(path: &Path) -> Result<Vec<ConnectionProfile>, String>
         .to_ascii_lowercase();
 
     match ext.as_str() {
-        "csv" => import_from_csv(path),
-        "properties" => load_profiles_from_properties(path),
+        "csv" => import_from_csv(path, missing_env),
+        "properties" => load_profiles_from_properties(path, missing_env),
         _ => Err("Only CSV and Properties files are supported".to_string()),
     }
 }
@@ -69,18 +173,22 @@ fn home_dir() -> Option<PathBuf> {
     env::current_dir().ok()
 }
 
-fn load_profiles_from_properties(path: &Path) -> Result<Vec<ConnectionProfile>, String> {
+fn load_profiles_from_properties(
+    path: &Path,
+    missing_env: MissingEnvVar,
+) -> Result<Vec<ConnectionProfile>, String> {
     let mut file = fs::File::open(path).map_err(|e| format!("Failed to open file: {e}"))?;
     let mut content = String::new();
     file.read_to_string(&mut content)
         .map_err(|e| format!("Failed to read file: {e}"))?;
 
-    let props = parse_properties(&content);
+    let mut props = parse_properties(&content);
     let count: usize = props.get("count").and_then(|v| v.parse().ok()).unwrap_or(0);
 
     let mut profiles = Vec::with_capacity(count);
     for i in 0..count {
         let prefix = format!("conn.{i}.");
+        let env_templates = expand_env_in_map(&mut props, &prefix, missing_env)?;
         let name = props
             .get(&(prefix.clone() + "name"))
             .cloned()
@@ -113,6 +221,18 @@ fn load_profiles_from_properties(path: &Path) -> Result<Vec<ConnectionProfile>,
         let timeout = props
             .get(&(format!("conn.{i}.timeout")))
             .and_then(|v| v.parse::<u64>().ok());
+        let connection_timeout = props
+            .get(&(format!("conn.{i}.connectionTimeout")))
+            .and_then(|v| v.parse::<u64>().ok());
+        let read_timeout = props
+            .get(&(format!("conn.{i}.readTimeout")))
+            .and_then(|v| v.parse::<u64>().ok());
+        let auto_reconnect = props
+            .get(&(format!("conn.{i}.autoReconnect")))
+            .and_then(|v| v.parse::<bool>().ok());
+        let keepalive_interval = props
+            .get(&(format!("conn.{i}.keepaliveInterval")))
+            .and_then(|v| v.parse::<u64>().ok());
         let ssl_mode = props
             .get(&(format!("conn.{i}.sslMode")))
             .cloned()
@@ -129,6 +249,13 @@ fn load_profiles_from_properties(path: &Path) -> Result<Vec<ConnectionProfile>,
             .get(&(format!("conn.{i}.sslKeyPath")))
             .cloned()
             .unwrap_or_default();
+        let default_row_limit = props
+            .get(&(format!("conn.{i}.defaultRowLimit")))
+            .and_then(|v| v.parse::<u64>().ok());
+        let init_sql = props
+            .get(&(format!("conn.{i}.initSql")))
+            .map(|v| split_init_sql(v))
+            .unwrap_or_default();
 
         profiles.push(ConnectionProfile {
             name: Some(name),
@@ -152,8 +279,10 @@ fn load_profiles_from_properties(path: &Path) -> Result<Vec<ConnectionProfile>,
                 Some(collation)
             },
             timeout,
-            connection_timeout: None, // 从配置文件加载时默认为 None，后续使用默认值
-            auto_reconnect: None,     // NEW: 从配置文件加载时默认为 None，后续使用默认值（false）
+            connection_timeout, // 未配置时为 None，后续使用默认值
+            read_timeout,
+            auto_reconnect, // 未配置时为 None，后续使用默认值（false）
+            keepalive_interval,
             ssl: None,
             ssl_mode: if ssl_mode.is_empty() {
                 None
@@ -175,6 +304,9 @@ fn load_profiles_from_properties(path: &Path) -> Result<Vec<ConnectionProfile>,
             } else {
                 Some(ssl_key_path)
             },
+            default_row_limit,
+            init_sql,
+            env_templates,
         });
     }
 
@@ -186,6 +318,7 @@ fn save_profiles_to_properties(path: &Path, profiles: &[ConnectionProfile]) -> R
     content.push_str(&format!("count={}\n", profiles.len()));
 
     for (i, profile) in profiles.iter().enumerate() {
+        let profile = &restore_env_templates(profile);
         let prefix = format!("conn.{i}.");
         content.push_str(&format!(
             "{}name={}\n",
@@ -228,6 +361,24 @@ fn save_profiles_to_properties(path: &Path, profiles: &[ConnectionProfile]) -> R
             prefix,
             profile.timeout.unwrap_or(30)
         ));
+        if let Some(connection_timeout) = profile.connection_timeout {
+            content.push_str(&format!(
+                "{}connectionTimeout={}\n",
+                prefix, connection_timeout
+            ));
+        }
+        if let Some(read_timeout) = profile.read_timeout {
+            content.push_str(&format!("{}readTimeout={}\n", prefix, read_timeout));
+        }
+        if let Some(auto_reconnect) = profile.auto_reconnect {
+            content.push_str(&format!("{}autoReconnect={}\n", prefix, auto_reconnect));
+        }
+        if let Some(keepalive_interval) = profile.keepalive_interval {
+            content.push_str(&format!(
+                "{}keepaliveInterval={}\n",
+                prefix, keepalive_interval
+            ));
+        }
         content.push_str(&format!(
             "{}sslMode={}\n",
             prefix,
@@ -248,6 +399,16 @@ fn save_profiles_to_properties(path: &Path, profiles: &[ConnectionProfile]) -> R
             prefix,
             escape_property_value(profile.ssl_key_path.as_deref().unwrap_or(""))
         ));
+        if let Some(limit) = profile.default_row_limit {
+            content.push_str(&format!("{}defaultRowLimit={}\n", prefix, limit));
+        }
+        if !profile.init_sql.is_empty() {
+            content.push_str(&format!(
+                "{}initSql={}\n",
+                prefix,
+                escape_property_value(&profile.init_sql.join("\n"))
+            ));
+        }
     }
 
     ensure_parent_dir(path)?;
@@ -340,6 +501,112 @@ fn unescape_property_value(value: &str) -> String {
     result
 }
 
+// 展开以 prefix 开头的键中的 ${VAR}，返回 字段 -> 原始模板
+fn expand_env_in_map(
+    map: &mut HashMap<String, String>,
+    prefix: &str,
+    missing_env: MissingEnvVar,
+) -> Result<BTreeMap<String, String>, String> {
+    let mut templates = BTreeMap::new();
+    for (key, value) in map.iter_mut() {
+        let Some(field) = key.strip_prefix(prefix) else {
+            continue;
+        };
+        if !value.contains("${") {
+            continue;
+        }
+        let expanded = expand_env_vars(value, missing_env)
+            .map_err(|e| format!("Failed to expand {key}: {e}"))?;
+        let template = std::mem::replace(value, expanded);
+        templates.insert(canonical_field(field).to_string(), template);
+    }
+    Ok(templates)
+}
+
+fn expand_env_vars(value: &str, missing_env: MissingEnvVar) -> Result<String, String> {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start + 2..].find('}') else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        let var = rest[start + 2..start + 2 + len].trim();
+        if var.is_empty() {
+            return Err("Empty environment variable reference".to_string());
+        }
+        match (env::var(var), missing_env) {
+            (Ok(resolved), _) => result.push_str(&resolved),
+            (Err(_), MissingEnvVar::Empty) => {}
+            (Err(_), MissingEnvVar::Keep) => result.push_str(&rest[start..start + 2 + len + 1]),
+            (Err(_), MissingEnvVar::Error) => {
+                return Err(format!("Environment variable {var} is not set"));
+            }
+        }
+        rest = &rest[start + 2 + len + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+// Properties 与 CSV 的字段名不同，统一成 CSV 表头的写法
+fn canonical_field(field: &str) -> &str {
+    match field {
+        "user" => "username",
+        "pwd" => "password",
+        "db" => "database",
+        "ssl_mode" => "sslMode",
+        "ssl_ca_path" => "sslCaPath",
+        "ssl_cert_path" => "sslCertPath",
+        "ssl_key_path" => "sslKeyPath",
+        other => other,
+    }
+}
+
+/// 值仍等于模板的展开结果（即未被修改）时写回 `${VAR}` 模板
+pub fn restore_env_templates(profile: &ConnectionProfile) -> ConnectionProfile {
+    let mut restored = profile.clone();
+    let templates = std::mem::take(&mut restored.env_templates);
+    let restore = |field: &str, value: &mut String| {
+        if let Some(template) = templates.get(field) {
+            let expanded = expand_env_vars(template, MissingEnvVar::Keep);
+            if expanded.as_deref() == Ok(value.as_str()) {
+                *value = template.clone();
+            }
+        }
+    };
+    restore("host", &mut restored.host);
+    restore("username", &mut restored.username);
+    restore("password", &mut restored.password);
+    for (field, value) in [
+        ("database", &mut restored.database),
+        ("charset", &mut restored.charset),
+        ("collation", &mut restored.collation),
+        ("sslMode", &mut restored.ssl_mode),
+        ("sslCaPath", &mut restored.ssl_ca_path),
+        ("sslCertPath", &mut restored.ssl_cert_path),
+        ("sslKeyPath", &mut restored.ssl_key_path),
+    ] {
+        if let Some(value) = value.as_mut() {
+            restore(field, value);
+        }
+    }
+    restored
+}
+
+fn optional_to_string<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+// 每行一条初始化语句
+fn split_init_sql(value: &str) -> Vec<String> {
+    value
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
 fn escape_property_value(value: &str) -> String {
     let mut result = String::new();
     for ch in value.chars() {
@@ -359,7 +626,10 @@ fn escape_property_value(value: &str) -> String {
     result
 }
 
-fn import_from_csv(path: &Path) -> Result<Vec<ConnectionProfile>, String> {
+fn import_from_csv(
+    path: &Path,
+    missing_env: MissingEnvVar,
+) -> Result<Vec<ConnectionProfile>, String> {
     let mut reader = csv::ReaderBuilder::new()
         .has_headers(true)
         .from_path(path)
@@ -379,6 +649,7 @@ fn import_from_csv(path: &Path) -> Result<Vec<ConnectionProfile>, String> {
                 map.insert(key.to_string(), value.to_string());
             }
         }
+        let env_templates = expand_env_in_map(&mut map, "", missing_env)?;
 
         let profile = ConnectionProfile {
             name: map.get("name").cloned(),
@@ -392,33 +663,50 @@ fn import_from_csv(path: &Path) -> Result<Vec<ConnectionProfile>, String> {
                 .cloned()
                 .unwrap_or_else(|| "root".to_string()),
             password: map.get("password").cloned().unwrap_or_default(),
-            database: map.get("database").cloned(),
-            charset: map.get("charset").cloned(),
-            collation: map.get("collation").cloned(),
+            // 空单元格视为未设置，与 properties 的读取保持一致
+            database: map.get("database").cloned().filter(|v| !v.is_empty()),
+            charset: map.get("charset").cloned().filter(|v| !v.is_empty()),
+            collation: map.get("collation").cloned().filter(|v| !v.is_empty()),
             timeout: map.get("timeout").and_then(|v| v.parse::<u64>().ok()),
             connection_timeout: map
                 .get("connectionTimeout")
                 .and_then(|v| v.parse::<u64>().ok()),
+            read_timeout: map.get("readTimeout").and_then(|v| v.parse::<u64>().ok()),
             auto_reconnect: map
                 .get("autoReconnect")
                 .and_then(|v| v.parse::<bool>().ok()), // NEW: 从 CSV 加载自动重连配置
+            keepalive_interval: map
+                .get("keepaliveInterval")
+                .and_then(|v| v.parse::<u64>().ok()),
             ssl: None,
             ssl_mode: map
                 .get("sslMode")
                 .cloned()
-                .or_else(|| map.get("ssl_mode").cloned()),
+                .or_else(|| map.get("ssl_mode").cloned())
+                .filter(|v| !v.is_empty()),
             ssl_ca_path: map
                 .get("sslCaPath")
                 .cloned()
-                .or_else(|| map.get("ssl_ca_path").cloned()),
+                .or_else(|| map.get("ssl_ca_path").cloned())
+                .filter(|v| !v.is_empty()),
             ssl_cert_path: map
                 .get("sslCertPath")
                 .cloned()
-                .or_else(|| map.get("ssl_cert_path").cloned()),
+                .or_else(|| map.get("ssl_cert_path").cloned())
+                .filter(|v| !v.is_empty()),
             ssl_key_path: map
                 .get("sslKeyPath")
                 .cloned()
-                .or_else(|| map.get("ssl_key_path").cloned()),
+                .or_else(|| map.get("ssl_key_path").cloned())
+                .filter(|v| !v.is_empty()),
+            default_row_limit: map
+                .get("defaultRowLimit")
+                .and_then(|v| v.parse::<u64>().ok()),
+            init_sql: map
+                .get("initSql")
+                .map(|v| split_init_sql(v))
+                .unwrap_or_default(),
+            env_templates,
         };
         results.push(profile);
     }
@@ -443,14 +731,21 @@ fn export_to_csv(path: &Path, profiles: &[ConnectionProfile]) -> Result<(), Stri
             "charset",
             "collation",
             "timeout",
+            "connectionTimeout",
+            "readTimeout",
+            "autoReconnect",
+            "keepaliveInterval",
             "sslMode",
             "sslCaPath",
             "sslCertPath",
             "sslKeyPath",
+            "defaultRowLimit",
+            "initSql",
         ])
         .map_err(|e| format!("Failed to write CSV headers: {e}"))?;
 
     for profile in profiles {
+        let profile = &restore_env_templates(profile);
         writer
             .write_record([
                 profile.name.as_deref().unwrap_or(""),
@@ -462,10 +757,16 @@ fn export_to_csv(path: &Path, profiles: &[ConnectionProfile]) -> Result<(), Stri
                 profile.charset.as_deref().unwrap_or(""),
                 profile.collation.as_deref().unwrap_or(""),
                 &profile.timeout.unwrap_or(30).to_string(),
+                &optional_to_string(profile.connection_timeout),
+                &optional_to_string(profile.read_timeout),
+                &optional_to_string(profile.auto_reconnect),
+                &optional_to_string(profile.keepalive_interval),
                 profile.ssl_mode.as_deref().unwrap_or(""),
                 profile.ssl_ca_path.as_deref().unwrap_or(""),
                 profile.ssl_cert_path.as_deref().unwrap_or(""),
                 profile.ssl_key_path.as_deref().unwrap_or(""),
+                &optional_to_string(profile.default_row_limit),
+                &profile.init_sql.join("\n"),
             ])
             .map_err(|e| format!("Failed to write CSV record: {e}"))?;
     }
@@ -474,3 +775,185 @@ fn export_to_csv(path: &Path, profiles: &[ConnectionProfile]) -> Result<(), Stri
         .flush()
         .map_err(|e| format!("Failed to flush CSV: {e}"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::test_support::{profile, temp_path};
+
+    fn round_trip(name: &str, profiles: &[ConnectionProfile]) -> Vec<ConnectionProfile> {
+        let path = temp_path(name);
+        export_connections(&path, profiles).unwrap();
+        let loaded = import_connections(&path, MissingEnvVar::Error).unwrap();
+        fs::remove_file(&path).unwrap();
+        loaded
+    }
+
+    #[test]
+    fn keepalive_settings_survive_save_and_load() {
+        let mut saved = profile("db.internal", 3307, "app", "secret");
+        saved.name = Some("keepalive".to_string());
+        saved.connection_timeout = Some(12);
+        saved.auto_reconnect = Some(true);
+        saved.keepalive_interval = Some(45);
+
+        let loaded = round_trip("keepalive.properties", &[saved]);
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].connection_timeout, Some(12));
+        assert_eq!(loaded[0].auto_reconnect, Some(true));
+        assert_eq!(loaded[0].keepalive_interval, Some(45));
+
+        let mut unset = profile("db.internal", 3307, "app", "secret");
+        unset.name = Some("defaults".to_string());
+        unset.connection_timeout = None;
+        let loaded = round_trip("keepalive_unset.properties", &[unset]);
+        assert_eq!(loaded[0].connection_timeout, None);
+        assert_eq!(loaded[0].auto_reconnect, None);
+        assert_eq!(loaded[0].keepalive_interval, None);
+    }
+
+    #[test]
+    fn csv_and_properties_round_trip_the_same_fields() {
+        let mut full = profile("10.0.0.5", 3306, "report", "p@ss,word");
+        full.name = Some("reporting".to_string());
+        full.database = Some("sales".to_string());
+        full.charset = Some("utf8mb4".to_string());
+        full.collation = Some("utf8mb4_0900_ai_ci".to_string());
+        full.timeout = Some(600);
+        full.read_timeout = Some(30);
+        full.auto_reconnect = Some(false);
+        full.keepalive_interval = Some(20);
+        full.ssl_mode = Some("required".to_string());
+        full.ssl_ca_path = Some("/etc/ssl/ca.pem".to_string());
+        full.default_row_limit = Some(1000);
+        full.init_sql = vec!["SET time_zone = '+00:00'".to_string()];
+        let expected = serde_json::to_value([&full]).unwrap();
+
+        let from_csv = round_trip("symmetric.csv", std::slice::from_ref(&full));
+        let from_properties = round_trip("symmetric.properties", std::slice::from_ref(&full));
+        assert_eq!(serde_json::to_value(&from_csv).unwrap(), expected);
+        assert_eq!(serde_json::to_value(&from_properties).unwrap(), expected);
+    }
+
+    fn unreachable(name: &str, port: u16) -> ConnectionProfile {
+        // 本机未监听的端口，连接会立即被拒绝
+        let mut profile = profile("127.0.0.1", port, "root", "");
+        profile.name = Some(name.to_string());
+        profile.connection_timeout = Some(2);
+        profile
+    }
+
+    #[test]
+    fn test_all_reports_each_unreachable_profile_in_order() {
+        let statuses = test_all(vec![unreachable("first", 1), unreachable("second", 2)]);
+        let names: Vec<&str> = statuses.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["first", "second"]);
+        assert!(statuses.iter().all(|s| !s.ok && s.error.is_some()));
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn test_all_mixes_reachable_and_unreachable_profiles() {
+        let mut reachable = crate::backend::test_support::mysql_profile();
+        reachable.name = Some("reachable".to_string());
+        let statuses = test_all(vec![unreachable("down", 1), reachable]);
+        assert_eq!(statuses[0].name, "down");
+        assert!(!statuses[0].ok);
+        assert_eq!(statuses[1].name, "reachable");
+        assert!(statuses[1].ok, "{:?}", statuses[1].error);
+        assert!(statuses[1].error.is_none());
+    }
+
+    #[test]
+    fn profile_ref_resolves_inline_and_named() {
+        let inline: ProfileRef = serde_json::from_value(serde_json::json!({
+            "host": "db",
+            "port": 3306,
+            "username": "root",
+            "password": ""
+        }))
+        .unwrap();
+        assert_eq!(resolve_ref(inline).unwrap().host, "db");
+
+        let named: ProfileRef = serde_json::from_value(serde_json::json!("prod")).unwrap();
+        assert!(matches!(&named, ProfileRef::Named(name) if name == "prod"));
+
+        let mut prod = profile("10.0.0.5", 3306, "app", "");
+        prod.name = Some("prod".to_string());
+        let saved = vec![profile("localhost", 3306, "root", ""), prod];
+        assert_eq!(find_named(saved.clone(), "prod").unwrap().host, "10.0.0.5");
+        assert_eq!(
+            find_named(saved, "staging").unwrap_err(),
+            "Saved connection not found: staging"
+        );
+    }
+
+    #[test]
+    fn expands_env_references_and_reports_unset_variables() {
+        env::set_var("DBW_TEST_EXPAND_HOST", "db.internal");
+        assert_eq!(
+            expand_env_vars("${DBW_TEST_EXPAND_HOST}:3306", MissingEnvVar::Error).unwrap(),
+            "db.internal:3306"
+        );
+
+        env::remove_var("DBW_TEST_EXPAND_UNSET");
+        assert_eq!(
+            expand_env_vars("${DBW_TEST_EXPAND_UNSET}", MissingEnvVar::Error).unwrap_err(),
+            "Environment variable DBW_TEST_EXPAND_UNSET is not set"
+        );
+        assert_eq!(
+            expand_env_vars("pre-${DBW_TEST_EXPAND_UNSET}-post", MissingEnvVar::Empty).unwrap(),
+            "pre--post"
+        );
+    }
+
+    #[test]
+    fn env_templates_travel_with_the_profile_through_load_and_save() {
+        env::set_var("DBW_TEST_TEMPLATE_HOST", "db.internal");
+        env::set_var("DBW_TEST_TEMPLATE_PWD", "s3cret");
+        env::remove_var("DBW_TEST_TEMPLATE_UNSET");
+        let path = temp_path("env_templates.properties");
+        fs::write(
+            &path,
+            "count=2\n\
+             conn.0.name=prod\n\
+             conn.0.host=${DBW_TEST_TEMPLATE_HOST}\n\
+             conn.0.user=app\n\
+             conn.0.pwd=${DBW_TEST_TEMPLATE_PWD}\n\
+             conn.1.name=broken\n\
+             conn.1.host=${DBW_TEST_TEMPLATE_UNSET}\n",
+        )
+        .unwrap();
+
+        // 未设置的变量只影响引用它的连接
+        let mut loaded = load_profiles_from_properties(&path, MissingEnvVar::Keep).unwrap();
+        assert_eq!(loaded[0].host, "db.internal");
+        assert_eq!(loaded[0].password, "s3cret");
+        assert_eq!(loaded[1].host, "${DBW_TEST_TEMPLATE_UNSET}");
+        assert!(load_profiles_from_properties(&path, MissingEnvVar::Error)
+            .unwrap_err()
+            .contains("DBW_TEST_TEMPLATE_UNSET"));
+
+        // 改名后模板仍跟随连接；被改过的字段按新值保存
+        loaded[0].name = Some("production".to_string());
+        loaded[0].host = "db2.internal".to_string();
+        save_profiles_to_properties(&path, &loaded).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("conn.0.pwd=${DBW_TEST_TEMPLATE_PWD}"));
+        assert!(content.contains("conn.0.host=db2.internal"));
+        assert!(content.contains("conn.1.host=${DBW_TEST_TEMPLATE_UNSET}"));
+        assert!(!content.contains("s3cret"));
+
+        let reloaded = load_profiles_from_properties(&path, MissingEnvVar::Keep).unwrap();
+        assert_eq!(reloaded[0].name.as_deref(), Some("production"));
+        assert_eq!(reloaded[0].password, "s3cret");
+        assert_eq!(
+            reloaded[0]
+                .env_templates
+                .get("password")
+                .map(String::as_str),
+            Some("${DBW_TEST_TEMPLATE_PWD}")
+        );
+        fs::remove_file(&path).unwrap();
+    }
+}
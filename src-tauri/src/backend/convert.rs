@@ -0,0 +1,67 @@
+use crate::backend::export::{self, ExportFormat, ExportResult, QueryExportOptions};
+use crate::backend::import::{self, ImportFormat};
+use std::path::Path;
+
+/// 在内存中完成文件格式转换，不经过数据库
+pub fn file_to_file(
+    input_path: &Path,
+    input_format: ImportFormat,
+    output_path: &Path,
+    output_format: ExportFormat,
+) -> Result<ExportResult, String> {
+    let (headers, rows) = import::read_tabular_file(input_path, input_format)?;
+    if headers.is_empty() {
+        return Err("Input file has no columns".to_string());
+    }
+
+    let table_name = input_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string());
+    let options = QueryExportOptions {
+        table_name,
+        ..Default::default()
+    };
+    let rows: Vec<Vec<Option<String>>> = rows
+        .into_iter()
+        .map(|row| row.into_iter().map(Some).collect())
+        .collect();
+    export::export_query_result(output_path, &headers, &rows, output_format, &options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::test_support::temp_path;
+
+    fn convert_csv(name: &str, output_format: ExportFormat) -> String {
+        let input = temp_path(&format!("{name}.csv"));
+        std::fs::write(&input, "id,name\n1,Alice\n2,\"Bob, Jr.\"\n").unwrap();
+        let output = temp_path(&format!("{name}.{}", output_format.file_extension()));
+        let result = file_to_file(&input, ImportFormat::Csv, &output, output_format).unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.rows_exported, 2);
+        let content = std::fs::read_to_string(&output).unwrap();
+        std::fs::remove_file(&input).unwrap();
+        std::fs::remove_file(&output).unwrap();
+        content
+    }
+
+    #[test]
+    fn converts_csv_to_jsonl() {
+        // CSV 没有类型信息，所有值按字符串输出
+        let content = convert_csv("people", ExportFormat::Jsonl);
+        assert_eq!(
+            content,
+            "{\"id\":\"1\",\"name\":\"Alice\"}\n{\"id\":\"2\",\"name\":\"Bob, Jr.\"}\n"
+        );
+    }
+
+    #[test]
+    fn converts_csv_to_markdown() {
+        let content = convert_csv("people", ExportFormat::Markdown);
+        assert_eq!(
+            content,
+            "| id | name |\n| --- | --- |\n| 1 | Alice |\n| 2 | Bob, Jr. |\n"
+        );
+    }
+}
@@ -1,13 +1,36 @@
+use crate::backend::metadata;
 use crate::backend::models::ConnectionProfile;
 use crate::backend::pool;
+use crate::backend::spatial::{self, SpatialFormat};
+use arrow_array::builder::{
+    BinaryBuilder, Date32Builder, Decimal128Builder, Float64Builder, Int64Builder, StringBuilder,
+    TimestampMicrosecondBuilder, UInt64Builder,
+};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{Datelike, Timelike};
+use dashmap::DashMap;
+use flate2::write::GzEncoder;
+use flate2::Compression as GzCompression;
+use mysql::consts::{ColumnFlags, ColumnType};
 use mysql::prelude::*;
-use rust_xlsxwriter::{Format, FormatAlign, Workbook};
-use serde::Serialize;
+use once_cell::sync::Lazy;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use rust_xlsxwriter::{ExcelDateTime, Format, FormatAlign, Workbook, Worksheet, XlsxError};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::fs::File;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
 use std::io::{BufWriter, Write};
-use std::path::Path;
-use std::time::Instant;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 #[derive(Serialize)]
 pub struct ExportResult {
@@ -18,12 +41,19 @@ pub struct ExportResult {
     pub file_path: String,
     #[serde(rename = "durationMs")]
     pub duration_ms: u64,
+    #[serde(rename = "rowsPerSec")]
+    pub rows_per_sec: f64,
+    #[serde(rename = "bytesPerSec")]
+    pub bytes_per_sec: f64,
+    // 输出文件的 SHA-256（十六进制），写入时同步计算
+    pub checksum: Option<String>,
     pub error: Option<String>,
 }
 
 #[derive(Clone, Copy)]
 pub enum ExportFormat {
     Csv,
+    Tsv,
     Txt,
     Json,
     Html,
@@ -31,12 +61,15 @@ pub enum ExportFormat {
     Sql,
     Jsonl,
     Xlsx,
+    Parquet,
+    Markdown,
 }
 
 impl ExportFormat {
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_ascii_lowercase().as_str() {
             "csv" => Some(ExportFormat::Csv),
+            "tsv" => Some(ExportFormat::Tsv),
             "txt" => Some(ExportFormat::Txt),
             "json" => Some(ExportFormat::Json),
             "html" => Some(ExportFormat::Html),
@@ -44,14 +77,16 @@ impl ExportFormat {
             "sql" => Some(ExportFormat::Sql),
             "jsonl" => Some(ExportFormat::Jsonl),
             "xlsx" => Some(ExportFormat::Xlsx),
+            "parquet" => Some(ExportFormat::Parquet),
+            "md" | "markdown" => Some(ExportFormat::Markdown),
             _ => None,
         }
     }
 
-    #[allow(dead_code)]
     pub fn file_extension(&self) -> &'static str {
         match self {
             ExportFormat::Csv => "csv",
+            ExportFormat::Tsv => "tsv",
             ExportFormat::Txt => "txt",
             ExportFormat::Json => "json",
             ExportFormat::Html => "html",
@@ -59,42 +94,790 @@ impl ExportFormat {
             ExportFormat::Sql => "sql",
             ExportFormat::Jsonl => "jsonl",
             ExportFormat::Xlsx => "xlsx",
+            ExportFormat::Parquet => "parquet",
+            ExportFormat::Markdown => "md",
         }
     }
 }
 
+#[derive(Clone, Default, Deserialize)]
+pub struct TableExportOptions {
+    pub columns: Option<Vec<String>>,
+    // 仅对 SQL 格式生效：目标为视图时在数据前写入 CREATE VIEW
+    #[serde(rename = "includeViewDefinition", default)]
+    pub include_view_definition: bool,
+    // 生成的 CREATE VIEW 去掉 DEFINER 子句，便于导入到其他账号/服务器
+    #[serde(rename = "stripViewDefiner", default)]
+    pub strip_view_definer: bool,
+    // JSON/JSONL 默认保留数字和 NULL 类型，开启后所有值按字符串输出（兼容旧格式，如保留 "001"）
+    #[serde(rename = "stringifyValues", alias = "allAsStrings", default)]
+    pub stringify_values: bool,
+    // 空间列（GEOMETRY/POINT 等）按 WKT 或 GeoJSON 导出，未设置时导出原始 WKB
+    #[serde(rename = "spatialFormat", default)]
+    pub spatial_format: Option<SpatialFormat>,
+    // 附带数据字典：XLSX 写入第二个工作表，其他格式写同名 .schema.csv
+    #[serde(rename = "includeSchemaSheet", default)]
+    pub include_schema_sheet: bool,
+    // gzip 压缩输出，文件名自动补 .gz；路径本身以 .gz 结尾时同样压缩
+    #[serde(default)]
+    pub compress: bool,
+    // 追加到已有文件末尾（不写 BOM 与表头），仅支持 CSV/TSV/JSONL/SQL，JSON 按 JSONL 追加
+    #[serde(default)]
+    pub append: bool,
+    // NULL 在 CSV/TXT/HTML/XML/Markdown 中的写法（如 \N、NULL），未设置时为空；
+    // JSON/JSONL 未设置时仍输出 null
+    #[serde(rename = "nullToken", default)]
+    pub null_token: Option<String>,
+    // 按条件/排序/行数导出部分数据，columns 优先于外层 columns
+    #[serde(rename = "querySpec", default)]
+    pub query_spec: Option<ExportQuerySpec>,
+    // 仅对 XLSX 生效：数值/日期单元格类型
+    #[serde(default)]
+    pub xlsx: XlsxTypeOptions,
+    // 仅对 SQL 格式生效：INSERT 之前写入建表语句
+    #[serde(default)]
+    pub sql: SqlExportOptions,
+    // 导出任务 ID，供 export_cancel 中途取消
+    #[serde(rename = "jobId", default)]
+    pub job_id: Option<String>,
+    // 二进制列（BLOB/VARBINARY 等）的文本写法；SQL 格式下非 utf8Lossy 时统一写为 0x 字面量
+    #[serde(rename = "binaryEncoding", default)]
+    pub binary_encoding: BinaryEncoding,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BinaryEncoding {
+    #[default]
+    Utf8Lossy,
+    Base64,
+    Hex,
+}
+
+impl BinaryEncoding {
+    // Hex 带 0x 前缀，与 MySQL 十六进制字面量一致
+    fn encode(self, bytes: &[u8]) -> String {
+        match self {
+            BinaryEncoding::Utf8Lossy => String::from_utf8_lossy(bytes).into_owned(),
+            BinaryEncoding::Base64 => STANDARD.encode(bytes),
+            BinaryEncoding::Hex => {
+                let mut hex = String::with_capacity(2 + bytes.len() * 2);
+                hex.push_str("0x");
+                for byte in bytes {
+                    hex.push_str(&format!("{byte:02X}"));
+                }
+                hex
+            }
+        }
+    }
+}
+
+// SQL 中的二进制字面量；空值写成 X''，单独的 0x 不是合法字面量
+fn hex_literal(bytes: &[u8]) -> String {
+    if bytes.is_empty() {
+        "X''".to_string()
+    } else {
+        BinaryEncoding::Hex.encode(bytes)
+    }
+}
+
+/// 目标为视图时两项均忽略（视图见 includeViewDefinition）
+#[derive(Clone, Default, Deserialize)]
+pub struct SqlExportOptions {
+    #[serde(rename = "addDropTable", default)]
+    pub add_drop_table: bool,
+    #[serde(rename = "includeCreate", default)]
+    pub include_create: bool,
+}
+
+impl TableExportOptions {
+    fn selected_columns(&self) -> Option<&Vec<String>> {
+        self.query_spec
+            .as_ref()
+            .and_then(|spec| spec.columns.as_ref())
+            .or(self.columns.as_ref())
+    }
+}
+
+/// whereClause/orderBy 为原样拼入的 SQL 片段（不含 WHERE/ORDER BY 关键字），
+/// 列名统一加反引号转义
+#[derive(Clone, Default, Deserialize)]
+pub struct ExportQuerySpec {
+    #[serde(rename = "whereClause", default)]
+    pub where_clause: Option<String>,
+    #[serde(rename = "orderBy", default)]
+    pub order_by: Option<String>,
+    #[serde(default)]
+    pub limit: Option<u64>,
+    #[serde(default)]
+    pub columns: Option<Vec<String>>,
+}
+
+impl ExportQuerySpec {
+    // 片段只能是单条 SELECT 的一部分；注释会吞掉后面拼接的 ORDER BY/LIMIT
+    fn validate(&self) -> Result<(), String> {
+        for (name, clause) in [
+            ("WHERE", self.where_clause.as_deref()),
+            ("ORDER BY", self.order_by.as_deref()),
+        ] {
+            match clause.and_then(find_unquoted_terminator) {
+                Some(";") => return Err(format!("{name} clause must not contain ';'")),
+                Some(_) => return Err(format!("{name} clause must not contain comments")),
+                None => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+// 跳过字符串和反引号标识符，返回第一个分号或注释起始符
+fn find_unquoted_terminator(clause: &str) -> Option<&'static str> {
+    let mut chars = clause.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\'' | '"' | '`' => {
+                while let Some(inner) = chars.next() {
+                    if inner == '\\' && ch != '`' {
+                        chars.next();
+                    } else if inner == ch {
+                        // 连写两个引号是转义
+                        if chars.peek() != Some(&ch) {
+                            break;
+                        }
+                        chars.next();
+                    }
+                }
+            }
+            ';' => return Some(";"),
+            '#' => return Some("#"),
+            '-' if chars.peek() == Some(&'-') => return Some("--"),
+            '/' if chars.peek() == Some(&'*') => return Some("/*"),
+            _ => {}
+        }
+    }
+    None
+}
+
+#[derive(Serialize)]
+pub struct TabExportResult {
+    #[serde(rename = "schemaFile")]
+    pub schema_file: String,
+    #[serde(rename = "dataFile")]
+    pub data_file: String,
+    #[serde(rename = "rowsExported")]
+    pub rows_exported: u64,
+    #[serde(rename = "durationMs")]
+    pub duration_ms: u64,
+}
+
+/// CSV 方言；delimiter/quote 由单个 ASCII 字符的字符串反序列化（如 ";"），未给出的字段取默认值
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct CsvOptions {
+    #[serde(deserialize_with = "deserialize_csv_char")]
+    pub delimiter: u8,
+    #[serde(deserialize_with = "deserialize_csv_char")]
+    pub quote: u8,
+    #[serde(rename = "writeBom")]
+    pub write_bom: bool,
+    #[serde(rename = "lineTerminator")]
+    pub line_terminator: String,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: b',',
+            quote: b'"',
+            write_bom: true,
+            line_terminator: "\n".to_string(),
+        }
+    }
+}
+
+fn deserialize_csv_char<'de, D>(deserializer: D) -> Result<u8, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) if ch.is_ascii() => Ok(ch as u8),
+        _ => Err(serde::de::Error::custom(format!(
+            "CSV delimiter and quote must be a single ASCII character, got {value:?}"
+        ))),
+    }
+}
+
+impl CsvOptions {
+    fn tsv() -> Self {
+        CsvOptions {
+            delimiter: b'\t',
+            ..Default::default()
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.delimiter == self.quote {
+            return Err("CSV delimiter and quote character must differ".to_string());
+        }
+        if [self.delimiter, self.quote]
+            .iter()
+            .any(|b| matches!(b, b'\n' | b'\r') || !b.is_ascii())
+        {
+            return Err("CSV delimiter and quote must be ASCII and not a line break".to_string());
+        }
+        if !matches!(self.line_terminator.as_str(), "\n" | "\r\n" | "\r") {
+            return Err("CSV line terminator must be \\n, \\r\\n or \\r".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Default, Deserialize)]
+pub struct QueryExportOptions {
+    // HTML 标题与 SQL INSERT 的目标表名
+    #[serde(rename = "tableName", default)]
+    pub table_name: Option<String>,
+    // 仅对 HTML 生效：内嵌原生 JS，提供列排序与搜索框（不依赖 CDN）
+    #[serde(default)]
+    pub interactive: bool,
+    // 同 TableExportOptions::compress
+    #[serde(default)]
+    pub compress: bool,
+    // 同 TableExportOptions::append
+    #[serde(default)]
+    pub append: bool,
+    // 仅对 CSV 生效，未设置时为逗号分隔、双引号、带 BOM
+    #[serde(default)]
+    pub csv: Option<CsvOptions>,
+    // 同 TableExportOptions::null_token；前端传入的 null 单元格按此输出
+    #[serde(rename = "nullToken", default)]
+    pub null_token: Option<String>,
+    // 同 TableExportOptions::xlsx；textColumns 按表头名匹配
+    #[serde(default)]
+    pub xlsx: XlsxTypeOptions,
+    // 同 TableExportOptions::job_id
+    #[serde(rename = "jobId", default)]
+    pub job_id: Option<String>,
+    // 同 TableExportOptions::binary_encoding，仅对 export_query_to_file 生效
+    #[serde(rename = "binaryEncoding", default)]
+    pub binary_encoding: BinaryEncoding,
+    // 同 TableExportOptions::spatial_format，仅对 export_query_to_file 生效；
+    // 任意查询无法改写 SELECT，在客户端解析 WKB
+    #[serde(rename = "spatialFormat", default)]
+    pub spatial_format: Option<SpatialFormat>,
+}
+
+/// 数值列写为数字，CHAR/VARCHAR 与 textColumns 中的列始终写为文本
+#[derive(Clone, Default, Deserialize)]
+pub struct XlsxTypeOptions {
+    // BIGINT 列以及带前导零/超过 15 位的数字按文本写入，避免丢失前导零或精度
+    #[serde(rename = "idsAsText", default)]
+    pub ids_as_text: bool,
+    // DATE/DATETIME/TIMESTAMP 写为 Excel 日期并设置数字格式，否则写为字符串
+    #[serde(rename = "nativeDates", default)]
+    pub native_dates: bool,
+    #[serde(rename = "textColumns", default)]
+    pub text_columns: Vec<String>,
+}
+
+#[derive(Clone, Copy)]
+pub enum ChartAggregation {
+    Sum,
+    Avg,
+    Count,
+    Min,
+    Max,
+}
+
+impl ChartAggregation {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "sum" => Some(ChartAggregation::Sum),
+            "avg" | "average" => Some(ChartAggregation::Avg),
+            "count" => Some(ChartAggregation::Count),
+            "min" => Some(ChartAggregation::Min),
+            "max" => Some(ChartAggregation::Max),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct ReplaceRule {
+    // 为空时作用于所有列
+    pub column: Option<String>,
+    pub find: String,
+    pub replace: String,
+    #[serde(default)]
+    pub regex: bool,
+}
+
+#[derive(Serialize)]
+pub struct ChartSeries {
+    pub name: String,
+    pub data: Vec<f64>,
+}
+
+#[derive(Serialize)]
+pub struct ChartData {
+    pub labels: Vec<String>,
+    pub series: Vec<ChartSeries>,
+}
+
+/// 导出结束后由 ChecksumWriter 写入十六进制 SHA-256；gzip 为 true 时输出经 gzip 压缩。
+/// memory 非空时输出写入内存（不计算摘要、忽略文件路径）；
+/// append 为 true 时追加到已有内容之后，摘要只覆盖本次写入的字节
+#[derive(Clone, Default)]
+struct ChecksumSlot {
+    digest: Arc<Mutex<Option<String>>>,
+    gzip: bool,
+    append: bool,
+    memory: Option<Arc<Mutex<Vec<u8>>>>,
+}
+
+impl ChecksumSlot {
+    fn new(gzip: bool) -> Self {
+        Self {
+            gzip,
+            ..Default::default()
+        }
+    }
+
+    // 目标文件已有内容时才跳过 BOM/表头，首次追加仍写完整文件头
+    fn for_output(file_path: &Path, gzip: bool, append: bool) -> Self {
+        Self {
+            append: append && file_size(file_path) > 0,
+            ..Self::new(gzip)
+        }
+    }
+
+    fn in_memory() -> Self {
+        Self {
+            memory: Some(Arc::default()),
+            ..Default::default()
+        }
+    }
+
+    fn take(&self) -> Option<String> {
+        self.digest.lock().ok().and_then(|mut slot| slot.take())
+    }
+
+    fn take_buffer(&self) -> Vec<u8> {
+        self.memory
+            .as_ref()
+            .and_then(|buffer| {
+                buffer
+                    .lock()
+                    .ok()
+                    .map(|mut bytes| std::mem::take(&mut *bytes))
+            })
+            .unwrap_or_default()
+    }
+}
+
+// 包在输出文件外层，写入的同时计算摘要，避免导出完成后重新读取文件
+struct HashedFile {
+    file: File,
+    hasher: Sha256,
+    slot: ChecksumSlot,
+}
+
+impl Write for HashedFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.file.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
+
+// 外层 BufWriter/GzEncoder 先于本结构体落盘，drop 时摘要已覆盖全部字节
+impl Drop for HashedFile {
+    fn drop(&mut self) {
+        let digest = self.hasher.finalize_reset();
+        let hex: String = digest.iter().map(|b| format!("{b:02x}")).collect();
+        if let Ok(mut slot) = self.slot.digest.lock() {
+            *slot = Some(hex);
+        }
+    }
+}
+
+// 各格式的写出目标；压缩时摘要按落盘的 .gz 字节计算。
+// BOM 等文件头经由本写入器写出，因此位于压缩流内部
+enum ChecksumWriter {
+    Plain(HashedFile),
+    Gzip(GzEncoder<HashedFile>),
+    Memory(Arc<Mutex<Vec<u8>>>),
+}
+
+impl ChecksumWriter {
+    fn create(file_path: &Path, slot: &ChecksumSlot) -> Result<Self, String> {
+        if let Some(buffer) = &slot.memory {
+            return Ok(ChecksumWriter::Memory(buffer.clone()));
+        }
+        let file = if slot.append {
+            OpenOptions::new().append(true).open(file_path)
+        } else {
+            File::create(file_path)
+        }
+        .map_err(|e| format!("Failed to create file: {e}"))?;
+        let file = HashedFile {
+            file,
+            hasher: Sha256::new(),
+            slot: slot.clone(),
+        };
+        Ok(if slot.gzip {
+            ChecksumWriter::Gzip(GzEncoder::new(file, GzCompression::default()))
+        } else {
+            ChecksumWriter::Plain(file)
+        })
+    }
+
+    // 显式写完 gzip 尾部，压缩失败时返回错误；GzEncoder 在 drop 中会忽略该错误，
+    // 摘要也会缺少尾部字节
+    fn finish(self) -> Result<Option<HashedFile>, String> {
+        match self {
+            ChecksumWriter::Plain(mut file) => {
+                file.flush()
+                    .map_err(|e| format!("Failed to flush file: {e}"))?;
+                Ok(Some(file))
+            }
+            ChecksumWriter::Gzip(encoder) => encoder
+                .finish()
+                .map(Some)
+                .map_err(|e| format!("Failed to finish gzip stream: {e}")),
+            ChecksumWriter::Memory(_) => Ok(None),
+        }
+    }
+}
+
+// 刷出缓冲并结束压缩流；返回后 HashedFile 已 drop，摘要已写入 ChecksumSlot
+fn finish_export(writer: BufWriter<ChecksumWriter>) -> Result<(), String> {
+    writer
+        .into_inner()
+        .map_err(|e| format!("Failed to flush file: {}", e.error()))?
+        .finish()?;
+    Ok(())
+}
+
+impl Write for ChecksumWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            ChecksumWriter::Plain(file) => file.write(buf),
+            ChecksumWriter::Gzip(encoder) => encoder.write(buf),
+            ChecksumWriter::Memory(buffer) => {
+                buffer
+                    .lock()
+                    .map_err(|_| std::io::Error::other("Export buffer lock failed"))?
+                    .extend_from_slice(buf);
+                Ok(buf.len())
+            }
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            ChecksumWriter::Plain(file) => file.flush(),
+            ChecksumWriter::Gzip(encoder) => encoder.flush(),
+            ChecksumWriter::Memory(_) => Ok(()),
+        }
+    }
+}
+
+pub const EXPORT_CANCELLED: &str = "cancelled";
+// 每导出这么多行检查一次取消标记
+const CANCEL_CHECK_ROWS: u64 = 1000;
+// 每导出这么多行回调一次进度
+const PROGRESS_REPORT_ROWS: u64 = 10_000;
+
+/// 导出进度，done 为 true 时为最后一次回调（成功、失败或取消）
+#[derive(Clone, Serialize)]
+pub struct ExportProgress {
+    #[serde(rename = "rowsExported")]
+    pub rows_exported: u64,
+    pub done: bool,
+}
+
+pub type ExportProgressFn = Arc<dyn Fn(&ExportProgress) + Send + Sync>;
+
+struct ExportJobState {
+    cancelled: AtomicBool,
+    // 检测到取消时已写出的行数
+    cancelled_rows: AtomicU64,
+    progress: Option<ExportProgressFn>,
+}
+
+static EXPORT_JOBS: Lazy<DashMap<String, Arc<ExportJobState>>> = Lazy::new(DashMap::new);
+
+// 导出期间在 EXPORT_JOBS 中登记取消标记与进度回调，结束（含出错）时注销
+struct ExportJob(Option<String>);
+
+impl ExportJob {
+    // 保留 watch_export 预先登记的进度回调
+    fn register(job_id: Option<&str>) -> Self {
+        if let Some(id) = job_id {
+            EXPORT_JOBS.entry(id.to_string()).or_insert_with(|| {
+                Arc::new(ExportJobState {
+                    cancelled: AtomicBool::new(false),
+                    cancelled_rows: AtomicU64::new(0),
+                    progress: None,
+                })
+            });
+        }
+        ExportJob(job_id.map(str::to_string))
+    }
+
+    // 成功时为导出行数；取消时为取消前已写出的行数，其余错误为 0
+    fn rows_exported(&self, result: &Result<u64, String>) -> u64 {
+        match result {
+            Ok(rows) => *rows,
+            Err(_) => self
+                .0
+                .as_deref()
+                .and_then(job_state)
+                .map(|state| state.cancelled_rows.load(Ordering::Relaxed))
+                .unwrap_or(0),
+        }
+    }
+
+    fn finish(&self, rows_exported: u64) {
+        if let Some(progress) = self.0.as_deref().and_then(job_progress) {
+            progress(&ExportProgress {
+                rows_exported,
+                done: true,
+            });
+        }
+    }
+}
+
+impl Drop for ExportJob {
+    fn drop(&mut self) {
+        if let Some(id) = &self.0 {
+            EXPORT_JOBS.remove(id);
+        }
+    }
+}
+
+fn job_state(job_id: &str) -> Option<Arc<ExportJobState>> {
+    EXPORT_JOBS.get(job_id).map(|state| state.clone())
+}
+
+fn job_progress(job_id: &str) -> Option<ExportProgressFn> {
+    job_state(job_id).and_then(|state| state.progress.clone())
+}
+
+/// 在启动 job_id 对应的导出前调用：导出每 10000 行及结束时回调 progress，
+/// 回调随导出结束注销
+pub fn watch_export(job_id: &str, progress: ExportProgressFn) {
+    let state = ExportJobState {
+        cancelled: AtomicBool::new(false),
+        cancelled_rows: AtomicU64::new(0),
+        progress: Some(progress),
+    };
+    EXPORT_JOBS.insert(job_id.to_string(), Arc::new(state));
+}
+
+/// 标记导出任务取消；任务不存在（未登记或已结束）时返回 false
+pub fn cancel_export(job_id: &str) -> bool {
+    match job_state(job_id) {
+        Some(state) => {
+            state.cancelled.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+// 每行写入前调用：按间隔检查取消标记并回调进度
+fn track_export_row(job_id: Option<&str>, rows_exported: u64) -> Result<(), String> {
+    if !rows_exported.is_multiple_of(CANCEL_CHECK_ROWS) {
+        return Ok(());
+    }
+    let Some(state) = job_id.and_then(job_state) else {
+        return Ok(());
+    };
+    if state.cancelled.load(Ordering::Relaxed) {
+        state.cancelled_rows.store(rows_exported, Ordering::Relaxed);
+        return Err(EXPORT_CANCELLED.to_string());
+    }
+    if let Some(progress) = state.progress.as_ref() {
+        if rows_exported > 0 && rows_exported.is_multiple_of(PROGRESS_REPORT_ROWS) {
+            progress(&ExportProgress {
+                rows_exported,
+                done: false,
+            });
+        }
+    }
+    Ok(())
+}
+
+// 追加模式只支持逐行写出的格式；JSON 数组无法追加，改按 JSONL 写出
+fn append_format(format: ExportFormat, append: bool) -> Result<ExportFormat, String> {
+    if !append {
+        return Ok(format);
+    }
+    match format {
+        ExportFormat::Json => Ok(ExportFormat::Jsonl),
+        ExportFormat::Csv | ExportFormat::Tsv | ExportFormat::Jsonl | ExportFormat::Sql => {
+            Ok(format)
+        }
+        other => Err(format!(
+            "Append mode is not supported for {} export",
+            other.file_extension()
+        )),
+    }
+}
+
+// 指定 compress 或路径以 .gz 结尾时压缩输出；前者会补上 .gz 后缀
+fn resolve_output_path(file_path: &Path, compress: bool) -> (PathBuf, bool) {
+    let has_gz_suffix = file_path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gz"));
+    if compress && !has_gz_suffix {
+        let mut path = file_path.as_os_str().to_owned();
+        path.push(".gz");
+        return (PathBuf::from(path), true);
+    }
+    (file_path.to_path_buf(), has_gz_suffix)
+}
+
 pub fn export_table(
     profile: &ConnectionProfile,
     schema: &str,
     table: &str,
     file_path: &Path,
     format: ExportFormat,
+) -> ExportResult {
+    export_table_with_options(
+        profile,
+        schema,
+        table,
+        file_path,
+        format,
+        &TableExportOptions::default(),
+    )
+}
+
+pub fn export_table_with_options(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    file_path: &Path,
+    format: ExportFormat,
+    options: &TableExportOptions,
+) -> ExportResult {
+    export_table_with_csv_options(
+        profile,
+        schema,
+        table,
+        file_path,
+        format,
+        options,
+        &CsvOptions::default(),
+    )
+}
+
+/// 与 export_table_with_options 相同，csv 指定 CSV 格式的分隔符、引号、BOM 与换行
+pub fn export_table_with_csv_options(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    file_path: &Path,
+    format: ExportFormat,
+    options: &TableExportOptions,
+    csv: &CsvOptions,
 ) -> ExportResult {
     let start = Instant::now();
-    let result = match format {
-        ExportFormat::Csv => do_export_csv(profile, schema, table, file_path),
-        ExportFormat::Txt => do_export_txt(profile, schema, table, file_path),
-        ExportFormat::Json => do_export_json(profile, schema, table, file_path),
-        ExportFormat::Html => do_export_html(profile, schema, table, file_path),
-        ExportFormat::Xml => do_export_xml(profile, schema, table, file_path),
-        ExportFormat::Sql => do_export_sql(profile, schema, table, file_path),
-        ExportFormat::Jsonl => do_export_jsonl(profile, schema, table, file_path),
-        ExportFormat::Xlsx => do_export_xlsx(profile, schema, table, file_path),
+    let job = ExportJob::register(options.job_id.as_deref());
+    let (output_path, gzip) = resolve_output_path(file_path, options.compress);
+    let file_path = output_path.as_path();
+    let checksum = ChecksumSlot::for_output(file_path, gzip, options.append);
+    let (format, format_check) = match append_format(format, options.append) {
+        Ok(format) => (format, Ok(())),
+        Err(err) => (format, Err(err)),
     };
+    let result = format_check
+        .and_then(|_| csv.validate())
+        .and_then(|_| validate_export_columns(profile, schema, table, options))
+        .and_then(|_| match format {
+            ExportFormat::Csv => {
+                do_export_csv(profile, schema, table, file_path, options, csv, &checksum)
+            }
+            ExportFormat::Tsv => do_export_csv(
+                profile,
+                schema,
+                table,
+                file_path,
+                options,
+                &CsvOptions::tsv(),
+                &checksum,
+            ),
+            ExportFormat::Txt => {
+                do_export_txt(profile, schema, table, file_path, options, &checksum)
+            }
+            ExportFormat::Json => {
+                do_export_json(profile, schema, table, file_path, options, &checksum)
+            }
+            ExportFormat::Html => {
+                do_export_html(profile, schema, table, file_path, options, &checksum)
+            }
+            ExportFormat::Xml => {
+                do_export_xml(profile, schema, table, file_path, options, &checksum)
+            }
+            ExportFormat::Sql => {
+                do_export_sql(profile, schema, table, file_path, options, &checksum)
+            }
+            ExportFormat::Jsonl => {
+                do_export_jsonl(profile, schema, table, file_path, options, &checksum)
+            }
+            ExportFormat::Xlsx => {
+                do_export_xlsx(profile, schema, table, file_path, options, &checksum)
+            }
+            ExportFormat::Parquet => {
+                do_export_parquet(profile, schema, table, file_path, options, &checksum)
+            }
+            ExportFormat::Markdown => {
+                do_export_markdown(profile, schema, table, file_path, options, &checksum)
+            }
+        });
+    let result = result.and_then(|rows| {
+        if options.include_schema_sheet && !matches!(format, ExportFormat::Xlsx) {
+            write_schema_sidecar(profile, schema, table, file_path, options)?;
+        }
+        Ok(rows)
+    });
+    let rows_exported = job.rows_exported(&result);
+    job.finish(rows_exported);
 
+    let elapsed = start.elapsed();
     match result {
-        Ok(rows) => ExportResult {
-            success: true,
-            rows_exported: rows,
-            file_path: file_path.to_string_lossy().to_string(),
-            duration_ms: start.elapsed().as_millis() as u64,
-            error: None,
-        },
+        Ok(rows) => {
+            let (rows_per_sec, bytes_per_sec) =
+                compute_throughput(rows, file_size(file_path), elapsed);
+            ExportResult {
+                success: true,
+                rows_exported: rows,
+                file_path: file_path.to_string_lossy().to_string(),
+                duration_ms: elapsed.as_millis() as u64,
+                rows_per_sec,
+                bytes_per_sec,
+                checksum: checksum.take(),
+                error: None,
+            }
+        }
         Err(err) => ExportResult {
             success: false,
-            rows_exported: 0,
+            rows_exported,
             file_path: file_path.to_string_lossy().to_string(),
-            duration_ms: start.elapsed().as_millis() as u64,
+            duration_ms: elapsed.as_millis() as u64,
+            rows_per_sec: 0.0,
+            bytes_per_sec: 0.0,
+            checksum: None,
             error: Some(err),
         },
     }
@@ -119,65 +902,532 @@ pub fn export_table_to_jsonl(
     export_table(profile, schema, table, file_path, ExportFormat::Jsonl)
 }
 
+/// 仿 `mysqldump --tab`：在 out_dir 下写出 `<table>.sql`（建表语句）和 `<table>.txt`（数据）。
+/// 数据文件为 LOAD DATA INFILE 默认格式：制表符分隔、无表头、NULL 写作 `\N`，
+/// 反斜杠、制表符与换行按 mysqldump 的方式转义
+pub fn export_table_tab(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    out_dir: &Path,
+) -> Result<TabExportResult, String> {
+    let start = Instant::now();
+    std::fs::create_dir_all(out_dir).map_err(|e| format!("Failed to create directory: {e}"))?;
+
+    let ddl = metadata::load_ddl(profile, schema, table)?;
+    if ddl.is_empty() {
+        return Err(format!("Table `{schema}`.`{table}` not found"));
+    }
+    let stem = file_stem_for(table);
+    let schema_file = out_dir.join(format!("{stem}.sql"));
+    let script = format!(
+        "DROP TABLE IF EXISTS `{0}`;\n{1};\n",
+        escape_identifier(table),
+        ddl
+    );
+    std::fs::write(&schema_file, script).map_err(|e| format!("Failed to write file: {e}"))?;
+
+    let data_file = out_dir.join(format!("{stem}.txt"));
+    let sql = format!(
+        "SELECT * FROM `{}`.`{}`",
+        escape_identifier(schema),
+        escape_identifier(table)
+    );
+    let rows_exported = pool::with_temp_connection(profile, |conn| {
+        let file = File::create(&data_file).map_err(|e| format!("Failed to create file: {e}"))?;
+        let mut writer = BufWriter::with_capacity(64 * 1024, file);
+        let mut result_set = conn
+            .query_iter(&sql)
+            .map_err(|e| format!("Query failed: {e}"))?;
+
+        let mut rows_exported: u64 = 0;
+        let mut line: Vec<u8> = Vec::new();
+        for row_result in result_set.by_ref() {
+            let row: mysql::Row = row_result.map_err(|e| format!("Row read error: {e}"))?;
+            line.clear();
+            for idx in 0..row.len() {
+                if idx > 0 {
+                    line.push(b'\t');
+                }
+                write_tab_field(&mut line, row.as_ref(idx).unwrap_or(&mysql::Value::NULL));
+            }
+            line.push(b'\n');
+            writer
+                .write_all(&line)
+                .map_err(|e| format!("File write error: {e}"))?;
+            rows_exported += 1;
+        }
+
+        writer
+            .flush()
+            .map_err(|e| format!("Failed to flush file: {e}"))?;
+        Ok(rows_exported)
+    })?;
+
+    Ok(TabExportResult {
+        schema_file: schema_file.to_string_lossy().to_string(),
+        data_file: data_file.to_string_lossy().to_string(),
+        rows_exported,
+        duration_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+// 表名可以包含 / \ 等字符，直接拼进路径会写到 out_dir 之外；替换为下划线
+fn file_stem_for(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    if stem.trim_matches('.').is_empty() {
+        "_".repeat(stem.len().max(1))
+    } else {
+        stem
+    }
+}
+
 pub fn export_query_result(
     file_path: &Path,
     headers: &[String],
-    rows: &[Vec<String>],
+    rows: &[Vec<Option<String>>],
     format: ExportFormat,
-    table_name: Option<&str>,
+    options: &QueryExportOptions,
 ) -> Result<ExportResult, String> {
     let start = Instant::now();
-    let result = match format {
-        ExportFormat::Csv => do_export_query_csv(file_path, headers, rows),
-        ExportFormat::Txt => do_export_query_txt(file_path, headers, rows),
-        ExportFormat::Json => do_export_query_json(file_path, headers, rows),
-        ExportFormat::Html => do_export_query_html(file_path, headers, rows, table_name),
-        ExportFormat::Xml => do_export_query_xml(file_path, headers, rows),
-        ExportFormat::Sql => do_export_query_sql(file_path, headers, rows, table_name),
-        ExportFormat::Jsonl => do_export_query_jsonl(file_path, headers, rows),
-        ExportFormat::Xlsx => do_export_query_xlsx(file_path, headers, rows, table_name),
-    };
+    let job = ExportJob::register(options.job_id.as_deref());
+    let (output_path, gzip) = resolve_output_path(file_path, options.compress);
+    let file_path = output_path.as_path();
+    let checksum = ChecksumSlot::for_output(file_path, gzip, options.append);
+    let rows = rows.iter().map(Ok);
+    let result = append_format(format, options.append).and_then(|format| {
+        write_query_export(file_path, headers, rows, format, options, &[], &checksum)
+    });
+    job.finish(job.rows_exported(&result));
+    Ok(build_query_export_result(
+        file_path, start, &checksum, result, &job,
+    ))
+}
 
+/// 不落盘，直接返回导出内容（如复制到剪贴板）；XLSX/Parquet 为二进制，返回 base64，
+/// 文本格式去掉 CSV 的 BOM
+pub fn export_query_result_to_string(
+    headers: &[String],
+    rows: &[Vec<Option<String>>],
+    format: ExportFormat,
+    options: &QueryExportOptions,
+) -> Result<String, String> {
+    let checksum = ChecksumSlot::in_memory();
+    let rows = rows.iter().map(Ok);
+    write_query_export(
+        Path::new(""),
+        headers,
+        rows,
+        format,
+        options,
+        &[],
+        &checksum,
+    )?;
+    let bytes = checksum.take_buffer();
+    match format {
+        ExportFormat::Xlsx | ExportFormat::Parquet => Ok(STANDARD.encode(bytes)),
+        _ => {
+            let text = String::from_utf8(bytes)
+                .map_err(|e| format!("Export output is not valid UTF-8: {e}"))?;
+            Ok(match text.strip_prefix('\u{feff}') {
+                Some(stripped) => stripped.to_string(),
+                None => text,
+            })
+        }
+    }
+}
+
+/// 在池中已借出的连接上执行查询，逐行写入导出文件，结果集不经过前端中转
+pub fn export_query_to_file(
+    pool_id: u64,
+    conn_id: u64,
+    sql: &str,
+    file_path: &Path,
+    format: ExportFormat,
+    options: &QueryExportOptions,
+) -> ExportResult {
+    let start = Instant::now();
+    let job = ExportJob::register(options.job_id.as_deref());
+    let (output_path, gzip) = resolve_output_path(file_path, options.compress);
+    let file_path = output_path.as_path();
+    let checksum = ChecksumSlot::for_output(file_path, gzip, options.append);
+    let result = append_format(format, options.append).and_then(|format| {
+        export_query_rows(pool_id, conn_id, sql, file_path, format, options, &checksum)
+    });
+    job.finish(job.rows_exported(&result));
+    build_query_export_result(file_path, start, &checksum, result, &job)
+}
+
+fn export_query_rows(
+    pool_id: u64,
+    conn_id: u64,
+    sql: &str,
+    file_path: &Path,
+    format: ExportFormat,
+    options: &QueryExportOptions,
+    checksum: &ChecksumSlot,
+) -> Result<u64, String> {
+    pool::with_connection(pool_id, conn_id, |conn| {
+        let mut result_set = conn
+            .query_iter(sql)
+            .map_err(|e| format!("Query failed: {e}"))?;
+        let headers: Vec<String> = result_set
+            .columns()
+            .as_ref()
+            .iter()
+            .map(|c| c.name_str().to_string())
+            .collect();
+        if headers.is_empty() {
+            return Err("Statement did not return a result set".to_string());
+        }
+        // 与表导出一致：SQL 格式下二进制列写为不加引号的 0x 字面量
+        let literal_columns: Vec<usize> = if matches!(format, ExportFormat::Sql)
+            && options.binary_encoding != BinaryEncoding::Utf8Lossy
+        {
+            result_set
+                .columns()
+                .as_ref()
+                .iter()
+                .enumerate()
+                .filter(|(_, column)| is_binary_column(column))
+                .map(|(idx, _)| idx)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let spatial_columns: Vec<usize> = match options.spatial_format {
+            Some(_) => result_set
+                .columns()
+                .as_ref()
+                .iter()
+                .enumerate()
+                .filter(|(_, column)| column.column_type() == ColumnType::MYSQL_TYPE_GEOMETRY)
+                .map(|(idx, _)| idx)
+                .collect(),
+            None => Vec::new(),
+        };
+        let rows = result_set.by_ref().map(|row_result| {
+            let row: mysql::Row = row_result.map_err(|e| format!("Row read error: {e}"))?;
+            let row = if literal_columns.is_empty() {
+                encode_binary_cells(row, options.binary_encoding)
+            } else {
+                row
+            };
+            Ok(row
+                .unwrap()
+                .iter()
+                .enumerate()
+                .map(|(idx, value)| match value {
+                    mysql::Value::NULL => None,
+                    mysql::Value::Bytes(bytes) if literal_columns.contains(&idx) => {
+                        Some(hex_literal(bytes))
+                    }
+                    mysql::Value::Bytes(bytes) if spatial_columns.contains(&idx) => options
+                        .spatial_format
+                        .and_then(|spatial| spatial::render_geometry(bytes, spatial))
+                        .or_else(|| Some(value_to_string(value))),
+                    other => Some(value_to_string(other)),
+                })
+                .collect::<Vec<_>>())
+        });
+        write_query_export(
+            file_path,
+            &headers,
+            rows,
+            format,
+            options,
+            &literal_columns,
+            checksum,
+        )
+    })
+}
+
+// NULL 单元格为 None：JSON/JSONL 写为 null（设置了 null_token 时写该标记），SQL 写为 NULL，
+// Parquet 写为空值，其余格式先按 null_token 填成文本。literal_columns 中的单元格已是 SQL 字面量，
+// SQL 格式原样写出
+fn write_query_export<R: AsRef<[Option<String>]>>(
+    file_path: &Path,
+    headers: &[String],
+    rows: impl IntoIterator<Item = Result<R, String>>,
+    format: ExportFormat,
+    options: &QueryExportOptions,
+    literal_columns: &[usize],
+    checksum: &ChecksumSlot,
+) -> Result<u64, String> {
+    let table_name = options.table_name.as_deref();
+    let job_id = options.job_id.as_deref();
+    let null_token = options.null_token.as_deref();
+    let rows = rows.into_iter().enumerate().map(move |(idx, row)| {
+        track_export_row(job_id, idx as u64)?;
+        row
+    });
+    let fill =
+        |row: Result<R, String>| row.map(|cells| fill_null_cells(cells.as_ref(), null_token));
+    match format {
+        ExportFormat::Csv => {
+            let csv = options.csv.clone().unwrap_or_default();
+            csv.validate()?;
+            do_export_query_csv(file_path, headers, rows.map(fill), &csv, checksum)
+        }
+        ExportFormat::Tsv => do_export_query_csv(
+            file_path,
+            headers,
+            rows.map(fill),
+            &CsvOptions::tsv(),
+            checksum,
+        ),
+        ExportFormat::Txt => do_export_query_txt(file_path, headers, rows.map(fill), checksum),
+        ExportFormat::Json => do_export_query_json(file_path, headers, rows, null_token, checksum),
+        ExportFormat::Html => do_export_query_html(
+            file_path,
+            headers,
+            rows.map(fill),
+            table_name,
+            options.interactive,
+            checksum,
+        ),
+        ExportFormat::Xml => do_export_query_xml(file_path, headers, rows.map(fill), checksum),
+        ExportFormat::Sql => do_export_query_sql(
+            file_path,
+            headers,
+            rows,
+            table_name,
+            literal_columns,
+            checksum,
+        ),
+        ExportFormat::Jsonl => {
+            do_export_query_jsonl(file_path, headers, rows, null_token, checksum)
+        }
+        ExportFormat::Xlsx => do_export_query_xlsx(
+            file_path,
+            headers,
+            rows.map(fill),
+            table_name,
+            &options.xlsx,
+            XLSX_ROWS_PER_SHEET,
+            checksum,
+        ),
+        ExportFormat::Parquet => do_export_query_parquet(file_path, headers, rows, checksum),
+        ExportFormat::Markdown => {
+            do_export_query_markdown(file_path, headers, rows.map(fill), checksum)
+        }
+    }
+}
+
+fn build_query_export_result(
+    file_path: &Path,
+    start: Instant,
+    checksum: &ChecksumSlot,
+    result: Result<u64, String>,
+    job: &ExportJob,
+) -> ExportResult {
+    let elapsed = start.elapsed();
+    let rows_exported = job.rows_exported(&result);
     match result {
-        Ok(row_count) => Ok(ExportResult {
-            success: true,
-            rows_exported: row_count,
-            file_path: file_path.to_string_lossy().to_string(),
-            duration_ms: start.elapsed().as_millis() as u64,
-            error: None,
-        }),
-        Err(err) => Ok(ExportResult {
+        Ok(row_count) => {
+            let (rows_per_sec, bytes_per_sec) =
+                compute_throughput(row_count, file_size(file_path), elapsed);
+            ExportResult {
+                success: true,
+                rows_exported: row_count,
+                file_path: file_path.to_string_lossy().to_string(),
+                duration_ms: elapsed.as_millis() as u64,
+                rows_per_sec,
+                bytes_per_sec,
+                checksum: checksum.take(),
+                error: None,
+            }
+        }
+        Err(err) => ExportResult {
             success: false,
-            rows_exported: 0,
+            rows_exported,
             file_path: file_path.to_string_lossy().to_string(),
-            duration_ms: start.elapsed().as_millis() as u64,
+            duration_ms: elapsed.as_millis() as u64,
+            rows_per_sec: 0.0,
+            bytes_per_sec: 0.0,
+            checksum: None,
             error: Some(err),
-        }),
+        },
     }
 }
 
+/// 按 x_col 对已取回的行分组，并对每个 y_cols 列做聚合；标签保持各分组首次出现的顺序
+pub fn to_chart_series(
+    headers: &[String],
+    rows: &[Vec<String>],
+    x_col: &str,
+    y_cols: &[String],
+    agg: ChartAggregation,
+) -> Result<ChartData, String> {
+    let find_column = |name: &str| {
+        headers
+            .iter()
+            .position(|h| h == name)
+            .or_else(|| headers.iter().position(|h| h.eq_ignore_ascii_case(name)))
+            .ok_or_else(|| format!("Column not found: {name}"))
+    };
+
+    let x_idx = find_column(x_col)?;
+    if y_cols.is_empty() {
+        return Err("At least one value column is required".to_string());
+    }
+    let y_indexes = y_cols
+        .iter()
+        .map(|c| find_column(c))
+        .collect::<Result<Vec<usize>, String>>()?;
+
+    let mut labels: Vec<String> = Vec::new();
+    let mut label_index: HashMap<String, usize> = HashMap::new();
+    // 每个系列、每个标签：(sum, count, min, max)
+    let mut accumulators: Vec<Vec<(f64, u64, f64, f64)>> = vec![Vec::new(); y_indexes.len()];
+
+    for (row_idx, row) in rows.iter().enumerate() {
+        let label = row.get(x_idx).cloned().unwrap_or_default();
+        let group = match label_index.get(&label) {
+            Some(idx) => *idx,
+            None => {
+                let idx = labels.len();
+                label_index.insert(label.clone(), idx);
+                labels.push(label);
+                for acc in accumulators.iter_mut() {
+                    acc.push((0.0, 0, f64::INFINITY, f64::NEG_INFINITY));
+                }
+                idx
+            }
+        };
+
+        for (series_idx, col_idx) in y_indexes.iter().enumerate() {
+            let raw = row.get(*col_idx).map(|v| v.trim()).unwrap_or("");
+            let entry = &mut accumulators[series_idx][group];
+            if matches!(agg, ChartAggregation::Count) {
+                if !raw.is_empty() {
+                    entry.1 += 1;
+                }
+                continue;
+            }
+            if raw.is_empty() {
+                continue;
+            }
+            let value = raw.parse::<f64>().map_err(|_| {
+                format!(
+                    "Row {} column {} is not numeric: {}",
+                    row_idx + 1,
+                    headers[*col_idx],
+                    raw
+                )
+            })?;
+            entry.0 += value;
+            entry.1 += 1;
+            entry.2 = entry.2.min(value);
+            entry.3 = entry.3.max(value);
+        }
+    }
+
+    let series = y_indexes
+        .iter()
+        .zip(accumulators)
+        .map(|(col_idx, groups)| ChartSeries {
+            name: headers[*col_idx].clone(),
+            data: groups
+                .into_iter()
+                .map(|(sum, count, min, max)| match agg {
+                    ChartAggregation::Sum => sum,
+                    ChartAggregation::Count => count as f64,
+                    ChartAggregation::Avg if count > 0 => sum / count as f64,
+                    ChartAggregation::Min if count > 0 => min,
+                    ChartAggregation::Max if count > 0 => max,
+                    _ => 0.0,
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(ChartData { labels, series })
+}
+
+/// 按顺序对已取回的结果行应用查找替换规则（如重新导出前）。
+/// 正则规则的 `replace` 支持 `$1` 形式的分组引用。
+pub fn transform_cells(
+    headers: &[String],
+    mut rows: Vec<Vec<String>>,
+    rules: &[ReplaceRule],
+) -> Result<Vec<Vec<String>>, String> {
+    for rule in rules {
+        if rule.find.is_empty() {
+            return Err("Find text must not be empty".to_string());
+        }
+        let column_idx = match &rule.column {
+            Some(name) => Some(
+                headers
+                    .iter()
+                    .position(|h| h == name)
+                    .or_else(|| headers.iter().position(|h| h.eq_ignore_ascii_case(name)))
+                    .ok_or_else(|| format!("Column not found: {name}"))?,
+            ),
+            None => None,
+        };
+        let pattern = if rule.regex {
+            Some(
+                regex::Regex::new(&rule.find)
+                    .map_err(|e| format!("Invalid regex {}: {e}", rule.find))?,
+            )
+        } else {
+            None
+        };
+
+        for row in rows.iter_mut() {
+            for (idx, cell) in row.iter_mut().enumerate() {
+                if column_idx.is_some_and(|target| target != idx) {
+                    continue;
+                }
+                let replaced = match &pattern {
+                    Some(pattern) => pattern
+                        .replace_all(cell, rule.replace.as_str())
+                        .into_owned(),
+                    None => cell.replace(&rule.find, &rule.replace),
+                };
+                *cell = replaced;
+            }
+        }
+    }
+    Ok(rows)
+}
+
+// CSV 与 TSV 共用：仅在必要时加引号
 fn do_export_csv(
     profile: &ConnectionProfile,
     schema: &str,
     table: &str,
     file_path: &Path,
+    options: &TableExportOptions,
+    csv: &CsvOptions,
+    checksum: &ChecksumSlot,
 ) -> Result<u64, String> {
     let schema = schema.to_string();
     let table = table.to_string();
+    let null_token = options.null_token.as_deref();
+    let separator = char::from(csv.delimiter).to_string();
+    let terminator = csv.line_terminator.as_bytes();
 
     pool::with_temp_connection(profile, |conn| {
-        let file = File::create(file_path).map_err(|e| format!("Failed to create file: {e}"))?;
-        let mut writer = BufWriter::with_capacity(64 * 1024, file);
-
-        writer
-            .write_all(&[0xEF, 0xBB, 0xBF])
-            .map_err(|e| format!("Failed to write BOM: {e}"))?;
+        let file = ChecksumWriter::create(file_path, checksum)?;
+        let mut writer = BufWriter::with_capacity(64 * 1024, file);
 
-        let sql = format!(
-            "SELECT * FROM `{}`.`{}`",
-            escape_identifier(&schema),
-            escape_identifier(&table)
-        );
+        if csv.write_bom && !checksum.append {
+            writer
+                .write_all(&[0xEF, 0xBB, 0xBF])
+                .map_err(|e| format!("Failed to write BOM: {e}"))?;
+        }
+
+        let table_columns = load_spatial_columns(conn, &schema, &table, options)?;
+        let sql = build_table_select_sql(&schema, &table, options, &table_columns);
 
         let mut result_set = conn
             .query_iter(sql)
@@ -189,6 +1439,8 @@ fn do_export_csv(
 
         for row_result in result_set.by_ref() {
             let row: mysql::Row = row_result.map_err(|e| format!("Row read error: {e}"))?;
+            track_export_row(options.job_id.as_deref(), rows_exported)?;
+            let row = encode_binary_cells(row, options.binary_encoding);
 
             if is_first_row {
                 columns = row
@@ -201,13 +1453,16 @@ fn do_export_csv(
                     return Ok(0);
                 }
 
-                let header: Vec<String> = columns.iter().map(|c| escape_csv_field(c)).collect();
-                writer
-                    .write_all(header.join(",").as_bytes())
-                    .map_err(|e| format!("Failed to write header: {e}"))?;
-                writer
-                    .write_all(b"\n")
-                    .map_err(|e| format!("Failed to write newline: {e}"))?;
+                if !checksum.append {
+                    let header: Vec<String> =
+                        columns.iter().map(|c| escape_csv_field(c, csv)).collect();
+                    writer
+                        .write_all(header.join(&separator).as_bytes())
+                        .map_err(|e| format!("Failed to write header: {e}"))?;
+                    writer
+                        .write_all(terminator)
+                        .map_err(|e| format!("Failed to write newline: {e}"))?;
+                }
 
                 is_first_row = false;
             }
@@ -215,23 +1470,21 @@ fn do_export_csv(
             let mut record: Vec<String> = Vec::with_capacity(columns.len());
             for idx in 0..columns.len() {
                 let value: mysql::Value = row.get(idx).unwrap_or(mysql::Value::NULL);
-                let str_val = value_to_string(&value);
-                record.push(escape_csv_field(&str_val));
+                let str_val = value_to_text(&value, null_token);
+                record.push(escape_csv_field(&str_val, csv));
             }
 
             writer
-                .write_all(record.join(",").as_bytes())
+                .write_all(record.join(&separator).as_bytes())
                 .map_err(|e| format!("File write error: {e}"))?;
             writer
-                .write_all(b"\n")
+                .write_all(terminator)
                 .map_err(|e| format!("Failed to write newline: {e}"))?;
 
             rows_exported += 1;
         }
 
-        writer
-            .flush()
-            .map_err(|e| format!("Failed to flush file: {e}"))?;
+        finish_export(writer)?;
         Ok(rows_exported)
     })
 }
@@ -241,23 +1494,23 @@ fn do_export_txt(
     schema: &str,
     table: &str,
     file_path: &Path,
+    options: &TableExportOptions,
+    checksum: &ChecksumSlot,
 ) -> Result<u64, String> {
     let schema = schema.to_string();
     let table = table.to_string();
+    let null_token = options.null_token.as_deref();
 
     pool::with_temp_connection(profile, |conn| {
-        let file = File::create(file_path).map_err(|e| format!("Failed to create file: {e}"))?;
+        let file = ChecksumWriter::create(file_path, checksum)?;
         let mut writer = BufWriter::with_capacity(64 * 1024, file);
 
         writer
             .write_all(&[0xEF, 0xBB, 0xBF])
             .map_err(|e| format!("Failed to write BOM: {e}"))?;
 
-        let sql = format!(
-            "SELECT * FROM `{}`.`{}`",
-            escape_identifier(&schema),
-            escape_identifier(&table)
-        );
+        let table_columns = load_spatial_columns(conn, &schema, &table, options)?;
+        let sql = build_table_select_sql(&schema, &table, options, &table_columns);
 
         let mut result_set = conn
             .query_iter(sql)
@@ -269,6 +1522,8 @@ fn do_export_txt(
 
         for row_result in result_set.by_ref() {
             let row: mysql::Row = row_result.map_err(|e| format!("Row read error: {e}"))?;
+            track_export_row(options.job_id.as_deref(), rows_exported)?;
+            let row = encode_binary_cells(row, options.binary_encoding);
 
             if is_first_row {
                 columns = row
@@ -296,7 +1551,7 @@ fn do_export_txt(
             let mut record: Vec<String> = Vec::with_capacity(columns.len());
             for idx in 0..columns.len() {
                 let value: mysql::Value = row.get(idx).unwrap_or(mysql::Value::NULL);
-                let str_val = value_to_string(&value);
+                let str_val = value_to_text(&value, null_token);
                 record.push(format!("\"{}\"", str_val));
             }
 
@@ -310,9 +1565,61 @@ fn do_export_txt(
             rows_exported += 1;
         }
 
-        writer
-            .flush()
-            .map_err(|e| format!("Failed to flush file: {e}"))?;
+        finish_export(writer)?;
+        Ok(rows_exported)
+    })
+}
+
+fn do_export_markdown(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    file_path: &Path,
+    options: &TableExportOptions,
+    checksum: &ChecksumSlot,
+) -> Result<u64, String> {
+    let schema = schema.to_string();
+    let table = table.to_string();
+    let null_token = options.null_token.as_deref();
+
+    pool::with_temp_connection(profile, |conn| {
+        let table_columns = load_spatial_columns(conn, &schema, &table, options)?;
+        let sql = build_table_select_sql(&schema, &table, options, &table_columns);
+
+        let mut result_set = conn
+            .query_iter(sql)
+            .map_err(|e| format!("Query failed: {e}"))?;
+
+        // 以结果集元数据写表头，空表也输出表头与分隔行
+        let columns: Vec<String> = result_set
+            .columns()
+            .as_ref()
+            .iter()
+            .map(|c| c.name_str().to_string())
+            .collect();
+        if columns.is_empty() {
+            return Ok(0);
+        }
+
+        let file = ChecksumWriter::create(file_path, checksum)?;
+        let mut writer = BufWriter::with_capacity(64 * 1024, file);
+        write_markdown_header(&mut writer, &columns)?;
+
+        let mut rows_exported: u64 = 0;
+        for row_result in result_set.by_ref() {
+            let row: mysql::Row = row_result.map_err(|e| format!("Row read error: {e}"))?;
+            track_export_row(options.job_id.as_deref(), rows_exported)?;
+            let row = encode_binary_cells(row, options.binary_encoding);
+            let cells: Vec<String> = (0..columns.len())
+                .map(|idx| {
+                    value_to_text(row.as_ref(idx).unwrap_or(&mysql::Value::NULL), null_token)
+                })
+                .collect();
+            write_markdown_row(&mut writer, &cells)?;
+            rows_exported += 1;
+        }
+
+        finish_export(writer)?;
         Ok(rows_exported)
     })
 }
@@ -322,19 +1629,19 @@ fn do_export_json(
     schema: &str,
     table: &str,
     file_path: &Path,
+    options: &TableExportOptions,
+    checksum: &ChecksumSlot,
 ) -> Result<u64, String> {
     let schema = schema.to_string();
     let table = table.to_string();
+    let null_token = options.null_token.as_deref();
 
     pool::with_temp_connection(profile, |conn| {
-        let file = File::create(file_path).map_err(|e| format!("Failed to create file: {e}"))?;
+        let file = ChecksumWriter::create(file_path, checksum)?;
         let mut writer = BufWriter::with_capacity(64 * 1024, file);
 
-        let sql = format!(
-            "SELECT * FROM `{}`.`{}`",
-            escape_identifier(&schema),
-            escape_identifier(&table)
-        );
+        let table_columns = load_spatial_columns(conn, &schema, &table, options)?;
+        let sql = build_table_select_sql(&schema, &table, options, &table_columns);
 
         let mut result_set = conn
             .query_iter(sql)
@@ -342,6 +1649,7 @@ fn do_export_json(
 
         let mut rows_exported: u64 = 0;
         let mut columns: Vec<String> = Vec::new();
+        let mut column_types: Vec<ColumnType> = Vec::new();
         let mut is_first_row = true;
 
         // Write JSON array start
@@ -351,6 +1659,8 @@ fn do_export_json(
 
         for row_result in result_set.by_ref() {
             let row: mysql::Row = row_result.map_err(|e| format!("Row read error: {e}"))?;
+            track_export_row(options.job_id.as_deref(), rows_exported)?;
+            let row = encode_binary_cells(row, options.binary_encoding);
 
             if is_first_row {
                 columns = row
@@ -358,6 +1668,7 @@ fn do_export_json(
                     .iter()
                     .map(|c| c.name_str().to_string())
                     .collect();
+                column_types = row.columns_ref().iter().map(|c| c.column_type()).collect();
                 is_first_row = false;
             }
 
@@ -376,7 +1687,11 @@ fn do_export_json(
             // Write fields in column order
             for (idx, col) in columns.iter().enumerate() {
                 let value: mysql::Value = row.get(idx).unwrap_or(mysql::Value::NULL);
-                let json_value = mysql_value_to_json(&value);
+                let json_value = match (&value, null_token) {
+                    (mysql::Value::NULL, Some(token)) => json!(token),
+                    _ if options.stringify_values => mysql_value_to_json(&value),
+                    _ => typed_value_to_json(&value, column_types[idx]),
+                };
 
                 // Write field name
                 writer
@@ -424,9 +1739,7 @@ fn do_export_json(
             .write_all(b"]\n")
             .map_err(|e| format!("Write error: {e}"))?;
 
-        writer
-            .flush()
-            .map_err(|e| format!("Failed to flush file: {e}"))?;
+        finish_export(writer)?;
         Ok(rows_exported)
     })
 }
@@ -436,19 +1749,19 @@ fn do_export_html(
     schema: &str,
     table: &str,
     file_path: &Path,
+    options: &TableExportOptions,
+    checksum: &ChecksumSlot,
 ) -> Result<u64, String> {
     let schema = schema.to_string();
     let table = table.to_string();
+    let null_token = options.null_token.as_deref();
 
     pool::with_temp_connection(profile, |conn| {
-        let file = File::create(file_path).map_err(|e| format!("Failed to create file: {e}"))?;
+        let file = ChecksumWriter::create(file_path, checksum)?;
         let mut writer = BufWriter::with_capacity(64 * 1024, file);
 
-        let sql = format!(
-            "SELECT * FROM `{}`.`{}`",
-            escape_identifier(&schema),
-            escape_identifier(&table)
-        );
+        let table_columns = load_spatial_columns(conn, &schema, &table, options)?;
+        let sql = build_table_select_sql(&schema, &table, options, &table_columns);
 
         let mut result_set = conn
             .query_iter(sql)
@@ -465,6 +1778,8 @@ fn do_export_html(
 
         for row_result in result_set.by_ref() {
             let row: mysql::Row = row_result.map_err(|e| format!("Row read error: {e}"))?;
+            track_export_row(options.job_id.as_deref(), rows_exported)?;
+            let row = encode_binary_cells(row, options.binary_encoding);
 
             if is_first_row {
                 columns = row
@@ -477,9 +1792,7 @@ fn do_export_html(
                     writer
                         .write_all(HTML_FOOTER.as_bytes())
                         .map_err(|e| format!("Failed to write HTML footer: {e}"))?;
-                    writer
-                        .flush()
-                        .map_err(|e| format!("Failed to flush file: {e}"))?;
+                    finish_export(writer)?;
                     return Ok(0);
                 }
 
@@ -505,7 +1818,7 @@ fn do_export_html(
                 .map_err(|e| format!("Write error: {e}"))?;
             for idx in 0..columns.len() {
                 let value: mysql::Value = row.get(idx).unwrap_or(mysql::Value::NULL);
-                let str_val = value_to_string(&value);
+                let str_val = value_to_text(&value, null_token);
                 let display_val = if str_val.is_empty() {
                     "&nbsp;".to_string()
                 } else {
@@ -529,9 +1842,7 @@ fn do_export_html(
             .write_all(HTML_FOOTER.as_bytes())
             .map_err(|e| format!("Failed to write HTML footer: {e}"))?;
 
-        writer
-            .flush()
-            .map_err(|e| format!("Failed to flush file: {e}"))?;
+        finish_export(writer)?;
         Ok(rows_exported)
     })
 }
@@ -541,19 +1852,19 @@ fn do_export_xml(
     schema: &str,
     table: &str,
     file_path: &Path,
+    options: &TableExportOptions,
+    checksum: &ChecksumSlot,
 ) -> Result<u64, String> {
     let schema = schema.to_string();
     let table = table.to_string();
+    let null_token = options.null_token.as_deref();
 
     pool::with_temp_connection(profile, |conn| {
-        let file = File::create(file_path).map_err(|e| format!("Failed to create file: {e}"))?;
+        let file = ChecksumWriter::create(file_path, checksum)?;
         let mut writer = BufWriter::with_capacity(64 * 1024, file);
 
-        let sql = format!(
-            "SELECT * FROM `{}`.`{}`",
-            escape_identifier(&schema),
-            escape_identifier(&table)
-        );
+        let table_columns = load_spatial_columns(conn, &schema, &table, options)?;
+        let sql = build_table_select_sql(&schema, &table, options, &table_columns);
 
         let mut result_set = conn
             .query_iter(sql)
@@ -573,6 +1884,8 @@ fn do_export_xml(
 
         for row_result in result_set.by_ref() {
             let row: mysql::Row = row_result.map_err(|e| format!("Row read error: {e}"))?;
+            track_export_row(options.job_id.as_deref(), rows_exported)?;
+            let row = encode_binary_cells(row, options.binary_encoding);
 
             if is_first_row {
                 columns = row
@@ -588,7 +1901,7 @@ fn do_export_xml(
                 .map_err(|e| format!("Write error: {e}"))?;
             for (idx, col) in columns.iter().enumerate() {
                 let value: mysql::Value = row.get(idx).unwrap_or(mysql::Value::NULL);
-                let str_val = value_to_string(&value);
+                let str_val = value_to_text(&value, null_token);
                 let escaped_val = xml_escape(&str_val);
                 writer
                     .write_all(
@@ -612,9 +1925,7 @@ fn do_export_xml(
             .write_all(b"</RECORDS>\n")
             .map_err(|e| format!("Failed to write XML footer: {e}"))?;
 
-        writer
-            .flush()
-            .map_err(|e| format!("Failed to flush file: {e}"))?;
+        finish_export(writer)?;
         Ok(rows_exported)
     })
 }
@@ -624,19 +1935,51 @@ fn do_export_sql(
     schema: &str,
     table: &str,
     file_path: &Path,
+    options: &TableExportOptions,
+    checksum: &ChecksumSlot,
 ) -> Result<u64, String> {
     let schema = schema.to_string();
     let table = table.to_string();
 
     pool::with_temp_connection(profile, |conn| {
-        let file = File::create(file_path).map_err(|e| format!("Failed to create file: {e}"))?;
+        let file = ChecksumWriter::create(file_path, checksum)?;
         let mut writer = BufWriter::with_capacity(64 * 1024, file);
 
-        let sql = format!(
-            "SELECT * FROM `{}`.`{}`",
-            escape_identifier(&schema),
-            escape_identifier(&table)
-        );
+        // 追加到已有内容之后只写数据：DDL 已在首次导出时写出，再写一次 DROP 会清掉之前的行
+        let wants_view_ddl = options.include_view_definition && !checksum.append;
+        let wants_table_ddl =
+            (options.sql.include_create || options.sql.add_drop_table) && !checksum.append;
+        let view = if wants_view_ddl || wants_table_ddl {
+            load_view_definition(conn, &schema, &table)?
+        } else {
+            None
+        };
+        // 整个文件包在 FOREIGN_KEY_CHECKS = 0 中，DROP/CREATE 不受引用关系影响
+        let table_ddl = if wants_table_ddl && view.is_none() {
+            Some(build_table_ddl_sql(profile, &schema, &table, &options.sql)?)
+        } else {
+            None
+        };
+        if table_ddl.is_some() {
+            writer
+                .write_all(b"SET FOREIGN_KEY_CHECKS = 0;\n\n")
+                .map_err(|e| format!("Write error: {e}"))?;
+        }
+
+        if let Some(view) = view.filter(|_| wants_view_ddl) {
+            let sql = build_create_view_sql(&schema, &table, &view, options.strip_view_definer);
+            writer
+                .write_all(sql.as_bytes())
+                .map_err(|e| format!("Write error: {e}"))?;
+        }
+        if let Some(ddl) = &table_ddl {
+            writer
+                .write_all(ddl.as_bytes())
+                .map_err(|e| format!("Write error: {e}"))?;
+        }
+
+        let table_columns = load_spatial_columns(conn, &schema, &table, options)?;
+        let sql = build_table_select_sql(&schema, &table, options, &table_columns);
 
         let mut result_set = conn
             .query_iter(sql)
@@ -648,6 +1991,7 @@ fn do_export_sql(
 
         for row_result in result_set.by_ref() {
             let row: mysql::Row = row_result.map_err(|e| format!("Row read error: {e}"))?;
+            track_export_row(options.job_id.as_deref(), rows_exported)?;
 
             if is_first_row {
                 columns = row
@@ -661,7 +2005,12 @@ fn do_export_sql(
             let mut values: Vec<String> = Vec::with_capacity(columns.len());
             for idx in 0..columns.len() {
                 let value: mysql::Value = row.get(idx).unwrap_or(mysql::Value::NULL);
-                values.push(mysql_value_to_sql(&value));
+                let is_binary = options.binary_encoding != BinaryEncoding::Utf8Lossy
+                    && row.columns_ref().get(idx).is_some_and(is_binary_column);
+                match value {
+                    mysql::Value::Bytes(bytes) if is_binary => values.push(hex_literal(&bytes)),
+                    value => values.push(mysql_value_to_sql(&value)),
+                }
             }
 
             let col_names: Vec<String> = columns
@@ -682,31 +2031,53 @@ fn do_export_sql(
             rows_exported += 1;
         }
 
-        writer
-            .flush()
-            .map_err(|e| format!("Failed to flush file: {e}"))?;
+        if table_ddl.is_some() {
+            writer
+                .write_all(b"\nSET FOREIGN_KEY_CHECKS = 1;\n")
+                .map_err(|e| format!("Write error: {e}"))?;
+        }
+        finish_export(writer)?;
         Ok(rows_exported)
     })
 }
 
+fn jsonl_row_object(
+    row: &mysql::Row,
+    columns: &[String],
+    column_types: &[ColumnType],
+    options: &TableExportOptions,
+) -> serde_json::Map<String, serde_json::Value> {
+    let null_token = options.null_token.as_deref();
+    let mut obj = serde_json::Map::new();
+    for (idx, col) in columns.iter().enumerate() {
+        let value: mysql::Value = row.get(idx).unwrap_or(mysql::Value::NULL);
+        let json_value = match (&value, null_token) {
+            (mysql::Value::NULL, Some(token)) => json!(token),
+            _ if options.stringify_values => json!(value_to_string(&value)),
+            _ => typed_value_to_json(&value, column_types[idx]),
+        };
+        obj.insert(col.clone(), json_value);
+    }
+    obj
+}
+
 fn do_export_jsonl(
     profile: &ConnectionProfile,
     schema: &str,
     table: &str,
     file_path: &Path,
+    options: &TableExportOptions,
+    checksum: &ChecksumSlot,
 ) -> Result<u64, String> {
     let schema = schema.to_string();
     let table = table.to_string();
 
     pool::with_temp_connection(profile, |conn| {
-        let file = File::create(file_path).map_err(|e| format!("Failed to create file: {e}"))?;
+        let file = ChecksumWriter::create(file_path, checksum)?;
         let mut writer = BufWriter::with_capacity(64 * 1024, file);
 
-        let sql = format!(
-            "SELECT * FROM `{}`.`{}`",
-            escape_identifier(&schema),
-            escape_identifier(&table)
-        );
+        let table_columns = load_spatial_columns(conn, &schema, &table, options)?;
+        let sql = build_table_select_sql(&schema, &table, options, &table_columns);
 
         let mut result_set = conn
             .query_iter(sql)
@@ -714,10 +2085,13 @@ fn do_export_jsonl(
 
         let mut rows_exported: u64 = 0;
         let mut columns: Vec<String> = Vec::new();
+        let mut column_types: Vec<ColumnType> = Vec::new();
         let mut is_first_row = true;
 
         for row_result in result_set.by_ref() {
             let row: mysql::Row = row_result.map_err(|e| format!("Row read error: {e}"))?;
+            track_export_row(options.job_id.as_deref(), rows_exported)?;
+            let row = encode_binary_cells(row, options.binary_encoding);
 
             if is_first_row {
                 columns = row
@@ -725,6 +2099,7 @@ fn do_export_jsonl(
                     .iter()
                     .map(|c| c.name_str().to_string())
                     .collect();
+                column_types = row.columns_ref().iter().map(|c| c.column_type()).collect();
 
                 if columns.is_empty() {
                     return Ok(0);
@@ -733,11 +2108,7 @@ fn do_export_jsonl(
                 is_first_row = false;
             }
 
-            let mut obj = serde_json::Map::new();
-            for (idx, col) in columns.iter().enumerate() {
-                let value: mysql::Value = row.get(idx).unwrap_or(mysql::Value::NULL);
-                obj.insert(col.clone(), json!(value_to_string(&value)));
-            }
+            let obj = jsonl_row_object(&row, &columns, &column_types, options);
             let line =
                 serde_json::to_string(&obj).map_err(|e| format!("JSON serialize failed: {e}"))?;
             writer
@@ -750,58 +2121,70 @@ fn do_export_jsonl(
             rows_exported += 1;
         }
 
-        writer
-            .flush()
-            .map_err(|e| format!("Failed to flush file: {e}"))?;
+        finish_export(writer)?;
         Ok(rows_exported)
     })
 }
 
 // Query result export functions
-fn do_export_query_csv(
+fn do_export_query_csv<R: AsRef<[String]>>(
     file_path: &Path,
     headers: &[String],
-    rows: &[Vec<String>],
+    rows: impl IntoIterator<Item = Result<R, String>>,
+    csv: &CsvOptions,
+    checksum: &ChecksumSlot,
 ) -> Result<u64, String> {
-    let file = File::create(file_path).map_err(|e| format!("Failed to create file: {e}"))?;
+    let separator = char::from(csv.delimiter).to_string();
+    let terminator = csv.line_terminator.as_bytes();
+    let file = ChecksumWriter::create(file_path, checksum)?;
     let mut writer = BufWriter::with_capacity(64 * 1024, file);
 
-    writer
-        .write_all(&[0xEF, 0xBB, 0xBF])
-        .map_err(|e| format!("Failed to write BOM: {e}"))?;
+    if !checksum.append {
+        if csv.write_bom {
+            writer
+                .write_all(&[0xEF, 0xBB, 0xBF])
+                .map_err(|e| format!("Failed to write BOM: {e}"))?;
+        }
 
-    // Write headers
-    let header: Vec<String> = headers.iter().map(|c| escape_csv_field(c)).collect();
-    writer
-        .write_all(header.join(",").as_bytes())
-        .map_err(|e| format!("Failed to write header: {e}"))?;
-    writer
-        .write_all(b"\n")
-        .map_err(|e| format!("Failed to write newline: {e}"))?;
+        // Write headers
+        let header: Vec<String> = headers.iter().map(|c| escape_csv_field(c, csv)).collect();
+        writer
+            .write_all(header.join(&separator).as_bytes())
+            .map_err(|e| format!("Failed to write header: {e}"))?;
+        writer
+            .write_all(terminator)
+            .map_err(|e| format!("Failed to write newline: {e}"))?;
+    }
 
     // Write rows
+    let mut rows_exported: u64 = 0;
     for row in rows {
-        let record: Vec<String> = row.iter().map(|v| escape_csv_field(v)).collect();
+        let row = row?;
+        let record: Vec<String> = row
+            .as_ref()
+            .iter()
+            .map(|v| escape_csv_field(v, csv))
+            .collect();
         writer
-            .write_all(record.join(",").as_bytes())
+            .write_all(record.join(&separator).as_bytes())
             .map_err(|e| format!("File write error: {e}"))?;
         writer
-            .write_all(b"\n")
+            .write_all(terminator)
             .map_err(|e| format!("Failed to write newline: {e}"))?;
+        rows_exported += 1;
     }
 
-    writer
-        .flush()
-        .map_err(|e| format!("Failed to flush file: {e}"))?;
-    Ok(rows.len() as u64)
+    finish_export(writer)?;
+    Ok(rows_exported)
 }
 
-fn do_export_query_txt(
+fn do_export_query_txt<R: AsRef<[String]>>(
     file_path: &Path,
     headers: &[String],
-    rows: &[Vec<String>],
+    rows: impl IntoIterator<Item = Result<R, String>>,
+    checksum: &ChecksumSlot,
 ) -> Result<u64, String> {
-    let file = File::create(file_path).map_err(|e| format!("Failed to create file: {e}"))?;
+    let file = ChecksumWriter::create(file_path, checksum)?;
     let mut writer = BufWriter::with_capacity(64 * 1024, file);
 
     writer
@@ -818,28 +2201,67 @@ fn do_export_query_txt(
         .map_err(|e| format!("Failed to write newline: {e}"))?;
 
     // Write rows
+    let mut rows_exported: u64 = 0;
     for row in rows {
-        let record: Vec<String> = row.iter().map(|v| format!("\"{}\"", v)).collect();
+        let row = row?;
+        let record: Vec<String> = row.as_ref().iter().map(|v| format!("\"{}\"", v)).collect();
         writer
             .write_all(record.join("\t").as_bytes())
             .map_err(|e| format!("File write error: {e}"))?;
         writer
             .write_all(b"\n")
             .map_err(|e| format!("Failed to write newline: {e}"))?;
+        rows_exported += 1;
+    }
+
+    finish_export(writer)?;
+    Ok(rows_exported)
+}
+
+fn do_export_query_markdown<R: AsRef<[String]>>(
+    file_path: &Path,
+    headers: &[String],
+    rows: impl IntoIterator<Item = Result<R, String>>,
+    checksum: &ChecksumSlot,
+) -> Result<u64, String> {
+    let file = ChecksumWriter::create(file_path, checksum)?;
+    let mut writer = BufWriter::with_capacity(64 * 1024, file);
+    write_markdown_header(&mut writer, headers)?;
+
+    let mut rows_exported: u64 = 0;
+    for row in rows {
+        let row = row?;
+        write_markdown_row(&mut writer, row.as_ref())?;
+        rows_exported += 1;
     }
 
+    finish_export(writer)?;
+    Ok(rows_exported)
+}
+
+fn write_markdown_header(writer: &mut impl Write, headers: &[String]) -> Result<(), String> {
+    write_markdown_row(writer, headers)?;
+    let separator = vec!["---".to_string(); headers.len()];
     writer
-        .flush()
-        .map_err(|e| format!("Failed to flush file: {e}"))?;
-    Ok(rows.len() as u64)
+        .write_all(format!("| {} |\n", separator.join(" | ")).as_bytes())
+        .map_err(|e| format!("Failed to write header: {e}"))
 }
 
-fn do_export_query_json(
+fn write_markdown_row(writer: &mut impl Write, cells: &[String]) -> Result<(), String> {
+    let cells: Vec<String> = cells.iter().map(|c| escape_markdown_cell(c)).collect();
+    writer
+        .write_all(format!("| {} |\n", cells.join(" | ")).as_bytes())
+        .map_err(|e| format!("File write error: {e}"))
+}
+
+fn do_export_query_json<R: AsRef<[Option<String>]>>(
     file_path: &Path,
     headers: &[String],
-    rows: &[Vec<String>],
+    rows: impl IntoIterator<Item = Result<R, String>>,
+    null_token: Option<&str>,
+    checksum: &ChecksumSlot,
 ) -> Result<u64, String> {
-    let file = File::create(file_path).map_err(|e| format!("Failed to create file: {e}"))?;
+    let file = ChecksumWriter::create(file_path, checksum)?;
     let mut writer = BufWriter::with_capacity(64 * 1024, file);
 
     // Write JSON array start
@@ -847,9 +2269,12 @@ fn do_export_query_json(
         .write_all(b"[\n")
         .map_err(|e| format!("Write error: {e}"))?;
 
-    for (row_idx, row) in rows.iter().enumerate() {
+    let mut rows_exported: u64 = 0;
+    for row in rows {
+        let row = row?;
+        let row = row.as_ref();
         // Write comma before each object except the first
-        if row_idx > 0 {
+        if rows_exported > 0 {
             writer
                 .write_all(b",\n")
                 .map_err(|e| format!("Write error: {e}"))?;
@@ -862,7 +2287,7 @@ fn do_export_query_json(
 
         // Write fields in header order
         for (idx, header) in headers.iter().enumerate() {
-            let value = row.get(idx).map(|s| s.as_str()).unwrap_or("");
+            let cell = row.get(idx).and_then(|cell| cell.as_deref());
 
             // Write field name
             writer
@@ -870,14 +2295,18 @@ fn do_export_query_json(
                 .map_err(|e| format!("Write error: {e}"))?;
 
             // Write field value - try to parse as number for proper JSON types
-            let value_str = if let Ok(n) = value.parse::<i64>() {
-                n.to_string()
-            } else if let Ok(n) = value.parse::<f64>() {
-                n.to_string()
-            } else if value.is_empty() {
-                "null".to_string()
-            } else {
-                format!("\"{}\"", escape_json_string(value))
+            let value_str = match (cell, null_token) {
+                (None, Some(token)) => format!("\"{}\"", escape_json_string(token)),
+                (None, None) => "null".to_string(),
+                (Some(value), _) => {
+                    if let Ok(n) = value.parse::<i64>() {
+                        n.to_string()
+                    } else if let Ok(n) = value.parse::<f64>() {
+                        n.to_string()
+                    } else {
+                        format!("\"{}\"", escape_json_string(value))
+                    }
+                }
             };
 
             writer
@@ -899,10 +2328,11 @@ fn do_export_query_json(
         writer
             .write_all(b"  }")
             .map_err(|e| format!("Write error: {e}"))?;
+        rows_exported += 1;
     }
 
     // Write JSON array end
-    if !rows.is_empty() {
+    if rows_exported > 0 {
         writer
             .write_all(b"\n")
             .map_err(|e| format!("Write error: {e}"))?;
@@ -911,24 +2341,28 @@ fn do_export_query_json(
         .write_all(b"]\n")
         .map_err(|e| format!("Write error: {e}"))?;
 
-    writer
-        .flush()
-        .map_err(|e| format!("Failed to flush file: {e}"))?;
-    Ok(rows.len() as u64)
+    finish_export(writer)?;
+    Ok(rows_exported)
 }
 
-fn do_export_query_html(
+fn do_export_query_html<R: AsRef<[String]>>(
     file_path: &Path,
     headers: &[String],
-    rows: &[Vec<String>],
+    rows: impl IntoIterator<Item = Result<R, String>>,
     table_name: Option<&str>,
+    interactive: bool,
+    checksum: &ChecksumSlot,
 ) -> Result<u64, String> {
-    let file = File::create(file_path).map_err(|e| format!("Failed to create file: {e}"))?;
+    let file = ChecksumWriter::create(file_path, checksum)?;
     let mut writer = BufWriter::with_capacity(64 * 1024, file);
 
     let name = table_name.unwrap_or("Query Result");
+    let mut header = HTML_HEADER.replace("{table_name}", name);
+    if interactive {
+        header = header.replacen("    <table>\n", HTML_INTERACTIVE_TABLE_OPEN, 1);
+    }
     writer
-        .write_all(HTML_HEADER.replace("{table_name}", name).as_bytes())
+        .write_all(header.as_bytes())
         .map_err(|e| format!("Failed to write HTML header: {e}"))?;
 
     // Write table header
@@ -945,11 +2379,13 @@ fn do_export_query_html(
         .map_err(|e| format!("Write error: {e}"))?;
 
     // Write rows
+    let mut rows_exported: u64 = 0;
     for row in rows {
+        let row = row?;
         writer
             .write_all(b"      <tr>\n")
             .map_err(|e| format!("Write error: {e}"))?;
-        for cell in row {
+        for cell in row.as_ref() {
             let display_val = if cell.is_empty() {
                 "&nbsp;".to_string()
             } else {
@@ -962,27 +2398,32 @@ fn do_export_query_html(
         writer
             .write_all(b"      </tr>\n")
             .map_err(|e| format!("Write error: {e}"))?;
+        rows_exported += 1;
     }
 
+    let footer = if interactive {
+        HTML_FOOTER.replacen("</body>", HTML_INTERACTIVE_SCRIPT, 1)
+    } else {
+        HTML_FOOTER.to_string()
+    };
     writer
         .write_all(b"    </tbody>\n")
         .map_err(|e| format!("Write error: {e}"))?;
     writer
-        .write_all(HTML_FOOTER.as_bytes())
+        .write_all(footer.as_bytes())
         .map_err(|e| format!("Failed to write HTML footer: {e}"))?;
 
-    writer
-        .flush()
-        .map_err(|e| format!("Failed to flush file: {e}"))?;
-    Ok(rows.len() as u64)
+    finish_export(writer)?;
+    Ok(rows_exported)
 }
 
-fn do_export_query_xml(
+fn do_export_query_xml<R: AsRef<[String]>>(
     file_path: &Path,
     headers: &[String],
-    rows: &[Vec<String>],
+    rows: impl IntoIterator<Item = Result<R, String>>,
+    checksum: &ChecksumSlot,
 ) -> Result<u64, String> {
-    let file = File::create(file_path).map_err(|e| format!("Failed to create file: {e}"))?;
+    let file = ChecksumWriter::create(file_path, checksum)?;
     let mut writer = BufWriter::with_capacity(64 * 1024, file);
 
     writer
@@ -992,7 +2433,10 @@ fn do_export_query_xml(
         .write_all(b"<RECORDS>\n")
         .map_err(|e| format!("Failed to write XML root: {e}"))?;
 
+    let mut rows_exported: u64 = 0;
     for row in rows {
+        let row = row?;
+        let row = row.as_ref();
         writer
             .write_all(b"\t<RECORD>\n")
             .map_err(|e| format!("Write error: {e}"))?;
@@ -1013,25 +2457,26 @@ fn do_export_query_xml(
         writer
             .write_all(b"\t</RECORD>\n")
             .map_err(|e| format!("Write error: {e}"))?;
+        rows_exported += 1;
     }
 
     writer
         .write_all(b"</RECORDS>\n")
         .map_err(|e| format!("Failed to write XML footer: {e}"))?;
 
-    writer
-        .flush()
-        .map_err(|e| format!("Failed to flush file: {e}"))?;
-    Ok(rows.len() as u64)
+    finish_export(writer)?;
+    Ok(rows_exported)
 }
 
-fn do_export_query_sql(
+fn do_export_query_sql<R: AsRef<[Option<String>]>>(
     file_path: &Path,
     headers: &[String],
-    rows: &[Vec<String>],
+    rows: impl IntoIterator<Item = Result<R, String>>,
     table_name: Option<&str>,
+    literal_columns: &[usize],
+    checksum: &ChecksumSlot,
 ) -> Result<u64, String> {
-    let file = File::create(file_path).map_err(|e| format!("Failed to create file: {e}"))?;
+    let file = ChecksumWriter::create(file_path, checksum)?;
     let mut writer = BufWriter::with_capacity(64 * 1024, file);
 
     let table = table_name.unwrap_or("table_name");
@@ -1040,15 +2485,17 @@ fn do_export_query_sql(
         .map(|c| format!("`{}`", escape_identifier(c)))
         .collect();
 
+    let mut rows_exported: u64 = 0;
     for row in rows {
+        let row = row?;
         let values: Vec<String> = row
+            .as_ref()
             .iter()
-            .map(|v| {
-                if v.is_empty() {
-                    "NULL".to_string()
-                } else {
-                    format!("'{}'", escape_sql_string(v))
-                }
+            .enumerate()
+            .map(|(idx, v)| match v {
+                None => "NULL".to_string(),
+                Some(v) if literal_columns.contains(&idx) => v.clone(),
+                Some(v) => format!("'{}'", escape_sql_string(v)),
             })
             .collect();
 
@@ -1062,27 +2509,35 @@ fn do_export_query_sql(
         writer
             .write_all(insert_sql.as_bytes())
             .map_err(|e| format!("Write error: {e}"))?;
+        rows_exported += 1;
     }
 
-    writer
-        .flush()
-        .map_err(|e| format!("Failed to flush file: {e}"))?;
-    Ok(rows.len() as u64)
+    finish_export(writer)?;
+    Ok(rows_exported)
 }
 
-fn do_export_query_jsonl(
+fn do_export_query_jsonl<R: AsRef<[Option<String>]>>(
     file_path: &Path,
     headers: &[String],
-    rows: &[Vec<String>],
+    rows: impl IntoIterator<Item = Result<R, String>>,
+    null_token: Option<&str>,
+    checksum: &ChecksumSlot,
 ) -> Result<u64, String> {
-    let file = File::create(file_path).map_err(|e| format!("Failed to create file: {e}"))?;
+    let file = ChecksumWriter::create(file_path, checksum)?;
     let mut writer = BufWriter::with_capacity(64 * 1024, file);
 
+    let mut rows_exported: u64 = 0;
     for row in rows {
+        let row = row?;
+        let row = row.as_ref();
         let mut obj = serde_json::Map::new();
         for (idx, header) in headers.iter().enumerate() {
-            let value = row.get(idx).map(|s| s.as_str()).unwrap_or("");
-            obj.insert(header.clone(), json!(value));
+            let value = match (row.get(idx).and_then(|cell| cell.as_deref()), null_token) {
+                (Some(value), _) => json!(value),
+                (None, Some(token)) => json!(token),
+                (None, None) => serde_json::Value::Null,
+            };
+            obj.insert(header.clone(), value);
         }
         let line =
             serde_json::to_string(&obj).map_err(|e| format!("JSON serialize failed: {e}"))?;
@@ -1092,62 +2547,58 @@ fn do_export_query_jsonl(
         writer
             .write_all(b"\n")
             .map_err(|e| format!("Failed to write newline: {e}"))?;
+        rows_exported += 1;
     }
 
-    writer
-        .flush()
-        .map_err(|e| format!("Failed to flush file: {e}"))?;
-    Ok(rows.len() as u64)
+    finish_export(writer)?;
+    Ok(rows_exported)
 }
 
-fn do_export_query_xlsx(
+fn do_export_query_xlsx<R: AsRef<[String]>>(
     file_path: &Path,
     headers: &[String],
-    rows: &[Vec<String>],
+    rows: impl IntoIterator<Item = Result<R, String>>,
     _table_name: Option<&str>,
+    xlsx: &XlsxTypeOptions,
+    rows_per_sheet: u32,
+    checksum: &ChecksumSlot,
 ) -> Result<u64, String> {
+    let cell_writer = XlsxCellWriter::for_headers(headers, xlsx);
     let mut workbook = Workbook::new();
-    let worksheet = workbook.add_worksheet();
 
     // Create header format (bold)
     let header_format = Format::new().set_bold().set_align(FormatAlign::Center);
-
-    // Write headers
-    for (col_idx, header) in headers.iter().enumerate() {
-        worksheet
-            .write_string_with_format(0, col_idx as u16, header, &header_format)
-            .map_err(|e| format!("Failed to write header: {e}"))?;
-    }
+    let mut sheet_count = 1;
+    let mut worksheet = add_xlsx_data_sheet(&mut workbook, sheet_count, headers, &header_format)?;
 
     // Write data rows
-    for (row_idx, row) in rows.iter().enumerate() {
-        for (col_idx, value) in row.iter().enumerate() {
-            // Try to parse as number
-            if let Ok(n) = value.parse::<f64>() {
-                worksheet
-                    .write_number((row_idx + 1) as u32, col_idx as u16, n)
-                    .map_err(|e| format!("Failed to write number: {e}"))?;
-            } else {
-                worksheet
-                    .write_string((row_idx + 1) as u32, col_idx as u16, value)
-                    .map_err(|e| format!("Failed to write string: {e}"))?;
-            }
+    let mut rows_exported: u64 = 0;
+    let mut sheet_rows: u32 = 0;
+    for row in rows {
+        let row = row?;
+        if sheet_rows == rows_per_sheet {
+            sheet_count += 1;
+            worksheet = add_xlsx_data_sheet(&mut workbook, sheet_count, headers, &header_format)?;
+            sheet_rows = 0;
         }
+        sheet_rows += 1;
+        for (col_idx, value) in row.as_ref().iter().enumerate() {
+            cell_writer
+                .write_text(worksheet, sheet_rows, col_idx as u16, value)
+                .map_err(|e| format!("Failed to write cell: {e}"))?;
+        }
+        rows_exported += 1;
     }
 
-    // Auto-adjust column widths
-    for (idx, header) in headers.iter().enumerate() {
-        let width = (header.len() + 5) as f64;
-        worksheet
-            .set_column_width(idx as u16, width)
-            .map_err(|e| format!("Failed to set column width: {e}"))?;
-    }
-
-    workbook
-        .save(file_path)
+    let buffer = workbook
+        .save_to_buffer()
         .map_err(|e| format!("Failed to save Excel file: {e}"))?;
+    let mut file = ChecksumWriter::create(file_path, checksum)?;
+    file.write_all(&buffer)
+        .map_err(|e| format!("Failed to save Excel file: {e}"))?;
+    file.finish()?;
 
-    Ok(rows.len() as u64)
+    Ok(rows_exported)
 }
 
 // HTML template
@@ -1190,40 +2641,420 @@ const HTML_HEADER: &str = r#"<!DOCTYPE html>
         tr:hover {
             background-color: #f1f1f1;
         }
-        .null-value {
-            color: #999;
-            font-style: italic;
+        .null-value {
+            color: #999;
+            font-style: italic;
+        }
+    </style>
+</head>
+<body>
+    <h1>{table_name}</h1>
+    <table>
+"#;
+
+const HTML_FOOTER: &str = r#"    </table>
+</body>
+</html>
+"#;
+
+// 交互模式：表格前加搜索框，表头可点击排序
+const HTML_INTERACTIVE_TABLE_OPEN: &str = r#"    <input id="dwb-search" type="search" placeholder="Search..." style="margin-bottom: 12px; padding: 8px; width: 300px;">
+    <table id="dwb-table">
+"#;
+
+// 交互模式脚本：单击表头按该列排序（数字按数值比较，再次单击反向），搜索框按行文本过滤
+const HTML_INTERACTIVE_SCRIPT: &str = r#"<script>
+(function () {
+    var table = document.getElementById('dwb-table');
+    var body = table.tBodies[0];
+    if (!body) { return; }
+    var headers = table.querySelectorAll('thead th');
+    var sortColumn = -1;
+    var ascending = true;
+    function cellValue(row, index) {
+        var cell = row.cells[index];
+        return cell ? cell.textContent.trim() : '';
+    }
+    function compare(a, b) {
+        var x = parseFloat(a), y = parseFloat(b);
+        if (!isNaN(x) && !isNaN(y) && String(x) === a && String(y) === b) { return x - y; }
+        return a.localeCompare(b);
+    }
+    Array.prototype.forEach.call(headers, function (th, index) {
+        th.style.cursor = 'pointer';
+        th.addEventListener('click', function () {
+            ascending = sortColumn === index ? !ascending : true;
+            sortColumn = index;
+            var rows = Array.prototype.slice.call(body.rows);
+            rows.sort(function (r1, r2) {
+                var result = compare(cellValue(r1, index), cellValue(r2, index));
+                return ascending ? result : -result;
+            });
+            rows.forEach(function (row) { body.appendChild(row); });
+            Array.prototype.forEach.call(headers, function (h) { h.removeAttribute('data-sort'); });
+            th.setAttribute('data-sort', ascending ? 'asc' : 'desc');
+        });
+    });
+    document.getElementById('dwb-search').addEventListener('input', function (event) {
+        var keyword = event.target.value.toLowerCase();
+        Array.prototype.forEach.call(body.rows, function (row) {
+            row.style.display = row.textContent.toLowerCase().indexOf(keyword) >= 0 ? '' : 'none';
+        });
+    });
+})();
+</script>
+</body>"#;
+
+// Helper functions
+/// 返回 (行/秒, 字节/秒)；耗时为 0 时均为 0
+pub(crate) fn compute_throughput(rows: u64, bytes: u64, elapsed: Duration) -> (f64, f64) {
+    let secs = elapsed.as_secs_f64();
+    if secs <= 0.0 {
+        return (0.0, 0.0);
+    }
+    (rows as f64 / secs, bytes as f64 / secs)
+}
+
+pub(crate) fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+fn validate_export_columns(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    options: &TableExportOptions,
+) -> Result<(), String> {
+    if let Some(spec) = options.query_spec.as_ref() {
+        spec.validate()?;
+    }
+    let Some(columns) = options.selected_columns() else {
+        return Ok(());
+    };
+    if columns.is_empty() {
+        return Err("No columns selected for export".to_string());
+    }
+    let existing: Vec<String> = metadata::list_columns(profile, schema, table)?
+        .into_iter()
+        .filter_map(|mut column| column.remove("COLUMN_NAME"))
+        .collect();
+    check_columns_exist(columns, &existing)
+}
+
+fn check_columns_exist(columns: &[String], existing: &[String]) -> Result<(), String> {
+    match columns
+        .iter()
+        .find(|column| !existing.iter().any(|name| name == *column))
+    {
+        Some(column) => Err(format!("Unknown column: {column}")),
+        None => Ok(()),
+    }
+}
+
+struct ViewDefinition {
+    definition: String,
+    algorithm: Option<String>,
+    definer: Option<String>,
+    security_type: Option<String>,
+    check_option: Option<String>,
+}
+
+type ViewDefinitionRow = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+// SHOW CREATE TABLE 输出的表名不带库名，这里补上，与 INSERT 的目标保持一致
+fn build_table_ddl_sql(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    options: &SqlExportOptions,
+) -> Result<String, String> {
+    let qualified = format!(
+        "`{}`.`{}`",
+        escape_identifier(schema),
+        escape_identifier(table)
+    );
+    let mut sql = String::new();
+    if options.add_drop_table {
+        sql.push_str(&format!("DROP TABLE IF EXISTS {qualified};\n"));
+    }
+    if options.include_create {
+        let ddl = metadata::load_ddl(profile, schema, table)?;
+        if ddl.is_empty() {
+            return Err(format!("Table `{schema}`.`{table}` not found"));
+        }
+        let prefix = format!("CREATE TABLE `{}`", escape_identifier(table));
+        match ddl.strip_prefix(&prefix) {
+            Some(rest) => sql.push_str(&format!("CREATE TABLE {qualified}{rest}")),
+            None => sql.push_str(&ddl),
+        }
+        sql.push_str(";\n");
+    }
+    sql.push('\n');
+    Ok(sql)
+}
+
+// 基表返回 None
+fn load_view_definition(
+    conn: &mut mysql::Conn,
+    schema: &str,
+    table: &str,
+) -> Result<Option<ViewDefinition>, String> {
+    let table_type: Option<String> = conn
+        .exec_first(
+            "SELECT TABLE_TYPE FROM INFORMATION_SCHEMA.TABLES WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?",
+            (schema, table),
+        )
+        .map_err(|e| format!("Query failed: {e}"))?;
+    if !table_type.is_some_and(|t| t.eq_ignore_ascii_case("VIEW")) {
+        return Ok(None);
+    }
+    let row: Option<ViewDefinitionRow> = conn
+        .exec_first(
+            "SELECT VIEW_DEFINITION, DEFINER, SECURITY_TYPE, CHECK_OPTION FROM INFORMATION_SCHEMA.VIEWS WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?",
+            (schema, table),
+        )
+        .map_err(|e| format!("Query failed: {e}"))?;
+    let Some((Some(definition), definer, security_type, check_option)) = row else {
+        return Ok(None);
+    };
+    if definition.trim().is_empty() {
+        return Ok(None);
+    }
+
+    // INFORMATION_SCHEMA.VIEWS 不含 ALGORITHM，只能从 SHOW CREATE VIEW 中取
+    let create_sql = format!(
+        "SHOW CREATE VIEW `{}`.`{}`",
+        escape_identifier(schema),
+        escape_identifier(table)
+    );
+    let create_view: Option<mysql::Row> = conn
+        .query_first(create_sql)
+        .map_err(|e| format!("Query failed: {e}"))?;
+    let algorithm = create_view
+        .and_then(|row| row.get::<String, _>(1))
+        .and_then(|ddl| parse_view_algorithm(&ddl));
+
+    Ok(Some(ViewDefinition {
+        definition,
+        algorithm,
+        definer,
+        security_type,
+        check_option,
+    }))
+}
+
+fn parse_view_algorithm(ddl: &str) -> Option<String> {
+    let upper = ddl.to_ascii_uppercase();
+    let start = upper.find("ALGORITHM=")? + "ALGORITHM=".len();
+    let algorithm: String = upper[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect();
+    (!algorithm.is_empty()).then_some(algorithm)
+}
+
+// DEFINER 在 INFORMATION_SCHEMA 中形如 user@host，主机部分不会含 @
+fn quote_definer(definer: &str) -> String {
+    match definer.rsplit_once('@') {
+        Some((user, host)) => format!(
+            "`{}`@`{}`",
+            escape_identifier(user),
+            escape_identifier(host)
+        ),
+        None => format!("`{}`", escape_identifier(definer)),
+    }
+}
+
+fn build_create_view_sql(
+    schema: &str,
+    view: &str,
+    definition: &ViewDefinition,
+    strip_definer: bool,
+) -> String {
+    let mut clauses = String::new();
+    if let Some(algorithm) = definition.algorithm.as_deref() {
+        clauses.push_str(&format!("ALGORITHM={algorithm} "));
+    }
+    if !strip_definer {
+        if let Some(definer) = definition.definer.as_deref().filter(|d| !d.is_empty()) {
+            clauses.push_str(&format!("DEFINER={} ", quote_definer(definer)));
+        }
+    }
+    if let Some(security) = definition.security_type.as_deref() {
+        clauses.push_str(&format!("SQL SECURITY {security} "));
+    }
+    let check_option = match definition.check_option.as_deref() {
+        Some(option) if !option.eq_ignore_ascii_case("NONE") => {
+            format!(" WITH {option} CHECK OPTION")
         }
-    </style>
-</head>
-<body>
-    <h1>{table_name}</h1>
-    <table>
-"#;
+        _ => String::new(),
+    };
+    format!(
+        "-- View structure for `{}`\nCREATE OR REPLACE {}VIEW `{}`.`{}` AS {}{};\n\n",
+        view,
+        clauses,
+        escape_identifier(schema),
+        escape_identifier(view),
+        definition.definition.trim().trim_end_matches(';'),
+        check_option
+    )
+}
 
-const HTML_FOOTER: &str = r#"    </table>
-</body>
-</html>
-"#;
+const SPATIAL_DATA_TYPES: [&str; 9] = [
+    "geometry",
+    "point",
+    "linestring",
+    "polygon",
+    "multipoint",
+    "multilinestring",
+    "multipolygon",
+    "geometrycollection",
+    "geomcollection",
+];
+
+// 仅在需要转换空间列时查询列信息，返回 (列名, 是否空间列)
+fn load_spatial_columns(
+    conn: &mut mysql::Conn,
+    schema: &str,
+    table: &str,
+    options: &TableExportOptions,
+) -> Result<Vec<(String, bool)>, String> {
+    if options.spatial_format.is_none() {
+        return Ok(Vec::new());
+    }
+    let rows: Vec<(String, String)> = conn
+        .exec(
+            "SELECT COLUMN_NAME, DATA_TYPE FROM INFORMATION_SCHEMA.COLUMNS WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? ORDER BY ORDINAL_POSITION",
+            (schema, table),
+        )
+        .map_err(|e| format!("Query failed: {e}"))?;
+    Ok(rows
+        .into_iter()
+        .map(|(name, data_type)| {
+            let is_spatial = SPATIAL_DATA_TYPES.contains(&data_type.to_ascii_lowercase().as_str());
+            (name, is_spatial)
+        })
+        .collect())
+}
+
+// 未指定列时导出全部列，否则按给定顺序导出；空间列包一层 ST_AsText/ST_AsGeoJSON
+fn build_table_select_sql(
+    schema: &str,
+    table: &str,
+    options: &TableExportOptions,
+    table_columns: &[(String, bool)],
+) -> String {
+    let spatial_function = options
+        .spatial_format
+        .filter(|_| table_columns.iter().any(|(_, is_spatial)| *is_spatial))
+        .map(SpatialFormat::sql_function);
+    let selected: Option<Vec<&str>> = match options.selected_columns().filter(|c| !c.is_empty()) {
+        Some(columns) => Some(columns.iter().map(String::as_str).collect()),
+        None if spatial_function.is_some() => Some(
+            table_columns
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect(),
+        ),
+        None => None,
+    };
+    let column_list = match selected {
+        Some(columns) => columns
+            .iter()
+            .map(|c| {
+                let is_spatial = table_columns
+                    .iter()
+                    .any(|(name, is_spatial)| *is_spatial && name == c);
+                match spatial_function {
+                    Some(function) if is_spatial => {
+                        format!("{function}(`{0}`) AS `{0}`", escape_identifier(c))
+                    }
+                    _ => format!("`{}`", escape_identifier(c)),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        None => "*".to_string(),
+    };
+    let mut sql = format!(
+        "SELECT {} FROM `{}`.`{}`",
+        column_list,
+        escape_identifier(schema),
+        escape_identifier(table)
+    );
+    if let Some(spec) = options.query_spec.as_ref() {
+        if let Some(clause) = spec.where_clause.as_deref().map(str::trim) {
+            if !clause.is_empty() {
+                sql.push_str(&format!(" WHERE {clause}"));
+            }
+        }
+        if let Some(clause) = spec.order_by.as_deref().map(str::trim) {
+            if !clause.is_empty() {
+                sql.push_str(&format!(" ORDER BY {clause}"));
+            }
+        }
+        if let Some(limit) = spec.limit {
+            sql.push_str(&format!(" LIMIT {limit}"));
+        }
+    }
+    sql
+}
 
-// Helper functions
 fn escape_identifier(input: &str) -> String {
     input.replace('`', "``")
 }
 
-fn escape_csv_field(value: &str) -> String {
-    let mut needs_quote = false;
-    for ch in value.chars() {
-        if ch == ',' || ch == '"' || ch == '\n' || ch == '\r' {
-            needs_quote = true;
-            break;
+// 按字节转义，二进制列原样保留
+fn write_tab_field(out: &mut Vec<u8>, value: &mysql::Value) {
+    let text;
+    let bytes: &[u8] = match value {
+        mysql::Value::NULL => {
+            out.extend_from_slice(b"\\N");
+            return;
+        }
+        mysql::Value::Bytes(bytes) => bytes,
+        other => {
+            text = value_to_string(other);
+            text.as_bytes()
+        }
+    };
+    for &byte in bytes {
+        match byte {
+            b'\\' => out.extend_from_slice(b"\\\\"),
+            b'\t' => out.extend_from_slice(b"\\t"),
+            b'\n' => out.extend_from_slice(b"\\n"),
+            b'\r' => out.extend_from_slice(b"\\r"),
+            0 => out.extend_from_slice(b"\\0"),
+            other => out.push(other),
         }
     }
+}
+
+// GFM 表格单元格：管道符转义，换行转为 <br>
+fn escape_markdown_cell(value: &str) -> String {
+    value
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace(['\r', '\n'], "<br>")
+}
+
+// 含分隔符、引号或换行时加引号，字段内的引号双写转义
+fn escape_csv_field(value: &str, csv: &CsvOptions) -> String {
+    let delimiter = char::from(csv.delimiter);
+    let quote = char::from(csv.quote);
+    let needs_quote = value
+        .chars()
+        .any(|ch| ch == delimiter || ch == quote || ch == '\n' || ch == '\r');
     if !needs_quote {
         return value.to_string();
     }
-    let escaped = value.replace('"', "\"\"");
-    format!("\"{}\"", escaped)
+    let escaped = value.replace(quote, &format!("{quote}{quote}"));
+    format!("{quote}{escaped}{quote}")
 }
 
 fn escape_sql_string(value: &str) -> String {
@@ -1279,6 +3110,59 @@ fn xml_escape_name(input: &str) -> String {
     result
 }
 
+// 文本类导出的单元格内容；设置了 null_token 时 NULL 输出该标记，否则为空串
+fn value_to_text(value: &mysql::Value, null_token: Option<&str>) -> String {
+    match value {
+        mysql::Value::NULL => null_token.unwrap_or_default().to_string(),
+        other => value_to_string(other),
+    }
+}
+
+fn fill_null_cells(cells: &[Option<String>], null_token: Option<&str>) -> Vec<String> {
+    cells
+        .iter()
+        .map(|cell| match cell {
+            Some(value) => value.clone(),
+            None => null_token.unwrap_or_default().to_string(),
+        })
+        .collect()
+}
+
+fn is_binary_column(column: &mysql::Column) -> bool {
+    column.character_set() == BINARY_CHARSET_ID
+        && matches!(
+            column.column_type(),
+            ColumnType::MYSQL_TYPE_TINY_BLOB
+                | ColumnType::MYSQL_TYPE_BLOB
+                | ColumnType::MYSQL_TYPE_MEDIUM_BLOB
+                | ColumnType::MYSQL_TYPE_LONG_BLOB
+                | ColumnType::MYSQL_TYPE_STRING
+                | ColumnType::MYSQL_TYPE_VAR_STRING
+                | ColumnType::MYSQL_TYPE_VARCHAR
+        )
+}
+
+// 二进制列的值替换为编码后的文本，后续按普通字符串输出
+fn encode_binary_cells(mut row: mysql::Row, encoding: BinaryEncoding) -> mysql::Row {
+    if encoding == BinaryEncoding::Utf8Lossy {
+        return row;
+    }
+    let binary_columns: Vec<usize> = row
+        .columns_ref()
+        .iter()
+        .enumerate()
+        .filter(|(_, column)| is_binary_column(column))
+        .map(|(idx, _)| idx)
+        .collect();
+    for idx in binary_columns {
+        if let Some(mysql::Value::Bytes(bytes)) = row.as_ref(idx) {
+            let encoded = encoding.encode(bytes);
+            row.place(idx, mysql::Value::Bytes(encoded.into_bytes()));
+        }
+    }
+    row
+}
+
 fn value_to_string(value: &mysql::Value) -> String {
     match value {
         mysql::Value::NULL => "".to_string(),
@@ -1309,124 +3193,772 @@ fn do_export_xlsx(
     schema: &str,
     table: &str,
     file_path: &Path,
+    options: &TableExportOptions,
+    checksum: &ChecksumSlot,
 ) -> Result<u64, String> {
     let schema = schema.to_string();
     let table = table.to_string();
+    let dictionary = if options.include_schema_sheet {
+        Some(load_schema_dictionary(profile, &schema, &table, options)?)
+    } else {
+        None
+    };
 
     pool::with_temp_connection(profile, |conn| {
-        let sql = format!(
-            "SELECT * FROM `{}`.`{}`",
-            escape_identifier(&schema),
-            escape_identifier(&table)
-        );
+        let table_columns = load_spatial_columns(conn, &schema, &table, options)?;
+        let sql = build_table_select_sql(&schema, &table, options, &table_columns);
 
         let mut result_set = conn
             .query_iter(sql)
             .map_err(|e| format!("Query failed: {e}"))?;
+        let columns: Vec<String> = result_set
+            .columns()
+            .as_ref()
+            .iter()
+            .map(|c| c.name_str().to_string())
+            .collect();
+        if columns.is_empty() {
+            return Ok(0);
+        }
+        let cell_writer = XlsxCellWriter::for_columns(result_set.columns().as_ref(), &options.xlsx);
 
         let mut workbook = Workbook::new();
-        let worksheet = workbook.add_worksheet();
 
         // Create header format (bold)
         let header_format = Format::new().set_bold().set_align(FormatAlign::Center);
+        let mut sheet_count = 1;
+        let mut worksheet =
+            add_xlsx_data_sheet(&mut workbook, sheet_count, &columns, &header_format)?;
 
         let mut rows_exported: u64 = 0;
-        let mut columns: Vec<String> = Vec::new();
-        let mut is_first_row = true;
-        let mut row_index: u32 = 0;
+        let mut sheet_rows: u32 = 0;
 
         for row_result in result_set.by_ref() {
             let row: mysql::Row = row_result.map_err(|e| format!("Row read error: {e}"))?;
+            track_export_row(options.job_id.as_deref(), rows_exported)?;
+            let row = encode_binary_cells(row, options.binary_encoding);
+            if sheet_rows == XLSX_ROWS_PER_SHEET {
+                sheet_count += 1;
+                worksheet =
+                    add_xlsx_data_sheet(&mut workbook, sheet_count, &columns, &header_format)?;
+                sheet_rows = 0;
+            }
+            sheet_rows += 1;
 
-            if is_first_row {
-                columns = row
-                    .columns_ref()
-                    .iter()
-                    .map(|c| c.name_str().to_string())
-                    .collect();
+            // Write data rows
+            for idx in 0..columns.len() {
+                let value: mysql::Value = row.get(idx).unwrap_or(mysql::Value::NULL);
+                cell_writer
+                    .write_value(worksheet, sheet_rows, idx as u16, &value)
+                    .map_err(|e| format!("Failed to write cell: {e}"))?;
+            }
 
-                if columns.is_empty() {
-                    return Ok(0);
+            rows_exported += 1;
+        }
+
+        if let Some(dictionary) = &dictionary {
+            let sheet = workbook.add_worksheet();
+            sheet
+                .set_name("Schema")
+                .map_err(|e| format!("Failed to name schema sheet: {e}"))?;
+            for (col_idx, header) in SCHEMA_DICTIONARY_HEADERS.iter().enumerate() {
+                sheet
+                    .write_string_with_format(0, col_idx as u16, *header, &header_format)
+                    .map_err(|e| format!("Failed to write header: {e}"))?;
+            }
+            for (row_idx, entry) in dictionary.iter().enumerate() {
+                for (col_idx, value) in entry.iter().enumerate() {
+                    sheet
+                        .write_string(row_idx as u32 + 1, col_idx as u16, value)
+                        .map_err(|e| format!("Failed to write cell: {e}"))?;
                 }
+            }
+        }
 
-                // Write headers with bold format
-                for (col_idx, col_name) in columns.iter().enumerate() {
-                    worksheet
-                        .write_string_with_format(
-                            row_index,
-                            col_idx as u16,
-                            col_name,
-                            &header_format,
-                        )
-                        .map_err(|e| format!("Failed to write header: {e}"))?;
+        // 先生成到内存再写出，校验和与写入同步计算
+        let buffer = workbook
+            .save_to_buffer()
+            .map_err(|e| format!("Failed to save Excel file: {e}"))?;
+        let mut file = ChecksumWriter::create(file_path, checksum)?;
+        file.write_all(&buffer)
+            .map_err(|e| format!("Failed to save Excel file: {e}"))?;
+        file.finish()?;
+
+        Ok(rows_exported)
+    })
+}
+
+// Parquet 每个行组缓冲的行数
+const PARQUET_BATCH_ROWS: usize = 8192;
+const BINARY_CHARSET_ID: u16 = 63;
+
+// 按列类型累积一个批次的值；整数与 DECIMAL 保留数值类型，日期转为 Date32/Timestamp
+enum ParquetColumn {
+    Int64(Int64Builder),
+    UInt64(UInt64Builder),
+    Float64(Float64Builder),
+    Decimal(Decimal128Builder, i8),
+    Date(Date32Builder),
+    Timestamp(TimestampMicrosecondBuilder),
+    Binary(BinaryBuilder),
+    Utf8(StringBuilder),
+}
+
+impl ParquetColumn {
+    fn for_column(column: &mysql::Column) -> (Self, DataType) {
+        let unsigned = column.flags().contains(ColumnFlags::UNSIGNED_FLAG);
+        match column.column_type() {
+            ColumnType::MYSQL_TYPE_LONGLONG if unsigned => (
+                ParquetColumn::UInt64(UInt64Builder::new()),
+                DataType::UInt64,
+            ),
+            ColumnType::MYSQL_TYPE_TINY
+            | ColumnType::MYSQL_TYPE_SHORT
+            | ColumnType::MYSQL_TYPE_INT24
+            | ColumnType::MYSQL_TYPE_LONG
+            | ColumnType::MYSQL_TYPE_LONGLONG
+            | ColumnType::MYSQL_TYPE_YEAR => {
+                (ParquetColumn::Int64(Int64Builder::new()), DataType::Int64)
+            }
+            ColumnType::MYSQL_TYPE_FLOAT | ColumnType::MYSQL_TYPE_DOUBLE => (
+                ParquetColumn::Float64(Float64Builder::new()),
+                DataType::Float64,
+            ),
+            ColumnType::MYSQL_TYPE_DECIMAL | ColumnType::MYSQL_TYPE_NEWDECIMAL => {
+                // column_length 含小数点与符号位
+                let scale = column.decimals();
+                let precision = u64::from(column.column_length())
+                    .saturating_sub(u64::from(scale > 0) + u64::from(!unsigned));
+                match u8::try_from(precision) {
+                    Ok(precision) if (1..=38).contains(&precision) => {
+                        let scale = scale as i8;
+                        let data_type = DataType::Decimal128(precision, scale);
+                        let builder = Decimal128Builder::new().with_data_type(data_type.clone());
+                        (ParquetColumn::Decimal(builder, scale), data_type)
+                    }
+                    _ => (ParquetColumn::Utf8(StringBuilder::new()), DataType::Utf8),
                 }
-                row_index += 1;
-                is_first_row = false;
             }
+            ColumnType::MYSQL_TYPE_DATE | ColumnType::MYSQL_TYPE_NEWDATE => {
+                (ParquetColumn::Date(Date32Builder::new()), DataType::Date32)
+            }
+            ColumnType::MYSQL_TYPE_DATETIME
+            | ColumnType::MYSQL_TYPE_DATETIME2
+            | ColumnType::MYSQL_TYPE_TIMESTAMP
+            | ColumnType::MYSQL_TYPE_TIMESTAMP2 => (
+                ParquetColumn::Timestamp(TimestampMicrosecondBuilder::new()),
+                DataType::Timestamp(TimeUnit::Microsecond, None),
+            ),
+            ColumnType::MYSQL_TYPE_TINY_BLOB
+            | ColumnType::MYSQL_TYPE_BLOB
+            | ColumnType::MYSQL_TYPE_MEDIUM_BLOB
+            | ColumnType::MYSQL_TYPE_LONG_BLOB
+            | ColumnType::MYSQL_TYPE_STRING
+            | ColumnType::MYSQL_TYPE_VAR_STRING
+            | ColumnType::MYSQL_TYPE_VARCHAR
+            | ColumnType::MYSQL_TYPE_GEOMETRY
+                if column.character_set() == BINARY_CHARSET_ID =>
+            {
+                (
+                    ParquetColumn::Binary(BinaryBuilder::new()),
+                    DataType::Binary,
+                )
+            }
+            _ => (ParquetColumn::Utf8(StringBuilder::new()), DataType::Utf8),
+        }
+    }
 
-            // Write data rows
-            for (idx, _col) in columns.iter().enumerate() {
-                let value: mysql::Value = row.get(idx).unwrap_or(mysql::Value::NULL);
-                write_excel_value(worksheet, row_index, idx as u16, &value)
-                    .map_err(|e| format!("Failed to write cell: {e}"))?;
+    // 无法解析的值（如 0000-00-00）写为 null
+    fn append(&mut self, value: &mysql::Value) {
+        let text = match value {
+            mysql::Value::Bytes(bytes) => Some(String::from_utf8_lossy(bytes)),
+            _ => None,
+        };
+        let text = text.as_deref().map(str::trim);
+        match self {
+            ParquetColumn::Int64(builder) => builder.append_option(match value {
+                mysql::Value::Int(v) => Some(*v),
+                mysql::Value::UInt(v) => i64::try_from(*v).ok(),
+                _ => text.and_then(|t| t.parse().ok()),
+            }),
+            ParquetColumn::UInt64(builder) => builder.append_option(match value {
+                mysql::Value::UInt(v) => Some(*v),
+                mysql::Value::Int(v) => u64::try_from(*v).ok(),
+                _ => text.and_then(|t| t.parse().ok()),
+            }),
+            ParquetColumn::Float64(builder) => builder.append_option(match value {
+                mysql::Value::Float(v) => Some(f64::from(*v)),
+                mysql::Value::Double(v) => Some(*v),
+                mysql::Value::Int(v) => Some(*v as f64),
+                mysql::Value::UInt(v) => Some(*v as f64),
+                _ => text.and_then(|t| t.parse().ok()),
+            }),
+            ParquetColumn::Decimal(builder, scale) => {
+                builder.append_option(text.and_then(|t| parse_scaled_decimal(t, *scale)))
             }
+            ParquetColumn::Date(builder) => builder.append_option(
+                match value {
+                    mysql::Value::Date(y, m, d, ..) => {
+                        chrono::NaiveDate::from_ymd_opt(i32::from(*y), u32::from(*m), u32::from(*d))
+                    }
+                    _ => text.and_then(|t| chrono::NaiveDate::parse_from_str(t, "%Y-%m-%d").ok()),
+                }
+                .map(|date| (date - chrono::NaiveDate::default()).num_days() as i32),
+            ),
+            ParquetColumn::Timestamp(builder) => builder.append_option(
+                match value {
+                    mysql::Value::Date(y, m, d, hh, mm, ss, us) => {
+                        chrono::NaiveDate::from_ymd_opt(i32::from(*y), u32::from(*m), u32::from(*d))
+                            .and_then(|date| {
+                                date.and_hms_micro_opt(
+                                    u32::from(*hh),
+                                    u32::from(*mm),
+                                    u32::from(*ss),
+                                    *us,
+                                )
+                            })
+                    }
+                    _ => text.and_then(|t| {
+                        chrono::NaiveDateTime::parse_from_str(t, "%Y-%m-%d %H:%M:%S%.f").ok()
+                    }),
+                }
+                .map(|datetime| datetime.and_utc().timestamp_micros()),
+            ),
+            ParquetColumn::Binary(builder) => match value {
+                mysql::Value::Bytes(bytes) => builder.append_value(bytes),
+                _ => builder.append_null(),
+            },
+            ParquetColumn::Utf8(builder) => match value {
+                mysql::Value::NULL => builder.append_null(),
+                other => builder.append_value(value_to_string(other)),
+            },
+        }
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        match self {
+            ParquetColumn::Int64(builder) => Arc::new(builder.finish()),
+            ParquetColumn::UInt64(builder) => Arc::new(builder.finish()),
+            ParquetColumn::Float64(builder) => Arc::new(builder.finish()),
+            ParquetColumn::Decimal(builder, _) => Arc::new(builder.finish()),
+            ParquetColumn::Date(builder) => Arc::new(builder.finish()),
+            ParquetColumn::Timestamp(builder) => Arc::new(builder.finish()),
+            ParquetColumn::Binary(builder) => Arc::new(builder.finish()),
+            ParquetColumn::Utf8(builder) => Arc::new(builder.finish()),
+        }
+    }
+}
+
+// "-12.5" 按 scale=2 解析为 -1250；超出 scale 的小数位截断
+fn parse_scaled_decimal(text: &str, scale: i8) -> Option<i128> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+    let scale = usize::try_from(scale).ok()?;
+    let mut combined = String::with_capacity(int_part.len() + scale);
+    combined.push_str(int_part);
+    combined.extend(frac_part.chars().chain(std::iter::repeat('0')).take(scale));
+    let value: i128 = combined.parse().ok()?;
+    Some(if negative { -value } else { value })
+}
+
+struct ParquetBatchWriter {
+    schema: Arc<Schema>,
+    columns: Vec<ParquetColumn>,
+    writer: ArrowWriter<ChecksumWriter>,
+    buffered: usize,
+}
+
+impl ParquetBatchWriter {
+    fn create(
+        file_path: &Path,
+        fields: Vec<Field>,
+        columns: Vec<ParquetColumn>,
+        checksum: &ChecksumSlot,
+    ) -> Result<Self, String> {
+        let schema = Arc::new(Schema::new(fields));
+        let file = ChecksumWriter::create(file_path, checksum)?;
+        let props = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+        let writer = ArrowWriter::try_new(file, schema.clone(), Some(props))
+            .map_err(|e| format!("Failed to create Parquet writer: {e}"))?;
+        Ok(Self {
+            schema,
+            columns,
+            writer,
+            buffered: 0,
+        })
+    }
+
+    fn append_row<'a>(
+        &mut self,
+        values: impl IntoIterator<Item = &'a mysql::Value>,
+    ) -> Result<(), String> {
+        for (column, value) in self.columns.iter_mut().zip(values) {
+            column.append(value);
+        }
+        self.buffered += 1;
+        if self.buffered >= PARQUET_BATCH_ROWS {
+            self.flush_batch()?;
+        }
+        Ok(())
+    }
+
+    fn flush_batch(&mut self) -> Result<(), String> {
+        if self.buffered == 0 {
+            return Ok(());
+        }
+        let arrays: Vec<ArrayRef> = self.columns.iter_mut().map(ParquetColumn::finish).collect();
+        let batch = RecordBatch::try_new(self.schema.clone(), arrays)
+            .map_err(|e| format!("Failed to build Parquet batch: {e}"))?;
+        self.writer
+            .write(&batch)
+            .map_err(|e| format!("File write error: {e}"))?;
+        self.buffered = 0;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<(), String> {
+        self.flush_batch()?;
+        self.writer
+            .into_inner()
+            .map_err(|e| format!("Failed to finish Parquet file: {e}"))?
+            .finish()?;
+        Ok(())
+    }
+}
+
+fn do_export_parquet(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    file_path: &Path,
+    options: &TableExportOptions,
+    checksum: &ChecksumSlot,
+) -> Result<u64, String> {
+    let schema = schema.to_string();
+    let table = table.to_string();
+
+    pool::with_temp_connection(profile, |conn| {
+        let table_columns = load_spatial_columns(conn, &schema, &table, options)?;
+        let sql = build_table_select_sql(&schema, &table, options, &table_columns);
+
+        let mut result_set = conn
+            .query_iter(sql)
+            .map_err(|e| format!("Query failed: {e}"))?;
+
+        // 以结果集元数据建 schema，空表也能写出带列定义的文件
+        let (columns, fields): (Vec<ParquetColumn>, Vec<Field>) = result_set
+            .columns()
+            .as_ref()
+            .iter()
+            .map(|column| {
+                let (builder, data_type) = ParquetColumn::for_column(column);
+                (builder, Field::new(column.name_str(), data_type, true))
+            })
+            .unzip();
+        if columns.is_empty() {
+            return Ok(0);
+        }
+        let mut writer = ParquetBatchWriter::create(file_path, fields, columns, checksum)?;
 
-            row_index += 1;
+        let mut rows_exported: u64 = 0;
+        for row_result in result_set.by_ref() {
+            let row: mysql::Row = row_result.map_err(|e| format!("Row read error: {e}"))?;
+            track_export_row(options.job_id.as_deref(), rows_exported)?;
+            let values = (0..row.len()).map(|idx| row.as_ref(idx).unwrap_or(&mysql::Value::NULL));
+            writer.append_row(values)?;
             rows_exported += 1;
         }
 
-        // Auto-adjust column widths
-        for (idx, col_name) in columns.iter().enumerate() {
-            let width = (col_name.len() + 5) as f64;
-            worksheet
-                .set_column_width(idx as u16, width)
-                .map_err(|e| format!("Failed to set column width: {e}"))?;
+        writer.finish()?;
+        Ok(rows_exported)
+    })
+}
+
+// 查询结果在前端已是字符串，全部按 Utf8 列写出
+fn do_export_query_parquet<R: AsRef<[Option<String>]>>(
+    file_path: &Path,
+    headers: &[String],
+    rows: impl IntoIterator<Item = Result<R, String>>,
+    checksum: &ChecksumSlot,
+) -> Result<u64, String> {
+    let fields = headers
+        .iter()
+        .map(|header| Field::new(header, DataType::Utf8, true))
+        .collect();
+    let columns = headers
+        .iter()
+        .map(|_| ParquetColumn::Utf8(StringBuilder::new()))
+        .collect();
+    let mut writer = ParquetBatchWriter::create(file_path, fields, columns, checksum)?;
+
+    let mut rows_exported: u64 = 0;
+    for row in rows {
+        let row = row?;
+        let values: Vec<mysql::Value> = (0..headers.len())
+            .map(
+                |idx| match row.as_ref().get(idx).and_then(Option::as_deref) {
+                    Some(cell) => mysql::Value::Bytes(cell.as_bytes().to_vec()),
+                    None => mysql::Value::NULL,
+                },
+            )
+            .collect();
+        writer.append_row(&values)?;
+        rows_exported += 1;
+    }
+
+    writer.finish()?;
+    Ok(rows_exported)
+}
+
+const SCHEMA_DICTIONARY_HEADERS: [&str; 4] = ["Column", "Type", "Nullable", "Comment"];
+
+// 每列一行：列名、类型、是否可空、注释；指定了导出列时只列出这些列并保持其顺序
+fn load_schema_dictionary(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    options: &TableExportOptions,
+) -> Result<Vec<[String; 4]>, String> {
+    let columns = metadata::list_columns(profile, schema, table)?;
+    let field = |column: &std::collections::BTreeMap<String, String>, key: &str| {
+        column.get(key).cloned().unwrap_or_default()
+    };
+    let entries: Vec<[String; 4]> = columns
+        .iter()
+        .map(|column| {
+            [
+                field(column, "COLUMN_NAME"),
+                field(column, "COLUMN_TYPE"),
+                field(column, "IS_NULLABLE"),
+                field(column, "COLUMN_COMMENT"),
+            ]
+        })
+        .collect();
+    Ok(match options.selected_columns() {
+        Some(selected) if !selected.is_empty() => selected
+            .iter()
+            .filter_map(|name| entries.iter().find(|entry| &entry[0] == name).cloned())
+            .collect(),
+        _ => entries,
+    })
+}
+
+fn write_schema_sidecar(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    file_path: &Path,
+    options: &TableExportOptions,
+) -> Result<(), String> {
+    let dictionary = load_schema_dictionary(profile, schema, table, options)?;
+    let sidecar_path = schema_sidecar_path(file_path);
+    let file = File::create(&sidecar_path).map_err(|e| format!("Failed to create file: {e}"))?;
+    let mut writer = BufWriter::new(file);
+    let mut lines = vec![SCHEMA_DICTIONARY_HEADERS.join(",")];
+    lines.extend(dictionary.iter().map(|entry| {
+        entry
+            .iter()
+            .map(|value| escape_csv_field(value, &CsvOptions::default()))
+            .collect::<Vec<_>>()
+            .join(",")
+    }));
+    for line in lines {
+        writeln!(writer, "{line}").map_err(|e| format!("Failed to write schema file: {e}"))?;
+    }
+    writer
+        .flush()
+        .map_err(|e| format!("Failed to flush schema file: {e}"))
+}
+
+// 去掉 .gz 压缩后缀与数据格式扩展名，再接 .schema.csv：out.csv.gz → out.schema.csv
+fn schema_sidecar_path(file_path: &Path) -> PathBuf {
+    let data_path = if file_path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gz"))
+    {
+        file_path.with_extension("")
+    } else {
+        file_path.to_path_buf()
+    };
+    data_path.with_extension("schema.csv")
+}
+
+// Excel 单个工作表上限 1,048,576 行，超出部分依次写入 Sheet2、Sheet3…
+const XLSX_ROWS_PER_SHEET: u32 = 1_000_000;
+
+// 新增数据工作表 Sheet{n}，写入加粗表头并按表头设置列宽
+fn add_xlsx_data_sheet<'a>(
+    workbook: &'a mut Workbook,
+    sheet_no: usize,
+    headers: &[String],
+    header_format: &Format,
+) -> Result<&'a mut Worksheet, String> {
+    let worksheet = workbook.add_worksheet();
+    worksheet
+        .set_name(format!("Sheet{sheet_no}"))
+        .map_err(|e| format!("Failed to name worksheet: {e}"))?;
+    for (col_idx, header) in headers.iter().enumerate() {
+        worksheet
+            .write_string_with_format(0, col_idx as u16, header, header_format)
+            .map_err(|e| format!("Failed to write header: {e}"))?;
+        worksheet
+            .set_column_width(col_idx as u16, (header.len() + 5) as f64)
+            .map_err(|e| format!("Failed to set column width: {e}"))?;
+    }
+    Ok(worksheet)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum XlsxCellKind {
+    // 查询结果只有文本：能解析为数字的写为数字
+    Auto,
+    Text,
+    Number,
+    Date,
+    DateTime,
+}
+
+struct XlsxCellWriter {
+    kinds: Vec<XlsxCellKind>,
+    ids_as_text: bool,
+    native_dates: bool,
+    date_format: Format,
+    datetime_format: Format,
+}
+
+impl XlsxCellWriter {
+    fn new(kinds: Vec<XlsxCellKind>, options: &XlsxTypeOptions) -> Self {
+        Self {
+            kinds,
+            ids_as_text: options.ids_as_text,
+            native_dates: options.native_dates,
+            date_format: Format::new().set_num_format("yyyy-mm-dd"),
+            datetime_format: Format::new().set_num_format("yyyy-mm-dd hh:mm:ss"),
+        }
+    }
+
+    // 按 columns_ref() 的列类型决定单元格类型
+    fn for_columns(columns: &[mysql::Column], options: &XlsxTypeOptions) -> Self {
+        let kinds = columns
+            .iter()
+            .map(|column| {
+                if options
+                    .text_columns
+                    .iter()
+                    .any(|name| name == column.name_str().as_ref())
+                {
+                    return XlsxCellKind::Text;
+                }
+                match column.column_type() {
+                    ColumnType::MYSQL_TYPE_LONGLONG if options.ids_as_text => XlsxCellKind::Text,
+                    ColumnType::MYSQL_TYPE_TINY
+                    | ColumnType::MYSQL_TYPE_SHORT
+                    | ColumnType::MYSQL_TYPE_INT24
+                    | ColumnType::MYSQL_TYPE_LONG
+                    | ColumnType::MYSQL_TYPE_LONGLONG
+                    | ColumnType::MYSQL_TYPE_YEAR
+                    | ColumnType::MYSQL_TYPE_FLOAT
+                    | ColumnType::MYSQL_TYPE_DOUBLE
+                    | ColumnType::MYSQL_TYPE_DECIMAL
+                    | ColumnType::MYSQL_TYPE_NEWDECIMAL => XlsxCellKind::Number,
+                    ColumnType::MYSQL_TYPE_DATE | ColumnType::MYSQL_TYPE_NEWDATE
+                        if options.native_dates =>
+                    {
+                        XlsxCellKind::Date
+                    }
+                    ColumnType::MYSQL_TYPE_DATETIME
+                    | ColumnType::MYSQL_TYPE_DATETIME2
+                    | ColumnType::MYSQL_TYPE_TIMESTAMP
+                    | ColumnType::MYSQL_TYPE_TIMESTAMP2
+                        if options.native_dates =>
+                    {
+                        XlsxCellKind::DateTime
+                    }
+                    _ => XlsxCellKind::Text,
+                }
+            })
+            .collect();
+        Self::new(kinds, options)
+    }
+
+    fn for_headers(headers: &[String], options: &XlsxTypeOptions) -> Self {
+        let kinds = headers
+            .iter()
+            .map(|header| {
+                if options.text_columns.contains(header) {
+                    XlsxCellKind::Text
+                } else {
+                    XlsxCellKind::Auto
+                }
+            })
+            .collect();
+        Self::new(kinds, options)
+    }
+
+    fn kind(&self, col: u16) -> XlsxCellKind {
+        self.kinds
+            .get(usize::from(col))
+            .copied()
+            .unwrap_or(XlsxCellKind::Auto)
+    }
+
+    fn write_value(
+        &self,
+        worksheet: &mut Worksheet,
+        row: u32,
+        col: u16,
+        value: &mysql::Value,
+    ) -> Result<(), XlsxError> {
+        let kind = self.kind(col);
+        match value {
+            mysql::Value::NULL => {
+                worksheet.write_string(row, col, "")?;
+            }
+            mysql::Value::Bytes(bytes) => {
+                self.write_text(worksheet, row, col, &String::from_utf8_lossy(bytes))?;
+            }
+            mysql::Value::Int(_) | mysql::Value::UInt(_) if kind == XlsxCellKind::Text => {
+                worksheet.write_string(row, col, value_to_string(value))?;
+            }
+            mysql::Value::Int(v) => {
+                worksheet.write_number(row, col, *v as f64)?;
+            }
+            mysql::Value::UInt(v) => {
+                worksheet.write_number(row, col, *v as f64)?;
+            }
+            mysql::Value::Float(v) => {
+                worksheet.write_number(row, col, *v as f64)?;
+            }
+            mysql::Value::Double(v) => {
+                worksheet.write_number(row, col, *v)?;
+            }
+            mysql::Value::Date(y, m, d, hh, mm, ss, us) => {
+                let datetime = ExcelDateTime::from_ymd(*y, *m, *d)
+                    .and_then(|date| {
+                        date.and_hms(
+                            u16::from(*hh),
+                            *mm,
+                            f64::from(*ss) + f64::from(*us) / 1_000_000.0,
+                        )
+                    })
+                    .ok()
+                    .filter(|_| self.native_dates);
+                match datetime {
+                    Some(datetime) => {
+                        let format = if kind == XlsxCellKind::Date {
+                            &self.date_format
+                        } else {
+                            &self.datetime_format
+                        };
+                        worksheet.write_datetime_with_format(row, col, datetime, format)?;
+                    }
+                    None => {
+                        let date_str = format!("{y:04}-{m:02}-{d:02} {hh:02}:{mm:02}:{ss:02}");
+                        worksheet.write_string(row, col, &date_str)?;
+                    }
+                }
+            }
+            mysql::Value::Time(_neg, days, hours, mins, secs, _us) => {
+                let time_str = format!("{:02}:{:02}:{:02}", days * 24 + *hours as u32, mins, secs);
+                worksheet.write_string(row, col, &time_str)?;
+            }
+        }
+        Ok(())
+    }
+
+    // 解析失败（如 0000-00-00、非数字文本）时退回字符串
+    fn write_text(
+        &self,
+        worksheet: &mut Worksheet,
+        row: u32,
+        col: u16,
+        text: &str,
+    ) -> Result<(), XlsxError> {
+        match self.kind(col) {
+            XlsxCellKind::Text => {}
+            XlsxCellKind::Number => {
+                if let Ok(n) = text.trim().parse::<f64>() {
+                    worksheet.write_number(row, col, n)?;
+                    return Ok(());
+                }
+            }
+            XlsxCellKind::Date | XlsxCellKind::DateTime => {
+                if let Some((datetime, has_time)) = parse_excel_datetime(text) {
+                    let format = if has_time {
+                        &self.datetime_format
+                    } else {
+                        &self.date_format
+                    };
+                    worksheet.write_datetime_with_format(row, col, datetime, format)?;
+                    return Ok(());
+                }
+            }
+            XlsxCellKind::Auto => {
+                if self.native_dates {
+                    if let Some((datetime, has_time)) = parse_excel_datetime(text) {
+                        let format = if has_time {
+                            &self.datetime_format
+                        } else {
+                            &self.date_format
+                        };
+                        worksheet.write_datetime_with_format(row, col, datetime, format)?;
+                        return Ok(());
+                    }
+                }
+                if !(self.ids_as_text && looks_like_identifier(text)) {
+                    if let Ok(n) = text.parse::<f64>() {
+                        worksheet.write_number(row, col, n)?;
+                        return Ok(());
+                    }
+                }
+            }
         }
-
-        workbook
-            .save(file_path)
-            .map_err(|e| format!("Failed to save Excel file: {e}"))?;
-
-        Ok(rows_exported)
-    })
+        worksheet.write_string(row, col, text)?;
+        Ok(())
+    }
 }
 
-fn write_excel_value(
-    worksheet: &mut rust_xlsxwriter::Worksheet,
-    row: u32,
-    col: u16,
-    value: &mysql::Value,
-) -> Result<(), rust_xlsxwriter::XlsxError> {
-    match value {
-        mysql::Value::NULL => {
-            worksheet.write_string(row, col, "")?;
-        }
-        mysql::Value::Bytes(bytes) => {
-            let s = String::from_utf8_lossy(bytes);
-            worksheet.write_string(row, col, &*s)?;
-        }
-        mysql::Value::Int(v) => {
-            worksheet.write_number(row, col, *v as f64)?;
-        }
-        mysql::Value::UInt(v) => {
-            worksheet.write_number(row, col, *v as f64)?;
-        }
-        mysql::Value::Float(v) => {
-            worksheet.write_number(row, col, *v as f64)?;
-        }
-        mysql::Value::Double(v) => {
-            worksheet.write_number(row, col, *v)?;
-        }
-        mysql::Value::Date(y, m, d, hh, mm, ss, _us) => {
-            let date_str = format!("{y:04}-{m:02}-{d:02} {hh:02}:{mm:02}:{ss:02}");
-            worksheet.write_string(row, col, &date_str)?;
-        }
-        mysql::Value::Time(_neg, days, hours, mins, secs, _us) => {
-            let time_str = format!("{:02}:{:02}:{:02}", days * 24 + *hours as u32, mins, secs);
-            worksheet.write_string(row, col, &time_str)?;
-        }
+// "2024-01-31" 或 "2024-01-31 08:00:00[.ffffff]"；第二项表示是否带时间
+fn parse_excel_datetime(text: &str) -> Option<(ExcelDateTime, bool)> {
+    let text = text.trim();
+    let (datetime, has_time) =
+        match chrono::NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f") {
+            Ok(datetime) => (datetime, true),
+            Err(_) => (
+                chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                    .ok()?
+                    .and_time(chrono::NaiveTime::MIN),
+                false,
+            ),
+        };
+    let date = ExcelDateTime::from_ymd(
+        u16::try_from(datetime.year()).ok()?,
+        datetime.month() as u8,
+        datetime.day() as u8,
+    )
+    .ok()?;
+    if !has_time {
+        return Some((date, false));
     }
-    Ok(())
+    let seconds = f64::from(datetime.second())
+        + f64::from(datetime.nanosecond() % 1_000_000_000) / 1_000_000_000.0;
+    let datetime = date
+        .and_hms(datetime.hour() as u16, datetime.minute() as u8, seconds)
+        .ok()?;
+    Some((datetime, true))
+}
+
+// 带前导零（00123）或超过 15 位的纯数字，写成数字会丢失前导零或精度
+fn looks_like_identifier(text: &str) -> bool {
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    !digits.is_empty()
+        && digits.bytes().all(|b| b.is_ascii_digit())
+        && ((digits.len() > 1 && digits.starts_with('0')) || digits.len() > 15)
 }
 
 fn mysql_value_to_json(value: &mysql::Value) -> serde_json::Value {
@@ -1457,6 +3989,37 @@ fn mysql_value_to_json(value: &mysql::Value) -> serde_json::Value {
     }
 }
 
+// 文本协议下数值也以字节返回，按列类型还原为 JSON 数字；DECIMAL 保持字符串以免丢失精度
+fn typed_value_to_json(value: &mysql::Value, column_type: ColumnType) -> serde_json::Value {
+    let mysql::Value::Bytes(bytes) = value else {
+        return mysql_value_to_json(value);
+    };
+    let text = String::from_utf8_lossy(bytes);
+    match column_type {
+        ColumnType::MYSQL_TYPE_TINY
+        | ColumnType::MYSQL_TYPE_SHORT
+        | ColumnType::MYSQL_TYPE_INT24
+        | ColumnType::MYSQL_TYPE_LONG
+        | ColumnType::MYSQL_TYPE_LONGLONG
+        | ColumnType::MYSQL_TYPE_YEAR => {
+            if let Ok(v) = text.parse::<i64>() {
+                json!(v)
+            } else if let Ok(v) = text.parse::<u64>() {
+                json!(v)
+            } else {
+                json!(text.to_string())
+            }
+        }
+        ColumnType::MYSQL_TYPE_FLOAT | ColumnType::MYSQL_TYPE_DOUBLE => text
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(serde_json::Value::Number)
+            .unwrap_or_else(|| json!(text.to_string())),
+        _ => json!(text.to_string()),
+    }
+}
+
 fn mysql_value_to_sql(value: &mysql::Value) -> String {
     match value {
         mysql::Value::NULL => "NULL".to_string(),
@@ -1482,3 +4045,1459 @@ fn mysql_value_to_sql(value: &mysql::Value) -> String {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::test_support::{
+        fresh_schema, mysql_profile, run_sql, temp_path, typed_row,
+    };
+    use mysql::Column;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn table(rows: &[&[&str]]) -> Vec<Vec<String>> {
+        rows.iter().map(|row| strings(row)).collect()
+    }
+
+    fn cells(rows: &[&[&str]]) -> Vec<Vec<Option<String>>> {
+        rows.iter()
+            .map(|row| row.iter().map(|v| Some(v.to_string())).collect())
+            .collect()
+    }
+
+    #[test]
+    fn chart_series_sums_per_group_in_first_seen_order() {
+        let headers = strings(&["region", "sales", "units"]);
+        let rows = table(&[
+            &["north", "10.5", "1"],
+            &["south", "4", "2"],
+            &["north", "2", "3"],
+            &["south", "", "4"],
+        ]);
+        let chart = to_chart_series(
+            &headers,
+            &rows,
+            "region",
+            &strings(&["sales", "units"]),
+            ChartAggregation::Sum,
+        )
+        .unwrap();
+        assert_eq!(chart.labels, strings(&["north", "south"]));
+        assert_eq!(chart.series[0].name, "sales");
+        assert_eq!(chart.series[0].data, vec![12.5, 4.0]);
+        assert_eq!(chart.series[1].data, vec![4.0, 6.0]);
+
+        let chart = to_chart_series(
+            &headers,
+            &rows,
+            "REGION",
+            &strings(&["sales"]),
+            ChartAggregation::Count,
+        )
+        .unwrap();
+        assert_eq!(chart.series[0].data, vec![2.0, 1.0]);
+    }
+
+    #[test]
+    fn chart_series_rejects_non_numeric_values() {
+        let headers = strings(&["region", "sales"]);
+        let rows = table(&[&["north", "1"], &["south", "n/a"]]);
+        let err = to_chart_series(
+            &headers,
+            &rows,
+            "region",
+            &strings(&["sales"]),
+            ChartAggregation::Avg,
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err, "Row 2 column sales is not numeric: n/a");
+
+        let err = to_chart_series(
+            &headers,
+            &rows,
+            "region",
+            &strings(&["missing"]),
+            ChartAggregation::Sum,
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err, "Column not found: missing");
+    }
+
+    #[test]
+    fn throughput_uses_elapsed_time() {
+        assert_eq!(
+            compute_throughput(500, 2048, Duration::from_secs(2)),
+            (250.0, 1024.0)
+        );
+        assert_eq!(
+            compute_throughput(3, 300, Duration::from_millis(1500)),
+            (2.0, 200.0)
+        );
+        assert_eq!(compute_throughput(10, 10, Duration::ZERO), (0.0, 0.0));
+    }
+
+    #[test]
+    fn export_result_reports_throughput_from_start_time() {
+        let path = temp_path("throughput.csv");
+        std::fs::write(&path, vec![b'x'; 4000]).unwrap();
+        let start = Instant::now() - Duration::from_secs(4);
+        let result = build_query_export_result(
+            &path,
+            start,
+            &ChecksumSlot::in_memory(),
+            Ok(400),
+            &ExportJob::register(None),
+        );
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.success);
+        assert!(result.duration_ms >= 4000);
+        // 允许测试自身耗时带来的微小偏差
+        assert!(
+            (99.0..=100.0).contains(&result.rows_per_sec),
+            "{}",
+            result.rows_per_sec
+        );
+        assert!(
+            (990.0..=1000.0).contains(&result.bytes_per_sec),
+            "{}",
+            result.bytes_per_sec
+        );
+
+        let failed = build_query_export_result(
+            &path,
+            start,
+            &ChecksumSlot::in_memory(),
+            Err("boom".to_string()),
+            &ExportJob::register(None),
+        );
+        assert_eq!(failed.rows_per_sec, 0.0);
+        assert_eq!(failed.error.as_deref(), Some("boom"));
+    }
+
+    fn table_columns(names: &[&str]) -> Vec<(String, bool)> {
+        names.iter().map(|name| (name.to_string(), false)).collect()
+    }
+
+    #[test]
+    fn table_select_uses_selected_columns_in_order() {
+        let columns = table_columns(&["id", "name", "email"]);
+        let options = TableExportOptions {
+            columns: Some(strings(&["email", "id"])),
+            ..Default::default()
+        };
+        assert_eq!(
+            build_table_select_sql("shop", "users", &options, &columns),
+            "SELECT `email`, `id` FROM `shop`.`users`"
+        );
+        assert_eq!(
+            build_table_select_sql("shop", "users", &TableExportOptions::default(), &columns),
+            "SELECT * FROM `shop`.`users`"
+        );
+    }
+
+    #[test]
+    fn unknown_export_column_is_rejected() {
+        let existing = strings(&["id", "name"]);
+        assert!(check_columns_exist(&strings(&["name", "id"]), &existing).is_ok());
+        assert_eq!(
+            check_columns_exist(&strings(&["id", "nmae"]), &existing).unwrap_err(),
+            "Unknown column: nmae"
+        );
+    }
+
+    fn export_table_text(
+        profile: &ConnectionProfile,
+        schema: &str,
+        table: &str,
+        format: ExportFormat,
+        options: &TableExportOptions,
+    ) -> String {
+        let path = temp_path(&format!("{table}.{}", format.file_extension()));
+        let result = export_table_with_options(profile, schema, table, &path, format, options);
+        assert!(result.success, "{:?}", result.error);
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        content
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn sql_export_of_view_includes_create_view() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "export_view");
+        run_sql(
+            &profile,
+            &[
+                &format!("CREATE TABLE `{schema}`.`users` (id INT PRIMARY KEY, name VARCHAR(32))"),
+                &format!("INSERT INTO `{schema}`.`users` VALUES (1, 'Alice')"),
+                &format!(
+                    "CREATE VIEW `{schema}`.`user_names` AS SELECT name FROM `{schema}`.`users`"
+                ),
+            ],
+        );
+        let options = TableExportOptions {
+            include_view_definition: true,
+            ..Default::default()
+        };
+
+        let view_sql =
+            export_table_text(&profile, &schema, "user_names", ExportFormat::Sql, &options);
+        let create = view_sql
+            .find(&format!("VIEW `{schema}`.`user_names` AS"))
+            .expect("CREATE VIEW missing");
+        let insert = view_sql.find("INSERT INTO").expect("INSERT missing");
+        assert!(create < insert);
+
+        let table_sql = export_table_text(&profile, &schema, "users", ExportFormat::Sql, &options);
+        assert!(!table_sql.contains("CREATE OR REPLACE"));
+        assert!(table_sql.contains("INSERT INTO"));
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+    }
+
+    #[test]
+    fn jsonl_keeps_integers_as_numbers_and_null_as_null() {
+        let column_defs = vec![
+            Column::new(ColumnType::MYSQL_TYPE_LONG).with_name(b"id"),
+            Column::new(ColumnType::MYSQL_TYPE_VAR_STRING).with_name(b"note"),
+        ];
+        let column_types: Vec<ColumnType> = column_defs.iter().map(|c| c.column_type()).collect();
+        let row = typed_row(
+            column_defs,
+            vec![mysql::Value::Bytes(b"123".to_vec()), mysql::Value::NULL],
+        );
+        let columns = strings(&["id", "note"]);
+
+        let typed = jsonl_row_object(
+            &row,
+            &columns,
+            &column_types,
+            &TableExportOptions::default(),
+        );
+        assert_eq!(
+            serde_json::to_string(&typed).unwrap(),
+            r#"{"id":123,"note":null}"#
+        );
+
+        let options = TableExportOptions {
+            stringify_values: true,
+            ..Default::default()
+        };
+        let stringified = jsonl_row_object(&row, &columns, &column_types, &options);
+        assert_eq!(
+            serde_json::to_string(&stringified).unwrap(),
+            r#"{"id":"123","note":""}"#
+        );
+    }
+
+    #[test]
+    fn replace_rules_respect_column_scope_and_regex_groups() {
+        let headers = strings(&["name", "phone"]);
+        let rows = table(&[&["a-b", "555-1234"], &["c-d", "555-9876"]]);
+        let rules = [
+            ReplaceRule {
+                column: Some("NAME".to_string()),
+                find: "-".to_string(),
+                replace: "_".to_string(),
+                regex: false,
+            },
+            ReplaceRule {
+                column: Some("phone".to_string()),
+                find: r"^(\d{3})-(\d{4})$".to_string(),
+                replace: "($1) $2".to_string(),
+                regex: true,
+            },
+        ];
+
+        let transformed = transform_cells(&headers, rows, &rules).unwrap();
+        assert_eq!(
+            transformed,
+            table(&[&["a_b", "(555) 1234"], &["c_d", "(555) 9876"]])
+        );
+    }
+
+    #[test]
+    fn regex_replace_without_column_applies_to_every_cell() {
+        let headers = strings(&["id", "note"]);
+        let rows = table(&[&["1", "a  b"], &["22", " c "]]);
+        let rules = [ReplaceRule {
+            column: None,
+            find: r"\s+|\d".to_string(),
+            replace: "#".to_string(),
+            regex: true,
+        }];
+
+        let transformed = transform_cells(&headers, rows, &rules).unwrap();
+        assert_eq!(transformed, table(&[&["#", "a#b"], &["##", "#c#"]]));
+    }
+
+    #[test]
+    fn spatial_columns_are_wrapped_in_st_astext() {
+        let columns = vec![("id".to_string(), false), ("location".to_string(), true)];
+        let options = TableExportOptions {
+            spatial_format: Some(SpatialFormat::Wkt),
+            ..Default::default()
+        };
+        assert_eq!(
+            build_table_select_sql("geo", "places", &options, &columns),
+            "SELECT `id`, ST_AsText(`location`) AS `location` FROM `geo`.`places`"
+        );
+
+        // 表中没有空间列时保持 SELECT *
+        let plain = table_columns(&["id", "name"]);
+        assert_eq!(
+            build_table_select_sql("geo", "people", &options, &plain),
+            "SELECT * FROM `geo`.`people`"
+        );
+    }
+
+    #[test]
+    fn schema_sidecar_strips_compression_and_format_extension() {
+        let cases = [
+            ("out.csv", "out.schema.csv"),
+            ("out.csv.gz", "out.schema.csv"),
+            ("out.tar.csv", "out.tar.schema.csv"),
+            ("dir/report", "dir/report.schema.csv"),
+        ];
+        for (data, sidecar) in cases {
+            assert_eq!(schema_sidecar_path(Path::new(data)), PathBuf::from(sidecar));
+        }
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn xlsx_export_adds_schema_sheet() {
+        use calamine::Reader;
+
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "export_schema_sheet");
+        run_sql(
+            &profile,
+            &[
+                &format!(
+                    "CREATE TABLE `{schema}`.`users` (id INT NOT NULL PRIMARY KEY, \
+                     email VARCHAR(64) NULL COMMENT 'login')"
+                ),
+                &format!("INSERT INTO `{schema}`.`users` VALUES (1, 'a@example.com')"),
+            ],
+        );
+        let path = temp_path("users.xlsx");
+        let options = TableExportOptions {
+            include_schema_sheet: true,
+            ..Default::default()
+        };
+
+        let result = export_table_with_options(
+            &profile,
+            &schema,
+            "users",
+            &path,
+            ExportFormat::Xlsx,
+            &options,
+        );
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+        assert!(result.success, "{:?}", result.error);
+        let mut workbook = calamine::open_workbook_auto(&path).unwrap();
+        let sheet_names = workbook.sheet_names().to_vec();
+        let dictionary = workbook.worksheet_range("Schema").unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(sheet_names, strings(&["Sheet1", "Schema"]));
+        let rows: Vec<Vec<String>> = dictionary
+            .rows()
+            .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+            .collect();
+        assert_eq!(rows[0], strings(&SCHEMA_DICTIONARY_HEADERS));
+        let columns: Vec<(&str, &str)> = rows[1..]
+            .iter()
+            .map(|row| (row[0].as_str(), row[2].as_str()))
+            .collect();
+        assert_eq!(columns, vec![("id", "NO"), ("email", "YES")]);
+        assert_eq!(rows[2][3], "login");
+    }
+
+    fn view_definition() -> ViewDefinition {
+        ViewDefinition {
+            definition: "select `u`.`name` AS `name` from `shop`.`users` `u`;".to_string(),
+            algorithm: Some("MERGE".to_string()),
+            definer: Some("app@10.0.%".to_string()),
+            security_type: Some("DEFINER".to_string()),
+            check_option: Some("CASCADED".to_string()),
+        }
+    }
+
+    #[test]
+    fn create_view_keeps_quoted_definer() {
+        assert_eq!(quote_definer("app@10.0.%"), "`app`@`10.0.%`");
+        assert_eq!(quote_definer("we`ird@localhost"), "`we``ird`@`localhost`");
+        assert_eq!(
+            build_create_view_sql("shop", "names", &view_definition(), false),
+            "-- View structure for `names`\n\
+             CREATE OR REPLACE ALGORITHM=MERGE DEFINER=`app`@`10.0.%` SQL SECURITY DEFINER \
+             VIEW `shop`.`names` AS select `u`.`name` AS `name` from `shop`.`users` `u` \
+             WITH CASCADED CHECK OPTION;\n\n"
+        );
+    }
+
+    #[test]
+    fn create_view_can_strip_definer() {
+        let sql = build_create_view_sql("shop", "names", &view_definition(), true);
+        assert!(!sql.contains("DEFINER="), "{sql}");
+        assert!(sql.contains("CREATE OR REPLACE ALGORITHM=MERGE SQL SECURITY DEFINER VIEW"));
+    }
+
+    #[test]
+    fn streaming_checksum_matches_hash_of_written_file() {
+        let headers = strings(&["id", "name"]);
+        let rows: Vec<Vec<Option<String>>> = (0..2000)
+            .map(|i| vec![Some(i.to_string()), Some(format!("row {i}"))])
+            .collect();
+        for compress in [false, true] {
+            let path = temp_path("checksum.csv");
+            let options = QueryExportOptions {
+                compress,
+                ..Default::default()
+            };
+            let result =
+                export_query_result(&path, &headers, &rows, ExportFormat::Csv, &options).unwrap();
+            let written = std::fs::read(&result.file_path).unwrap();
+            let _ = std::fs::remove_file(&result.file_path);
+
+            let expected: String = Sha256::digest(&written)
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect();
+            assert!(result.success, "{:?}", result.error);
+            assert_eq!(result.checksum.as_deref(), Some(expected.as_str()));
+        }
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn query_to_file_streams_result_into_csv() {
+        let profile = mysql_profile();
+        let pool_id = pool::create_pool(&profile).unwrap();
+        let conn_id = pool::get_connection(pool_id, None).unwrap();
+        let path = temp_path("query.csv");
+        let options = QueryExportOptions {
+            csv: Some(CsvOptions {
+                write_bom: false,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let result = export_query_to_file(
+            pool_id,
+            conn_id,
+            "SELECT 1 AS id, 'a,b' AS name UNION ALL SELECT 2, NULL",
+            &path,
+            ExportFormat::Csv,
+            &options,
+        );
+        pool::release_connection(pool_id, conn_id).unwrap();
+        pool::close_pool(pool_id);
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.rows_exported, 2);
+        assert_eq!(content, "id,name\n1,\"a,b\"\n2,\n");
+    }
+
+    #[test]
+    fn interactive_html_embeds_sort_and_search_script() {
+        let headers = strings(&["id", "name"]);
+        let rows = cells(&[&["1", "Alice"], &["2", "Bob"]]);
+        let render = |interactive: bool| {
+            let options = QueryExportOptions {
+                interactive,
+                ..Default::default()
+            };
+            export_query_result_to_string(&headers, &rows, ExportFormat::Html, &options).unwrap()
+        };
+
+        let interactive = render(true);
+        assert!(interactive.contains(r#"<input id="dwb-search" type="search""#));
+        assert!(interactive.contains(r#"<table id="dwb-table">"#));
+        assert!(interactive.contains("<script>"));
+        assert!(!interactive.contains("<script src="));
+        assert!(interactive.trim_end().ends_with("</html>"));
+
+        let static_html = render(false);
+        assert!(!static_html.contains("<script"));
+        assert!(!static_html.contains("dwb-search"));
+        assert!(static_html.contains("<td>Alice</td>"));
+    }
+
+    #[test]
+    fn tab_fields_escape_like_mysqldump() {
+        let mut line = Vec::new();
+        write_tab_field(&mut line, &mysql::Value::NULL);
+        line.push(b'\t');
+        write_tab_field(&mut line, &mysql::Value::Bytes(b"a\tb\nc\\d".to_vec()));
+        line.push(b'\t');
+        write_tab_field(&mut line, &mysql::Value::Int(-7));
+        assert_eq!(line, b"\\N\ta\\tb\\nc\\\\d\t-7");
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn tab_export_writes_schema_and_data_files() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "export_tab");
+        run_sql(
+            &profile,
+            &[
+                &format!("CREATE TABLE `{schema}`.`users` (id INT PRIMARY KEY, name VARCHAR(32))"),
+                &format!("INSERT INTO `{schema}`.`users` VALUES (1, 'Ann\tLee'), (2, NULL)"),
+            ],
+        );
+        let out_dir = temp_path("tab");
+
+        let result = export_table_tab(&profile, &schema, "users", &out_dir);
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+        let result = result.unwrap();
+        let ddl = std::fs::read_to_string(&result.schema_file).unwrap();
+        let data = std::fs::read_to_string(&result.data_file).unwrap();
+        std::fs::remove_dir_all(&out_dir).unwrap();
+
+        assert_eq!(result.rows_exported, 2);
+        assert_eq!(Path::new(&result.schema_file), out_dir.join("users.sql"));
+        assert_eq!(Path::new(&result.data_file), out_dir.join("users.txt"));
+        assert!(
+            ddl.starts_with("DROP TABLE IF EXISTS `users`;\nCREATE TABLE `users`"),
+            "{ddl}"
+        );
+        assert!(ddl.trim_end().ends_with(';'));
+        assert_eq!(data, "1\tAnn\\tLee\n2\t\\N\n");
+    }
+
+    #[test]
+    fn parquet_round_trips_typed_columns_and_row_count() {
+        use arrow_array::{Array, Date32Array, Float64Array, Int64Array, StringArray};
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let (columns, fields): (Vec<ParquetColumn>, Vec<Field>) = [
+            Column::new(ColumnType::MYSQL_TYPE_LONGLONG).with_name(b"id"),
+            Column::new(ColumnType::MYSQL_TYPE_DOUBLE).with_name(b"score"),
+            Column::new(ColumnType::MYSQL_TYPE_DATE).with_name(b"day"),
+            Column::new(ColumnType::MYSQL_TYPE_VAR_STRING).with_name(b"name"),
+        ]
+        .iter()
+        .map(|column| {
+            let (builder, data_type) = ParquetColumn::for_column(column);
+            (builder, Field::new(column.name_str(), data_type, true))
+        })
+        .unzip();
+        let path = temp_path("typed.parquet");
+        let mut writer =
+            ParquetBatchWriter::create(&path, fields, columns, &ChecksumSlot::new(false)).unwrap();
+        writer
+            .append_row(&[
+                mysql::Value::Int(1),
+                mysql::Value::Double(1.5),
+                mysql::Value::Date(2024, 2, 29, 0, 0, 0, 0),
+                mysql::Value::Bytes(b"Ann".to_vec()),
+            ])
+            .unwrap();
+        writer
+            .append_row(&[
+                mysql::Value::Int(2),
+                mysql::Value::NULL,
+                mysql::Value::NULL,
+                mysql::Value::NULL,
+            ])
+            .unwrap();
+        writer.finish().unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(Result::unwrap).collect();
+        let _ = std::fs::remove_file(&path);
+
+        let schema = batches[0].schema();
+        let types: Vec<_> = schema
+            .fields()
+            .iter()
+            .map(|f| (f.name().as_str(), f.data_type().clone()))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ("id", DataType::Int64),
+                ("score", DataType::Float64),
+                ("day", DataType::Date32),
+                ("name", DataType::Utf8),
+            ]
+        );
+        assert_eq!(batches.iter().map(RecordBatch::num_rows).sum::<usize>(), 2);
+
+        let batch = &batches[0];
+        let ids = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!(ids.values(), &[1, 2]);
+        let scores = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(scores.value(0), 1.5);
+        assert!(scores.is_null(1));
+        let days = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<Date32Array>()
+            .unwrap();
+        assert_eq!(
+            days.value_as_date(0),
+            chrono::NaiveDate::from_ymd_opt(2024, 2, 29)
+        );
+        let names = batch
+            .column(3)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(names.value(0), "Ann");
+        assert!(names.is_null(1));
+    }
+
+    #[test]
+    fn markdown_export_writes_exact_pipe_table() {
+        let path = temp_path("table.md");
+        let headers = strings(&["id", "note"]);
+        let rows = vec![
+            vec![Some("1".to_string()), Some("a|b".to_string())],
+            vec![Some("2".to_string()), None],
+        ];
+        let result = export_query_result(
+            &path,
+            &headers,
+            &rows,
+            ExportFormat::Markdown,
+            &QueryExportOptions::default(),
+        )
+        .unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.rows_exported, 2);
+        assert_eq!(
+            written,
+            "| id | note |\n| --- | --- |\n| 1 | a\\|b |\n| 2 |  |\n"
+        );
+        assert_eq!(escape_markdown_cell("x\r\ny\nz"), "x<br>y<br>z");
+    }
+
+    #[test]
+    fn gzip_export_decompresses_to_plain_output_with_bom() {
+        use flate2::read::GzDecoder;
+        use std::io::Read;
+
+        let headers = strings(&["id", "name"]);
+        let rows = cells(&[&["1", "Ann"], &["2", "李雷"]]);
+        let mut outputs = Vec::new();
+        for compress in [false, true] {
+            let options = QueryExportOptions {
+                compress,
+                ..Default::default()
+            };
+            let result = export_query_result(
+                &temp_path("gzip.csv"),
+                &headers,
+                &rows,
+                ExportFormat::Csv,
+                &options,
+            )
+            .unwrap();
+            assert!(result.success, "{:?}", result.error);
+            assert_eq!(result.file_path.ends_with(".gz"), compress);
+            let written = std::fs::read(&result.file_path).unwrap();
+            let _ = std::fs::remove_file(&result.file_path);
+            let expected: String = Sha256::digest(&written)
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect();
+            assert_eq!(result.checksum.as_deref(), Some(expected.as_str()));
+            outputs.push(written);
+        }
+
+        let mut decompressed = Vec::new();
+        GzDecoder::new(outputs[1].as_slice())
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert!(decompressed.starts_with(b"\xEF\xBB\xBF"));
+        assert_eq!(decompressed, outputs[0]);
+    }
+
+    #[test]
+    fn csv_options_semicolon_delimiter_without_bom() {
+        let path = temp_path("semicolon.csv");
+        let headers = strings(&["id", "note"]);
+        let rows = cells(&[&["1", "a;b"], &["2", "c,d"]]);
+        let options = QueryExportOptions {
+            csv: Some(CsvOptions {
+                delimiter: b';',
+                write_bom: false,
+                line_terminator: "\r\n".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        export_query_result(&path, &headers, &rows, ExportFormat::Csv, &options).unwrap();
+        let written = std::fs::read(&path).unwrap();
+
+        assert_eq!(written, b"id;note\r\n1;\"a;b\"\r\n2;c,d\r\n");
+
+        export_query_result(
+            &path,
+            &headers,
+            &rows,
+            ExportFormat::Csv,
+            &QueryExportOptions::default(),
+        )
+        .unwrap();
+        let written = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(written.starts_with(b"\xEF\xBB\xBFid,note\n"));
+
+        let same = CsvOptions {
+            quote: b';',
+            delimiter: b';',
+            ..Default::default()
+        };
+        assert!(same.validate().is_err());
+    }
+
+    #[test]
+    fn csv_options_deserialize_from_single_character_strings() {
+        let options: CsvOptions =
+            serde_json::from_value(json!({"delimiter": ";", "quote": "'"})).unwrap();
+        assert_eq!(options.delimiter, b';');
+        assert_eq!(options.quote, b'\'');
+        assert!(options.write_bom);
+        assert_eq!(options.line_terminator, "\n");
+
+        let options: CsvOptions = serde_json::from_value(json!({"delimiter": "\t"})).unwrap();
+        assert_eq!(options.delimiter, b'\t');
+        assert_eq!(options.quote, b'"');
+
+        for bad in [
+            json!({"delimiter": ";;"}),
+            json!({"quote": ""}),
+            json!({"delimiter": "；"}),
+        ] {
+            assert!(serde_json::from_value::<CsvOptions>(bad).is_err());
+        }
+    }
+
+    #[test]
+    fn null_cells_use_token_in_text_and_null_in_json() {
+        let headers = strings(&["id", "note"]);
+        let rows = vec![
+            vec![Some("1".to_string()), None],
+            vec![Some("2".to_string()), Some(String::new())],
+        ];
+        let render = |format: ExportFormat, null_token: Option<&str>| {
+            let options = QueryExportOptions {
+                null_token: null_token.map(str::to_string),
+                ..Default::default()
+            };
+            export_query_result_to_string(&headers, &rows, format, &options).unwrap()
+        };
+
+        assert_eq!(render(ExportFormat::Csv, None), "id,note\n1,\n2,\n");
+        assert_eq!(
+            render(ExportFormat::Csv, Some("\\N")),
+            "id,note\n1,\\N\n2,\n"
+        );
+        assert_eq!(
+            render(ExportFormat::Jsonl, None),
+            "{\"id\":\"1\",\"note\":null}\n{\"id\":\"2\",\"note\":\"\"}\n"
+        );
+        assert_eq!(
+            render(ExportFormat::Jsonl, Some("NULL")),
+            "{\"id\":\"1\",\"note\":\"NULL\"}\n{\"id\":\"2\",\"note\":\"\"}\n"
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render(ExportFormat::Json, None)).unwrap();
+        assert_eq!(json[0]["note"], serde_json::Value::Null);
+        assert_eq!(json[1]["note"], json!(""));
+        let json: serde_json::Value =
+            serde_json::from_str(&render(ExportFormat::Json, Some("NULL"))).unwrap();
+        assert_eq!(json[0]["note"], json!("NULL"));
+
+        // SQL 只把 NULL 写成 NULL，空字符串保持 ''，不受 null_token 影响
+        let expected_sql = "INSERT INTO `table_name` (`id`, `note`) VALUES ('1', NULL);\n\
+                            INSERT INTO `table_name` (`id`, `note`) VALUES ('2', '');\n";
+        assert_eq!(render(ExportFormat::Sql, None), expected_sql);
+        assert_eq!(render(ExportFormat::Sql, Some("\\N")), expected_sql);
+    }
+
+    #[test]
+    fn query_parquet_keeps_null_distinct_from_empty_string() {
+        use arrow_array::{Array, StringArray};
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let headers = strings(&["note"]);
+        let rows = [vec![None], vec![Some(String::new())]];
+        let path = temp_path("query_nulls.parquet");
+        let options = QueryExportOptions {
+            null_token: Some("\\N".to_string()),
+            ..Default::default()
+        };
+        write_query_export(
+            &path,
+            &headers,
+            rows.iter().map(Ok),
+            ExportFormat::Parquet,
+            &options,
+            &[],
+            &ChecksumSlot::new(false),
+        )
+        .unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(Result::unwrap).collect();
+        let _ = std::fs::remove_file(&path);
+        let notes = batches[0]
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert!(notes.is_null(0));
+        assert!(!notes.is_null(1));
+        assert_eq!(notes.value(1), "");
+    }
+
+    #[test]
+    fn query_spec_appends_where_order_and_limit() {
+        let columns = table_columns(&["id", "name", "created_at"]);
+        let options = TableExportOptions {
+            columns: Some(strings(&["id", "name", "created_at"])),
+            query_spec: Some(ExportQuerySpec {
+                where_clause: Some(" created_at >= '2024-05-01' ".to_string()),
+                order_by: Some("id DESC".to_string()),
+                limit: Some(100),
+                columns: Some(strings(&["na`me", "id"])),
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            build_table_select_sql("shop", "orders", &options, &columns),
+            "SELECT `na``me`, `id` FROM `shop`.`orders` \
+             WHERE created_at >= '2024-05-01' ORDER BY id DESC LIMIT 100"
+        );
+
+        let spec = |where_clause: &str| ExportQuerySpec {
+            where_clause: Some(where_clause.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            spec("1=1; DROP TABLE orders").validate().unwrap_err(),
+            "WHERE clause must not contain ';'"
+        );
+        for clause in [
+            "total > 10 -- newest",
+            "total > 10 # newest",
+            "total /* x */ > 10",
+        ] {
+            assert_eq!(
+                spec(clause).validate().unwrap_err(),
+                "WHERE clause must not contain comments",
+                "{clause}"
+            );
+        }
+        for clause in [
+            "note = 'a;b'",
+            "note = \"x -- y\" AND tag <> '#1'",
+            "`a;b` = 'it''s; fine' AND c = 'back\\'slash;'",
+        ] {
+            assert!(spec(clause).validate().is_ok(), "{clause}");
+        }
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn query_spec_limits_exported_rows_and_columns() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "export_spec");
+        run_sql(
+            &profile,
+            &[
+                &format!(
+                    "CREATE TABLE `{schema}`.`orders` (id INT PRIMARY KEY, name VARCHAR(32), total INT)"
+                ),
+                &format!(
+                    "INSERT INTO `{schema}`.`orders` VALUES (1, 'a', 10), (2, 'b', 20), (3, 'c', 30)"
+                ),
+            ],
+        );
+        let options = TableExportOptions {
+            query_spec: Some(ExportQuerySpec {
+                where_clause: Some("total > 10".to_string()),
+                order_by: Some("id DESC".to_string()),
+                limit: Some(1),
+                columns: Some(strings(&["name", "id"])),
+            }),
+            ..Default::default()
+        };
+        let csv = export_table_text(&profile, &schema, "orders", ExportFormat::Csv, &options);
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+        assert_eq!(csv.trim_start_matches('\u{feff}'), "name,id\nc,3\n");
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn query_to_file_streams_large_result_row_by_row() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "export_stream");
+        run_sql(
+            &profile,
+            &[
+                &format!("CREATE TABLE `{schema}`.`digits` (d INT PRIMARY KEY)"),
+                &format!(
+                    "INSERT INTO `{schema}`.`digits` VALUES (0),(1),(2),(3),(4),(5),(6),(7),(8),(9)"
+                ),
+            ],
+        );
+        let pool_id = pool::create_pool(&profile).unwrap();
+        let conn_id = pool::get_connection(pool_id, None).unwrap();
+        let path = temp_path("stream.jsonl");
+        let sql = format!(
+            "SELECT a.d + b.d * 10 + c.d * 100 + e.d * 1000 + f.d * 10000 AS n \
+             FROM `{schema}`.`digits` a, `{schema}`.`digits` b, `{schema}`.`digits` c, \
+             `{schema}`.`digits` e, `{schema}`.`digits` f ORDER BY n"
+        );
+
+        let result = export_query_to_file(
+            pool_id,
+            conn_id,
+            &sql,
+            &path,
+            ExportFormat::Jsonl,
+            &QueryExportOptions::default(),
+        );
+        pool::release_connection(pool_id, conn_id).unwrap();
+        pool::close_pool(pool_id);
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.rows_exported, 100_000);
+        assert_eq!(content.lines().count(), 100_000);
+        assert_eq!(content.lines().last(), Some("{\"n\":\"99999\"}"));
+    }
+
+    #[test]
+    fn query_xlsx_splits_rows_across_sheets_with_header() {
+        use calamine::Reader;
+
+        let path = temp_path("split.xlsx");
+        let headers = strings(&["id", "name"]);
+        let rows = table(&[
+            &["1", "a"],
+            &["2", "b"],
+            &["3", "c"],
+            &["4", "d"],
+            &["5", "e"],
+        ]);
+        let exported = do_export_query_xlsx(
+            &path,
+            &headers,
+            rows.iter().map(Ok),
+            None,
+            &XlsxTypeOptions::default(),
+            2,
+            &ChecksumSlot::new(false),
+        )
+        .unwrap();
+        let mut workbook = calamine::open_workbook_auto(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(exported, 5);
+        assert_eq!(
+            workbook.sheet_names(),
+            strings(&["Sheet1", "Sheet2", "Sheet3"])
+        );
+        let sheets: Vec<Vec<Vec<String>>> = ["Sheet1", "Sheet2", "Sheet3"]
+            .iter()
+            .map(|name| {
+                workbook
+                    .worksheet_range(name)
+                    .unwrap()
+                    .rows()
+                    .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            sheets,
+            vec![
+                table(&[&["id", "name"], &["1", "a"], &["2", "b"]]),
+                table(&[&["id", "name"], &["3", "c"], &["4", "d"]]),
+                table(&[&["id", "name"], &["5", "e"]]),
+            ]
+        );
+    }
+
+    #[test]
+    fn xlsx_text_columns_keep_leading_zeros() {
+        use calamine::{Data, Reader};
+
+        let columns = [
+            Column::new(ColumnType::MYSQL_TYPE_VAR_STRING).with_name(b"code"),
+            Column::new(ColumnType::MYSQL_TYPE_LONG).with_name(b"qty"),
+            Column::new(ColumnType::MYSQL_TYPE_DATE).with_name(b"day"),
+        ];
+        let options = XlsxTypeOptions {
+            native_dates: true,
+            ..Default::default()
+        };
+        let cell_writer = XlsxCellWriter::for_columns(&columns, &options);
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        for (col, value) in [
+            mysql::Value::Bytes(b"00123".to_vec()),
+            mysql::Value::Bytes(b"42".to_vec()),
+            mysql::Value::Bytes(b"2024-02-29".to_vec()),
+        ]
+        .iter()
+        .enumerate()
+        {
+            cell_writer
+                .write_value(worksheet, 0, col as u16, value)
+                .unwrap();
+        }
+        // 查询结果没有列类型：idsAsText 时带前导零的数字按文本写入
+        let query_writer = XlsxCellWriter::for_headers(
+            &strings(&["code"]),
+            &XlsxTypeOptions {
+                ids_as_text: true,
+                ..Default::default()
+            },
+        );
+        query_writer.write_text(worksheet, 1, 0, "007").unwrap();
+        query_writer.write_text(worksheet, 1, 1, "8").unwrap();
+
+        let path = temp_path("types.xlsx");
+        workbook.save(&path).unwrap();
+        let mut reader = calamine::open_workbook_auto(&path).unwrap();
+        let range = reader.worksheet_range("Sheet1").unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(range.get((0, 0)), Some(&Data::String("00123".to_string())));
+        assert_eq!(range.get((0, 1)), Some(&Data::Float(42.0)));
+        assert!(matches!(range.get((0, 2)), Some(Data::DateTime(_))));
+        assert_eq!(range.get((1, 0)), Some(&Data::String("007".to_string())));
+        assert_eq!(range.get((1, 1)), Some(&Data::Float(8.0)));
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn sql_export_writes_create_table_before_inserts() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "export_ddl");
+        run_sql(
+            &profile,
+            &[
+                &format!("CREATE TABLE `{schema}`.`items` (id INT PRIMARY KEY, name VARCHAR(32))"),
+                &format!("INSERT INTO `{schema}`.`items` VALUES (1, 'pen'), (2, 'ink')"),
+            ],
+        );
+        let options = TableExportOptions {
+            sql: SqlExportOptions {
+                add_drop_table: true,
+                include_create: true,
+            },
+            ..Default::default()
+        };
+        let sql = export_table_text(&profile, &schema, "items", ExportFormat::Sql, &options);
+
+        let position = |needle: &str| {
+            sql.find(needle)
+                .unwrap_or_else(|| panic!("{needle} missing in {sql}"))
+        };
+        let guard = position("SET FOREIGN_KEY_CHECKS = 0;");
+        let drop = position(&format!("DROP TABLE IF EXISTS `{schema}`.`items`;"));
+        let create = position(&format!("CREATE TABLE `{schema}`.`items`"));
+        let insert = position("INSERT INTO");
+        assert!(guard < drop && drop < create && create < insert, "{sql}");
+        assert_eq!(sql.matches("INSERT INTO").count(), 2);
+        assert!(
+            sql.trim_end().ends_with("SET FOREIGN_KEY_CHECKS = 1;"),
+            "{sql}"
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn table_json_exports_numbers_unquoted() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "export_json_types");
+        run_sql(
+            &profile,
+            &[
+                &format!(
+                    "CREATE TABLE `{schema}`.`items` \
+                     (id INT PRIMARY KEY, ratio DOUBLE, code VARCHAR(8), note VARCHAR(8))"
+                ),
+                &format!("INSERT INTO `{schema}`.`items` VALUES (123, 0.5, '001', NULL)"),
+            ],
+        );
+        let parse = |format: ExportFormat, options: &TableExportOptions| {
+            let text = export_table_text(&profile, &schema, "items", format, options);
+            match format {
+                ExportFormat::Json => {
+                    serde_json::from_str::<serde_json::Value>(&text).unwrap()[0].clone()
+                }
+                _ => serde_json::from_str::<serde_json::Value>(text.trim_end()).unwrap(),
+            }
+        };
+
+        for format in [ExportFormat::Json, ExportFormat::Jsonl] {
+            let typed = parse(format, &TableExportOptions::default());
+            assert_eq!(
+                typed,
+                json!({"id": 123, "ratio": 0.5, "code": "001", "note": null})
+            );
+            let stringified = parse(
+                format,
+                &TableExportOptions {
+                    stringify_values: true,
+                    ..Default::default()
+                },
+            );
+            assert_eq!(stringified["id"], json!("123"));
+            assert_eq!(stringified["code"], json!("001"));
+        }
+    }
+
+    #[test]
+    fn query_result_exports_to_string_without_file() {
+        let headers = strings(&["id", "name"]);
+        let rows = cells(&[&["1", "Ann"], &["2", "Lee, Bo"]]);
+        let options = QueryExportOptions::default();
+
+        let csv = export_query_result_to_string(&headers, &rows, ExportFormat::Csv, &options);
+        assert_eq!(csv.unwrap(), "id,name\n1,Ann\n2,\"Lee, Bo\"\n");
+
+        let json = export_query_result_to_string(&headers, &rows, ExportFormat::Json, &options);
+        let json: serde_json::Value = serde_json::from_str(&json.unwrap()).unwrap();
+        assert_eq!(
+            json,
+            json!([{"id": 1, "name": "Ann"}, {"id": 2, "name": "Lee, Bo"}])
+        );
+
+        let xlsx = export_query_result_to_string(&headers, &rows, ExportFormat::Xlsx, &options);
+        let bytes = STANDARD.decode(xlsx.unwrap()).unwrap();
+        assert!(bytes.starts_with(b"PK"));
+    }
+
+    #[test]
+    fn cancelled_export_reports_rows_written_before_cancel() {
+        let path = temp_path("cancel.csv");
+        let job_id = "test-cancel-mid-export";
+        let headers = strings(&["n"]);
+        let rows: Vec<Vec<Option<String>>> =
+            (0..30_000).map(|i| vec![Some(i.to_string())]).collect();
+        // 第一次进度回调（10000 行）时取消，下一次检查（11000 行）中止导出
+        watch_export(
+            job_id,
+            Arc::new(move |progress: &ExportProgress| {
+                if !progress.done {
+                    cancel_export(job_id);
+                }
+            }),
+        );
+        let options = QueryExportOptions {
+            job_id: Some(job_id.to_string()),
+            csv: Some(CsvOptions {
+                write_bom: false,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let result =
+            export_query_result(&path, &headers, &rows, ExportFormat::Csv, &options).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert!(!result.success);
+        assert_eq!(result.error.as_deref(), Some(EXPORT_CANCELLED));
+        assert_eq!(result.rows_exported, 11_000);
+        assert_eq!(content.lines().count(), 11_001);
+        assert!(
+            !cancel_export(job_id),
+            "job should be unregistered once the export ends"
+        );
+    }
+
+    #[test]
+    fn progress_is_reported_every_interval_and_on_finish() {
+        let path = temp_path("progress.jsonl");
+        let job_id = "test-progress-events";
+        let headers = strings(&["n"]);
+        let rows: Vec<Vec<Option<String>>> =
+            (0..25_000).map(|i| vec![Some(i.to_string())]).collect();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        watch_export(
+            job_id,
+            Arc::new(move |progress: &ExportProgress| {
+                sink.lock()
+                    .unwrap()
+                    .push((progress.rows_exported, progress.done));
+            }),
+        );
+        let options = QueryExportOptions {
+            job_id: Some(job_id.to_string()),
+            ..Default::default()
+        };
+        let result =
+            export_query_result(&path, &headers, &rows, ExportFormat::Jsonl, &options).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![(10_000, false), (20_000, false), (25_000, true)]
+        );
+        assert_eq!(
+            serde_json::to_value(ExportProgress {
+                rows_exported: 5,
+                done: true
+            })
+            .unwrap(),
+            json!({"rowsExported": 5, "done": true})
+        );
+    }
+
+    #[test]
+    fn binary_cells_encode_non_utf8_bytes() {
+        let row = || {
+            typed_row(
+                vec![
+                    Column::new(ColumnType::MYSQL_TYPE_BLOB)
+                        .with_name(b"payload")
+                        .with_character_set(BINARY_CHARSET_ID),
+                    Column::new(ColumnType::MYSQL_TYPE_BLOB)
+                        .with_name(b"body")
+                        .with_character_set(255),
+                ],
+                vec![
+                    mysql::Value::Bytes(vec![0xFF, 0x00, 0xFE]),
+                    mysql::Value::Bytes(b"text".to_vec()),
+                ],
+            )
+        };
+        let encoded = |encoding| {
+            encode_binary_cells(row(), encoding)
+                .unwrap()
+                .iter()
+                .map(value_to_string)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(encoded(BinaryEncoding::Hex), strings(&["0xFF00FE", "text"]));
+        assert_eq!(encoded(BinaryEncoding::Base64), strings(&["/wD+", "text"]));
+        assert_eq!(
+            encoded(BinaryEncoding::Utf8Lossy),
+            strings(&["\u{fffd}\0\u{fffd}", "text"])
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn blob_column_exports_as_hex_literal_in_sql() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "export_blob");
+        run_sql(
+            &profile,
+            &[
+                &format!("CREATE TABLE `{schema}`.`files` (id INT PRIMARY KEY, data BLOB)"),
+                &format!("INSERT INTO `{schema}`.`files` VALUES (1, X'FF00FE'), (2, X'')"),
+            ],
+        );
+        let options = TableExportOptions {
+            binary_encoding: BinaryEncoding::Hex,
+            ..Default::default()
+        };
+        let sql = export_table_text(&profile, &schema, "files", ExportFormat::Sql, &options);
+        assert!(sql.contains(", 0xFF00FE);\n"), "{sql}");
+        assert!(sql.contains(", X'');\n"), "{sql}");
+
+        // 查询导出同样写成字面量，而不是带引号的 '0xFF00FE' 字符串
+        let pool_id = pool::create_pool(&profile).unwrap();
+        let conn_id = pool::get_connection(pool_id, None).unwrap();
+        let path = temp_path("files_query.sql");
+        let query_options = QueryExportOptions {
+            binary_encoding: BinaryEncoding::Hex,
+            ..Default::default()
+        };
+        let result = export_query_to_file(
+            pool_id,
+            conn_id,
+            &format!("SELECT id, data FROM `{schema}`.`files` ORDER BY id"),
+            &path,
+            ExportFormat::Sql,
+            &query_options,
+        );
+        pool::release_connection(pool_id, conn_id).unwrap();
+        pool::close_pool(pool_id);
+        let query_sql = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(
+            query_sql.contains("VALUES ('1', 0xFF00FE);\n"),
+            "{query_sql}"
+        );
+        assert!(query_sql.contains("VALUES ('2', X'');\n"), "{query_sql}");
+
+        let options = TableExportOptions {
+            binary_encoding: BinaryEncoding::Base64,
+            ..Default::default()
+        };
+        let csv = export_table_text(&profile, &schema, "files", ExportFormat::Csv, &options);
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+        assert_eq!(csv.trim_start_matches('\u{feff}'), "id,data\n1,/wD+\n2,\n");
+    }
+
+    #[test]
+    fn tab_export_file_names_stay_inside_output_dir() {
+        assert_eq!(file_stem_for("orders"), "orders");
+        assert_eq!(file_stem_for("../../etc/passwd"), ".._.._etc_passwd");
+        assert_eq!(file_stem_for("a\\b:c"), "a_b_c");
+        assert_eq!(file_stem_for(".."), "__");
+        let path = Path::new("/out").join(format!("{}.sql", file_stem_for("../x")));
+        assert_eq!(path.parent(), Some(Path::new("/out")));
+    }
+
+    #[test]
+    fn sql_binary_literals_are_written_unquoted() {
+        assert_eq!(hex_literal(&[]), "X''");
+        assert_eq!(hex_literal(&[0xFF, 0x01]), "0xFF01");
+
+        let headers = strings(&["id", "data"]);
+        let rows = [vec![Some("1".to_string()), Some(hex_literal(&[0xAB]))]];
+        let path = temp_path("literal.sql");
+        write_query_export(
+            &path,
+            &headers,
+            rows.iter().map(Ok),
+            ExportFormat::Sql,
+            &QueryExportOptions::default(),
+            &[1],
+            &ChecksumSlot::new(false),
+        )
+        .unwrap();
+        let sql = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            sql,
+            "INSERT INTO `table_name` (`id`, `data`) VALUES ('1', 0xAB);\n"
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn appending_sql_export_writes_ddl_only_once() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "export_append_sql");
+        run_sql(
+            &profile,
+            &[
+                &format!("CREATE TABLE `{schema}`.`items` (id INT PRIMARY KEY)"),
+                &format!("INSERT INTO `{schema}`.`items` VALUES (1)"),
+            ],
+        );
+        let options = TableExportOptions {
+            append: true,
+            sql: SqlExportOptions {
+                add_drop_table: true,
+                include_create: true,
+            },
+            ..Default::default()
+        };
+        let path = temp_path("append_items.sql");
+        for _ in 0..2 {
+            let result = export_table_with_options(
+                &profile,
+                &schema,
+                "items",
+                &path,
+                ExportFormat::Sql,
+                &options,
+            );
+            assert!(result.success, "{:?}", result.error);
+        }
+        let sql = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert_eq!(sql.matches("DROP TABLE").count(), 1, "{sql}");
+        assert_eq!(sql.matches("CREATE TABLE").count(), 1, "{sql}");
+        assert_eq!(
+            sql.matches("SET FOREIGN_KEY_CHECKS = 0").count(),
+            1,
+            "{sql}"
+        );
+        assert_eq!(sql.matches("INSERT INTO").count(), 2, "{sql}");
+    }
+
+    #[test]
+    fn appending_twice_keeps_a_single_header() {
+        let headers = strings(&["id", "name"]);
+        let batches = [
+            cells(&[&["1", "Ann"]]),
+            cells(&[&["2", "Bo"], &["3", "Cy"]]),
+        ];
+        let options = QueryExportOptions {
+            append: true,
+            ..Default::default()
+        };
+
+        let path = temp_path("append.csv");
+        let counts: Vec<u64> = batches
+            .iter()
+            .map(|rows| {
+                export_query_result(&path, &headers, rows, ExportFormat::Csv, &options)
+                    .unwrap()
+                    .rows_exported
+            })
+            .collect();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(counts, vec![1, 2]);
+        assert_eq!(csv, "\u{feff}id,name\n1,Ann\n2,Bo\n3,Cy\n");
+
+        // JSON 数组无法追加，按 JSONL 逐行写出
+        let path = temp_path("append.json");
+        for rows in &batches {
+            export_query_result(&path, &headers, rows, ExportFormat::Json, &options).unwrap();
+        }
+        let jsonl = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let parsed: Vec<serde_json::Value> = jsonl
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(parsed.len(), 3);
+        assert_eq!(parsed[2], json!({"id": "3", "name": "Cy"}));
+    }
+}
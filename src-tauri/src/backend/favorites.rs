@@ -91,6 +91,11 @@ pub fn clear_all() -> Result<(), String> {
     save_store(&[])
 }
 
+/// 整体替换收藏列表，保留条目原有的 id 和使用统计
+pub fn replace_all(items: &[FavoriteItem]) -> Result<(), String> {
+    save_store(items)
+}
+
 pub fn total() -> Result<i32, String> {
     let items = load_store()?;
     Ok(items.len() as i32)
@@ -114,6 +119,11 @@ fn normalize_item(item: &mut FavoriteItem) {
     if item.last_used_time == 0 {
         item.last_used_time = item.created_time;
     }
+    for field in [&mut item.description, &mut item.author] {
+        if field.as_deref().is_some_and(|v| v.trim().is_empty()) {
+            *field = None;
+        }
+    }
 }
 
 fn matches_keyword(item: &FavoriteItem, keyword: &str) -> bool {
@@ -126,6 +136,11 @@ fn matches_keyword(item: &FavoriteItem, keyword: &str) -> bool {
             return true;
         }
     }
+    if let Some(author) = &item.author {
+        if author.to_ascii_lowercase().contains(keyword) {
+            return true;
+        }
+    }
     if let Some(content) = &item.content {
         if content.to_ascii_lowercase().contains(keyword) {
             return true;
@@ -166,7 +181,17 @@ fn save_store(items: &[FavoriteItem]) -> Result<(), String> {
     Ok(())
 }
 
+#[cfg(test)]
+thread_local! {
+    // 测试时把收藏存储重定向到临时文件，避免读写用户目录
+    static TEST_STORE_PATH: std::cell::RefCell<Option<PathBuf>> = const { std::cell::RefCell::new(None) };
+}
+
 fn store_path() -> Result<PathBuf, String> {
+    #[cfg(test)]
+    if let Some(path) = TEST_STORE_PATH.with(|path| path.borrow().clone()) {
+        return Ok(path);
+    }
     let home = home_dir().ok_or_else(|| "Failed to resolve home directory".to_string())?;
     Ok(home.join(".dbworkbench").join("favorites.dat"))
 }
@@ -199,3 +224,69 @@ fn now_ms() -> i64 {
         .map(|d| d.as_millis() as i64)
         .unwrap_or(0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::test_support::temp_path;
+
+    fn with_temp_store<T>(action: impl FnOnce() -> T) -> T {
+        let path = temp_path("favorites.dat");
+        TEST_STORE_PATH.with(|slot| *slot.borrow_mut() = Some(path.clone()));
+        let result = action();
+        TEST_STORE_PATH.with(|slot| *slot.borrow_mut() = None);
+        let _ = fs::remove_file(&path);
+        result
+    }
+
+    fn snippet(name: &str, description: Option<&str>, author: Option<&str>) -> FavoriteItem {
+        FavoriteItem {
+            id: None,
+            name: name.to_string(),
+            description: description.map(str::to_string),
+            author: author.map(str::to_string),
+            favorite_type: FavoriteType::SqlQuery,
+            content: Some("SELECT 1".to_string()),
+            created_time: 0,
+            last_used_time: 0,
+            usage_count: 0,
+        }
+    }
+
+    #[test]
+    fn description_and_author_round_trip_through_add_and_get() {
+        let (added, loaded) = with_temp_store(|| {
+            let added = add(snippet(
+                "revenue",
+                Some("Run after the nightly ETL; excludes refunds"),
+                Some("ops"),
+            ))
+            .unwrap();
+            let loaded = get(added.id.as_deref().unwrap()).unwrap();
+            (added, loaded)
+        });
+
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.id, added.id);
+        assert_eq!(
+            loaded.description.as_deref(),
+            Some("Run after the nightly ETL; excludes refunds")
+        );
+        assert_eq!(loaded.author.as_deref(), Some("ops"));
+    }
+
+    #[test]
+    fn search_matches_description_and_blank_fields_are_dropped() {
+        let (found, blank) = with_temp_store(|| {
+            add(snippet("revenue", Some("Excludes REFUNDS"), Some("ops"))).unwrap();
+            add(snippet("churn", None, None)).unwrap();
+            let blank = add(snippet("blank", Some("   "), Some(""))).unwrap();
+            (search("refunds").unwrap(), blank)
+        });
+
+        let names: Vec<_> = found.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, ["revenue"]);
+        assert_eq!(blank.description, None);
+        assert_eq!(blank.author, None);
+    }
+}
@@ -1,18 +1,33 @@
+use crate::backend::export::{compute_throughput, file_size};
+use crate::backend::metadata;
 use crate::backend::models::ConnectionProfile;
 use crate::backend::pool;
-use calamine::{open_workbook, Reader};
+use calamine::Reader;
 use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
 use csv::ReaderBuilder;
+use encoding_rs::{Decoder, Encoding, UTF_8};
+use flate2::read::MultiGzDecoder;
 use mysql::prelude::*;
 use mysql::Value;
-use serde::Serialize;
+use once_cell::sync::Lazy;
+use quick_xml::events::Event;
+use quick_xml::Reader as XmlReader;
+use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use std::collections::{HashMap, HashSet};
 use std::fs::File;
-use std::io::{BufRead, BufReader, Read};
+use std::io::{BufRead, BufReader, Lines, Read};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
+// 进行中的导入：import_id -> 取消标记
+static IMPORT_CANCEL_TOKENS: Lazy<Mutex<HashMap<String, CancelToken>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+const IMPORT_CANCELLED: &str = "Import cancelled";
+
 #[derive(Serialize)]
 pub struct ImportResult {
     pub success: bool,
@@ -20,7 +35,192 @@ pub struct ImportResult {
     pub rows_imported: u64,
     #[serde(rename = "durationMs")]
     pub duration_ms: u64,
+    #[serde(rename = "rowsPerSec")]
+    pub rows_per_sec: f64,
+    #[serde(rename = "bytesPerSec")]
+    pub bytes_per_sec: f64,
+    // 非严格模式下 MySQL 静默截断/补零产生的警告
+    #[serde(rename = "truncationWarnings")]
+    pub truncation_warnings: Vec<String>,
+    // 文件编码与目标列字符集可能不兼容（如 UTF-8 文件导入 latin1 列）
+    #[serde(rename = "charsetWarnings")]
+    pub charset_warnings: Vec<String>,
+    // 被取消时事务已回滚，表保持导入前的状态
+    pub cancelled: bool,
     pub error: Option<String>,
+    // dry-run 时各行的解析错误
+    pub errors: Vec<String>,
+    // create_if_missing 时自动建表执行的 DDL
+    #[serde(rename = "createdTableDdl")]
+    pub created_table_ddl: Option<String>,
+}
+
+impl ImportResult {
+    pub fn failed(error: String) -> Self {
+        ImportResult {
+            success: false,
+            rows_imported: 0,
+            duration_ms: 0,
+            rows_per_sec: 0.0,
+            bytes_per_sec: 0.0,
+            truncation_warnings: Vec::new(),
+            charset_warnings: Vec::new(),
+            cancelled: false,
+            error: Some(error),
+            errors: Vec::new(),
+            created_table_ddl: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct TableImportResult {
+    pub table: String,
+    #[serde(flatten)]
+    pub result: ImportResult,
+}
+
+#[derive(Clone, Default, Deserialize)]
+pub struct ImportOptions {
+    // 导入期间在会话 sql_mode 中追加 STRICT_ALL_TABLES，截断直接报错而非警告
+    #[serde(rename = "strictMode", default)]
+    pub strict_mode: bool,
+    // 前端生成的导入标识，供 cancel_import 找到对应的取消标记
+    #[serde(rename = "importId", default)]
+    pub import_id: Option<String>,
+    // 文件编码与目标列字符集不匹配时直接拒绝导入，而不只是给出警告
+    #[serde(rename = "strictCharset", default)]
+    pub strict_charset: bool,
+    // 与已有行主键/唯一键冲突时的处理方式
+    #[serde(default)]
+    pub mode: ImportMode,
+    // 导入前在同一事务内清空目标表，导入失败时连同删除一起回滚
+    #[serde(rename = "truncateBefore", default)]
+    pub truncate_before: bool,
+    // 每批合成一条多行 INSERT 写入的行数，也是进度汇报的间隔，默认 500
+    #[serde(rename = "batchSize", default)]
+    pub batch_size: Option<usize>,
+    // 文件表头（JSON/XML 为字段名）→ 表列名；提供时只导入列出的列，其余列取 NULL 或默认值
+    #[serde(rename = "columnMap", default)]
+    pub column_map: Option<HashMap<String, String>>,
+    // CSV/TSV 文件编码（如 gbk、latin1），为空时按 UTF-8 读取
+    #[serde(default)]
+    pub encoding: Option<String>,
+    // 文件为 gzip 压缩；扩展名为 .gz 时自动识别，无需设置
+    #[serde(default)]
+    pub compressed: bool,
+    // 只解析与校验，不写入数据库；rows_imported 为可导入的行数，解析错误记入 errors
+    #[serde(rename = "dryRun", default)]
+    pub dry_run: bool,
+    // Excel 工作表名，为空时取第一个工作表
+    #[serde(default)]
+    pub sheet: Option<String>,
+    // 日期/时间列先按这些 chrono 格式（如 %d.%m.%Y、%s）解析，失败再用内置格式
+    #[serde(rename = "datetimeFormats", default)]
+    pub datetime_formats: Vec<String>,
+    // 目标表不存在时按文件表头和抽样行推断列类型并建表
+    #[serde(rename = "createIfMissing", default)]
+    pub create_if_missing: bool,
+    // 每写完一批及提交后回调，由命令层根据 import_id 设置
+    #[serde(skip)]
+    pub progress: Option<ImportProgressFn>,
+    #[serde(skip)]
+    pub cancel: CancelToken,
+}
+
+const DEFAULT_IMPORT_BATCH_SIZE: usize = 500;
+// 单条预处理语句的占位符上限，列多时一批拆成多条 INSERT
+const MAX_STATEMENT_PLACEHOLDERS: usize = 65_535;
+
+impl ImportOptions {
+    pub fn batch_size(&self) -> usize {
+        self.batch_size.unwrap_or(DEFAULT_IMPORT_BATCH_SIZE).max(1)
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportMode {
+    // 冲突时报错，整个导入回滚
+    #[default]
+    Insert,
+    // INSERT IGNORE：跳过冲突行
+    InsertIgnore,
+    // ON DUPLICATE KEY UPDATE：用文件中的值覆盖非键列
+    Upsert,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ImportProgress {
+    #[serde(rename = "rowsImported")]
+    pub rows_imported: u64,
+    pub done: bool,
+}
+
+pub type ImportProgressFn = Arc<dyn Fn(&ImportProgress) + Send + Sync>;
+
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    // 取消后返回错误，提前退出会丢弃未提交的事务，由驱动回滚
+    fn check(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err(IMPORT_CANCELLED.to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// 导入结束（无论成功与否）时从登记表中移除取消标记
+struct CancelRegistration(Option<String>);
+
+impl Drop for CancelRegistration {
+    fn drop(&mut self) {
+        if let Some(import_id) = self.0.take() {
+            IMPORT_CANCEL_TOKENS.lock().unwrap().remove(&import_id);
+        }
+    }
+}
+
+fn register_cancel_token(options: &ImportOptions) -> (ImportOptions, CancelRegistration) {
+    let mut options = options.clone();
+    let Some(import_id) = options.import_id.clone().filter(|id| !id.is_empty()) else {
+        return (options, CancelRegistration(None));
+    };
+    options.cancel = CancelToken::default();
+    IMPORT_CANCEL_TOKENS
+        .lock()
+        .unwrap()
+        .insert(import_id.clone(), options.cancel.clone());
+    (options, CancelRegistration(Some(import_id)))
+}
+
+/// 请求取消正在进行的导入，返回是否找到该导入
+pub fn cancel_import(import_id: &str) -> bool {
+    match IMPORT_CANCEL_TOKENS.lock().unwrap().get(import_id) {
+        Some(token) => {
+            token.cancel();
+            true
+        }
+        None => false,
+    }
+}
+
+#[derive(Serialize)]
+pub struct PreviewResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<JsonValue>>,
+    pub warnings: Vec<String>,
 }
 
 #[derive(Clone)]
@@ -28,6 +228,20 @@ struct ColumnInfo {
     name: String,
     data_type: String,
     nullable: bool,
+    // 非字符列为 None
+    charset: Option<String>,
+    // 日期/时间列优先尝试的 chrono 格式，来自 ImportOptions::datetime_formats
+    datetime_formats: Arc<[String]>,
+}
+
+// 导入文件抽样得到的编码类别
+#[derive(Clone, Copy, PartialEq)]
+enum FileEncoding {
+    Ascii,
+    // 合法 UTF-8 且含多字节字符；four_byte 表示存在 emoji 等 BMP 以外字符
+    Utf8 { four_byte: bool },
+    // 非 UTF-8，通常是 latin1/GBK 等本地编码
+    Legacy,
 }
 
 #[derive(Clone, Copy)]
@@ -45,6 +259,7 @@ enum ColumnType {
 #[derive(Clone, Copy)]
 pub enum ImportFormat {
     Csv,
+    Tsv,
     Txt,
     Json,
     Jsonl,
@@ -57,6 +272,7 @@ impl ImportFormat {
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_ascii_lowercase().as_str() {
             "csv" => Some(ImportFormat::Csv),
+            "tsv" => Some(ImportFormat::Tsv),
             "txt" => Some(ImportFormat::Txt),
             "json" => Some(ImportFormat::Json),
             "jsonl" => Some(ImportFormat::Jsonl),
@@ -75,33 +291,154 @@ pub fn import_table(
     file_path: &Path,
     format: ImportFormat,
 ) -> ImportResult {
+    import_table_with_options(
+        profile,
+        schema,
+        table,
+        file_path,
+        format,
+        &ImportOptions::default(),
+    )
+}
+
+pub fn import_table_with_options(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    file_path: &Path,
+    format: ImportFormat,
+    options: &ImportOptions,
+) -> ImportResult {
+    let result = run_table_import(profile, schema, table, file_path, format, options);
+    // 成功、失败、取消都发送最后一次 done 事件，前端据此结束进度显示
+    report_done(options, result.rows_imported);
+    result
+}
+
+fn report_done(options: &ImportOptions, rows_imported: u64) {
+    if let Some(progress) = &options.progress {
+        progress(&ImportProgress {
+            rows_imported,
+            done: true,
+        });
+    }
+}
+
+fn run_table_import(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    file_path: &Path,
+    format: ImportFormat,
+    options: &ImportOptions,
+) -> ImportResult {
+    let (options, _registration) = register_cancel_token(options);
+    let options = &options;
     let start = Instant::now();
+    let created_table_ddl = if options.create_if_missing && !options.dry_run {
+        match create_table_if_missing(profile, schema, table, file_path, format, options) {
+            Ok(ddl) => ddl,
+            Err(err) => return ImportResult::failed(err),
+        }
+    } else {
+        None
+    };
+    // 预检查失败（如表不存在）时不阻断，交给实际导入报错
+    // 指定了编码时文件会先转成 UTF-8，按字节抽样的检测不再适用
+    let charset_warnings = if options.encoding.is_some() {
+        Vec::new()
+    } else {
+        check_charset_compatibility(
+            profile,
+            schema,
+            table,
+            file_path,
+            format,
+            options.compressed,
+        )
+        .unwrap_or_default()
+    };
+    if options.strict_charset && !charset_warnings.is_empty() {
+        let mut result = ImportResult::failed(format!(
+            "Charset mismatch between file and table: {}",
+            charset_warnings.join("; ")
+        ));
+        result.charset_warnings = charset_warnings;
+        result.created_table_ddl = discard_created_table(profile, schema, table, created_table_ddl);
+        return result;
+    }
+    let mut report = ImportReport::default();
+    let w = &mut report;
     let result = match format {
-        ImportFormat::Csv => do_import_csv(profile, schema, table, file_path),
-        ImportFormat::Txt => do_import_txt(profile, schema, table, file_path),
-        ImportFormat::Json => do_import_json(profile, schema, table, file_path),
-        ImportFormat::Jsonl => do_import_jsonl(profile, schema, table, file_path),
-        ImportFormat::Xml => do_import_xml(profile, schema, table, file_path),
-        ImportFormat::Xlsx => do_import_excel(profile, schema, table, file_path),
-        ImportFormat::Xls => do_import_excel(profile, schema, table, file_path),
+        ImportFormat::Csv => {
+            do_import_delimited(profile, schema, table, file_path, b',', options, w)
+        }
+        ImportFormat::Tsv => {
+            do_import_delimited(profile, schema, table, file_path, b'\t', options, w)
+        }
+        ImportFormat::Txt => do_import_txt(profile, schema, table, file_path, options, w),
+        ImportFormat::Json => do_import_json(profile, schema, table, file_path, options, w),
+        ImportFormat::Jsonl => do_import_jsonl(profile, schema, table, file_path, options, w),
+        ImportFormat::Xml => do_import_xml(profile, schema, table, file_path, options, w),
+        ImportFormat::Xlsx => do_import_excel(profile, schema, table, file_path, options, w),
+        ImportFormat::Xls => do_import_excel(profile, schema, table, file_path, options, w),
     };
 
+    let elapsed = start.elapsed();
     match result {
-        Ok(rows_imported) => ImportResult {
-            success: true,
-            rows_imported,
-            duration_ms: start.elapsed().as_millis() as u64,
-            error: None,
-        },
+        Ok(rows_imported) => {
+            let (rows_per_sec, bytes_per_sec) =
+                compute_throughput(rows_imported, file_size(file_path), elapsed);
+            ImportResult {
+                success: true,
+                rows_imported,
+                duration_ms: elapsed.as_millis() as u64,
+                rows_per_sec,
+                bytes_per_sec,
+                truncation_warnings: report.warnings,
+                charset_warnings,
+                cancelled: false,
+                error: None,
+                errors: report.errors,
+                created_table_ddl,
+            }
+        }
         Err(err) => ImportResult {
             success: false,
             rows_imported: 0,
-            duration_ms: start.elapsed().as_millis() as u64,
-            error: Some(err),
+            duration_ms: elapsed.as_millis() as u64,
+            rows_per_sec: 0.0,
+            bytes_per_sec: 0.0,
+            truncation_warnings: report.warnings,
+            charset_warnings,
+            cancelled: err == IMPORT_CANCELLED,
+            error: (err != IMPORT_CANCELLED).then_some(err),
+            errors: report.errors,
+            created_table_ddl: discard_created_table(profile, schema, table, created_table_ddl),
         },
     }
 }
 
+// 导入失败或取消时删除本次自动创建的表，不留下空表；删除失败时仍返回建表语句
+fn discard_created_table(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    created_table_ddl: Option<String>,
+) -> Option<String> {
+    created_table_ddl.as_ref()?;
+    let dropped = pool::with_temp_connection(profile, |conn| {
+        conn.query_drop(format!(
+            "DROP TABLE `{}`.`{}`",
+            escape_identifier(schema),
+            escape_identifier(table)
+        ))
+        .map_err(|e| format!("Drop table failed: {e}"))
+    });
+    metadata::invalidate_metadata_cache(profile);
+    created_table_ddl.filter(|_| dropped.is_err())
+}
+
 // Legacy functions for backward compatibility
 pub fn import_from_csv(
     profile: &ConnectionProfile,
@@ -130,67 +467,383 @@ pub fn import_from_jsonl(
     import_table(profile, schema, table, file_path, ImportFormat::Jsonl)
 }
 
-fn do_import_csv(
+/// 读取导入文件为 (表头, 行)，不连接数据库
+pub fn read_tabular_file(
+    file_path: &Path,
+    format: ImportFormat,
+) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    match format {
+        ImportFormat::Csv => read_delimited_file(file_path, b',', None),
+        ImportFormat::Tsv => read_delimited_file(file_path, b'\t', None),
+        ImportFormat::Txt => read_txt_file(file_path, false),
+        ImportFormat::Json => json_rows_to_table(read_json_rows(file_path, false)?),
+        ImportFormat::Jsonl => json_rows_to_table(read_jsonl_rows(file_path, false, None)?),
+        ImportFormat::Xml => Ok(keyed_rows_to_table(read_xml_records(file_path, false)?)),
+        ImportFormat::Xlsx | ImportFormat::Xls => read_excel_file(file_path, None, None),
+    }
+}
+
+/// 解析文件前 `limit` 行并按目标表列类型转换，仅用于导入前预览，不写入数据库。
+/// 无法转换的单元格保留原文并记入 warnings。
+pub fn preview_rows(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    file_path: &Path,
+    format: ImportFormat,
+    limit: usize,
+) -> Result<PreviewResult, String> {
+    let columns =
+        pool::with_temp_connection(profile, |conn| load_columns(conn, schema, table, &[]))?;
+    let (headers, rows) = match format {
+        ImportFormat::Csv => read_delimited_file(file_path, b',', Some(limit))?,
+        ImportFormat::Tsv => read_delimited_file(file_path, b'\t', Some(limit))?,
+        _ => read_tabular_file(file_path, format)?,
+    };
+    Ok(build_preview(&columns, &headers, rows, limit))
+}
+
+fn build_preview(
+    columns: &[ColumnInfo],
+    headers: &[String],
+    rows: Vec<Vec<String>>,
+    limit: usize,
+) -> PreviewResult {
+    let mut warnings = Vec::new();
+    let mut mapped: Vec<(usize, &ColumnInfo)> = Vec::new();
+    for (idx, header) in headers.iter().enumerate() {
+        let key = normalize_column_name(header);
+        match columns
+            .iter()
+            .find(|column| normalize_column_name(&column.name) == key)
+        {
+            Some(column) => mapped.push((idx, column)),
+            None => warnings.push(format!("Column {header} not found in table, ignored")),
+        }
+    }
+    for column in columns {
+        if !mapped.iter().any(|(_, mapped)| mapped.name == column.name) {
+            warnings.push(format!("Column {} missing from file", column.name));
+        }
+    }
+
+    let mut preview_rows = Vec::with_capacity(limit.min(rows.len()));
+    for (row_index, row) in rows.into_iter().take(limit).enumerate() {
+        let mut values = Vec::with_capacity(mapped.len());
+        for (idx, column) in &mapped {
+            let raw = row.get(*idx).map(String::as_str).unwrap_or("");
+            match parse_value(raw, column) {
+                Ok(value) => values.push(preview_value_to_json(value)),
+                Err(err) => {
+                    warnings.push(format!(
+                        "Row {} column {}: {err}",
+                        row_index + 1,
+                        column.name
+                    ));
+                    values.push(JsonValue::String(raw.to_string()));
+                }
+            }
+        }
+        preview_rows.push(values);
+    }
+
+    PreviewResult {
+        columns: mapped
+            .iter()
+            .map(|(_, column)| column.name.clone())
+            .collect(),
+        rows: preview_rows,
+        warnings,
+    }
+}
+
+fn preview_value_to_json(value: Value) -> JsonValue {
+    match value {
+        Value::NULL => JsonValue::Null,
+        Value::Int(v) => JsonValue::from(v),
+        Value::UInt(v) => JsonValue::from(v),
+        Value::Float(v) => JsonValue::from(v as f64),
+        Value::Double(v) => JsonValue::from(v),
+        Value::Bytes(bytes) => JsonValue::String(String::from_utf8_lossy(&bytes).to_string()),
+        Value::Date(y, m, d, 0, 0, 0, 0) => JsonValue::String(format!("{y:04}-{m:02}-{d:02}")),
+        Value::Date(y, m, d, hh, mm, ss, _) => {
+            JsonValue::String(format!("{y:04}-{m:02}-{d:02} {hh:02}:{mm:02}:{ss:02}"))
+        }
+        Value::Time(neg, days, hh, mm, ss, _) => JsonValue::String(format!(
+            "{}{:02}:{mm:02}:{ss:02}",
+            if neg { "-" } else { "" },
+            days * 24 + u32::from(hh)
+        )),
+    }
+}
+
+fn read_delimited_file(
+    file_path: &Path,
+    delimiter: u8,
+    limit: Option<usize>,
+) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let file = File::open(file_path).map_err(|e| format!("Read CSV failed: {e}"))?;
+    read_delimited(file, delimiter, limit)
+}
+
+fn read_delimited<R: Read>(
+    source: R,
+    delimiter: u8,
+    limit: Option<usize>,
+) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let mut reader = ReaderBuilder::new()
+        .has_headers(true)
+        .delimiter(delimiter)
+        .from_reader(source);
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| format!("Read CSV headers failed: {e}"))?
+        .iter()
+        .enumerate()
+        .map(|(idx, h)| {
+            if idx == 0 {
+                h.trim_start_matches('\u{FEFF}').to_string()
+            } else {
+                h.to_string()
+            }
+        })
+        .collect();
+
+    let mut rows = Vec::new();
+    for record in reader.records().take(limit.unwrap_or(usize::MAX)) {
+        let record = record.map_err(|e| format!("CSV parse failed: {e}"))?;
+        rows.push(record.iter().map(|v| v.to_string()).collect());
+    }
+    Ok((headers, rows))
+}
+
+fn read_txt_file(
+    file_path: &Path,
+    compressed: bool,
+) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let file =
+        open_import_file(file_path, compressed).map_err(|e| format!("Read TXT failed: {e}"))?;
+    read_txt_rows(file, None)
+}
+
+fn read_txt_rows<R: Read>(
+    source: R,
+    limit: Option<usize>,
+) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let mut lines = BufReader::new(source).lines();
+    let mut line_no = 0usize;
+
+    let (_, header_line) = read_txt_record(&mut lines, &mut line_no)?.ok_or("TXT file is empty")?;
+    let headers = parse_txt_line(header_line.trim_start_matches('\u{FEFF}'));
+
+    let limit = limit.unwrap_or(usize::MAX);
+    let mut rows = Vec::new();
+    while rows.len() < limit {
+        let Some((_, record)) = read_txt_record(&mut lines, &mut line_no)? else {
+            break;
+        };
+        if record.trim().is_empty() {
+            continue;
+        }
+        rows.push(parse_txt_line(&record));
+    }
+    Ok((headers, rows))
+}
+
+// 读取一条 TXT 记录：引号未闭合时继续拼接下一行，保留字段内的换行；返回记录起始行号
+fn read_txt_record<B: BufRead>(
+    lines: &mut Lines<B>,
+    line_no: &mut usize,
+) -> Result<Option<(usize, String)>, String> {
+    let Some(first) = lines.next() else {
+        return Ok(None);
+    };
+    *line_no += 1;
+    let start = *line_no;
+    let mut record = first.map_err(|e| format!("Read line {start} failed: {e}"))?;
+    while ends_inside_quotes(&record) {
+        let line = lines
+            .next()
+            .ok_or_else(|| format!("Unterminated quoted field starting at line {start}"))?;
+        *line_no += 1;
+        let line = line.map_err(|e| format!("Read line {} failed: {e}", *line_no))?;
+        record.push('\n');
+        record.push_str(&line);
+    }
+    Ok(Some((start, record)))
+}
+
+// 引号只有出现在字段开头（忽略前导空白）时才开启引用，字段中间的 " 按普通字符处理
+fn ends_inside_quotes(record: &str) -> bool {
+    let mut in_quotes = false;
+    let mut field_start = true;
+    let mut chars = record.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field_start => {
+                in_quotes = true;
+                field_start = false;
+            }
+            '\t' if !in_quotes => field_start = true,
+            c if !in_quotes && c.is_whitespace() => {}
+            _ if !in_quotes => field_start = false,
+            _ => {}
+        }
+    }
+    in_quotes
+}
+
+fn read_excel_file(
+    file_path: &Path,
+    sheet: Option<&str>,
+    limit: Option<usize>,
+) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let range = read_excel_sheet(file_path, sheet)?;
+
+    let mut rows_iter = range.rows();
+    let headers: Vec<String> = rows_iter
+        .next()
+        .ok_or("Excel file is empty")?
+        .iter()
+        .map(excel_cell_to_string)
+        .collect();
+    let rows = rows_iter
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|row| row.iter().map(excel_cell_to_string).collect())
+        .collect();
+    Ok((headers, rows))
+}
+
+// 按扩展名选择 xls/xlsx 读取器；未指定 sheet 时取第一个工作表
+fn read_excel_sheet(
+    file_path: &Path,
+    sheet: Option<&str>,
+) -> Result<calamine::Range<calamine::Data>, String> {
+    let mut workbook = calamine::open_workbook_auto(file_path)
+        .map_err(|e| format!("Failed to open Excel file: {e}"))?;
+    let sheet_names = workbook.sheet_names();
+    let sheet_name = match sheet {
+        Some(name) => sheet_names
+            .iter()
+            .find(|s| s.as_str() == name)
+            .ok_or_else(|| {
+                format!(
+                    "Sheet {name} not found, available sheets: {}",
+                    sheet_names.join(", ")
+                )
+            })?
+            .clone(),
+        None => sheet_names
+            .first()
+            .ok_or("Excel file has no sheets")?
+            .clone(),
+    };
+    workbook
+        .worksheet_range(&sheet_name)
+        .map_err(|e| format!("Failed to read worksheet: {e}"))
+}
+
+// 表头取所有记录键的并集，按首次出现顺序排列
+fn json_rows_to_table(rows: Vec<JsonValue>) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let mut keyed = Vec::with_capacity(rows.len());
+    for (index, row) in rows.into_iter().enumerate() {
+        let obj = match row {
+            JsonValue::Object(map) => map,
+            _ => return Err(format!("Row {} is not object", index + 1)),
+        };
+        keyed.push(
+            obj.into_iter()
+                .map(|(key, value)| {
+                    let text = match value {
+                        JsonValue::Null => String::new(),
+                        JsonValue::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    (key, text)
+                })
+                .collect(),
+        );
+    }
+    Ok(keyed_rows_to_table(keyed))
+}
+
+fn keyed_rows_to_table(rows: Vec<Vec<(String, String)>>) -> (Vec<String>, Vec<Vec<String>>) {
+    let mut headers: Vec<String> = Vec::new();
+    for row in &rows {
+        for (key, _) in row {
+            if !headers.contains(key) {
+                headers.push(key.clone());
+            }
+        }
+    }
+    let table = rows
+        .into_iter()
+        .map(|row| {
+            let map: HashMap<String, String> = row.into_iter().collect();
+            headers
+                .iter()
+                .map(|h| map.get(h).cloned().unwrap_or_default())
+                .collect()
+        })
+        .collect();
+    (headers, table)
+}
+
+// CSV 与 TSV 共用同一解析器，仅分隔符不同
+fn do_import_delimited(
     profile: &ConnectionProfile,
     schema: &str,
     table: &str,
     file_path: &Path,
+    delimiter: u8,
+    options: &ImportOptions,
+    report: &mut ImportReport,
 ) -> Result<u64, String> {
     let schema = schema.to_string();
     let table = table.to_string();
 
-    pool::with_temp_connection(profile, |conn| {
-        let columns = load_columns(conn, &schema, &table)?;
+    with_import_connection(profile, options, |conn| {
+        let columns = load_columns(conn, &schema, &table, &options.datetime_formats)?;
         let (header_map, ordered_columns) =
-            build_column_mapping_from_csv_header(file_path, &columns)?;
+            build_column_mapping_from_csv_header(file_path, &columns, delimiter, options)?;
 
-        let insert_sql = build_insert_sql(&schema, &table, &ordered_columns);
-        let stmt = conn
-            .prep(insert_sql)
-            .map_err(|e| format!("Prepare failed: {e}"))?;
-        let mut tx = conn
-            .start_transaction(Default::default())
-            .map_err(|e| format!("Transaction start failed: {e}"))?;
+        let mut writer = ImportWriter::open(conn, &schema, &table, &ordered_columns, options)?;
 
         let mut reader = ReaderBuilder::new()
             .has_headers(true)
             .flexible(false)
-            .from_path(file_path)
-            .map_err(|e| format!("Read CSV failed: {e}"))?;
-
-        let mut params_batch: Vec<Vec<Value>> = Vec::with_capacity(500);
-        let mut rows_imported = 0u64;
+            .delimiter(delimiter)
+            .from_reader(open_text_source(file_path, options)?);
+        let expected = reader
+            .headers()
+            .map_err(|e| format!("Read CSV headers failed: {e}"))?
+            .len();
 
         for (index, record) in reader.records().enumerate() {
+            options.cancel.check()?;
             let record = record.map_err(|e| format!("CSV parse failed: {e}"))?;
-            let expected = header_map.len();
             let actual = record.len();
-            if actual != expected {
-                return Err(format!(
+            let values = if actual != expected {
+                Err(format!(
                     "Row {} column mismatch, expected {}, got {}",
                     index + 2,
                     expected,
                     actual
-                ));
-            }
-
-            let values = build_values_from_csv(&record, &header_map, &ordered_columns, index + 2)?;
-            params_batch.push(values);
-            rows_imported += 1;
-
-            if params_batch.len() >= 500 {
-                tx.exec_batch(&stmt, params_batch.drain(..))
-                    .map_err(|e| format!("Batch insert failed: {e}"))?;
-            }
-        }
-
-        if !params_batch.is_empty() {
-            tx.exec_batch(&stmt, params_batch)
-                .map_err(|e| format!("Batch insert failed: {e}"))?;
+                ))
+            } else {
+                build_values_from_csv(&record, &header_map, &ordered_columns, index + 2)
+            };
+            writer.push(index + 2, values, report)?;
         }
 
-        tx.commit().map_err(|e| format!("Commit failed: {e}"))?;
-        Ok(rows_imported)
+        writer.finish(options, report)
     })
 }
 
@@ -199,22 +852,24 @@ fn do_import_txt(
     schema: &str,
     table: &str,
     file_path: &Path,
+    options: &ImportOptions,
+    report: &mut ImportReport,
 ) -> Result<u64, String> {
     let schema = schema.to_string();
     let table = table.to_string();
 
-    pool::with_temp_connection(profile, |conn| {
-        let columns = load_columns(conn, &schema, &table)?;
+    with_import_connection(profile, options, |conn| {
+        let columns = load_columns(conn, &schema, &table, &options.datetime_formats)?;
 
-        let file = File::open(file_path).map_err(|e| format!("Read TXT failed: {e}"))?;
+        let file = open_import_file(file_path, options.compressed)
+            .map_err(|e| format!("Read TXT failed: {e}"))?;
         let reader = BufReader::new(file);
         let mut lines = reader.lines();
+        let mut line_no = 0usize;
 
         // Read header line
-        let header_line = lines
-            .next()
-            .ok_or("TXT file is empty")?
-            .map_err(|e| format!("Read header failed: {e}"))?;
+        let (_, header_line) =
+            read_txt_record(&mut lines, &mut line_no)?.ok_or("TXT file is empty")?;
 
         // Remove BOM if present
         let header_line = header_line.trim_start_matches('\u{FEFF}');
@@ -233,63 +888,46 @@ fn do_import_txt(
             }
             header_map.insert(name, idx);
         }
+        let (header_map, ordered_columns) = match &options.column_map {
+            Some(column_map) => apply_column_map(&header_map, &columns, column_map)?,
+            None => {
+                // Validate column count
+                if header_map.len() != columns.len() {
+                    return Err(format!(
+                        "Column count mismatch, expected {}, got {}",
+                        columns.len(),
+                        header_map.len()
+                    ));
+                }
 
-        // Validate column count
-        if header_map.len() != columns.len() {
-            return Err(format!(
-                "Column count mismatch, expected {}, got {}",
-                columns.len(),
-                header_map.len()
-            ));
-        }
-
-        // Map columns
-        let mut ordered_columns: Vec<ColumnInfo> = Vec::with_capacity(columns.len());
-        for column in &columns {
-            let key = normalize_column_name(&column.name);
-            if !header_map.contains_key(&key) {
-                return Err(format!("TXT missing column: {}", column.name));
+                // Map columns
+                let mut ordered_columns: Vec<ColumnInfo> = Vec::with_capacity(columns.len());
+                for column in &columns {
+                    let key = normalize_column_name(&column.name);
+                    if !header_map.contains_key(&key) {
+                        return Err(format!("TXT missing column: {}", column.name));
+                    }
+                    ordered_columns.push(column.clone());
+                }
+                (header_map, ordered_columns)
             }
-            ordered_columns.push(column.clone());
-        }
-
-        let insert_sql = build_insert_sql(&schema, &table, &ordered_columns);
-        let stmt = conn
-            .prep(insert_sql)
-            .map_err(|e| format!("Prepare failed: {e}"))?;
-        let mut tx = conn
-            .start_transaction(Default::default())
-            .map_err(|e| format!("Transaction start failed: {e}"))?;
+        };
 
-        let mut params_batch: Vec<Vec<Value>> = Vec::with_capacity(500);
-        let mut rows_imported = 0u64;
+        let mut writer = ImportWriter::open(conn, &schema, &table, &ordered_columns, options)?;
 
-        for (index, line_result) in lines.enumerate() {
-            let line = line_result.map_err(|e| format!("Read line {} failed: {e}", index + 2))?;
-            let trimmed = line.trim();
-            if trimmed.is_empty() {
+        while let Some((row_line, record)) = read_txt_record(&mut lines, &mut line_no)? {
+            options.cancel.check()?;
+            if record.trim().is_empty() {
                 continue;
             }
 
-            let values_str = parse_txt_line(&line);
+            let values_str = parse_txt_line(&record);
             let values =
-                build_values_from_txt(&values_str, &header_map, &ordered_columns, index + 2)?;
-            params_batch.push(values);
-            rows_imported += 1;
-
-            if params_batch.len() >= 500 {
-                tx.exec_batch(&stmt, params_batch.drain(..))
-                    .map_err(|e| format!("Batch insert failed: {e}"))?;
-            }
-        }
-
-        if !params_batch.is_empty() {
-            tx.exec_batch(&stmt, params_batch)
-                .map_err(|e| format!("Batch insert failed: {e}"))?;
+                build_values_from_txt(&values_str, &header_map, &ordered_columns, row_line);
+            writer.push(row_line, values, report)?;
         }
 
-        tx.commit().map_err(|e| format!("Commit failed: {e}"))?;
-        Ok(rows_imported)
+        writer.finish(options, report)
     })
 }
 
@@ -301,15 +939,16 @@ fn parse_txt_line(line: &str) -> Vec<String> {
 
     while let Some(ch) = chars.next() {
         match ch {
-            '"' => {
-                if in_quotes && chars.peek() == Some(&'"') {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
                     // Escaped quote
                     current.push('"');
                     chars.next();
                 } else {
-                    in_quotes = !in_quotes;
+                    in_quotes = false;
                 }
             }
+            '"' if current.trim().is_empty() => in_quotes = true,
             '\t' if !in_quotes => {
                 // Trim quotes from the value if present
                 let trimmed = current.trim();
@@ -366,8 +1005,93 @@ fn do_import_json(
     schema: &str,
     table: &str,
     file_path: &Path,
+    options: &ImportOptions,
+    report: &mut ImportReport,
 ) -> Result<u64, String> {
-    let mut file = File::open(file_path).map_err(|e| format!("Read JSON failed: {e}"))?;
+    let rows = read_json_rows(file_path, options.compressed)?;
+    import_json_rows(profile, schema, table, rows, options, report)
+}
+
+/// 导入 `{ "表名": [...], ... }` 形式的 JSON，按外键依赖顺序逐表导入，各表独立提交
+pub fn import_multi_table_json(
+    profile: &ConnectionProfile,
+    schema: &str,
+    file_path: &Path,
+    options: &ImportOptions,
+) -> Result<Vec<TableImportResult>, String> {
+    let mut file = open_import_file(file_path, options.compressed)
+        .map_err(|e| format!("Read JSON failed: {e}"))?;
+    let mut content = String::new();
+    file.read_to_string(&mut content)
+        .map_err(|e| format!("Read JSON failed: {e}"))?;
+    let json: JsonValue =
+        serde_json::from_str(&content).map_err(|e| format!("JSON parse failed: {e}"))?;
+    let JsonValue::Object(mut tables) = json else {
+        return Err("JSON must be an object keyed by table name".to_string());
+    };
+
+    let (options, _registration) = register_cancel_token(options);
+    let options = &options;
+    let names: Vec<String> = tables.keys().cloned().collect();
+    let ordered = metadata::order_tables_by_dependencies(profile, schema, &names)?;
+
+    let mut results = Vec::with_capacity(ordered.len());
+    for table in ordered {
+        let start = Instant::now();
+        let mut report = ImportReport::default();
+        let rows = match tables.remove(&table) {
+            Some(JsonValue::Array(rows)) => Ok(rows),
+            Some(row @ JsonValue::Object(_)) => Ok(vec![row]),
+            _ => Err(format!("Value of {table} must be array or object")),
+        };
+        let outcome = rows
+            .and_then(|rows| import_json_rows(profile, schema, &table, rows, options, &mut report));
+        let elapsed = start.elapsed();
+        let result = match outcome {
+            Ok(rows_imported) => {
+                let (rows_per_sec, _) = compute_throughput(rows_imported, 0, elapsed);
+                ImportResult {
+                    success: true,
+                    rows_imported,
+                    duration_ms: elapsed.as_millis() as u64,
+                    rows_per_sec,
+                    bytes_per_sec: 0.0,
+                    truncation_warnings: report.warnings,
+                    charset_warnings: Vec::new(),
+                    cancelled: false,
+                    error: None,
+                    errors: report.errors,
+                    created_table_ddl: None,
+                }
+            }
+            Err(err) => ImportResult {
+                success: false,
+                rows_imported: 0,
+                duration_ms: elapsed.as_millis() as u64,
+                rows_per_sec: 0.0,
+                bytes_per_sec: 0.0,
+                truncation_warnings: report.warnings,
+                charset_warnings: Vec::new(),
+                cancelled: err == IMPORT_CANCELLED,
+                error: (err != IMPORT_CANCELLED).then_some(err),
+                errors: report.errors,
+                created_table_ddl: None,
+            },
+        };
+        report_done(options, result.rows_imported);
+        let cancelled = result.cancelled;
+        results.push(TableImportResult { table, result });
+        // 已提交的表保留，取消只回滚当前表并停止后续表
+        if cancelled {
+            break;
+        }
+    }
+    Ok(results)
+}
+
+fn read_json_rows(file_path: &Path, compressed: bool) -> Result<Vec<JsonValue>, String> {
+    let mut file =
+        open_import_file(file_path, compressed).map_err(|e| format!("Read JSON failed: {e}"))?;
     let mut content = String::new();
     file.read_to_string(&mut content)
         .map_err(|e| format!("Read JSON failed: {e}"))?;
@@ -381,8 +1105,7 @@ fn do_import_json(
         JsonValue::Object(_) => rows.push(json),
         _ => return Err("JSON must be array or object".to_string()),
     }
-
-    import_json_rows(profile, schema, table, rows)
+    Ok(rows)
 }
 
 fn do_import_jsonl(
@@ -390,12 +1113,28 @@ fn do_import_jsonl(
     schema: &str,
     table: &str,
     file_path: &Path,
+    options: &ImportOptions,
+    report: &mut ImportReport,
 ) -> Result<u64, String> {
-    let file = File::open(file_path).map_err(|e| format!("Read JSONL failed: {e}"))?;
+    let rows = read_jsonl_rows(file_path, options.compressed, None)?;
+    import_json_rows(profile, schema, table, rows, options, report)
+}
+
+fn read_jsonl_rows(
+    file_path: &Path,
+    compressed: bool,
+    limit: Option<usize>,
+) -> Result<Vec<JsonValue>, String> {
+    let file =
+        open_import_file(file_path, compressed).map_err(|e| format!("Read JSONL failed: {e}"))?;
     let reader = BufReader::new(file);
     let mut rows: Vec<JsonValue> = Vec::new();
+    let limit = limit.unwrap_or(usize::MAX);
 
     for (index, line) in reader.lines().enumerate() {
+        if rows.len() >= limit {
+            break;
+        }
         let line = line.map_err(|e| format!("Read JSONL failed: {e}"))?;
         let trimmed = line.trim();
         if trimmed.is_empty() {
@@ -405,8 +1144,7 @@ fn do_import_jsonl(
             .map_err(|e| format!("JSONL row {} parse failed: {e}", index + 1))?;
         rows.push(value);
     }
-
-    import_json_rows(profile, schema, table, rows)
+    Ok(rows)
 }
 
 fn do_import_xml(
@@ -414,130 +1152,37 @@ fn do_import_xml(
     schema: &str,
     table: &str,
     file_path: &Path,
+    options: &ImportOptions,
+    report: &mut ImportReport,
 ) -> Result<u64, String> {
     let schema = schema.to_string();
     let table = table.to_string();
 
-    let mut file = File::open(file_path).map_err(|e| format!("Read XML failed: {e}"))?;
-    let mut content = String::new();
-    file.read_to_string(&mut content)
-        .map_err(|e| format!("Read XML failed: {e}"))?;
-
-    // Parse XML records
-    let mut rows: Vec<HashMap<String, String>> = Vec::new();
-
-    // Simple XML parsing for <RECORDS><RECORD>...</RECORD></RECORDS> format
-    let content_trimmed = content.trim();
-    if !content_trimmed.starts_with("<?xml") && !content_trimmed.starts_with("<RECORDS") {
-        return Err("Invalid XML format: expected <?xml or <RECORDS>".to_string());
-    }
-
-    // Extract RECORD elements
-    let mut pos = 0;
-    while let Some(record_start) = content[pos..].find("<RECORD>") {
-        let start_idx = pos + record_start + 8; // Skip "<RECORD>"
-        if let Some(record_end) = content[start_idx..].find("</RECORD>") {
-            let record_content = &content[start_idx..start_idx + record_end];
-
-            // Parse fields within the record
-            let mut row = HashMap::new();
-            let mut field_pos = 0;
-
-            while let Some(field_start) = record_content[field_pos..].find('<') {
-                let field_start_idx = field_pos + field_start + 1;
-                if let Some(field_end) = record_content[field_start_idx..].find('>') {
-                    let field_name = &record_content[field_start_idx..field_start_idx + field_end];
-
-                    // Skip if it's a closing tag
-                    if field_name.starts_with('/') {
-                        field_pos = field_start_idx + field_end + 1;
-                        continue;
-                    }
-
-                    let value_start = field_start_idx + field_end + 1;
-                    let close_tag = format!("</{}>", field_name);
+    let rows: Vec<HashMap<String, String>> = read_xml_records(file_path, options.compressed)?
+        .into_iter()
+        .map(|record| record.into_iter().collect())
+        .collect();
 
-                    if let Some(value_end) = record_content[value_start..].find(&close_tag) {
-                        let value = &record_content[value_start..value_start + value_end];
-                        row.insert(field_name.to_string(), xml_unescape(value));
-                        field_pos = value_start + value_end + close_tag.len();
-                    } else {
-                        break;
-                    }
-                } else {
-                    break;
-                }
+    // Import rows
+    with_import_connection(profile, options, |conn| {
+        let columns = load_columns(conn, &schema, &table, &options.datetime_formats)?;
+        let (ordered_columns, rows) = match &options.column_map {
+            Some(column_map) => {
+                let (ordered_columns, fields) = map_record_fields(&columns, column_map)?;
+                let rows: Vec<HashMap<String, String>> = rows
+                    .into_iter()
+                    .map(|row| rename_fields(row, &fields))
+                    .collect();
+                (ordered_columns, rows)
             }
+            None => (match_xml_columns(&columns, &rows)?, rows),
+        };
 
-            if !row.is_empty() {
-                rows.push(row);
-            }
-
-            pos = start_idx + record_end + 9; // Skip "</RECORD>"
-        } else {
-            break;
-        }
-    }
-
-    if rows.is_empty() {
-        return Err("No valid records found in XML".to_string());
-    }
+        let mut writer = ImportWriter::open(conn, &schema, &table, &ordered_columns, options)?;
 
-    // Import rows
-    pool::with_temp_connection(profile, |conn| {
-        let columns = load_columns(conn, &schema, &table)?;
-
-        // Build column mapping from XML fields
-        let mut header_map = HashMap::new();
-        if let Some(first_row) = rows.first() {
-            for (idx, key) in first_row.keys().enumerate() {
-                let normalized = normalize_column_name(key);
-                header_map.insert(normalized, idx);
-            }
-        }
-
-        // Validate columns
-        if header_map.len() != columns.len() {
-            return Err(format!(
-                "Column count mismatch, expected {}, got {}",
-                columns.len(),
-                header_map.len()
-            ));
-        }
-
-        let mut ordered_columns: Vec<ColumnInfo> = Vec::with_capacity(columns.len());
-        for column in &columns {
-            let key = normalize_column_name(&column.name);
-            if !header_map.contains_key(&key) {
-                // Try to find matching field with different normalization
-                let mut found = false;
-                for (field_name, _) in header_map.iter() {
-                    if field_name.eq_ignore_ascii_case(&column.name) {
-                        found = true;
-                        break;
-                    }
-                }
-                if !found {
-                    return Err(format!("XML missing column: {}", column.name));
-                }
-            }
-            ordered_columns.push(column.clone());
-        }
-
-        let insert_sql = build_insert_sql(&schema, &table, &ordered_columns);
-        let stmt = conn
-            .prep(insert_sql)
-            .map_err(|e| format!("Prepare failed: {e}"))?;
-        let mut tx = conn
-            .start_transaction(Default::default())
-            .map_err(|e| format!("Transaction start failed: {e}"))?;
-
-        let mut params_batch: Vec<Vec<Value>> = Vec::with_capacity(500);
-        let mut rows_imported = 0u64;
-
-        for (_index, row) in rows.iter().enumerate() {
-            let mut values = Vec::with_capacity(columns.len());
-            for column in &ordered_columns {
+        for (index, row) in rows.iter().enumerate() {
+            options.cancel.check()?;
+            let values = ordered_columns.iter().map(|column| {
                 let key = normalize_column_name(&column.name);
                 let raw = row
                     .get(&key)
@@ -552,36 +1197,144 @@ fn do_import_xml(
                     })
                     .map(|s| s.as_str())
                     .unwrap_or("");
-                let value = parse_value(raw, column)?;
-                values.push(value);
-            }
+                parse_value(raw, column)
+            });
+            writer.push(index + 1, values.collect(), report)?;
+        }
 
-            params_batch.push(values);
-            rows_imported += 1;
+        writer.finish(options, report)
+    })
+}
+
+// 以首条记录的字段为准，要求与表列一一对应
+fn match_xml_columns(
+    columns: &[ColumnInfo],
+    rows: &[HashMap<String, String>],
+) -> Result<Vec<ColumnInfo>, String> {
+    // Build column mapping from XML fields
+    let mut header_map = HashMap::new();
+    if let Some(first_row) = rows.first() {
+        for (idx, key) in first_row.keys().enumerate() {
+            let normalized = normalize_column_name(key);
+            header_map.insert(normalized, idx);
+        }
+    }
+
+    // Validate columns
+    if header_map.len() != columns.len() {
+        return Err(format!(
+            "Column count mismatch, expected {}, got {}",
+            columns.len(),
+            header_map.len()
+        ));
+    }
 
-            if params_batch.len() >= 500 {
-                tx.exec_batch(&stmt, params_batch.drain(..))
-                    .map_err(|e| format!("Batch insert failed: {e}"))?;
+    let mut ordered_columns: Vec<ColumnInfo> = Vec::with_capacity(columns.len());
+    for column in columns {
+        let key = normalize_column_name(&column.name);
+        if !header_map.contains_key(&key) {
+            // Try to find matching field with different normalization
+            let mut found = false;
+            for (field_name, _) in header_map.iter() {
+                if field_name.eq_ignore_ascii_case(&column.name) {
+                    found = true;
+                    break;
+                }
+            }
+            if !found {
+                return Err(format!("XML missing column: {}", column.name));
             }
         }
+        ordered_columns.push(column.clone());
+    }
+    Ok(ordered_columns)
+}
 
-        if !params_batch.is_empty() {
-            tx.exec_batch(&stmt, params_batch)
-                .map_err(|e| format!("Batch insert failed: {e}"))?;
+// 保留字段在记录中的出现顺序
+fn read_xml_records(
+    file_path: &Path,
+    compressed: bool,
+) -> Result<Vec<Vec<(String, String)>>, String> {
+    let file =
+        open_import_file(file_path, compressed).map_err(|e| format!("Read XML failed: {e}"))?;
+    let mut reader = XmlReader::from_reader(BufReader::new(file));
+    let mut buf = Vec::new();
+
+    let mut rows: Vec<Vec<(String, String)>> = Vec::new();
+    let mut row: Vec<(String, String)> = Vec::new();
+    let mut root_seen = false;
+    let mut in_record = false;
+    // 当前字段及其内部嵌套元素的层数，嵌套元素只取文本内容
+    let mut field: Option<(String, String)> = None;
+    let mut nested = 0usize;
+
+    loop {
+        let event = reader.read_event_into(&mut buf).map_err(|e| {
+            format!(
+                "XML parse failed at position {}: {e}",
+                reader.buffer_position()
+            )
+        })?;
+        match event {
+            Event::Start(tag) => {
+                let name = String::from_utf8_lossy(tag.name().as_ref()).into_owned();
+                if !root_seen {
+                    if name != "RECORDS" {
+                        return Err("Invalid XML format: expected <RECORDS> root".to_string());
+                    }
+                    root_seen = true;
+                } else if !in_record {
+                    in_record = name == "RECORD";
+                } else if field.is_none() {
+                    field = Some((name, String::new()));
+                    nested = 0;
+                } else {
+                    nested += 1;
+                }
+            }
+            // <col/> 视为空字符串
+            Event::Empty(tag) if in_record && field.is_none() => {
+                let name = String::from_utf8_lossy(tag.name().as_ref()).into_owned();
+                row.push((name, String::new()));
+            }
+            Event::Text(text) => {
+                if let Some((_, value)) = field.as_mut() {
+                    let text = text
+                        .unescape()
+                        .map_err(|e| format!("XML parse failed: {e}"))?;
+                    value.push_str(&text);
+                }
+            }
+            Event::CData(data) => {
+                if let Some((_, value)) = field.as_mut() {
+                    value.push_str(&String::from_utf8_lossy(&data.into_inner()));
+                }
+            }
+            Event::End(_) => {
+                if field.is_some() {
+                    if nested == 0 {
+                        row.extend(field.take());
+                    } else {
+                        nested -= 1;
+                    }
+                } else if in_record {
+                    in_record = false;
+                    if !row.is_empty() {
+                        rows.push(std::mem::take(&mut row));
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
         }
+        buf.clear();
+    }
 
-        tx.commit().map_err(|e| format!("Commit failed: {e}"))?;
-        Ok(rows_imported)
-    })
-}
+    if rows.is_empty() {
+        return Err("No valid records found in XML".to_string());
+    }
 
-fn xml_unescape(input: &str) -> String {
-    input
-        .replace("&amp;", "&")
-        .replace("&lt;", "<")
-        .replace("&gt;", ">")
-        .replace("&quot;", "\"")
-        .replace("&apos;", "'")
+    Ok(rows)
 }
 
 fn do_import_excel(
@@ -589,27 +1342,16 @@ fn do_import_excel(
     schema: &str,
     table: &str,
     file_path: &Path,
+    options: &ImportOptions,
+    report: &mut ImportReport,
 ) -> Result<u64, String> {
     let schema = schema.to_string();
     let table = table.to_string();
 
-    pool::with_temp_connection(profile, |conn| {
-        let columns = load_columns(conn, &schema, &table)?;
-
-        // Open workbook using calamine
-        let mut workbook: calamine::Xlsx<_> =
-            open_workbook(file_path).map_err(|e| format!("Failed to open Excel file: {e}"))?;
+    with_import_connection(profile, options, |conn| {
+        let columns = load_columns(conn, &schema, &table, &options.datetime_formats)?;
 
-        // Get the first sheet
-        let sheet_name = workbook
-            .sheet_names()
-            .get(0)
-            .ok_or("Excel file has no sheets")?
-            .clone();
-
-        let range = workbook
-            .worksheet_range(&sheet_name)
-            .map_err(|e| format!("Failed to read worksheet: {e}"))?;
+        let range = read_excel_sheet(file_path, options.sheet.as_deref())?;
 
         // Read header row
         let mut rows_iter = range.rows();
@@ -617,153 +1359,331 @@ fn do_import_excel(
 
         let mut header_map = HashMap::new();
         for (idx, cell) in header_row.iter().enumerate() {
-            let cell_str = match cell {
-                calamine::Data::String(s) => s.clone(),
-                calamine::Data::Float(f) => f.to_string(),
-                calamine::Data::Int(i) => i.to_string(),
-                calamine::Data::Bool(b) => b.to_string(),
-                calamine::Data::DateTime(d) => d.to_string(),
-                calamine::Data::Error(e) => e.to_string(),
-                calamine::Data::Empty => String::new(),
-                _ => cell.to_string(),
-            };
+            let cell_str = excel_cell_to_string(cell);
             let name = normalize_column_name(&cell_str);
             if name.is_empty() {
                 return Err("Excel header contains empty column name".to_string());
             }
             header_map.insert(name, idx);
         }
+        let (header_map, ordered_columns) = match &options.column_map {
+            Some(column_map) => apply_column_map(&header_map, &columns, column_map)?,
+            None => {
+                // Validate column count
+                if header_map.len() != columns.len() {
+                    return Err(format!(
+                        "Column count mismatch, expected {}, got {}",
+                        columns.len(),
+                        header_map.len()
+                    ));
+                }
 
-        // Validate column count
-        if header_map.len() != columns.len() {
-            return Err(format!(
-                "Column count mismatch, expected {}, got {}",
-                columns.len(),
-                header_map.len()
-            ));
-        }
-
-        // Map columns
-        let mut ordered_columns: Vec<ColumnInfo> = Vec::with_capacity(columns.len());
-        for column in &columns {
-            let key = normalize_column_name(&column.name);
-            if !header_map.contains_key(&key) {
-                return Err(format!("Excel missing column: {}", column.name));
+                // Map columns
+                let mut ordered_columns: Vec<ColumnInfo> = Vec::with_capacity(columns.len());
+                for column in &columns {
+                    let key = normalize_column_name(&column.name);
+                    if !header_map.contains_key(&key) {
+                        return Err(format!("Excel missing column: {}", column.name));
+                    }
+                    ordered_columns.push(column.clone());
+                }
+                (header_map, ordered_columns)
             }
-            ordered_columns.push(column.clone());
-        }
-
-        let insert_sql = build_insert_sql(&schema, &table, &ordered_columns);
-        let stmt = conn
-            .prep(insert_sql)
-            .map_err(|e| format!("Prepare failed: {e}"))?;
-        let mut tx = conn
-            .start_transaction(Default::default())
-            .map_err(|e| format!("Transaction start failed: {e}"))?;
+        };
 
-        let mut params_batch: Vec<Vec<Value>> = Vec::with_capacity(500);
-        let mut rows_imported = 0u64;
+        let mut writer = ImportWriter::open(conn, &schema, &table, &ordered_columns, options)?;
 
         for (row_index, row) in rows_iter.enumerate() {
-            let mut values = Vec::with_capacity(columns.len());
-            for column in &ordered_columns {
+            options.cancel.check()?;
+            let values = ordered_columns.iter().map(|column| {
                 let key = normalize_column_name(&column.name);
                 let index = header_map.get(&key).ok_or_else(|| {
                     format!("Row {} missing column: {}", row_index + 2, column.name)
                 })?;
 
-                let cell_value: Option<&calamine::Data> = row.get(*index);
-                let raw = match cell_value {
-                    Some(calamine::Data::String(s)) => s.clone(),
-                    Some(calamine::Data::Float(f)) => f.to_string(),
-                    Some(calamine::Data::Int(i)) => i.to_string(),
-                    Some(calamine::Data::Bool(b)) => b.to_string(),
-                    Some(calamine::Data::DateTime(d)) => d.to_string(),
-                    Some(calamine::Data::Error(e)) => e.to_string(),
-                    Some(calamine::Data::Empty) => String::new(),
-                    Some(cell) => cell.to_string(),
-                    None => String::new(),
-                };
-                let value = parse_value(&raw, column)?;
-                values.push(value);
-            }
-
-            params_batch.push(values);
-            rows_imported += 1;
-
-            if params_batch.len() >= 500 {
-                tx.exec_batch(&stmt, params_batch.drain(..))
-                    .map_err(|e| format!("Batch insert failed: {e}"))?;
-            }
-        }
-
-        if !params_batch.is_empty() {
-            tx.exec_batch(&stmt, params_batch)
-                .map_err(|e| format!("Batch insert failed: {e}"))?;
+                let raw = row
+                    .get(*index)
+                    .map(excel_cell_to_string)
+                    .unwrap_or_default();
+                parse_value(&raw, column)
+            });
+            writer.push(row_index + 2, values.collect(), report)?;
         }
 
-        tx.commit().map_err(|e| format!("Commit failed: {e}"))?;
-        Ok(rows_imported)
+        writer.finish(options, report)
     })
 }
 
+fn excel_cell_to_string(cell: &calamine::Data) -> String {
+    match cell {
+        calamine::Data::String(s) => s.clone(),
+        calamine::Data::Float(f) => f.to_string(),
+        calamine::Data::Int(i) => i.to_string(),
+        calamine::Data::Bool(b) => b.to_string(),
+        calamine::Data::DateTime(d) => d.to_string(),
+        calamine::Data::Error(e) => e.to_string(),
+        calamine::Data::Empty => String::new(),
+        _ => cell.to_string(),
+    }
+}
+
 fn import_json_rows(
     profile: &ConnectionProfile,
     schema: &str,
     table: &str,
     rows: Vec<JsonValue>,
+    options: &ImportOptions,
+    report: &mut ImportReport,
 ) -> Result<u64, String> {
     let schema = schema.to_string();
     let table = table.to_string();
 
-    pool::with_temp_connection(profile, |conn| {
-        let columns = load_columns(conn, &schema, &table)?;
-        let insert_sql = build_insert_sql(&schema, &table, &columns);
-        let stmt = conn
-            .prep(insert_sql)
-            .map_err(|e| format!("Prepare failed: {e}"))?;
-        let mut tx = conn
-            .start_transaction(Default::default())
-            .map_err(|e| format!("Transaction start failed: {e}"))?;
-
-        let mut params_batch: Vec<Vec<Value>> = Vec::with_capacity(500);
-        let mut rows_imported = 0u64;
+    with_import_connection(profile, options, |conn| {
+        let columns = load_columns(conn, &schema, &table, &options.datetime_formats)?;
+        let (columns, fields) = match &options.column_map {
+            Some(column_map) => {
+                let (columns, fields) = map_record_fields(&columns, column_map)?;
+                (columns, Some(fields))
+            }
+            None => (columns, None),
+        };
+        let mut writer = ImportWriter::open(conn, &schema, &table, &columns, options)?;
 
         for (index, row) in rows.into_iter().enumerate() {
-            let obj = match row {
-                JsonValue::Object(map) => map,
-                _ => return Err(format!("Row {} is not object", index + 1)),
+            options.cancel.check()?;
+            let values = match row {
+                JsonValue::Object(obj) => {
+                    let obj = match &fields {
+                        Some(fields) => rename_fields(obj, fields),
+                        None => obj,
+                    };
+                    build_values_from_json(obj, &columns, index + 1)
+                }
+                _ => Err(format!("Row {} is not object", index + 1)),
             };
-            let values = build_values_from_json(obj, &columns, index + 1)?;
-            params_batch.push(values);
-            rows_imported += 1;
+            writer.push(index + 1, values, report)?;
+        }
+
+        writer.finish(options, report)
+    })
+}
+
+// 最多保留的导入警告条数，避免大批量脏数据撑爆结果
+const MAX_IMPORT_WARNINGS: usize = 100;
+
+// 严格模式只作用于本次导入，结束后恢复连接原有 sql_mode，避免影响连接池中的后续使用
+fn with_import_connection<T, F>(
+    profile: &ConnectionProfile,
+    options: &ImportOptions,
+    action: F,
+) -> Result<T, String>
+where
+    F: FnOnce(&mut mysql::Conn) -> Result<T, String>,
+{
+    pool::with_temp_connection(profile, |conn| {
+        if !options.strict_mode {
+            return action(conn);
+        }
+        let previous: Option<String> = conn
+            .query_first("SELECT @@SESSION.sql_mode")
+            .map_err(|e| format!("Query failed: {e}"))?;
+        conn.query_drop(
+            "SET SESSION sql_mode = CONCAT_WS(',', NULLIF(@@SESSION.sql_mode, ''), 'STRICT_ALL_TABLES')",
+        )
+        .map_err(|e| format!("Enable strict mode failed: {e}"))?;
+        let result = action(conn);
+        let restored = conn.exec_drop("SET SESSION sql_mode = ?", (previous.unwrap_or_default(),));
+        match (result, restored) {
+            (Ok(value), Ok(())) => Ok(value),
+            (Ok(_), Err(e)) => Err(format!("Restore sql_mode failed: {e}")),
+            (Err(err), _) => Err(err),
+        }
+    })
+}
+
+// 导入过程中收集的 MySQL 警告与 dry-run 行错误，以及实际执行的 INSERT 条数
+#[derive(Default)]
+struct ImportReport {
+    warnings: Vec<String>,
+    errors: Vec<String>,
+    insert_statements: u64,
+}
+
+// 分批写入目标表；dry-run 时不准备语句也不开启事务，只统计可导入的行数
+struct ImportWriter<'a> {
+    target: Option<(mysql::Transaction<'a>, BatchInsert)>,
+    batch: Vec<Vec<Value>>,
+    batch_size: usize,
+    rows: u64,
+    progress: Option<ImportProgressFn>,
+}
 
-            if params_batch.len() >= 500 {
-                tx.exec_batch(&stmt, params_batch.drain(..))
-                    .map_err(|e| format!("Batch insert failed: {e}"))?;
+impl<'a> ImportWriter<'a> {
+    fn open(
+        conn: &'a mut mysql::Conn,
+        schema: &str,
+        table: &str,
+        columns: &[ColumnInfo],
+        options: &ImportOptions,
+    ) -> Result<Self, String> {
+        let batch_size = options.batch_size();
+        let target = if options.dry_run {
+            None
+        } else {
+            let insert = build_batch_insert(conn, schema, table, columns, options.mode)?;
+            // 提前准备整批的语句：语法或权限问题在开始事务前报出，之后执行时复用语句缓存
+            conn.prep(insert.sql(batch_size.min(insert.max_rows())))
+                .map_err(|e| format!("Prepare failed: {e}"))?;
+            let tx = start_import_transaction(conn, schema, table, options)?;
+            Some((tx, insert))
+        };
+        Ok(Self {
+            target,
+            batch: Vec::with_capacity(batch_size),
+            batch_size,
+            rows: 0,
+            progress: options.progress.clone(),
+        })
+    }
+
+    // 行解析失败时正式导入直接中止；dry-run 记录错误后继续校验后续行
+    fn push(
+        &mut self,
+        row: usize,
+        values: Result<Vec<Value>, String>,
+        report: &mut ImportReport,
+    ) -> Result<(), String> {
+        let values = match values {
+            Ok(values) => values,
+            Err(err) if self.target.is_none() => {
+                if report.errors.len() < MAX_IMPORT_WARNINGS {
+                    report.errors.push(if err.starts_with("Row ") {
+                        err
+                    } else {
+                        format!("Row {row}: {err}")
+                    });
+                }
+                return Ok(());
             }
+            Err(err) => return Err(err),
+        };
+        self.rows += 1;
+        if let Some((tx, insert)) = self.target.as_mut() {
+            self.batch.push(values);
+            if self.batch.len() >= self.batch_size {
+                let batch = std::mem::take(&mut self.batch);
+                exec_batch_checked(tx, insert, batch, self.rows, report)?;
+            }
+        }
+        // 批次边界上汇报进度，dry-run 按同样的间隔；done 事件由调用方在结束时统一发送
+        if self.rows.is_multiple_of(self.batch_size as u64) {
+            self.report_progress();
         }
+        Ok(())
+    }
 
-        if !params_batch.is_empty() {
-            tx.exec_batch(&stmt, params_batch)
-                .map_err(|e| format!("Batch insert failed: {e}"))?;
+    fn report_progress(&self) {
+        if let Some(progress) = &self.progress {
+            progress(&ImportProgress {
+                rows_imported: self.rows,
+                done: false,
+            });
         }
+    }
 
-        tx.commit().map_err(|e| format!("Commit failed: {e}"))?;
-        Ok(rows_imported)
-    })
+    fn finish(mut self, options: &ImportOptions, report: &mut ImportReport) -> Result<u64, String> {
+        if let Some((mut tx, insert)) = self.target.take() {
+            if !self.batch.is_empty() {
+                let batch = std::mem::take(&mut self.batch);
+                exec_batch_checked(&mut tx, &insert, batch, self.rows, report)?;
+            }
+            options.cancel.check()?;
+            tx.commit().map_err(|e| format!("Commit failed: {e}"))?;
+        }
+        Ok(self.rows)
+    }
+}
+
+// 用 DELETE 而不是 TRUNCATE：TRUNCATE 会隐式提交，无法随导入失败回滚
+fn start_import_transaction<'a>(
+    conn: &'a mut mysql::Conn,
+    schema: &str,
+    table: &str,
+    options: &ImportOptions,
+) -> Result<mysql::Transaction<'a>, String> {
+    let mut tx = conn
+        .start_transaction(Default::default())
+        .map_err(|e| format!("Transaction start failed: {e}"))?;
+    if options.truncate_before {
+        tx.query_drop(format!(
+            "DELETE FROM `{}`.`{}`",
+            escape_identifier(schema),
+            escape_identifier(table)
+        ))
+        .map_err(|e| format!("Clear table failed: {e}"))?;
+    }
+    Ok(tx)
+}
+
+// 一批行合成一条多行 INSERT 执行（占位符超限时拆成多条）。服务器只保留最后一条语句的诊断信息，
+// 因此每条语句执行后立即读取 SHOW WARNINGS，并按消息中的 "at row N" 换算成文件中的行号
+fn exec_batch_checked(
+    tx: &mut mysql::Transaction,
+    insert: &BatchInsert,
+    batch: Vec<Vec<Value>>,
+    last_row: u64,
+    report: &mut ImportReport,
+) -> Result<(), String> {
+    let mut first_row = last_row + 1 - batch.len() as u64;
+    let mut rows = batch.into_iter();
+    while rows.len() > 0 {
+        let chunk: Vec<Vec<Value>> = rows.by_ref().take(insert.max_rows()).collect();
+        let count = chunk.len() as u64;
+        let params: Vec<Value> = chunk.into_iter().flatten().collect();
+        tx.exec_drop(insert.sql(count as usize), params)
+            .map_err(|e| format!("Batch insert failed: {e}"))?;
+        report.insert_statements += 1;
+        if tx.warnings() > 0 && report.warnings.len() < MAX_IMPORT_WARNINGS {
+            let warnings: Vec<(String, u32, String)> = tx
+                .query("SHOW WARNINGS")
+                .map_err(|e| format!("Query failed: {e}"))?;
+            let last = first_row + count - 1;
+            for (level, code, message) in warnings {
+                let location = match warning_row(&message, first_row, last) {
+                    Some(row) => format!("Row {row}"),
+                    None => format!("Rows {first_row}-{last}"),
+                };
+                report
+                    .warnings
+                    .push(format!("{location}: {level} {code}: {message}"));
+            }
+            report.warnings.truncate(MAX_IMPORT_WARNINGS);
+        }
+        first_row += count;
+    }
+    Ok(())
+}
+
+// 多行 INSERT 的警告以 "at row N"（语句内从 1 开始）标出出错行
+fn warning_row(message: &str, first_row: u64, last_row: u64) -> Option<u64> {
+    let (_, tail) = message.rsplit_once(" at row ")?;
+    let offset: u64 = tail.trim().parse().ok()?;
+    let row = first_row + offset.checked_sub(1)?;
+    (row <= last_row).then_some(row)
 }
 
 fn load_columns(
     conn: &mut mysql::Conn,
     schema: &str,
     table: &str,
+    datetime_formats: &[String],
 ) -> Result<Vec<ColumnInfo>, String> {
-    let sql = r#"SELECT column_name, data_type, is_nullable
+    let datetime_formats: Arc<[String]> = datetime_formats.into();
+    let sql = r#"SELECT column_name, data_type, is_nullable, character_set_name
                 FROM information_schema.columns
                 WHERE table_schema = ? AND table_name = ?
                 ORDER BY ordinal_position"#;
-    let rows: Vec<(String, String, String)> = conn
+    let rows: Vec<(String, String, String, Option<String>)> = conn
         .exec(sql, (schema, table))
         .map_err(|e| format!("Load columns failed: {e}"))?;
 
@@ -773,22 +1693,341 @@ fn load_columns(
 
     Ok(rows
         .into_iter()
-        .map(|(name, data_type, nullable)| ColumnInfo {
+        .map(|(name, data_type, nullable, charset)| ColumnInfo {
             name,
             data_type,
             nullable: nullable.eq_ignore_ascii_case("YES"),
+            charset,
+            datetime_formats: datetime_formats.clone(),
         })
         .collect())
 }
 
+// 推断列类型时抽样的行数
+const CREATE_TABLE_SAMPLE_ROWS: usize = 100;
+
+// 表已存在时返回 None；否则建表并返回执行的 DDL。DDL 会隐式提交，导入失败时由 discard_created_table 删除
+fn create_table_if_missing(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    file_path: &Path,
+    format: ImportFormat,
+    options: &ImportOptions,
+) -> Result<Option<String>, String> {
+    let exists: Option<u64> = pool::with_temp_connection(profile, |conn| {
+        conn.exec_first(
+            "SELECT COUNT(*) FROM information_schema.tables WHERE table_schema = ? AND table_name = ?",
+            (schema, table),
+        )
+        .map_err(|e| format!("Query failed: {e}"))
+    })?;
+    if exists.unwrap_or(0) > 0 {
+        return Ok(None);
+    }
+
+    let (headers, rows) = read_sample_rows(file_path, format, options, CREATE_TABLE_SAMPLE_ROWS)?;
+    let ddl = build_create_table_sql(schema, table, &headers, &rows)?;
+    pool::with_temp_connection(profile, |conn| {
+        conn.query_drop(&ddl)
+            .map_err(|e| format!("Create table failed: {e}"))
+    })?;
+    metadata::invalidate_metadata_cache(profile);
+    Ok(Some(ddl))
+}
+
+// 按导入时的读取方式（gzip、编码、工作表）读取表头和前 limit 行。
+// JSON 数组与 XML 需整体解析，解析后再截取
+fn read_sample_rows(
+    file_path: &Path,
+    format: ImportFormat,
+    options: &ImportOptions,
+    limit: usize,
+) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let compressed = options.compressed;
+    match format {
+        ImportFormat::Csv => {
+            read_delimited(open_text_source(file_path, options)?, b',', Some(limit))
+        }
+        ImportFormat::Tsv => {
+            read_delimited(open_text_source(file_path, options)?, b'\t', Some(limit))
+        }
+        ImportFormat::Txt => read_txt_rows(open_text_source(file_path, options)?, Some(limit)),
+        ImportFormat::Json => {
+            let mut rows = read_json_rows(file_path, compressed)?;
+            rows.truncate(limit);
+            json_rows_to_table(rows)
+        }
+        ImportFormat::Jsonl => {
+            json_rows_to_table(read_jsonl_rows(file_path, compressed, Some(limit))?)
+        }
+        ImportFormat::Xml => {
+            let mut records = read_xml_records(file_path, compressed)?;
+            records.truncate(limit);
+            Ok(keyed_rows_to_table(records))
+        }
+        ImportFormat::Xlsx | ImportFormat::Xls => {
+            read_excel_file(file_path, options.sheet.as_deref(), Some(limit))
+        }
+    }
+}
+
+fn build_create_table_sql(
+    schema: &str,
+    table: &str,
+    headers: &[String],
+    rows: &[Vec<String>],
+) -> Result<String, String> {
+    if headers.is_empty() {
+        return Err("File has no columns to create table from".to_string());
+    }
+    let sample = &rows[..rows.len().min(CREATE_TABLE_SAMPLE_ROWS)];
+    let mut seen = HashSet::new();
+    let mut definitions = Vec::with_capacity(headers.len());
+    for (idx, header) in headers.iter().enumerate() {
+        let name = header.trim().trim_start_matches('\u{FEFF}');
+        if name.is_empty() {
+            return Err("File header contains empty column name".to_string());
+        }
+        if !seen.insert(normalize_column_name(name)) {
+            return Err(format!("File header contains duplicate column: {name}"));
+        }
+        let values: Vec<&str> = sample
+            .iter()
+            .filter_map(|row| row.get(idx))
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+            .collect();
+        definitions.push(format!(
+            "`{}` {} NULL",
+            escape_identifier(name),
+            infer_column_type(&values)
+        ));
+    }
+    Ok(format!(
+        "CREATE TABLE `{}`.`{}` (\n  {}\n)",
+        escape_identifier(schema),
+        escape_identifier(table),
+        definitions.join(",\n  ")
+    ))
+}
+
+// 抽样值全部能按同一类型解析时使用该类型，否则（或全为空）退回 TEXT
+fn infer_column_type(values: &[&str]) -> &'static str {
+    if values.is_empty() {
+        "TEXT"
+    } else if values
+        .iter()
+        .all(|v| is_plain_number(v) && v.parse::<i64>().is_ok())
+    {
+        "BIGINT"
+    } else if values
+        .iter()
+        .all(|v| is_plain_number(v) && v.parse::<f64>().is_ok())
+    {
+        "DOUBLE"
+    } else if values
+        .iter()
+        .all(|v| NaiveDate::parse_from_str(v, "%Y-%m-%d").is_ok())
+    {
+        "DATE"
+    } else if values.iter().all(|v| parse_datetime(v, &[]).is_ok()) {
+        "DATETIME"
+    } else {
+        "TEXT"
+    }
+}
+
+// 带前导零的编号（001、007）建成数字列会丢掉前导零，按文本处理；
+// 同时排除 inf、NaN 等 Rust 能解析但 MySQL 不接受的写法
+fn is_plain_number(value: &str) -> bool {
+    let digits = value.strip_prefix(['-', '+']).unwrap_or(value);
+    let leading_zero = digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0.");
+    digits.starts_with(|c: char| c.is_ascii_digit()) && !leading_zero
+}
+
+// 抽样检测文件编码，与目标表字符列的 CHARACTER_SET_NAME 比较，返回有损风险的提示。
+// Excel 内部为 Unicode，不做检查
+fn check_charset_compatibility(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    file_path: &Path,
+    format: ImportFormat,
+    compressed: bool,
+) -> Result<Vec<String>, String> {
+    if matches!(format, ImportFormat::Xlsx | ImportFormat::Xls) {
+        return Ok(Vec::new());
+    }
+    let encoding = detect_file_encoding(file_path, compressed)?;
+    if encoding == FileEncoding::Ascii {
+        return Ok(Vec::new());
+    }
+    let columns =
+        pool::with_temp_connection(profile, |conn| load_columns(conn, schema, table, &[]))?;
+    Ok(charset_mismatch_warnings(encoding, &columns))
+}
+
+// .gz 扩展名或显式指定 compressed 时按 gzip 解压读取
+fn open_import_file(file_path: &Path, compressed: bool) -> std::io::Result<Box<dyn Read>> {
+    let file = File::open(file_path)?;
+    let gzip = compressed
+        || file_path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("gz"));
+    // 按多成员读取：拼接（cat a.gz b.gz）或分块压缩的文件不会只读到第一段
+    if gzip {
+        Ok(Box::new(MultiGzDecoder::new(file)))
+    } else {
+        Ok(Box::new(file))
+    }
+}
+
+// 打开文本导入文件：去掉 UTF-8/UTF-16 BOM，非 UTF-8 内容按 encoding（或 BOM）转成 UTF-8
+fn open_text_source(file_path: &Path, options: &ImportOptions) -> Result<Box<dyn Read>, String> {
+    let file = open_import_file(file_path, options.compressed)
+        .map_err(|e| format!("Failed to open file: {e}"))?;
+    let mut reader = BufReader::new(file);
+    let head = reader
+        .fill_buf()
+        .map_err(|e| format!("Failed to read file: {e}"))?;
+    let bom = Encoding::for_bom(head);
+    let encoding = match options.encoding.as_deref() {
+        Some(label) => Some(
+            Encoding::for_label(label.trim().as_bytes())
+                .ok_or_else(|| format!("Unsupported encoding: {label}"))?,
+        ),
+        None => bom.map(|(encoding, _)| encoding).filter(|e| *e != UTF_8),
+    };
+    match encoding {
+        // new_decoder 会识别并跳过 BOM
+        Some(encoding) => Ok(Box::new(DecodingReader::new(reader, encoding))),
+        None => {
+            if let Some((_, bom_len)) = bom {
+                reader.consume(bom_len);
+            }
+            Ok(Box::new(reader))
+        }
+    }
+}
+
+struct DecodingReader<R> {
+    inner: R,
+    decoder: Decoder,
+    input: Vec<u8>,
+    output: Vec<u8>,
+    pos: usize,
+    finished: bool,
+}
+
+impl<R: Read> DecodingReader<R> {
+    fn new(inner: R, encoding: &'static Encoding) -> Self {
+        Self {
+            inner,
+            decoder: encoding.new_decoder(),
+            input: vec![0; 8192],
+            output: Vec::new(),
+            pos: 0,
+            finished: false,
+        }
+    }
+}
+
+impl<R: Read> Read for DecodingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.pos >= self.output.len() {
+            if self.finished {
+                return Ok(0);
+            }
+            let n = self.inner.read(&mut self.input)?;
+            let last = n == 0;
+            let capacity = self.decoder.max_utf8_buffer_length(n).unwrap_or(n * 3 + 16);
+            self.output.resize(capacity, 0);
+            let (_, _, written, _) =
+                self.decoder
+                    .decode_to_utf8(&self.input[..n], &mut self.output, last);
+            self.output.truncate(written);
+            self.pos = 0;
+            self.finished = last;
+        }
+        let len = buf.len().min(self.output.len() - self.pos);
+        buf[..len].copy_from_slice(&self.output[self.pos..self.pos + len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+const ENCODING_SAMPLE_BYTES: usize = 1024 * 1024;
+
+fn detect_file_encoding(file_path: &Path, compressed: bool) -> Result<FileEncoding, String> {
+    let file =
+        open_import_file(file_path, compressed).map_err(|e| format!("Failed to open file: {e}"))?;
+    let mut sample = Vec::with_capacity(ENCODING_SAMPLE_BYTES);
+    file.take(ENCODING_SAMPLE_BYTES as u64)
+        .read_to_end(&mut sample)
+        .map_err(|e| format!("Failed to read file: {e}"))?;
+    let sample = sample.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(&sample);
+    if sample.is_ascii() {
+        return Ok(FileEncoding::Ascii);
+    }
+    let text = match std::str::from_utf8(sample) {
+        Ok(text) => text,
+        // 抽样截断在多字节字符中间时 error_len 为 None，前面部分仍是合法 UTF-8
+        Err(err) if err.error_len().is_none() => {
+            std::str::from_utf8(&sample[..err.valid_up_to()]).unwrap_or_default()
+        }
+        Err(_) => return Ok(FileEncoding::Legacy),
+    };
+    Ok(FileEncoding::Utf8 {
+        four_byte: text.chars().any(|c| c.len_utf8() == 4),
+    })
+}
+
+fn charset_mismatch_warnings(encoding: FileEncoding, columns: &[ColumnInfo]) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for column in columns {
+        let Some(charset) = column.charset.as_deref() else {
+            continue;
+        };
+        let charset = charset.to_ascii_lowercase();
+        let is_unicode = matches!(
+            charset.as_str(),
+            "utf8mb4" | "utf8" | "utf8mb3" | "utf16" | "utf16le" | "utf32" | "ucs2"
+        );
+        // utf8mb3 / ucs2 只能存放 BMP 字符
+        let bmp_only = matches!(charset.as_str(), "utf8" | "utf8mb3" | "ucs2");
+        let warning = match encoding {
+            FileEncoding::Ascii => None,
+            FileEncoding::Utf8 { .. } if !is_unicode => Some(format!(
+                "File is UTF-8 with non-ASCII text but column {} uses {charset}; characters outside {charset} will be lost. Convert the column to utf8mb4 or re-encode the file as {charset}",
+                column.name
+            )),
+            FileEncoding::Utf8 { four_byte: true } if bmp_only => Some(format!(
+                "File contains 4-byte UTF-8 characters (e.g. emoji) but column {} uses {charset}; convert the column to utf8mb4",
+                column.name
+            )),
+            FileEncoding::Utf8 { .. } => None,
+            FileEncoding::Legacy if is_unicode => Some(format!(
+                "File is not valid UTF-8 but column {} uses {charset}; multibyte text may be corrupted. Convert the file to UTF-8 before importing",
+                column.name
+            )),
+            FileEncoding::Legacy => None,
+        };
+        warnings.extend(warning);
+    }
+    warnings
+}
+
 fn build_column_mapping_from_csv_header(
     file_path: &Path,
     columns: &[ColumnInfo],
+    delimiter: u8,
+    options: &ImportOptions,
 ) -> Result<(HashMap<String, usize>, Vec<ColumnInfo>), String> {
     let mut reader = ReaderBuilder::new()
         .has_headers(true)
-        .from_path(file_path)
-        .map_err(|e| format!("Read CSV failed: {e}"))?;
+        .delimiter(delimiter)
+        .from_reader(open_text_source(file_path, options)?);
     let headers = reader
         .headers()
         .map_err(|e| format!("Read CSV headers failed: {e}"))?;
@@ -810,6 +2049,10 @@ fn build_column_mapping_from_csv_header(
         header_map.insert(name, idx);
     }
 
+    if let Some(column_map) = &options.column_map {
+        return apply_column_map(&header_map, columns, column_map);
+    }
+
     if header_map.len() != columns.len() {
         return Err(format!(
             "Column count mismatch, expected {}, got {}",
@@ -830,9 +2073,172 @@ fn build_column_mapping_from_csv_header(
     Ok((header_map, ordered_columns))
 }
 
-fn build_insert_sql(schema: &str, table: &str, columns: &[ColumnInfo]) -> String {
+// 按 column_map 重新建立 表列 → 文件列下标 的映射，未列出的表列不参与 INSERT
+fn apply_column_map(
+    header_map: &HashMap<String, usize>,
+    columns: &[ColumnInfo],
+    column_map: &HashMap<String, String>,
+) -> Result<(HashMap<String, usize>, Vec<ColumnInfo>), String> {
+    if column_map.is_empty() {
+        return Err("Column map is empty".to_string());
+    }
+
+    let mut mapped = HashMap::new();
+    for (file_column, table_column) in column_map {
+        let index = header_map
+            .get(&normalize_column_name(file_column))
+            .ok_or_else(|| format!("File missing column: {file_column}"))?;
+        let key = normalize_column_name(table_column);
+        if !columns
+            .iter()
+            .any(|c| normalize_column_name(&c.name) == key)
+        {
+            return Err(format!("Table has no column: {table_column}"));
+        }
+        if mapped.insert(key, *index).is_some() {
+            return Err(format!("Column mapped more than once: {table_column}"));
+        }
+    }
+
+    let ordered_columns = columns
+        .iter()
+        .filter(|c| mapped.contains_key(&normalize_column_name(&c.name)))
+        .cloned()
+        .collect();
+    Ok((mapped, ordered_columns))
+}
+
+// JSON/JSONL/XML 按字段名取值，记录间字段可能不同，因此不按下标映射：
+// 返回映射到的表列和 文件字段 → 表列 的改名表，逐条记录用 rename_fields 改名
+fn map_record_fields(
+    columns: &[ColumnInfo],
+    column_map: &HashMap<String, String>,
+) -> Result<(Vec<ColumnInfo>, HashMap<String, String>), String> {
+    if column_map.is_empty() {
+        return Err("Column map is empty".to_string());
+    }
+
+    let mut fields = HashMap::new();
+    let mut mapped = HashSet::new();
+    for (file_column, table_column) in column_map {
+        let key = normalize_column_name(table_column);
+        if !columns
+            .iter()
+            .any(|c| normalize_column_name(&c.name) == key)
+        {
+            return Err(format!("Table has no column: {table_column}"));
+        }
+        if !mapped.insert(key.clone()) {
+            return Err(format!("Column mapped more than once: {table_column}"));
+        }
+        fields.insert(normalize_column_name(file_column), key);
+    }
+
+    let ordered_columns = columns
+        .iter()
+        .filter(|c| mapped.contains(&normalize_column_name(&c.name)))
+        .cloned()
+        .collect();
+    Ok((ordered_columns, fields))
+}
+
+// 未出现在改名表中的字段丢弃
+fn rename_fields<V, C: FromIterator<(String, V)>>(
+    record: impl IntoIterator<Item = (String, V)>,
+    fields: &HashMap<String, String>,
+) -> C {
+    record
+        .into_iter()
+        .filter_map(|(name, value)| {
+            fields
+                .get(&normalize_column_name(&name))
+                .map(|column| (column.clone(), value))
+        })
+        .collect()
+}
+
+// 多行 INSERT 的模板；写满的批次与最后不足一批的行数不同，按实际行数生成 SQL
+struct BatchInsert {
+    schema: String,
+    table: String,
+    columns: Vec<ColumnInfo>,
+    mode: ImportMode,
+    key_columns: HashSet<String>,
+}
+
+impl BatchInsert {
+    fn sql(&self, rows: usize) -> String {
+        build_insert_sql(
+            &self.schema,
+            &self.table,
+            &self.columns,
+            self.mode,
+            &self.key_columns,
+            rows,
+        )
+    }
+
+    fn max_rows(&self) -> usize {
+        (MAX_STATEMENT_PLACEHOLDERS / self.columns.len().max(1)).max(1)
+    }
+}
+
+fn build_batch_insert(
+    conn: &mut mysql::Conn,
+    schema: &str,
+    table: &str,
+    columns: &[ColumnInfo],
+    mode: ImportMode,
+) -> Result<BatchInsert, String> {
+    let key_columns = if mode == ImportMode::Upsert {
+        load_key_columns(conn, schema, table)?
+    } else {
+        HashSet::new()
+    };
+    Ok(BatchInsert {
+        schema: schema.to_string(),
+        table: table.to_string(),
+        columns: columns.to_vec(),
+        mode,
+        key_columns,
+    })
+}
+
+// 主键与唯一索引包含的列，Upsert 时不参与更新
+fn load_key_columns(
+    conn: &mut mysql::Conn,
+    schema: &str,
+    table: &str,
+) -> Result<HashSet<String>, String> {
+    let sql = format!(
+        "SHOW KEYS FROM `{}`.`{}` WHERE Non_unique = 0",
+        escape_identifier(schema),
+        escape_identifier(table)
+    );
+    let rows: Vec<mysql::Row> = conn
+        .query(sql)
+        .map_err(|e| format!("Load keys failed: {e}"))?;
+    // 函数索引的键部分没有列名（Column_name 为 NULL），跳过即可
+    Ok(rows
+        .iter()
+        .filter_map(|row| row.get::<Option<String>, _>("Column_name").flatten())
+        .collect())
+}
+
+fn build_insert_sql(
+    schema: &str,
+    table: &str,
+    columns: &[ColumnInfo],
+    mode: ImportMode,
+    key_columns: &HashSet<String>,
+    rows: usize,
+) -> String {
     let mut sql = String::new();
-    sql.push_str("INSERT INTO `");
+    sql.push_str(if mode == ImportMode::InsertIgnore {
+        "INSERT IGNORE INTO `"
+    } else {
+        "INSERT INTO `"
+    });
     sql.push_str(&escape_identifier(schema));
     sql.push_str("`.`");
     sql.push_str(&escape_identifier(table));
@@ -845,14 +2251,33 @@ fn build_insert_sql(schema: &str, table: &str, columns: &[ColumnInfo]) -> String
         sql.push_str(&escape_identifier(&col.name));
         sql.push('`');
     }
-    sql.push_str(") VALUES (");
-    for idx in 0..columns.len() {
-        if idx > 0 {
+    sql.push_str(") VALUES ");
+    let placeholders = vec!["?"; columns.len()].join(", ");
+    for row in 0..rows.max(1) {
+        if row > 0 {
             sql.push_str(", ");
         }
-        sql.push('?');
+        sql.push('(');
+        sql.push_str(&placeholders);
+        sql.push(')');
+    }
+    if mode == ImportMode::Upsert {
+        let mut updates: Vec<String> = columns
+            .iter()
+            .filter(|col| !key_columns.contains(&col.name))
+            .map(|col| format!("`{0}` = VALUES(`{0}`)", escape_identifier(&col.name)))
+            .collect();
+        // 所有列都是键列时没有可更新的列，用无副作用的赋值保留冲突行
+        if updates.is_empty() {
+            if let Some(col) = columns.first() {
+                updates.push(format!("`{0}` = `{0}`", escape_identifier(&col.name)));
+            }
+        }
+        if !updates.is_empty() {
+            sql.push_str(" ON DUPLICATE KEY UPDATE ");
+            sql.push_str(&updates.join(", "));
+        }
     }
-    sql.push(')');
     sql
 }
 
@@ -919,9 +2344,9 @@ fn parse_value(raw: &str, column: &ColumnInfo) -> Result<Value, String> {
                 0
             },
         )),
-        ColumnType::Date => parse_date(raw),
-        ColumnType::DateTime => parse_datetime(raw),
-        ColumnType::Time => parse_time(raw),
+        ColumnType::Date => parse_date(raw, &column.datetime_formats),
+        ColumnType::DateTime => parse_datetime(raw, &column.datetime_formats),
+        ColumnType::Time => parse_time(raw, &column.datetime_formats),
         ColumnType::Json => Ok(Value::Bytes(raw.as_bytes().to_vec())),
         ColumnType::String => Ok(Value::Bytes(raw.as_bytes().to_vec())),
     }
@@ -978,15 +2403,15 @@ fn json_to_value(value: JsonValue, column: &ColumnInfo, row_index: usize) -> Res
         ColumnType::Date => value
             .as_str()
             .ok_or_else(|| format!("Row {row_index} invalid date"))
-            .and_then(parse_date),
+            .and_then(|text| parse_date(text, &column.datetime_formats)),
         ColumnType::DateTime => value
             .as_str()
             .ok_or_else(|| format!("Row {row_index} invalid datetime"))
-            .and_then(parse_datetime),
+            .and_then(|text| parse_datetime(text, &column.datetime_formats)),
         ColumnType::Time => value
             .as_str()
             .ok_or_else(|| format!("Row {row_index} invalid time"))
-            .and_then(parse_time),
+            .and_then(|text| parse_time(text, &column.datetime_formats)),
         ColumnType::Json => Ok(Value::Bytes(value.to_string().as_bytes().to_vec())),
         ColumnType::String => {
             // For strings, use the raw string value without JSON quotes
@@ -1012,12 +2437,30 @@ fn detect_column_type(data_type: &str) -> ColumnType {
     }
 }
 
-fn parse_date(text: &str) -> Result<Value, String> {
+// 依次尝试用户提供的格式，返回第一个解析成功的结果
+fn parse_with_formats<T>(
+    text: &str,
+    formats: &[String],
+    parse: impl Fn(&str, &str) -> chrono::ParseResult<T>,
+) -> Option<T> {
+    formats.iter().find_map(|format| parse(text, format).ok())
+}
+
+fn parse_date(text: &str, formats: &[String]) -> Result<Value, String> {
+    // 自定义格式可能含时间部分（如 %s 时间戳），此时取日期
+    let custom = parse_with_formats(text, formats, |text, format| {
+        NaiveDate::parse_from_str(text, format)
+            .or_else(|_| NaiveDateTime::parse_from_str(text, format).map(|dt| dt.date()))
+    });
     // Try multiple date formats
-    let date = NaiveDate::parse_from_str(text, "%Y-%m-%d")
-        .or_else(|_| NaiveDate::parse_from_str(text, "%Y/%m/%d"))
-        .or_else(|_| NaiveDate::parse_from_str(text, "%d/%m/%Y"))
-        .or_else(|_| NaiveDate::parse_from_str(text, "%m/%d/%Y"))
+    let date = custom
+        .map(Ok)
+        .unwrap_or_else(|| {
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .or_else(|_| NaiveDate::parse_from_str(text, "%Y/%m/%d"))
+                .or_else(|_| NaiveDate::parse_from_str(text, "%d/%m/%Y"))
+                .or_else(|_| NaiveDate::parse_from_str(text, "%m/%d/%Y"))
+        })
         .map_err(|_| format!("Invalid date: {text}"))?;
     Ok(Value::Date(
         date.year() as u16,
@@ -1030,10 +2473,20 @@ fn parse_date(text: &str) -> Result<Value, String> {
     ))
 }
 
-fn parse_datetime(text: &str) -> Result<Value, String> {
-    let dt = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S")
-        .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f"))
-        .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y/%m/%d %H:%M:%S"))
+fn parse_datetime(text: &str, formats: &[String]) -> Result<Value, String> {
+    // 只有日期部分的自定义格式按当天零点处理
+    let custom = parse_with_formats(text, formats, |text, format| {
+        NaiveDateTime::parse_from_str(text, format).or_else(|_| {
+            NaiveDate::parse_from_str(text, format).map(|d| d.and_time(NaiveTime::MIN))
+        })
+    });
+    let dt = custom
+        .map(Ok)
+        .unwrap_or_else(|| {
+            NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S")
+                .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f"))
+                .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y/%m/%d %H:%M:%S"))
+        })
         .map_err(|_| format!("Invalid datetime: {text}"))?;
     Ok(Value::Date(
         dt.date().year() as u16,
@@ -1046,9 +2499,14 @@ fn parse_datetime(text: &str) -> Result<Value, String> {
     ))
 }
 
-fn parse_time(text: &str) -> Result<Value, String> {
-    let time = NaiveTime::parse_from_str(text, "%H:%M:%S")
-        .or_else(|_| NaiveTime::parse_from_str(text, "%H:%M:%S%.f"))
+fn parse_time(text: &str, formats: &[String]) -> Result<Value, String> {
+    let custom = parse_with_formats(text, formats, NaiveTime::parse_from_str);
+    let time = custom
+        .map(Ok)
+        .unwrap_or_else(|| {
+            NaiveTime::parse_from_str(text, "%H:%M:%S")
+                .or_else(|_| NaiveTime::parse_from_str(text, "%H:%M:%S%.f"))
+        })
         .map_err(|_| format!("Invalid time: {text}"))?;
     Ok(Value::Time(
         false,
@@ -1067,3 +2525,1315 @@ fn normalize_column_name(name: &str) -> String {
 fn escape_identifier(input: &str) -> String {
     input.replace('`', "``")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::export::{self, ExportFormat, QueryExportOptions};
+    use crate::backend::test_support::{
+        fresh_schema, mysql_profile, profile, query_rows, run_sql, temp_path, text,
+    };
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn tsv_round_trips_tab_and_newline() {
+        let path = temp_path("round_trip.tsv");
+        let headers = strings(&["id", "note"]);
+        let rows = vec![
+            strings(&["1", "tab\there"]),
+            strings(&["2", "line one\nline two"]),
+            strings(&["3", "say \"hi\""]),
+        ];
+        let cells: Vec<Vec<Option<String>>> = rows
+            .iter()
+            .map(|row| row.iter().cloned().map(Some).collect())
+            .collect();
+        let result = export::export_query_result(
+            &path,
+            &headers,
+            &cells,
+            ExportFormat::Tsv,
+            &QueryExportOptions::default(),
+        )
+        .unwrap();
+        assert!(result.success, "{:?}", result.error);
+
+        let (read_headers, read_rows) = read_tabular_file(&path, ImportFormat::Tsv).unwrap();
+        assert_eq!(read_headers, headers);
+        assert_eq!(read_rows, rows);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    fn column(name: &str, data_type: &str) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable: true,
+            charset: None,
+            datetime_formats: Arc::from(Vec::new()),
+        }
+    }
+
+    // 写入临时文件（name 决定扩展名）后导入
+    fn import_file(
+        profile: &ConnectionProfile,
+        schema: &str,
+        table: &str,
+        name: &str,
+        format: ImportFormat,
+        content: &[u8],
+        options: &ImportOptions,
+    ) -> ImportResult {
+        let path = temp_path(name);
+        std::fs::write(&path, content).unwrap();
+        let result = import_table_with_options(profile, schema, table, &path, format, options);
+        std::fs::remove_file(&path).unwrap();
+        result
+    }
+
+    #[test]
+    fn preview_keeps_raw_text_and_warns_on_failed_coercion() {
+        let columns = vec![column("id", "int"), column("name", "varchar")];
+        let headers = strings(&["id", "name", "extra"]);
+        let rows = vec![
+            strings(&["1", "Alice", "x"]),
+            strings(&["abc", "Bob", "y"]),
+            strings(&["3", "Carol", "z"]),
+        ];
+
+        let preview = build_preview(&columns, &headers, rows, 2);
+        assert_eq!(preview.columns, strings(&["id", "name"]));
+        assert_eq!(
+            preview.rows,
+            vec![
+                vec![JsonValue::from(1), JsonValue::from("Alice")],
+                vec![JsonValue::from("abc"), JsonValue::from("Bob")],
+            ]
+        );
+        assert_eq!(
+            preview.warnings,
+            strings(&[
+                "Column extra not found in table, ignored",
+                "Row 2 column id: Invalid integer: abc",
+            ])
+        );
+    }
+
+    #[test]
+    fn utf8_file_into_latin1_column_is_warned() {
+        let path = temp_path("names.csv");
+        std::fs::write(&path, "id,name\n1,Zoë 张三\n").unwrap();
+        let encoding = detect_file_encoding(&path, false);
+        std::fs::remove_file(&path).unwrap();
+        let mut name = column("name", "varchar");
+        name.charset = Some("latin1".to_string());
+        let mut note = column("note", "text");
+        note.charset = Some("utf8mb4".to_string());
+        let columns = vec![column("id", "int"), name, note];
+
+        let encoding = encoding.unwrap();
+        assert!(encoding == FileEncoding::Utf8 { four_byte: false });
+        let warnings = charset_mismatch_warnings(encoding, &columns);
+        assert_eq!(warnings.len(), 1);
+        assert!(
+            warnings[0]
+                .starts_with("File is UTF-8 with non-ASCII text but column name uses latin1"),
+            "{}",
+            warnings[0]
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn batch_import_reports_truncation_warning() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "import_warnings");
+        run_sql(
+            &profile,
+            &[&format!(
+                "CREATE TABLE `{schema}`.`prices` (id INT PRIMARY KEY, amount DECIMAL(5,2))"
+            )],
+        );
+        let path = temp_path("prices.csv");
+        std::fs::write(&path, "id,amount\n1,1.234\n2,1.5\n").unwrap();
+        let options = ImportOptions {
+            batch_size: Some(2),
+            ..Default::default()
+        };
+
+        let result = import_table_with_options(
+            &profile,
+            &schema,
+            "prices",
+            &path,
+            ImportFormat::Csv,
+            &options,
+        );
+        std::fs::remove_file(&path).unwrap();
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.rows_imported, 2);
+        assert_eq!(result.truncation_warnings.len(), 1);
+        let warning = &result.truncation_warnings[0];
+        assert!(warning.starts_with("Row 1: Note 1265:"), "{warning}");
+    }
+
+    #[test]
+    fn txt_quoted_field_keeps_embedded_newlines() {
+        let path = temp_path("multiline.txt");
+        std::fs::write(
+            &path,
+            "id\tnote\n1\t\"first line\nsecond \"\"quoted\"\" line\"\n2\tplain\n",
+        )
+        .unwrap();
+
+        let (headers, rows) = read_txt_file(&path, false).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(headers, strings(&["id", "note"]));
+        assert_eq!(
+            rows,
+            vec![
+                strings(&["1", "first line\nsecond \"quoted\" line"]),
+                strings(&["2", "plain"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn txt_quote_inside_unquoted_field_is_literal() {
+        let content = "id\tsize\n1\t12\" pipe\n2\t3/4\"\n3\t\"a\tb\"\n";
+        let (headers, rows) = read_txt_rows(content.as_bytes(), None).unwrap();
+        assert_eq!(headers, strings(&["id", "size"]));
+        assert_eq!(
+            rows,
+            vec![
+                strings(&["1", "12\" pipe"]),
+                strings(&["2", "3/4\""]),
+                strings(&["3", "a\tb"]),
+            ]
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn imports_two_key_json_in_foreign_key_order() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "multi_json");
+        run_sql(
+            &profile,
+            &[
+                &format!("CREATE TABLE `{schema}`.`users` (id INT PRIMARY KEY, name VARCHAR(32))"),
+                &format!(
+                    "CREATE TABLE `{schema}`.`orders` (id INT PRIMARY KEY, user_id INT, \
+                     FOREIGN KEY (user_id) REFERENCES `{schema}`.`users` (id))"
+                ),
+            ],
+        );
+        // 子表在前，导入时仍需先写 users
+        let path = temp_path("multi.json");
+        std::fs::write(
+            &path,
+            r#"{"orders": [{"id": 10, "user_id": 1}, {"id": 11, "user_id": 2}],
+                "users": [{"id": 1, "name": "Alice"}, {"id": 2, "name": "Bob"}]}"#,
+        )
+        .unwrap();
+
+        let results = import_multi_table_json(&profile, &schema, &path, &ImportOptions::default());
+        std::fs::remove_file(&path).unwrap();
+        let counts: Vec<(u64, u64)> = query_rows(
+            &profile,
+            &format!(
+                "SELECT (SELECT COUNT(*) FROM `{schema}`.`users`), (SELECT COUNT(*) FROM `{schema}`.`orders`)"
+            ),
+        );
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        let results = results.unwrap();
+        let summary: Vec<(&str, bool, u64)> = results
+            .iter()
+            .map(|r| (r.table.as_str(), r.result.success, r.result.rows_imported))
+            .collect();
+        assert_eq!(summary, vec![("users", true, 2), ("orders", true, 2)]);
+        assert_eq!(counts, vec![(2, 2)]);
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn cancelling_mid_import_rolls_back() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "import_cancel");
+        run_sql(
+            &profile,
+            &[&format!(
+                "CREATE TABLE `{schema}`.`items` (id INT PRIMARY KEY)"
+            )],
+        );
+        let path = temp_path("items.csv");
+        std::fs::write(&path, "id\n1\n2\n3\n4\n5\n").unwrap();
+        // 第一批写入后触发取消，此时已有行进入事务
+        let cancel = CancelToken::default();
+        let on_progress = cancel.clone();
+        let options = ImportOptions {
+            batch_size: Some(2),
+            cancel,
+            progress: Some(Arc::new(move |_: &ImportProgress| on_progress.cancel())),
+            ..Default::default()
+        };
+
+        let result = import_table_with_options(
+            &profile,
+            &schema,
+            "items",
+            &path,
+            ImportFormat::Csv,
+            &options,
+        );
+        std::fs::remove_file(&path).unwrap();
+        let remaining: Vec<u64> = query_rows(
+            &profile,
+            &format!("SELECT COUNT(*) FROM `{schema}`.`items`"),
+        );
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert!(result.cancelled);
+        assert!(!result.success);
+        assert_eq!(result.error, None);
+        assert_eq!(result.rows_imported, 0);
+        assert_eq!(remaining, vec![0]);
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn tsv_table_export_reimports_unchanged() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "tsv_round_trip");
+        run_sql(
+            &profile,
+            &[
+                &format!("CREATE TABLE `{schema}`.`src` (id INT PRIMARY KEY, note VARCHAR(64))"),
+                &format!("CREATE TABLE `{schema}`.`dst` LIKE `{schema}`.`src`"),
+                &format!(
+                    "INSERT INTO `{schema}`.`src` VALUES \
+                     (1, 'tab\\there'), (2, 'line\\nbreak'), (3, 'say \"hi\"')"
+                ),
+            ],
+        );
+        let path = temp_path("round_trip_table.tsv");
+        let exported = export::export_table_with_options(
+            &profile,
+            &schema,
+            "src",
+            &path,
+            ExportFormat::Tsv,
+            &Default::default(),
+        );
+        assert!(exported.success, "{:?}", exported.error);
+
+        let imported = import_table_with_options(
+            &profile,
+            &schema,
+            "dst",
+            &path,
+            ImportFormat::Tsv,
+            &ImportOptions::default(),
+        );
+        std::fs::remove_file(&path).unwrap();
+        let rows: Vec<(i32, String)> = query_rows(
+            &profile,
+            &format!(
+                "SELECT d.id, d.note FROM `{schema}`.`dst` d \
+                 JOIN `{schema}`.`src` s ON s.id = d.id AND BINARY s.note = BINARY d.note \
+                 ORDER BY d.id"
+            ),
+        );
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert!(imported.success, "{:?}", imported.error);
+        assert_eq!(imported.rows_imported, 3);
+        assert_eq!(
+            rows,
+            vec![
+                (1, "tab\there".to_string()),
+                (2, "line\nbreak".to_string()),
+                (3, "say \"hi\"".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn multi_row_warnings_map_to_file_rows() {
+        let message = "Data truncated for column 'amount' at row 2";
+        assert_eq!(warning_row(message, 11, 20), Some(12));
+        assert_eq!(warning_row(message, 11, 11), None);
+        assert_eq!(warning_row("Out of range value at row 0", 1, 5), None);
+        assert_eq!(warning_row("Unknown table engine", 1, 5), None);
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn batch_truncation_warning_points_at_file_row() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "batch_truncation");
+        run_sql(
+            &profile,
+            &[&format!(
+                "CREATE TABLE `{schema}`.`codes` (id INT PRIMARY KEY, code VARCHAR(3))"
+            )],
+        );
+        // 文件中的第 10-12 行，第 11 行超长
+        let batch = vec![
+            vec![Value::Int(10), text("abc")],
+            vec![Value::Int(11), text("abcdef")],
+            vec![Value::Int(12), text("xyz")],
+        ];
+
+        // 非严格模式下超长值被截断并产生警告，而不是报错
+        let report = pool::with_temp_connection(&profile, |conn| {
+            conn.query_drop("SET SESSION sql_mode = ''")
+                .map_err(|e| e.to_string())?;
+            let columns = load_columns(conn, &schema, "codes", &[])?;
+            let insert = build_batch_insert(conn, &schema, "codes", &columns, ImportMode::Insert)?;
+            let mut tx = conn
+                .start_transaction(Default::default())
+                .map_err(|e| e.to_string())?;
+            let mut report = ImportReport::default();
+            exec_batch_checked(&mut tx, &insert, batch, 12, &mut report)?;
+            tx.commit().map_err(|e| e.to_string())?;
+            Ok(report)
+        });
+        let stored: Vec<(i64, String)> = query_rows(
+            &profile,
+            &format!("SELECT id, code FROM `{schema}`.`codes` ORDER BY id"),
+        );
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        let report = report.unwrap();
+        assert_eq!(report.insert_statements, 1);
+        assert_eq!(
+            report.warnings,
+            ["Row 11: Warning 1265: Data truncated for column 'code' at row 2"]
+        );
+        assert_eq!(
+            stored,
+            [
+                (10, "abc".to_string()),
+                (11, "abc".to_string()),
+                (12, "xyz".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn upsert_updates_non_key_columns() {
+        let columns = vec![column("id", "int"), column("name", "varchar")];
+        let keys: HashSet<String> = HashSet::from(["id".to_string()]);
+        assert_eq!(
+            build_insert_sql("shop", "items", &columns, ImportMode::Upsert, &keys, 1),
+            "INSERT INTO `shop`.`items` (`id`, `name`) VALUES (?, ?) \
+             ON DUPLICATE KEY UPDATE `name` = VALUES(`name`)"
+        );
+        assert_eq!(
+            build_insert_sql(
+                "shop",
+                "items",
+                &columns,
+                ImportMode::InsertIgnore,
+                &keys,
+                2
+            ),
+            "INSERT IGNORE INTO `shop`.`items` (`id`, `name`) VALUES (?, ?), (?, ?)"
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn upsert_replaces_existing_row_where_insert_fails() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "import_upsert");
+        run_sql(
+            &profile,
+            &[
+                &format!("CREATE TABLE `{schema}`.`items` (id INT PRIMARY KEY, name VARCHAR(32))"),
+                &format!("INSERT INTO `{schema}`.`items` VALUES (1, 'old')"),
+            ],
+        );
+        let csv = b"id,name\n1,new\n2,two\n";
+        let import = |mode| {
+            let options = ImportOptions {
+                mode,
+                ..Default::default()
+            };
+            import_file(
+                &profile,
+                &schema,
+                "items",
+                "items.csv",
+                ImportFormat::Csv,
+                csv,
+                &options,
+            )
+        };
+        let contents = || -> Vec<(i32, String)> {
+            query_rows(
+                &profile,
+                &format!("SELECT id, name FROM `{schema}`.`items` ORDER BY id"),
+            )
+        };
+
+        let inserted = import(ImportMode::Insert);
+        assert!(!inserted.success);
+        assert!(inserted.error.is_some());
+        assert_eq!(contents(), vec![(1, "old".to_string())]);
+
+        let upserted = import(ImportMode::Upsert);
+        let after = contents();
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert!(upserted.success, "{:?}", upserted.error);
+        assert_eq!(after, vec![(1, "new".to_string()), (2, "two".to_string())]);
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn key_columns_skip_functional_index_parts() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "import_functional_key");
+        run_sql(
+            &profile,
+            &[&format!(
+                "CREATE TABLE `{schema}`.`users` (id INT PRIMARY KEY, email VARCHAR(64), \
+                 UNIQUE KEY uq_email ((LOWER(email))))"
+            )],
+        );
+
+        let keys =
+            pool::with_temp_connection(&profile, |conn| load_key_columns(conn, &schema, "users"));
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert_eq!(keys.unwrap(), HashSet::from(["id".to_string()]));
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn failed_import_with_truncate_before_keeps_original_rows() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "import_truncate");
+        run_sql(
+            &profile,
+            &[
+                &format!("CREATE TABLE `{schema}`.`items` (id INT PRIMARY KEY, name VARCHAR(32))"),
+                &format!("INSERT INTO `{schema}`.`items` VALUES (1, 'kept'), (2, 'also kept')"),
+            ],
+        );
+        // 文件内主键重复，第二批写入失败
+        let options = ImportOptions {
+            truncate_before: true,
+            batch_size: Some(1),
+            ..Default::default()
+        };
+        let result = import_file(
+            &profile,
+            &schema,
+            "items",
+            "items.csv",
+            ImportFormat::Csv,
+            b"id,name\n5,new\n5,dup\n",
+            &options,
+        );
+        let rows: Vec<(i32, String)> = query_rows(
+            &profile,
+            &format!("SELECT id, name FROM `{schema}`.`items` ORDER BY id"),
+        );
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert!(!result.success);
+        assert_eq!(
+            rows,
+            vec![(1, "kept".to_string()), (2, "also kept".to_string())]
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn batch_size_controls_number_of_batches() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "import_batch_size");
+        run_sql(
+            &profile,
+            &[&format!(
+                "CREATE TABLE `{schema}`.`items` (id INT PRIMARY KEY)"
+            )],
+        );
+        let path = temp_path("items.csv");
+        std::fs::write(&path, "id\n1\n2\n3\n4\n5\n").unwrap();
+        // 每批一条多行 INSERT，剩余不足一批的行在提交前再写一条
+        let statements = |batch_size: Option<usize>| {
+            let options = ImportOptions {
+                batch_size,
+                truncate_before: true,
+                ..Default::default()
+            };
+            let mut report = ImportReport::default();
+            let rows = do_import_delimited(
+                &profile,
+                &schema,
+                "items",
+                &path,
+                b',',
+                &options,
+                &mut report,
+            );
+            assert_eq!(rows, Ok(5));
+            report.insert_statements
+        };
+
+        let small = statements(Some(2));
+        let zero = statements(Some(0));
+        let default = statements(None);
+        let count: Vec<u64> = query_rows(
+            &profile,
+            &format!("SELECT COUNT(*) FROM `{schema}`.`items`"),
+        );
+        std::fs::remove_file(&path).unwrap();
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert_eq!(small, 3);
+        assert_eq!(zero, 5);
+        assert_eq!(default, 1);
+        assert_eq!(count, vec![5]);
+    }
+
+    #[test]
+    fn column_map_maps_full_name_to_name() {
+        let columns = vec![
+            column("id", "int"),
+            column("name", "varchar"),
+            column("email", "varchar"),
+        ];
+        let header_map = HashMap::from([("full_name".to_string(), 0), ("id".to_string(), 1)]);
+        let column_map = HashMap::from([
+            ("full_name".to_string(), "name".to_string()),
+            ("id".to_string(), "id".to_string()),
+        ]);
+
+        let Ok((mapped, ordered)) = apply_column_map(&header_map, &columns, &column_map) else {
+            panic!("column map rejected");
+        };
+        assert_eq!(
+            mapped,
+            HashMap::from([("name".to_string(), 0), ("id".to_string(), 1)])
+        );
+        let names: Vec<&str> = ordered.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "name"]);
+
+        let unknown = HashMap::from([("full_name".to_string(), "nickname".to_string())]);
+        assert_eq!(
+            apply_column_map(&header_map, &columns, &unknown).err(),
+            Some("Table has no column: nickname".to_string())
+        );
+    }
+
+    #[test]
+    fn column_map_renames_json_fields_to_table_columns() {
+        let columns = vec![
+            column("id", "int"),
+            column("name", "varchar"),
+            column("email", "varchar"),
+        ];
+        let column_map = HashMap::from([
+            ("Full_Name".to_string(), "name".to_string()),
+            ("user_id".to_string(), "id".to_string()),
+        ]);
+        let (ordered, fields) = map_record_fields(&columns, &column_map).unwrap();
+        let names: Vec<&str> = ordered.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "name"]);
+
+        let JsonValue::Object(record) =
+            serde_json::json!({"user_id": 7, "full_name": "Ann", "extra": true})
+        else {
+            unreachable!()
+        };
+        let renamed: serde_json::Map<String, JsonValue> = rename_fields(record, &fields);
+        assert_eq!(
+            JsonValue::Object(renamed),
+            serde_json::json!({"id": 7, "name": "Ann"})
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn column_map_applies_to_jsonl_and_txt_imports() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "import_column_map_formats");
+        run_sql(
+            &profile,
+            &[&format!(
+                "CREATE TABLE `{schema}`.`users` \
+                 (id INT PRIMARY KEY, name VARCHAR(32), email VARCHAR(64) NULL)"
+            )],
+        );
+        let options = ImportOptions {
+            column_map: Some(HashMap::from([
+                ("full_name".to_string(), "name".to_string()),
+                ("user_id".to_string(), "id".to_string()),
+            ])),
+            ..Default::default()
+        };
+        let jsonl = import_file(
+            &profile,
+            &schema,
+            "users",
+            "users.jsonl",
+            ImportFormat::Jsonl,
+            b"{\"user_id\": 1, \"full_name\": \"Ann\", \"ignored\": 1}\n",
+            &options,
+        );
+        let txt = import_file(
+            &profile,
+            &schema,
+            "users",
+            "users.txt",
+            ImportFormat::Txt,
+            b"user_id\tfull_name\tignored\n2\tBo\tx\n",
+            &options,
+        );
+        let rows: Vec<(i32, String, Option<String>)> = query_rows(
+            &profile,
+            &format!("SELECT id, name, email FROM `{schema}`.`users` ORDER BY id"),
+        );
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert!(jsonl.success, "{:?}", jsonl.error);
+        assert!(txt.success, "{:?}", txt.error);
+        assert_eq!(
+            rows,
+            vec![(1, "Ann".to_string(), None), (2, "Bo".to_string(), None)]
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn column_map_imports_renamed_header_and_leaves_unmapped_null() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "import_column_map");
+        run_sql(
+            &profile,
+            &[&format!(
+                "CREATE TABLE `{schema}`.`users` \
+                 (id INT PRIMARY KEY, name VARCHAR(32), email VARCHAR(64) NULL)"
+            )],
+        );
+        let options = ImportOptions {
+            column_map: Some(HashMap::from([
+                ("full_name".to_string(), "name".to_string()),
+                ("user_id".to_string(), "id".to_string()),
+            ])),
+            ..Default::default()
+        };
+        let result = import_file(
+            &profile,
+            &schema,
+            "users",
+            "users.csv",
+            ImportFormat::Csv,
+            b"user_id,full_name,ignored\n1,Ann Lee,x\n",
+            &options,
+        );
+        let rows: Vec<(i32, String, Option<String>)> = query_rows(
+            &profile,
+            &format!("SELECT id, name, email FROM `{schema}`.`users`"),
+        );
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(rows, vec![(1, "Ann Lee".to_string(), None)]);
+    }
+
+    #[test]
+    fn text_source_decodes_gbk_and_strips_boms() {
+        let read = |bytes: &[u8], encoding: Option<&str>| {
+            let path = temp_path("encoded.csv");
+            std::fs::write(&path, bytes).unwrap();
+            let options = ImportOptions {
+                encoding: encoding.map(str::to_string),
+                ..Default::default()
+            };
+            let mut text = String::new();
+            open_text_source(&path, &options)
+                .unwrap()
+                .read_to_string(&mut text)
+                .unwrap();
+            std::fs::remove_file(&path).unwrap();
+            text
+        };
+        let csv = "id,name\n1,张三\n2,李四\n";
+        let (gbk, _, _) = encoding_rs::GBK.encode(csv);
+
+        assert_eq!(read(&gbk, Some("gbk")), csv);
+        assert_eq!(read(&[b"\xEF\xBB\xBF", csv.as_bytes()].concat(), None), csv);
+        let utf16: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain(csv.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        assert_eq!(read(&utf16, None), csv);
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn imports_gbk_csv_with_chinese_values() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "import_gbk");
+        run_sql(
+            &profile,
+            &[&format!(
+                "CREATE TABLE `{schema}`.`people` (id INT PRIMARY KEY, name VARCHAR(16)) \
+                 CHARACTER SET utf8mb4"
+            )],
+        );
+        let (gbk, _, _) = encoding_rs::GBK.encode("id,name\n1,张三\n2,李四\n");
+        let options = ImportOptions {
+            encoding: Some("GBK".to_string()),
+            ..Default::default()
+        };
+        let result = import_file(
+            &profile,
+            &schema,
+            "people",
+            "people.csv",
+            ImportFormat::Csv,
+            &gbk,
+            &options,
+        );
+        let rows: Vec<(i32, String)> = query_rows(
+            &profile,
+            &format!("SELECT id, name FROM `{schema}`.`people` ORDER BY id"),
+        );
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(rows, vec![(1, "张三".to_string()), (2, "李四".to_string())]);
+    }
+
+    #[test]
+    fn gzip_export_reads_back_through_import_source() {
+        let headers = strings(&["id", "name"]);
+        let rows = vec![
+            vec![Some("1".to_string()), Some("Ann".to_string())],
+            vec![Some("2".to_string()), Some("李雷".to_string())],
+        ];
+        let options = QueryExportOptions {
+            compress: true,
+            ..Default::default()
+        };
+        let exported = export::export_query_result(
+            &temp_path("gzip_round_trip.csv"),
+            &headers,
+            &rows,
+            ExportFormat::Csv,
+            &options,
+        )
+        .unwrap();
+        assert!(exported.success, "{:?}", exported.error);
+        let path = std::path::PathBuf::from(&exported.file_path);
+        assert!(exported.file_path.ends_with(".csv.gz"));
+
+        let mut text = String::new();
+        open_text_source(&path, &ImportOptions::default())
+            .unwrap()
+            .read_to_string(&mut text)
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(text, "id,name\n1,Ann\n2,李雷\n");
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn gzipped_table_export_reimports() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "import_gzip");
+        run_sql(
+            &profile,
+            &[
+                &format!("CREATE TABLE `{schema}`.`src` (id INT PRIMARY KEY, name VARCHAR(32))"),
+                &format!("CREATE TABLE `{schema}`.`dst` LIKE `{schema}`.`src`"),
+                &format!("INSERT INTO `{schema}`.`src` VALUES (1, 'Ann'), (2, 'Bo')"),
+            ],
+        );
+        let exported = export::export_table_with_options(
+            &profile,
+            &schema,
+            "src",
+            &temp_path("src.csv"),
+            ExportFormat::Csv,
+            &export::TableExportOptions {
+                compress: true,
+                ..Default::default()
+            },
+        );
+        assert!(exported.success, "{:?}", exported.error);
+        let path = std::path::PathBuf::from(&exported.file_path);
+
+        let result = import_table_with_options(
+            &profile,
+            &schema,
+            "dst",
+            &path,
+            ImportFormat::Csv,
+            &ImportOptions::default(),
+        );
+        std::fs::remove_file(&path).unwrap();
+        let rows: Vec<(i32, String)> = query_rows(
+            &profile,
+            &format!("SELECT id, name FROM `{schema}`.`dst` ORDER BY id"),
+        );
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(rows, vec![(1, "Ann".to_string()), (2, "Bo".to_string())]);
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn dry_run_counts_rows_without_writing() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "import_dry_run");
+        run_sql(
+            &profile,
+            &[&format!(
+                "CREATE TABLE `{schema}`.`items` (id INT PRIMARY KEY, qty INT)"
+            )],
+        );
+        let options = ImportOptions {
+            dry_run: true,
+            ..Default::default()
+        };
+        let result = import_file(
+            &profile,
+            &schema,
+            "items",
+            "items.csv",
+            ImportFormat::Csv,
+            b"id,qty\n1,5\n2,many\n3,7\n",
+            &options,
+        );
+        let count: Vec<u64> = query_rows(
+            &profile,
+            &format!("SELECT COUNT(*) FROM `{schema}`.`items`"),
+        );
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.rows_imported, 2);
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0].starts_with("Row 3"), "{:?}", result.errors);
+        assert_eq!(count, vec![0]);
+    }
+
+    #[test]
+    fn xml_records_decode_cdata_entities_and_attributes() {
+        let path = temp_path("records.xml");
+        std::fs::write(
+            &path,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<RECORDS>
+  <RECORD id="ignored">
+    <name>a &lt; b &amp; c</name>
+    <note><![CDATA[<b>bold</b> & raw]]></note>
+    <empty/>
+  </RECORD>
+  <RECORD>
+    <name>Tom &amp; Jerry</name>
+    <note>x<i>y</i>z</note>
+    <empty></empty>
+  </RECORD>
+</RECORDS>"#,
+        )
+        .unwrap();
+        let records = read_xml_records(&path, false).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let pairs = |values: &[&str]| -> Vec<(String, String)> {
+            ["name", "note", "empty"]
+                .iter()
+                .zip(values)
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        assert_eq!(
+            records,
+            vec![
+                pairs(&["a < b & c", "<b>bold</b> & raw", ""]),
+                pairs(&["Tom & Jerry", "xyz", ""]),
+            ]
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn progress_is_reported_at_batch_boundaries() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "import_progress");
+        run_sql(
+            &profile,
+            &[&format!(
+                "CREATE TABLE `{schema}`.`items` (id INT PRIMARY KEY)"
+            )],
+        );
+        let files: [(&str, ImportFormat, &[u8]); 2] = [
+            ("items.csv", ImportFormat::Csv, b"id\n1\n2\n3\n4\n5\n"),
+            (
+                "items.jsonl",
+                ImportFormat::Jsonl,
+                b"{\"id\":1}\n{\"id\":2}\n{\"id\":3}\n{\"id\":4}\n{\"id\":5}\n",
+            ),
+        ];
+        let mut reported = Vec::new();
+        for (name, format, content) in files {
+            let events = Arc::new(Mutex::new(Vec::new()));
+            let sink = events.clone();
+            let options = ImportOptions {
+                batch_size: Some(2),
+                truncate_before: true,
+                progress: Some(Arc::new(move |p: &ImportProgress| {
+                    sink.lock().unwrap().push((p.rows_imported, p.done));
+                })),
+                ..Default::default()
+            };
+            let result = import_file(&profile, &schema, "items", name, format, content, &options);
+            assert!(result.success, "{name}: {:?}", result.error);
+            reported.push(events.lock().unwrap().clone());
+        }
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        let expected = vec![(2, false), (4, false), (5, true)];
+        assert_eq!(reported, vec![expected.clone(), expected]);
+    }
+
+    #[test]
+    fn failed_import_still_reports_done() {
+        let path = temp_path("unreachable.csv");
+        std::fs::write(&path, "id\n1\n").unwrap();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let options = ImportOptions {
+            progress: Some(Arc::new(move |p: &ImportProgress| {
+                sink.lock().unwrap().push((p.rows_imported, p.done));
+            })),
+            ..Default::default()
+        };
+        // 端口 1 上没有 MySQL，连接阶段即失败
+        let result = import_table_with_options(
+            &profile("127.0.0.1", 1, "root", ""),
+            "db",
+            "items",
+            &path,
+            ImportFormat::Csv,
+            &options,
+        );
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(!result.success);
+        assert_eq!(*events.lock().unwrap(), vec![(0, true)]);
+    }
+
+    // 两个工作表：Summary 在前，Data 在后
+    fn two_sheet_workbook() -> Vec<u8> {
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let summary = workbook.add_worksheet().set_name("Summary").unwrap();
+        summary.write_string(0, 0, "total").unwrap();
+        summary.write_number(1, 0, 2).unwrap();
+        let data = workbook.add_worksheet().set_name("Data").unwrap();
+        data.write_string(0, 0, "id").unwrap();
+        data.write_string(0, 1, "name").unwrap();
+        data.write_number(1, 0, 1).unwrap();
+        data.write_string(1, 1, "Alice").unwrap();
+        data.write_number(2, 0, 2).unwrap();
+        data.write_string(2, 1, "Bob").unwrap();
+        workbook.save_to_buffer().unwrap()
+    }
+
+    #[test]
+    fn excel_sheet_is_selected_by_name() {
+        let path = temp_path("sheets.xlsx");
+        std::fs::write(&path, two_sheet_workbook()).unwrap();
+        let first = read_excel_sheet(&path, None).unwrap();
+        let data = read_excel_sheet(&path, Some("Data")).unwrap();
+        let missing = read_excel_sheet(&path, Some("Missing")).err();
+        std::fs::remove_file(&path).unwrap();
+
+        let text = |range: &calamine::Range<calamine::Data>| -> Vec<Vec<String>> {
+            range
+                .rows()
+                .map(|row| row.iter().map(excel_cell_to_string).collect())
+                .collect()
+        };
+        assert_eq!(text(&first), vec![strings(&["total"]), strings(&["2"])]);
+        assert_eq!(
+            text(&data),
+            vec![
+                strings(&["id", "name"]),
+                strings(&["1", "Alice"]),
+                strings(&["2", "Bob"]),
+            ]
+        );
+        assert_eq!(
+            missing.as_deref(),
+            Some("Sheet Missing not found, available sheets: Summary, Data")
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn imports_second_sheet_selected_by_name() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "import_sheet");
+        run_sql(
+            &profile,
+            &[&format!(
+                "CREATE TABLE `{schema}`.`people` (id INT PRIMARY KEY, name VARCHAR(20))"
+            )],
+        );
+        let options = ImportOptions {
+            sheet: Some("Data".to_string()),
+            ..Default::default()
+        };
+        let result = import_file(
+            &profile,
+            &schema,
+            "people",
+            "people.xlsx",
+            ImportFormat::Xlsx,
+            &two_sheet_workbook(),
+            &options,
+        );
+        let rows: Vec<(i32, String)> = query_rows(
+            &profile,
+            &format!("SELECT id, name FROM `{schema}`.`people` ORDER BY id"),
+        );
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.rows_imported, 2);
+        assert_eq!(rows, vec![(1, "Alice".to_string()), (2, "Bob".to_string())]);
+    }
+
+    #[test]
+    fn custom_datetime_format_parses_dotted_dates() {
+        let formats: Arc<[String]> = Arc::from(strings(&["%d.%m.%Y"]));
+        let date = ColumnInfo {
+            datetime_formats: formats.clone(),
+            ..column("d", "date")
+        };
+        let datetime = ColumnInfo {
+            datetime_formats: formats,
+            ..column("dt", "datetime")
+        };
+
+        assert_eq!(
+            parse_value("31.12.2023", &date).unwrap(),
+            Value::Date(2023, 12, 31, 0, 0, 0, 0)
+        );
+        assert_eq!(
+            parse_value("31.12.2023", &datetime).unwrap(),
+            Value::Date(2023, 12, 31, 0, 0, 0, 0)
+        );
+        // 内置格式仍然可用；未配置自定义格式时点分日期报错
+        assert_eq!(
+            parse_value("2023-01-02", &date).unwrap(),
+            Value::Date(2023, 1, 2, 0, 0, 0, 0)
+        );
+        assert_eq!(
+            parse_value("31.12.2023", &column("d", "date")),
+            Err("Invalid date: 31.12.2023".to_string())
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn imports_dotted_dates_with_custom_format() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "import_date_format");
+        run_sql(
+            &profile,
+            &[&format!(
+                "CREATE TABLE `{schema}`.`events` (id INT PRIMARY KEY, happened DATE)"
+            )],
+        );
+        let options = ImportOptions {
+            datetime_formats: strings(&["%d.%m.%Y"]),
+            ..Default::default()
+        };
+        let result = import_file(
+            &profile,
+            &schema,
+            "events",
+            "events.csv",
+            ImportFormat::Csv,
+            b"id,happened\n1,31.12.2023\n2,01.02.2024\n",
+            &options,
+        );
+        let rows: Vec<(i32, String)> = query_rows(
+            &profile,
+            &format!(
+                "SELECT id, DATE_FORMAT(happened, '%Y-%m-%d') FROM `{schema}`.`events` ORDER BY id"
+            ),
+        );
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(
+            rows,
+            vec![(1, "2023-12-31".to_string()), (2, "2024-02-01".to_string())]
+        );
+    }
+
+    // GBK 编码并 gzip 压缩，前 100 行之后追加 tail 行（不参与类型推断）
+    fn gzipped_gbk_sample_csv(tail: &str) -> Vec<u8> {
+        use std::io::Write;
+        let mut csv = String::from("编号,金额,日期,名称\n");
+        for i in 1..=CREATE_TABLE_SAMPLE_ROWS {
+            csv.push_str(&format!("{i},{i}.5,2024-01-{:02},名{i}\n", i % 28 + 1));
+        }
+        csv.push_str(tail);
+        let (gbk, _, _) = encoding_rs::GBK.encode(&csv);
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&gbk).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn concatenated_gzip_members_are_read_in_full() {
+        use std::io::Write;
+        let mut data = Vec::new();
+        for part in ["id,name\n1,a\n", "2,b\n"] {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(part.as_bytes()).unwrap();
+            data.extend(encoder.finish().unwrap());
+        }
+        let path = temp_path("multi.csv.gz");
+        std::fs::write(&path, data).unwrap();
+        let (headers, rows) =
+            read_sample_rows(&path, ImportFormat::Csv, &ImportOptions::default(), 10).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(headers, strings(&["id", "name"]));
+        assert_eq!(rows, vec![strings(&["1", "a"]), strings(&["2", "b"])]);
+    }
+
+    #[test]
+    fn create_table_sample_reads_gzip_and_encoding_and_infers_types() {
+        let path = temp_path("sample.csv.gz");
+        std::fs::write(&path, gzipped_gbk_sample_csv("n/a,free,soon,尾\n")).unwrap();
+        let options = ImportOptions {
+            encoding: Some("gbk".to_string()),
+            ..Default::default()
+        };
+        let (headers, rows) =
+            read_sample_rows(&path, ImportFormat::Csv, &options, CREATE_TABLE_SAMPLE_ROWS).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(headers, strings(&["编号", "金额", "日期", "名称"]));
+        assert_eq!(rows.len(), CREATE_TABLE_SAMPLE_ROWS);
+        assert_eq!(
+            build_create_table_sql("db", "t", &headers, &rows).unwrap(),
+            "CREATE TABLE `db`.`t` (\n  `编号` BIGINT NULL,\n  `金额` DOUBLE NULL,\n  \
+             `日期` DATE NULL,\n  `名称` TEXT NULL\n)"
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn create_if_missing_infers_column_types_from_gzipped_file() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "import_create_table");
+        let options = ImportOptions {
+            create_if_missing: true,
+            encoding: Some("gbk".to_string()),
+            ..Default::default()
+        };
+        let result = import_file(
+            &profile,
+            &schema,
+            "sampled",
+            "sampled.csv.gz",
+            ImportFormat::Csv,
+            &gzipped_gbk_sample_csv("101,0.25,2024-03-01,尾\n"),
+            &options,
+        );
+        let count: Vec<u64> = query_rows(
+            &profile,
+            &format!("SELECT COUNT(*) FROM `{schema}`.`sampled`"),
+        );
+        let types: Vec<(String, String)> = query_rows(
+            &profile,
+            &format!(
+                "SELECT COLUMN_NAME, DATA_TYPE FROM information_schema.columns \
+                 WHERE table_schema = '{schema}' AND table_name = 'sampled' \
+                 ORDER BY ORDINAL_POSITION"
+            ),
+        );
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.created_table_ddl.is_some());
+        assert_eq!(count, vec![CREATE_TABLE_SAMPLE_ROWS as u64 + 1]);
+        let pair = |name: &str, data_type: &str| (name.to_string(), data_type.to_string());
+        assert_eq!(
+            types,
+            vec![
+                pair("编号", "bigint"),
+                pair("金额", "double"),
+                pair("日期", "date"),
+                pair("名称", "text"),
+            ]
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn failed_import_drops_the_table_it_created() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "import_create_rollback");
+        let options = ImportOptions {
+            create_if_missing: true,
+            encoding: Some("gbk".to_string()),
+            ..Default::default()
+        };
+        // 第 101 行不参与推断，与推断出的 BIGINT/DOUBLE/DATE 冲突
+        let result = import_file(
+            &profile,
+            &schema,
+            "sampled",
+            "sampled.csv.gz",
+            ImportFormat::Csv,
+            &gzipped_gbk_sample_csv("n/a,free,soon,尾\n"),
+            &options,
+        );
+        let tables: Vec<String> = query_rows(
+            &profile,
+            &format!(
+                "SELECT TABLE_NAME FROM information_schema.tables WHERE table_schema = '{schema}'"
+            ),
+        );
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert!(!result.success);
+        assert!(result.created_table_ddl.is_none());
+        assert!(tables.is_empty(), "{tables:?}");
+    }
+
+    #[test]
+    fn leading_zero_codes_and_non_finite_values_stay_text() {
+        assert_eq!(infer_column_type(&["001", "002"]), "TEXT");
+        assert_eq!(infer_column_type(&["0", "-12", "+3"]), "BIGINT");
+        assert_eq!(infer_column_type(&["0.5", "-1.25", "10"]), "DOUBLE");
+        assert_eq!(infer_column_type(&["00.5"]), "TEXT");
+        assert_eq!(infer_column_type(&["1.5", "NaN"]), "TEXT");
+        assert_eq!(infer_column_type(&["inf"]), "TEXT");
+    }
+}
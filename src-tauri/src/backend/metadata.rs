@@ -1,10 +1,32 @@
+use crate::backend::audit;
 use crate::backend::models::{ConnectionProfile, DbType, UserModel};
 use crate::backend::pool;
 use crate::backend::sqlutils;
+use dashmap::DashMap;
 use mysql::params;
 use mysql::prelude::*;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+// (连接标识, 库名, 对象类型)
+type MetadataCacheKey = (String, String, &'static str);
+
+type ListObjectsFn = fn(&ConnectionProfile, &str) -> Result<Vec<String>, String>;
+
+// (约束名, 列, 引用库, 引用表, 引用列, ON UPDATE, ON DELETE)
+type FkRuleRow = (String, String, String, String, String, String, String);
+
+// 估算行数不超过该值时直接 ORDER BY RAND()，否则按比例随机过滤
+const SAMPLE_ORDER_BY_RAND_MAX_ROWS: u64 = 10_000;
+// 按比例抽样时放大比例，降低因行数估算偏差导致返回不足 n 行的概率
+const SAMPLE_OVERSAMPLE_FACTOR: f64 = 3.0;
+
+const METADATA_CACHE_TTL: Duration = Duration::from_secs(60);
+static METADATA_CACHE: Lazy<DashMap<MetadataCacheKey, (Instant, Vec<String>)>> =
+    Lazy::new(DashMap::new);
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct TableDetail {
@@ -74,6 +96,8 @@ pub struct RoutineParam {
     #[serde(rename = "type")]
     pub param_type: String,
     pub mode: Option<String>,
+    #[serde(rename = "characterSet")]
+    pub character_set: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -112,6 +136,295 @@ pub struct ErForeignKeyRecord {
     pub constraint_name: String,
 }
 
+#[derive(Serialize)]
+pub struct FkGraph {
+    pub nodes: Vec<String>,
+    pub edges: Vec<FkEdge>,
+    #[serde(rename = "selfReferences")]
+    pub self_references: Vec<String>,
+    pub cycles: Vec<Vec<String>>,
+}
+
+#[derive(Serialize)]
+pub struct FkEdge {
+    pub from: String,
+    pub to: String,
+    #[serde(rename = "constraintName")]
+    pub constraint_name: String,
+    pub columns: Vec<String>,
+    #[serde(rename = "referencedColumns")]
+    pub referenced_columns: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct IndexColumn {
+    pub name: String,
+    #[serde(rename = "subPart")]
+    pub sub_part: Option<u64>,
+    pub collation: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct IndexModel {
+    pub name: String,
+    pub unique: bool,
+    #[serde(rename = "type")]
+    pub index_type: String,
+    pub columns: Vec<IndexColumn>,
+    pub visible: bool,
+    pub comment: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ColumnModel {
+    pub name: String,
+    // 重命名列时为原列名
+    #[serde(rename = "originalName", default)]
+    pub original_name: Option<String>,
+    // 完整列类型，如 varchar(64)、int unsigned、enum('a','b')
+    #[serde(rename = "columnType")]
+    pub column_type: String,
+    pub nullable: bool,
+    // 按设计器的写法：数字/CURRENT_TIMESTAMP/NULL 原样输出，括号包裹的为表达式，其余作为字符串
+    #[serde(rename = "defaultValue", default)]
+    pub default_value: Option<String>,
+    #[serde(rename = "autoIncrement", default)]
+    pub auto_increment: bool,
+    #[serde(rename = "onUpdate", default)]
+    pub on_update: Option<String>,
+    #[serde(default)]
+    pub charset: Option<String>,
+    #[serde(default)]
+    pub collation: Option<String>,
+    #[serde(default)]
+    pub comment: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ForeignKeyModel {
+    pub name: String,
+    pub columns: Vec<String>,
+    // 为空表示与当前表同库
+    #[serde(rename = "referencedSchema", default)]
+    pub referenced_schema: Option<String>,
+    #[serde(rename = "referencedTable")]
+    pub referenced_table: String,
+    #[serde(rename = "referencedColumns")]
+    pub referenced_columns: Vec<String>,
+    #[serde(rename = "onUpdate", default = "default_fk_rule")]
+    pub on_update: String,
+    #[serde(rename = "onDelete", default = "default_fk_rule")]
+    pub on_delete: String,
+}
+
+fn default_fk_rule() -> String {
+    "RESTRICT".to_string()
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct TableModel {
+    pub columns: Vec<ColumnModel>,
+    #[serde(default)]
+    pub indexes: Vec<IndexModel>,
+    #[serde(rename = "foreignKeys", default)]
+    pub foreign_keys: Vec<ForeignKeyModel>,
+}
+
+#[derive(Serialize, Default)]
+pub struct TableChangePreview {
+    pub sql: String,
+    pub warnings: Vec<String>,
+}
+
+// 列类型的容量，用于判断修改是否可能截断或丢失数据
+#[derive(PartialEq)]
+enum TypeCapacity {
+    Text(u64),
+    Binary(u64),
+    Integer { bytes: u8, unsigned: bool },
+    Decimal { precision: u64, scale: u64 },
+    Float(u8),
+    Enum(Vec<String>),
+    Other(String),
+}
+
+#[derive(Serialize)]
+pub struct UnusedIndex {
+    pub table: String,
+    #[serde(rename = "indexName")]
+    pub index_name: String,
+    pub columns: Vec<String>,
+    pub unique: bool,
+}
+
+// 不含生成时间，结构未变时两次导出的内容完全相同，便于纳入版本控制比较
+#[derive(Serialize, Deserialize)]
+pub struct SchemaSnapshot {
+    pub schema: String,
+    pub tables: Vec<TableSnapshot>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TableSnapshot {
+    pub name: String,
+    pub columns: Vec<BTreeMap<String, String>>,
+    pub indexes: Vec<IndexModel>,
+    #[serde(rename = "foreignKeys")]
+    pub foreign_keys: Vec<BTreeMap<String, String>>,
+    pub triggers: Vec<BTreeMap<String, String>>,
+}
+
+#[derive(Serialize, Default)]
+pub struct SchemaDiff {
+    #[serde(rename = "addedTables")]
+    pub added_tables: Vec<String>,
+    #[serde(rename = "removedTables")]
+    pub removed_tables: Vec<String>,
+    #[serde(rename = "changedTables")]
+    pub changed_tables: Vec<TableDiff>,
+}
+
+#[derive(Serialize, Default)]
+pub struct TableDiff {
+    pub name: String,
+    #[serde(rename = "addedColumns")]
+    pub added_columns: Vec<String>,
+    #[serde(rename = "removedColumns")]
+    pub removed_columns: Vec<String>,
+    #[serde(rename = "changedColumns")]
+    pub changed_columns: Vec<String>,
+    #[serde(rename = "addedIndexes")]
+    pub added_indexes: Vec<String>,
+    #[serde(rename = "removedIndexes")]
+    pub removed_indexes: Vec<String>,
+    #[serde(rename = "changedIndexes")]
+    pub changed_indexes: Vec<String>,
+    #[serde(rename = "addedForeignKeys")]
+    pub added_foreign_keys: Vec<String>,
+    #[serde(rename = "removedForeignKeys")]
+    pub removed_foreign_keys: Vec<String>,
+    #[serde(rename = "addedTriggers")]
+    pub added_triggers: Vec<String>,
+    #[serde(rename = "removedTriggers")]
+    pub removed_triggers: Vec<String>,
+}
+
+impl TableDiff {
+    fn is_empty(&self) -> bool {
+        self.added_columns.is_empty()
+            && self.removed_columns.is_empty()
+            && self.changed_columns.is_empty()
+            && self.added_indexes.is_empty()
+            && self.removed_indexes.is_empty()
+            && self.changed_indexes.is_empty()
+            && self.added_foreign_keys.is_empty()
+            && self.removed_foreign_keys.is_empty()
+            && self.added_triggers.is_empty()
+            && self.removed_triggers.is_empty()
+    }
+}
+
+#[derive(Serialize, Default)]
+pub struct CloneDatabaseResult {
+    pub tables: u64,
+    pub views: u64,
+    pub routines: u64,
+    pub triggers: u64,
+    #[serde(rename = "rowsCopied")]
+    pub rows_copied: u64,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ObjectKind {
+    Table,
+    View,
+    Routine,
+    Trigger,
+    Event,
+}
+
+#[derive(Default)]
+struct DropTargets {
+    tables: Vec<String>,
+    views: Vec<String>,
+    routines: Vec<(String, String)>,
+    triggers: Vec<String>,
+    events: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct DatabaseOverview {
+    pub name: String,
+    #[serde(rename = "tableCount")]
+    pub table_count: u64,
+    #[serde(rename = "sizeBytes")]
+    pub size_bytes: u64,
+}
+
+#[derive(Serialize)]
+pub struct ServerOverview {
+    pub databases: Vec<DatabaseOverview>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RowKey {
+    pub column: String,
+    pub value: Option<String>,
+}
+
+/// 索引、外键、触发器列表的排序方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ListOrder {
+    // 定义顺序：复合索引/外键的列按 SEQ_IN_INDEX / ORDINAL_POSITION，触发器按执行顺序
+    #[default]
+    Ordinal,
+    // 按名称字母序，复合对象内的列也按列名排列
+    Name,
+}
+
+#[derive(Serialize)]
+pub struct RowLocator {
+    pub schema: String,
+    pub table: String,
+    // 定位列及其值，fetch_row_by_locator 按这些值绑定参数查询
+    pub keys: Vec<RowKey>,
+    // 供复制展示的规范化定位串
+    pub locator: String,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct TableStorage {
+    pub table: String,
+    #[serde(rename = "dataLength")]
+    pub data_length: u64,
+    #[serde(rename = "indexLength")]
+    pub index_length: u64,
+    #[serde(rename = "dataFree")]
+    pub data_free: u64,
+    pub total: u64,
+    #[serde(rename = "totalDisplay")]
+    pub total_display: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct SchemaWarmProgress {
+    pub schema: String,
+    pub stage: String,
+    pub count: usize,
+    pub done: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct RenameTableResult {
+    pub success: bool,
+    pub warnings: Vec<String>,
+}
+
 #[derive(Serialize, Clone)]
 pub struct RoutineDetail {
     pub name: String,
@@ -119,9 +432,38 @@ pub struct RoutineDetail {
     pub routine_type: String,
     #[serde(rename = "returnType")]
     pub return_type: Option<String>,
+    #[serde(rename = "returnCharacterSet")]
+    pub return_character_set: Option<String>,
     pub params: Vec<RoutineParam>,
+    pub deterministic: bool,
+    #[serde(rename = "sqlDataAccess")]
+    pub sql_data_access: Option<String>,
+    #[serde(rename = "securityType")]
+    pub security_type: Option<String>,
+    pub comment: Option<String>,
 }
 
+type RoutineRow = (
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+type RoutineParamRow = (
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
 #[derive(Serialize)]
 pub struct UserSummary {
     pub username: String,
@@ -141,6 +483,22 @@ pub struct UserModelPayload {
     pub database_privileges: BTreeMap<String, Vec<String>>,
 }
 
+#[derive(Deserialize, Default)]
+pub struct PrivFilter {
+    pub privilege: Option<String>,
+    pub database: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct UserPrivilegeSummary {
+    pub username: String,
+    pub host: String,
+    #[serde(rename = "serverPrivileges")]
+    pub server_privileges: Vec<String>,
+    #[serde(rename = "databasePrivileges")]
+    pub database_privileges: BTreeMap<String, Vec<String>>,
+}
+
 #[derive(Deserialize)]
 struct UserSqlPayload {
     username: String,
@@ -212,6 +570,42 @@ const DB_PRIVILEGES: [&str; 19] = [
 
 const COLUMN_LEVEL_PRIVILEGES: [&str; 4] = ["Select", "Insert", "Update", "References"];
 
+// 包含 5.7 中已废弃的模式，便于对旧版本服务器读写
+const KNOWN_SQL_MODES: [&str; 32] = [
+    "ALLOW_INVALID_DATES",
+    "ANSI",
+    "ANSI_QUOTES",
+    "DB2",
+    "ERROR_FOR_DIVISION_BY_ZERO",
+    "HIGH_NOT_PRECEDENCE",
+    "IGNORE_SPACE",
+    "MAXDB",
+    "MSSQL",
+    "MYSQL323",
+    "MYSQL40",
+    "NO_AUTO_CREATE_USER",
+    "NO_AUTO_VALUE_ON_ZERO",
+    "NO_BACKSLASH_ESCAPES",
+    "NO_DIR_IN_CREATE",
+    "NO_ENGINE_SUBSTITUTION",
+    "NO_FIELD_OPTIONS",
+    "NO_KEY_OPTIONS",
+    "NO_TABLE_OPTIONS",
+    "NO_UNSIGNED_SUBTRACTION",
+    "NO_ZERO_DATE",
+    "NO_ZERO_IN_DATE",
+    "ONLY_FULL_GROUP_BY",
+    "ORACLE",
+    "PAD_CHAR_TO_FULL_LENGTH",
+    "PIPES_AS_CONCAT",
+    "POSTGRESQL",
+    "REAL_AS_FLOAT",
+    "STRICT_ALL_TABLES",
+    "STRICT_TRANS_TABLES",
+    "TIME_TRUNCATE_FRACTIONAL",
+    "TRADITIONAL",
+];
+
 pub fn list_databases(profile: &ConnectionProfile) -> Result<Vec<String>, String> {
     pool::with_temp_connection(profile, |conn| {
         conn.query_map("SHOW DATABASES", |db: String| db)
@@ -219,13 +613,10 @@ pub fn list_databases(profile: &ConnectionProfile) -> Result<Vec<String>, String
     })
 }
 
+const BASE_TABLES_SQL: &str = "SELECT TABLE_NAME FROM INFORMATION_SCHEMA.TABLES WHERE TABLE_SCHEMA = :schema AND TABLE_TYPE = 'BASE TABLE' ORDER BY TABLE_NAME";
+
 pub fn list_tables(profile: &ConnectionProfile, schema: &str) -> Result<Vec<String>, String> {
-    let schema = schema.to_string();
-    pool::with_temp_connection(profile, |conn| {
-        let sql = "SELECT TABLE_NAME FROM INFORMATION_SCHEMA.TABLES WHERE TABLE_SCHEMA = :schema AND TABLE_TYPE = 'BASE TABLE' ORDER BY TABLE_NAME";
-        conn.exec_map(sql, params! {"schema" => &schema}, |name: String| name)
-            .map_err(|e| format!("Query failed: {e}"))
-    })
+    cached_object_names(profile, schema, "tables", BASE_TABLES_SQL)
 }
 
 pub fn list_table_details(
@@ -261,15 +652,88 @@ pub fn list_table_details(
     })
 }
 
-pub fn list_views(profile: &ConnectionProfile, schema: &str) -> Result<Vec<String>, String> {
-    let schema = schema.to_string();
+/// 一次聚合查询得到所有库的表数量与大小，代替逐库 list_tables
+pub fn get_server_overview(profile: &ConnectionProfile) -> Result<ServerOverview, String> {
     pool::with_temp_connection(profile, |conn| {
-        let sql = "SELECT TABLE_NAME FROM INFORMATION_SCHEMA.TABLES WHERE TABLE_SCHEMA = :schema AND TABLE_TYPE = 'VIEW' ORDER BY TABLE_NAME";
-        conn.exec_map(sql, params! {"schema" => &schema}, |name: String| name)
-            .map_err(|e| format!("Query failed: {e}"))
+        let sql = "SELECT s.SCHEMA_NAME, COUNT(t.TABLE_NAME), CAST(COALESCE(SUM(t.DATA_LENGTH + t.INDEX_LENGTH), 0) AS UNSIGNED) FROM INFORMATION_SCHEMA.SCHEMATA s LEFT JOIN INFORMATION_SCHEMA.TABLES t ON t.TABLE_SCHEMA = s.SCHEMA_NAME AND t.TABLE_TYPE = 'BASE TABLE' GROUP BY s.SCHEMA_NAME ORDER BY s.SCHEMA_NAME";
+        let rows: Vec<(String, u64, u64)> =
+            conn.query(sql).map_err(|e| format!("Query failed: {e}"))?;
+        Ok(ServerOverview {
+            databases: rows
+                .into_iter()
+                .map(|(name, table_count, size_bytes)| DatabaseOverview {
+                    name,
+                    table_count,
+                    size_bytes,
+                })
+                .collect(),
+        })
     })
 }
 
+// (表名, DATA_LENGTH, INDEX_LENGTH, DATA_FREE)
+type StorageRow = (String, Option<u64>, Option<u64>, Option<u64>);
+
+/// 各表数据、索引与碎片空间占用，按总大小降序
+pub fn get_storage_breakdown(
+    profile: &ConnectionProfile,
+    schema: &str,
+) -> Result<Vec<TableStorage>, String> {
+    let schema = schema.to_string();
+    let rows: Vec<StorageRow> = pool::with_temp_connection(profile, |conn| {
+        let sql = "SELECT TABLE_NAME, DATA_LENGTH, INDEX_LENGTH, DATA_FREE FROM INFORMATION_SCHEMA.TABLES WHERE TABLE_SCHEMA = :schema AND TABLE_TYPE = 'BASE TABLE'";
+        conn.exec(sql, params! {"schema" => &schema})
+            .map_err(|e| format!("Query failed: {e}"))
+    })?;
+    Ok(build_storage_breakdown(rows))
+}
+
+fn build_storage_breakdown(rows: Vec<StorageRow>) -> Vec<TableStorage> {
+    let mut tables: Vec<TableStorage> = rows
+        .into_iter()
+        .map(|(table, data_length, index_length, data_free)| {
+            let data_length = data_length.unwrap_or(0);
+            let index_length = index_length.unwrap_or(0);
+            let data_free = data_free.unwrap_or(0);
+            let total = data_length + index_length + data_free;
+            TableStorage {
+                table,
+                data_length,
+                index_length,
+                data_free,
+                total,
+                total_display: format_bytes(total),
+            }
+        })
+        .collect();
+    tables.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.table.cmp(&b.table)));
+    tables
+}
+
+/// 字节数转为 `1.5 MB` 形式
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+pub fn list_views(profile: &ConnectionProfile, schema: &str) -> Result<Vec<String>, String> {
+    cached_object_names(
+        profile,
+        schema,
+        "views",
+        "SELECT TABLE_NAME FROM INFORMATION_SCHEMA.TABLES WHERE TABLE_SCHEMA = :schema AND TABLE_TYPE = 'VIEW' ORDER BY TABLE_NAME",
+    )
+}
+
 pub fn list_view_details(
     profile: &ConnectionProfile,
     schema: &str,
@@ -319,66 +783,252 @@ pub fn list_view_details(
 }
 
 pub fn list_functions(profile: &ConnectionProfile, schema: &str) -> Result<Vec<String>, String> {
-    let schema = schema.to_string();
-    pool::with_temp_connection(profile, |conn| {
-        let sql = "SELECT ROUTINE_NAME FROM INFORMATION_SCHEMA.ROUTINES WHERE ROUTINE_SCHEMA = :schema ORDER BY ROUTINE_NAME";
-        conn.exec_map(sql, params! {"schema" => &schema}, |name: String| name)
-            .map_err(|e| format!("Query failed: {e}"))
+    cached_object_names(
+        profile,
+        schema,
+        "routines",
+        "SELECT ROUTINE_NAME FROM INFORMATION_SCHEMA.ROUTINES WHERE ROUTINE_SCHEMA = :schema ORDER BY ROUTINE_NAME",
+    )
+}
+
+fn metadata_cache_key(
+    profile: &ConnectionProfile,
+    schema: &str,
+    kind: &'static str,
+) -> MetadataCacheKey {
+    (
+        pool::PoolConfig::from_profile(profile).connection_key(),
+        schema.to_string(),
+        kind,
+    )
+}
+
+// 对象名列表短时缓存，过期或执行 DDL 后重新查询
+fn cached_object_names(
+    profile: &ConnectionProfile,
+    schema: &str,
+    kind: &'static str,
+    sql: &str,
+) -> Result<Vec<String>, String> {
+    cached_names(metadata_cache_key(profile, schema, kind), || {
+        pool::with_temp_connection(profile, |conn| {
+            conn.exec_map(sql, params! {"schema" => schema}, |name: String| name)
+                .map_err(|e| format!("Query failed: {e}"))
+        })
     })
 }
 
+fn cached_names(
+    key: MetadataCacheKey,
+    load: impl FnOnce() -> Result<Vec<String>, String>,
+) -> Result<Vec<String>, String> {
+    if let Some(entry) = METADATA_CACHE.get(&key) {
+        if entry.0.elapsed() < METADATA_CACHE_TTL {
+            return Ok(entry.1.clone());
+        }
+    }
+    let names = load()?;
+    METADATA_CACHE.insert(key, (Instant::now(), names.clone()));
+    Ok(names)
+}
+
+/// 清除该连接下所有库的对象列表缓存
+pub fn invalidate_metadata_cache(profile: &ConnectionProfile) {
+    invalidate_metadata_cache_for_key(&pool::PoolConfig::from_profile(profile).connection_key());
+}
+
+/// 按连接键清除对象列表缓存，连接池执行 DDL 后调用
+pub fn invalidate_metadata_cache_for_key(connection_key: &str) {
+    METADATA_CACHE.retain(|(key, _, _), _| key != connection_key);
+}
+
+/// 清除所有连接的对象列表缓存（前端全局刷新）
+pub fn clear_metadata_cache() {
+    METADATA_CACHE.clear();
+}
+
+/// 后台预取表、视图和存储过程列表写入缓存，每完成一类发送一次 `schema-warmed` 事件
+pub fn warm_schema(profile: ConnectionProfile, schema: String, app_handle: AppHandle) {
+    std::thread::spawn(move || {
+        let stages: [(&str, ListObjectsFn); 3] = [
+            ("tables", list_tables),
+            ("views", list_views),
+            ("routines", list_functions),
+        ];
+        let total = stages.len();
+        for (index, (stage, load)) in stages.into_iter().enumerate() {
+            let (count, error) = match load(&profile, &schema) {
+                Ok(names) => (names.len(), None),
+                Err(err) => (0, Some(err)),
+            };
+            let _ = app_handle.emit(
+                "schema-warmed",
+                SchemaWarmProgress {
+                    schema: schema.clone(),
+                    stage: stage.to_string(),
+                    count,
+                    done: index + 1 == total,
+                    error,
+                },
+            );
+        }
+    });
+}
+
 pub fn list_routines_with_details(
     profile: &ConnectionProfile,
     schema: &str,
 ) -> Result<Vec<RoutineDetail>, String> {
-    let schema = schema.to_string();
+    pool::with_temp_connection(profile, |conn| load_routine_details(conn, schema, None))
+}
+
+pub fn get_routine_detail(
+    profile: &ConnectionProfile,
+    schema: &str,
+    name: &str,
+) -> Result<RoutineDetail, String> {
     pool::with_temp_connection(profile, |conn| {
-        let sql_routine = "SELECT ROUTINE_NAME, ROUTINE_TYPE, DATA_TYPE, DTD_IDENTIFIER FROM INFORMATION_SCHEMA.ROUTINES WHERE ROUTINE_SCHEMA = :schema ORDER BY ROUTINE_NAME";
-        let routines: Vec<(String, String, Option<String>, Option<String>)> = conn
-            .exec(sql_routine, params! {"schema" => &schema})
-            .map_err(|e| format!("Query failed: {e}"))?;
+        load_routine_details(conn, schema, Some(name))?
+            .into_iter()
+            .next()
+            .ok_or_else(|| format!("Routine {schema}.{name} not found"))
+    })
+}
 
-        let mut routine_map: BTreeMap<String, RoutineDetail> = BTreeMap::new();
-        for (name, routine_type, data_type, dtd) in routines {
-            let return_type = dtd.or(data_type);
-            routine_map.insert(
-                name.clone(),
-                RoutineDetail {
-                    name,
-                    routine_type,
-                    return_type,
-                    params: Vec::new(),
-                },
-            );
+// name 为 None 时加载整个 schema 的例程
+fn load_routine_details(
+    conn: &mut mysql::Conn,
+    schema: &str,
+    name: Option<&str>,
+) -> Result<Vec<RoutineDetail>, String> {
+    let sql_routine = "SELECT ROUTINE_NAME, ROUTINE_TYPE, DATA_TYPE, DTD_IDENTIFIER, CHARACTER_SET_NAME, IS_DETERMINISTIC, SQL_DATA_ACCESS, SECURITY_TYPE, ROUTINE_COMMENT FROM INFORMATION_SCHEMA.ROUTINES WHERE ROUTINE_SCHEMA = :schema AND (:name IS NULL OR ROUTINE_NAME = :name) ORDER BY ROUTINE_NAME";
+    let routines: Vec<RoutineRow> = conn
+        .exec(sql_routine, params! {"schema" => schema, "name" => name})
+        .map_err(|e| format!("Query failed: {e}"))?;
+
+    let mut routine_map: BTreeMap<String, RoutineDetail> = BTreeMap::new();
+    for (
+        name,
+        routine_type,
+        data_type,
+        dtd,
+        charset,
+        is_deterministic,
+        sql_data_access,
+        security_type,
+        comment,
+    ) in routines
+    {
+        let return_type = dtd.or(data_type);
+        routine_map.insert(
+            name.clone(),
+            RoutineDetail {
+                name,
+                routine_type,
+                return_type,
+                return_character_set: charset,
+                params: Vec::new(),
+                deterministic: is_deterministic.as_deref() == Some("YES"),
+                sql_data_access,
+                security_type,
+                comment,
+            },
+        );
+    }
+
+    let sql_param = "SELECT SPECIFIC_NAME, PARAMETER_NAME, DATA_TYPE, DTD_IDENTIFIER, PARAMETER_MODE, CHARACTER_SET_NAME FROM INFORMATION_SCHEMA.PARAMETERS WHERE SPECIFIC_SCHEMA = :schema AND (:name IS NULL OR SPECIFIC_NAME = :name) ORDER BY SPECIFIC_NAME, ORDINAL_POSITION";
+    let params_rows: Vec<RoutineParamRow> = conn
+        .exec(sql_param, params! {"schema" => schema, "name" => name})
+        .map_err(|e| format!("Query failed: {e}"))?;
+
+    for (routine_name, param_name, data_type, dtd, mode, charset) in params_rows {
+        if let Some(param_name) = param_name {
+            if let Some(routine) = routine_map.get_mut(&routine_name) {
+                let param_type = dtd.or(data_type).unwrap_or_default();
+                routine.params.push(RoutineParam {
+                    name: param_name,
+                    param_type,
+                    mode,
+                    character_set: charset,
+                });
+            }
         }
+    }
 
-        let sql_param = "SELECT SPECIFIC_NAME, PARAMETER_NAME, DATA_TYPE, DTD_IDENTIFIER, PARAMETER_MODE, ORDINAL_POSITION FROM INFORMATION_SCHEMA.PARAMETERS WHERE SPECIFIC_SCHEMA = :schema ORDER BY SPECIFIC_NAME, ORDINAL_POSITION";
-        let params_rows: Vec<(
-            String,
-            Option<String>,
-            Option<String>,
-            Option<String>,
-            Option<String>,
-            Option<u32>,
-        )> = conn
-            .exec(sql_param, params! {"schema" => &schema})
-            .map_err(|e| format!("Query failed: {e}"))?;
+    Ok(routine_map.into_values().collect())
+}
 
-        for (routine_name, param_name, data_type, dtd, mode, _) in params_rows {
-            if let Some(param_name) = param_name {
-                if let Some(routine) = routine_map.get_mut(&routine_name) {
-                    let param_type = dtd.or(data_type).unwrap_or_default();
-                    routine.params.push(RoutineParam {
-                        name: param_name,
-                        param_type,
-                        mode,
-                    });
-                }
+/// 按 SHOW CREATE 的写法重建例程签名（不含 DEFINER 和例程体）
+pub fn generate_routine_header_sql(detail: &RoutineDetail) -> String {
+    let is_function = detail.routine_type.eq_ignore_ascii_case("FUNCTION");
+    let params = detail
+        .params
+        .iter()
+        .map(|param| {
+            let mut text = String::new();
+            // 函数参数只能是 IN，不写模式
+            if let Some(mode) = param.mode.as_deref().filter(|_| !is_function) {
+                text.push_str(mode);
+                text.push(' ');
             }
+            text.push_str(&format!("`{}` ", escape_identifier(&param.name)));
+            text.push_str(&with_charset(
+                &param.param_type,
+                param.character_set.as_deref(),
+            ));
+            text
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut sql = format!(
+        "CREATE {} `{}`({})",
+        if is_function { "FUNCTION" } else { "PROCEDURE" },
+        escape_identifier(&detail.name),
+        params
+    );
+    if is_function {
+        if let Some(return_type) = detail.return_type.as_deref() {
+            sql.push_str(" RETURNS ");
+            sql.push_str(&with_charset(
+                return_type,
+                detail.return_character_set.as_deref(),
+            ));
         }
+    }
+    // 与 SHOW CREATE 一致，默认特性（NOT DETERMINISTIC / CONTAINS SQL / SQL SECURITY DEFINER）省略
+    if detail.deterministic {
+        sql.push_str("\n    DETERMINISTIC");
+    }
+    if let Some(access) = detail
+        .sql_data_access
+        .as_deref()
+        .filter(|access| !access.eq_ignore_ascii_case("CONTAINS SQL"))
+    {
+        sql.push_str("\n    ");
+        sql.push_str(access);
+    }
+    if let Some(security) = detail
+        .security_type
+        .as_deref()
+        .filter(|security| !security.eq_ignore_ascii_case("DEFINER"))
+    {
+        sql.push_str("\n    SQL SECURITY ");
+        sql.push_str(security);
+    }
+    if let Some(comment) = detail.comment.as_deref().filter(|c| !c.is_empty()) {
+        sql.push_str(&format!("\n    COMMENT '{}'", escape_string(comment)));
+    }
+    sql
+}
 
-        Ok(routine_map.into_values().collect())
-    })
+fn with_charset(data_type: &str, charset: Option<&str>) -> String {
+    match charset {
+        Some(charset) if !data_type.to_ascii_lowercase().contains("charset") => {
+            format!("{data_type} CHARSET {charset}")
+        }
+        _ => data_type.to_string(),
+    }
 }
 
 pub fn list_function_details(
@@ -478,23 +1128,25 @@ pub fn get_routine_params(
     let schema = schema.to_string();
     let name = name.to_string();
     pool::with_temp_connection(profile, |conn| {
-        let sql = "SELECT PARAMETER_NAME, DATA_TYPE, DTD_IDENTIFIER, PARAMETER_MODE FROM INFORMATION_SCHEMA.PARAMETERS WHERE SPECIFIC_SCHEMA = :schema AND SPECIFIC_NAME = :name AND PARAMETER_NAME IS NOT NULL ORDER BY ORDINAL_POSITION";
+        let sql = "SELECT PARAMETER_NAME, DATA_TYPE, DTD_IDENTIFIER, PARAMETER_MODE, CHARACTER_SET_NAME FROM INFORMATION_SCHEMA.PARAMETERS WHERE SPECIFIC_SCHEMA = :schema AND SPECIFIC_NAME = :name AND PARAMETER_NAME IS NOT NULL ORDER BY ORDINAL_POSITION";
         let rows: Vec<(
             Option<String>,
             Option<String>,
             Option<String>,
             Option<String>,
+            Option<String>,
         )> = conn
             .exec(sql, params! {"schema" => &schema, "name" => &name})
             .map_err(|e| format!("Query failed: {e}"))?;
 
         Ok(rows
             .into_iter()
-            .filter_map(|(param_name, data_type, dtd, mode)| {
+            .filter_map(|(param_name, data_type, dtd, mode, charset)| {
                 param_name.map(|name| RoutineParam {
                     name,
                     param_type: dtd.or(data_type).unwrap_or_default(),
                     mode,
+                    character_set: charset,
                 })
             })
             .collect())
@@ -506,121 +1158,332 @@ pub fn list_columns(
     schema: &str,
     table: &str,
 ) -> Result<Vec<BTreeMap<String, String>>, String> {
-    let schema = schema.to_string();
-    let table = table.to_string();
-    pool::with_temp_connection(profile, |conn| {
-        let sql = "SELECT COLUMN_NAME, DATA_TYPE, COLUMN_TYPE, IS_NULLABLE, COLUMN_DEFAULT, COLUMN_KEY, EXTRA, CHARACTER_MAXIMUM_LENGTH, NUMERIC_PRECISION, NUMERIC_SCALE, COLUMN_COMMENT, CHARACTER_SET_NAME, COLLATION_NAME FROM INFORMATION_SCHEMA.COLUMNS WHERE TABLE_SCHEMA = :schema AND TABLE_NAME = :table ORDER BY ORDINAL_POSITION";
-        let rows: Vec<mysql::Row> = conn
-            .exec(sql, params! {"schema" => &schema, "table" => &table})
-            .map_err(|e| format!("Query failed: {e}"))?;
-        let mut result = Vec::new();
-        for row in rows {
-            let mut map = BTreeMap::new();
-            map.insert(
-                "COLUMN_NAME".to_string(),
-                row.get::<String, _>("COLUMN_NAME").unwrap_or_default(),
-            );
-            map.insert(
-                "DATA_TYPE".to_string(),
-                row.get::<String, _>("DATA_TYPE").unwrap_or_default(),
-            );
-            map.insert(
-                "COLUMN_TYPE".to_string(),
-                row.get::<String, _>("COLUMN_TYPE").unwrap_or_default(),
-            );
-            map.insert(
-                "IS_NULLABLE".to_string(),
-                row.get::<String, _>("IS_NULLABLE").unwrap_or_default(),
-            );
-            map.insert(
-                "COLUMN_DEFAULT".to_string(),
-                row.get::<Option<String>, _>("COLUMN_DEFAULT")
-                    .unwrap_or_default()
-                    .unwrap_or_default(),
-            );
-            map.insert(
-                "COLUMN_KEY".to_string(),
-                row.get::<Option<String>, _>("COLUMN_KEY")
-                    .unwrap_or_default()
-                    .unwrap_or_default(),
-            );
-            map.insert(
-                "EXTRA".to_string(),
-                row.get::<Option<String>, _>("EXTRA")
-                    .unwrap_or_default()
-                    .unwrap_or_default(),
-            );
-            map.insert(
-                "CHARACTER_MAXIMUM_LENGTH".to_string(),
-                row.get::<Option<i64>, _>("CHARACTER_MAXIMUM_LENGTH")
-                    .unwrap_or_default()
-                    .map(|v| v.to_string())
-                    .unwrap_or_default(),
-            );
-            map.insert(
-                "NUMERIC_PRECISION".to_string(),
-                row.get::<Option<i64>, _>("NUMERIC_PRECISION")
-                    .unwrap_or_default()
-                    .map(|v| v.to_string())
-                    .unwrap_or_default(),
-            );
-            map.insert(
-                "NUMERIC_SCALE".to_string(),
-                row.get::<Option<i64>, _>("NUMERIC_SCALE")
-                    .unwrap_or_default()
-                    .map(|v| v.to_string())
-                    .unwrap_or_default(),
-            );
-            map.insert(
-                "COLUMN_COMMENT".to_string(),
-                row.get::<Option<String>, _>("COLUMN_COMMENT")
-                    .unwrap_or_default()
-                    .unwrap_or_default(),
-            );
-            map.insert(
-                "CHARACTER_SET_NAME".to_string(),
-                row.get::<Option<String>, _>("CHARACTER_SET_NAME")
-                    .unwrap_or_default()
-                    .unwrap_or_default(),
-            );
-            map.insert(
-                "COLLATION_NAME".to_string(),
-                row.get::<Option<String>, _>("COLLATION_NAME")
-                    .unwrap_or_default()
-                    .unwrap_or_default(),
-            );
-            result.push(map);
-        }
-        Ok(result)
-    })
+    pool::with_temp_connection(profile, |conn| load_columns(conn, schema, table))
 }
 
-pub fn list_foreign_keys(
-    profile: &ConnectionProfile,
+fn load_columns(
+    conn: &mut mysql::Conn,
     schema: &str,
     table: &str,
 ) -> Result<Vec<BTreeMap<String, String>>, String> {
-    let schema = schema.to_string();
-    let table = table.to_string();
+    let sql = "SELECT COLUMN_NAME, DATA_TYPE, COLUMN_TYPE, IS_NULLABLE, COLUMN_DEFAULT, COLUMN_KEY, EXTRA, CHARACTER_MAXIMUM_LENGTH, NUMERIC_PRECISION, NUMERIC_SCALE, COLUMN_COMMENT, CHARACTER_SET_NAME, COLLATION_NAME FROM INFORMATION_SCHEMA.COLUMNS WHERE TABLE_SCHEMA = :schema AND TABLE_NAME = :table ORDER BY ORDINAL_POSITION";
+    let rows: Vec<mysql::Row> = conn
+        .exec(sql, params! {"schema" => schema, "table" => table})
+        .map_err(|e| format!("Query failed: {e}"))?;
+    let mut result = Vec::new();
+    for row in rows {
+        let mut map = BTreeMap::new();
+        map.insert(
+            "COLUMN_NAME".to_string(),
+            row.get::<String, _>("COLUMN_NAME").unwrap_or_default(),
+        );
+        map.insert(
+            "DATA_TYPE".to_string(),
+            row.get::<String, _>("DATA_TYPE").unwrap_or_default(),
+        );
+        map.insert(
+            "COLUMN_TYPE".to_string(),
+            row.get::<String, _>("COLUMN_TYPE").unwrap_or_default(),
+        );
+        map.insert(
+            "IS_NULLABLE".to_string(),
+            row.get::<String, _>("IS_NULLABLE").unwrap_or_default(),
+        );
+        map.insert(
+            "COLUMN_DEFAULT".to_string(),
+            row.get::<Option<String>, _>("COLUMN_DEFAULT")
+                .unwrap_or_default()
+                .unwrap_or_default(),
+        );
+        map.insert(
+            "COLUMN_KEY".to_string(),
+            row.get::<Option<String>, _>("COLUMN_KEY")
+                .unwrap_or_default()
+                .unwrap_or_default(),
+        );
+        map.insert(
+            "EXTRA".to_string(),
+            row.get::<Option<String>, _>("EXTRA")
+                .unwrap_or_default()
+                .unwrap_or_default(),
+        );
+        map.insert(
+            "CHARACTER_MAXIMUM_LENGTH".to_string(),
+            row.get::<Option<i64>, _>("CHARACTER_MAXIMUM_LENGTH")
+                .unwrap_or_default()
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+        );
+        map.insert(
+            "NUMERIC_PRECISION".to_string(),
+            row.get::<Option<i64>, _>("NUMERIC_PRECISION")
+                .unwrap_or_default()
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+        );
+        map.insert(
+            "NUMERIC_SCALE".to_string(),
+            row.get::<Option<i64>, _>("NUMERIC_SCALE")
+                .unwrap_or_default()
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+        );
+        map.insert(
+            "COLUMN_COMMENT".to_string(),
+            row.get::<Option<String>, _>("COLUMN_COMMENT")
+                .unwrap_or_default()
+                .unwrap_or_default(),
+        );
+        map.insert(
+            "CHARACTER_SET_NAME".to_string(),
+            row.get::<Option<String>, _>("CHARACTER_SET_NAME")
+                .unwrap_or_default()
+                .unwrap_or_default(),
+        );
+        map.insert(
+            "COLLATION_NAME".to_string(),
+            row.get::<Option<String>, _>("COLLATION_NAME")
+                .unwrap_or_default()
+                .unwrap_or_default(),
+        );
+        result.push(map);
+    }
+    Ok(result)
+}
+
+/// 随机抽样预览。TABLE_ROWS 只是估算值：大表按 `RAND() < 比例` 过滤并在取满 n 行后停止扫描，
+/// 结果偏向扫描靠前的部分，且可能少于 n 行；小表使用 `ORDER BY RAND()` 精确随机
+pub fn sample_rows(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    n: u64,
+) -> Result<pool::QueryResult, String> {
+    if n == 0 {
+        return Err("Sample size must be greater than 0".to_string());
+    }
     pool::with_temp_connection(profile, |conn| {
-        let sql = "SELECT COLUMN_NAME, REFERENCED_TABLE_SCHEMA, REFERENCED_TABLE_NAME, REFERENCED_COLUMN_NAME, CONSTRAINT_NAME FROM INFORMATION_SCHEMA.KEY_COLUMN_USAGE WHERE TABLE_SCHEMA = :schema AND TABLE_NAME = :table AND REFERENCED_TABLE_NAME IS NOT NULL";
-        let rows: Vec<(String, String, String, String, String)> = conn
-            .exec(sql, params! {"schema" => &schema, "table" => &table})
+        let estimated_rows: Option<Option<u64>> = conn
+            .exec_first(
+                "SELECT TABLE_ROWS FROM INFORMATION_SCHEMA.TABLES WHERE TABLE_SCHEMA = :schema AND TABLE_NAME = :table",
+                params! {"schema" => schema, "table" => table},
+            )
             .map_err(|e| format!("Query failed: {e}"))?;
-        let mut result = Vec::new();
-        for (column_name, ref_schema, ref_table, ref_col, constraint) in rows {
-            let mut map = BTreeMap::new();
-            map.insert("COLUMN_NAME".to_string(), column_name);
-            map.insert("REFERENCED_TABLE_SCHEMA".to_string(), ref_schema);
-            map.insert("REFERENCED_TABLE_NAME".to_string(), ref_table);
-            map.insert("REFERENCED_COLUMN_NAME".to_string(), ref_col);
-            map.insert("CONSTRAINT_NAME".to_string(), constraint);
-            result.push(map);
+        let estimated_rows =
+            estimated_rows.ok_or_else(|| format!("Table {schema}.{table} not found"))?;
+        let sql = build_sample_rows_sql(schema, table, n, estimated_rows.unwrap_or(0));
+        pool::execute_query(conn, &sql, None, pool::ResultFormat::default())
+    })
+}
+
+fn build_sample_rows_sql(schema: &str, table: &str, n: u64, estimated_rows: u64) -> String {
+    let target = format!(
+        "`{}`.`{}`",
+        escape_identifier(schema),
+        escape_identifier(table)
+    );
+    if estimated_rows <= SAMPLE_ORDER_BY_RAND_MAX_ROWS {
+        return format!("SELECT * FROM {target} ORDER BY RAND() LIMIT {n}");
+    }
+    let ratio = (n as f64 * SAMPLE_OVERSAMPLE_FACTOR / estimated_rows as f64).min(1.0);
+    format!("SELECT * FROM {target} WHERE RAND() < {ratio:.8} LIMIT {n}")
+}
+
+/// 按主键生成行定位串 `schema`.`table` WHERE ...；无主键时退化为全部列并给出警告
+pub fn build_row_locator(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    row: &HashMap<String, String>,
+) -> Result<RowLocator, String> {
+    let columns: Vec<(String, bool)> = list_columns(profile, schema, table)?
+        .iter()
+        .filter_map(|column| {
+            let name = column.get("COLUMN_NAME")?.clone();
+            let is_primary = column.get("COLUMN_KEY").map(String::as_str) == Some("PRI");
+            Some((name, is_primary))
+        })
+        .collect();
+    locate_row(schema, table, &columns, row)
+}
+
+// columns 为 (列名, 是否主键)
+fn locate_row(
+    schema: &str,
+    table: &str,
+    columns: &[(String, bool)],
+    row: &HashMap<String, String>,
+) -> Result<RowLocator, String> {
+    let primary_keys: Vec<&String> = columns
+        .iter()
+        .filter(|(_, is_primary)| *is_primary)
+        .map(|(name, _)| name)
+        .collect();
+
+    let mut warnings = Vec::new();
+    let key_columns = if primary_keys.is_empty() {
+        warnings.push(format!(
+            "Table {schema}.{table} has no primary key, all columns are used to locate the row"
+        ));
+        columns.iter().map(|(name, _)| name).collect()
+    } else {
+        for key in &primary_keys {
+            if !row.contains_key(*key) {
+                return Err(format!("Row is missing primary key column: {key}"));
+            }
         }
-        Ok(result)
+        primary_keys
+    };
+    if key_columns.is_empty() {
+        return Err(format!("Table {schema}.{table} has no columns"));
+    }
+
+    let keys: Vec<RowKey> = key_columns
+        .into_iter()
+        .map(|column| RowKey {
+            column: column.clone(),
+            value: row.get(column).cloned(),
+        })
+        .collect();
+    Ok(RowLocator {
+        schema: schema.to_string(),
+        table: table.to_string(),
+        locator: format_row_locator(schema, table, &keys),
+        keys,
+        warnings,
+    })
+}
+
+fn format_row_locator(schema: &str, table: &str, keys: &[RowKey]) -> String {
+    let conditions: Vec<String> = keys
+        .iter()
+        .map(|key| match &key.value {
+            Some(value) => format!(
+                "`{}` = '{}'",
+                escape_identifier(&key.column),
+                escape_string(value)
+            ),
+            None => format!("`{}` IS NULL", escape_identifier(&key.column)),
+        })
+        .collect();
+    format!(
+        "`{}`.`{}` WHERE {}",
+        escape_identifier(schema),
+        escape_identifier(table),
+        conditions.join(" AND ")
+    )
+}
+
+// 定位值全部作为绑定参数传入，不拼接进 SQL
+fn build_row_lookup_query(
+    schema: &str,
+    table: &str,
+    keys: &[RowKey],
+) -> Result<(String, Vec<mysql::Value>), String> {
+    if keys.is_empty() {
+        return Err("Row locator has no key columns".to_string());
+    }
+    let mut params = Vec::new();
+    let conditions: Vec<String> = keys
+        .iter()
+        .map(|key| match &key.value {
+            Some(value) => {
+                params.push(mysql::Value::from(value.as_str()));
+                format!("`{}` = ?", escape_identifier(&key.column))
+            }
+            None => format!("`{}` IS NULL", escape_identifier(&key.column)),
+        })
+        .collect();
+    let sql = format!(
+        "SELECT * FROM `{}`.`{}` WHERE {} LIMIT 1",
+        escape_identifier(schema),
+        escape_identifier(table),
+        conditions.join(" AND ")
+    );
+    Ok((sql, params))
+}
+
+/// 按 build_row_locator 返回的定位列读取该行，列值统一转为字符串，NULL 为 None
+pub fn fetch_row_by_locator(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    keys: &[RowKey],
+) -> Result<Option<BTreeMap<String, Option<String>>>, String> {
+    let (sql, params) = build_row_lookup_query(schema, table, keys)?;
+    pool::with_temp_connection(profile, |conn| {
+        let row: Option<mysql::Row> = conn
+            .exec_first(&sql, params)
+            .map_err(|e| format!("Query failed: {e}"))?;
+        Ok(row.map(|row| {
+            let names: Vec<String> = row
+                .columns_ref()
+                .iter()
+                .map(|column| column.name_str().to_string())
+                .collect();
+            names
+                .into_iter()
+                .zip(row.unwrap())
+                .map(|(name, value)| (name, locator_value_to_string(value)))
+                .collect()
+        }))
+    })
+}
+
+fn locator_value_to_string(value: mysql::Value) -> Option<String> {
+    match value {
+        mysql::Value::NULL => None,
+        mysql::Value::Bytes(bytes) => Some(String::from_utf8_lossy(&bytes).into_owned()),
+        mysql::Value::Int(v) => Some(v.to_string()),
+        mysql::Value::UInt(v) => Some(v.to_string()),
+        mysql::Value::Float(v) => Some(v.to_string()),
+        mysql::Value::Double(v) => Some(v.to_string()),
+        other => Some(other.as_sql(true).trim_matches('\'').to_string()),
+    }
+}
+
+pub fn list_foreign_keys(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    order: ListOrder,
+) -> Result<Vec<BTreeMap<String, String>>, String> {
+    pool::with_temp_connection(profile, |conn| {
+        load_foreign_keys(conn, schema, table, order)
     })
 }
 
+fn load_foreign_keys(
+    conn: &mut mysql::Conn,
+    schema: &str,
+    table: &str,
+    order: ListOrder,
+) -> Result<Vec<BTreeMap<String, String>>, String> {
+    let sql = foreign_key_list_sql(order);
+    let rows: Vec<(String, String, String, String, String)> = conn
+        .exec(sql, params! {"schema" => schema, "table" => table})
+        .map_err(|e| format!("Query failed: {e}"))?;
+    let mut result = Vec::new();
+    for (column_name, ref_schema, ref_table, ref_col, constraint) in rows {
+        let mut map = BTreeMap::new();
+        map.insert("COLUMN_NAME".to_string(), column_name);
+        map.insert("REFERENCED_TABLE_SCHEMA".to_string(), ref_schema);
+        map.insert("REFERENCED_TABLE_NAME".to_string(), ref_table);
+        map.insert("REFERENCED_COLUMN_NAME".to_string(), ref_col);
+        map.insert("CONSTRAINT_NAME".to_string(), constraint);
+        result.push(map);
+    }
+    Ok(result)
+}
+
+fn foreign_key_list_sql(order: ListOrder) -> String {
+    let order_by = match order {
+        ListOrder::Ordinal => "CONSTRAINT_NAME, ORDINAL_POSITION",
+        ListOrder::Name => "CONSTRAINT_NAME, COLUMN_NAME",
+    };
+    format!("SELECT COLUMN_NAME, REFERENCED_TABLE_SCHEMA, REFERENCED_TABLE_NAME, REFERENCED_COLUMN_NAME, CONSTRAINT_NAME FROM INFORMATION_SCHEMA.KEY_COLUMN_USAGE WHERE TABLE_SCHEMA = :schema AND TABLE_NAME = :table AND REFERENCED_TABLE_NAME IS NOT NULL ORDER BY {order_by}")
+}
+
 pub fn get_er_diagram_data(
     profile: &ConnectionProfile,
     schema: &str,
@@ -698,15 +1561,174 @@ pub fn get_er_diagram_data(
     })
 }
 
+/// 以邻接形式返回库内外键关系：每个外键约束一条边（子表 -> 父表）。
+/// 引用其他库的表以 `schema.table` 作为节点名；cycles 为长度大于 1 的强连通分量
+pub fn get_fk_graph(profile: &ConnectionProfile, schema: &str) -> Result<FkGraph, String> {
+    let schema = schema.to_string();
+    pool::with_temp_connection(profile, |conn| {
+        let tables_sql = "SELECT TABLE_NAME FROM INFORMATION_SCHEMA.TABLES WHERE TABLE_SCHEMA = :schema AND TABLE_TYPE = 'BASE TABLE' ORDER BY TABLE_NAME";
+        let mut nodes: Vec<String> = conn
+            .exec_map(tables_sql, params! {"schema" => &schema}, |name: String| {
+                name
+            })
+            .map_err(|e| format!("Query failed: {e}"))?;
+
+        let fk_sql = "SELECT TABLE_NAME, CONSTRAINT_NAME, COLUMN_NAME, REFERENCED_TABLE_SCHEMA, REFERENCED_TABLE_NAME, REFERENCED_COLUMN_NAME FROM INFORMATION_SCHEMA.KEY_COLUMN_USAGE WHERE TABLE_SCHEMA = :schema AND REFERENCED_TABLE_NAME IS NOT NULL ORDER BY TABLE_NAME, CONSTRAINT_NAME, ORDINAL_POSITION";
+        let fk_rows: Vec<FkGraphRow> = conn
+            .exec(fk_sql, params! {"schema" => &schema})
+            .map_err(|e| format!("Query failed: {e}"))?;
+
+        let mut edges: Vec<FkEdge> = Vec::new();
+        for (table, constraint, column, ref_schema, ref_table, ref_column) in fk_rows {
+            let to = match ref_schema {
+                Some(ref_schema) if ref_schema != schema => format!("{ref_schema}.{ref_table}"),
+                _ => ref_table,
+            };
+            match edges.last_mut() {
+                Some(last) if last.from == table && last.constraint_name == constraint => {
+                    last.columns.push(column);
+                    last.referenced_columns.push(ref_column.unwrap_or_default());
+                }
+                _ => edges.push(FkEdge {
+                    from: table,
+                    to,
+                    constraint_name: constraint,
+                    columns: vec![column],
+                    referenced_columns: vec![ref_column.unwrap_or_default()],
+                }),
+            }
+        }
+
+        for edge in &edges {
+            if !nodes.contains(&edge.to) {
+                nodes.push(edge.to.clone());
+            }
+        }
+
+        let self_references: BTreeSet<String> = edges
+            .iter()
+            .filter(|edge| edge.from == edge.to)
+            .map(|edge| edge.from.clone())
+            .collect();
+        let cycles = find_fk_cycles(&nodes, &edges);
+
+        Ok(FkGraph {
+            nodes,
+            edges,
+            self_references: self_references.into_iter().collect(),
+            cycles,
+        })
+    })
+}
+
+type FkGraphRow = (
+    String,
+    String,
+    String,
+    Option<String>,
+    String,
+    Option<String>,
+);
+
+// Tarjan 强连通分量；自引用不计入环，由 self_references 单独报告
+fn find_fk_cycles(nodes: &[String], edges: &[FkEdge]) -> Vec<Vec<String>> {
+    struct Tarjan<'a> {
+        adjacency: Vec<Vec<usize>>,
+        nodes: &'a [String],
+        index: Vec<Option<usize>>,
+        low: Vec<usize>,
+        on_stack: Vec<bool>,
+        stack: Vec<usize>,
+        next_index: usize,
+        components: Vec<Vec<String>>,
+    }
+
+    impl Tarjan<'_> {
+        fn visit(&mut self, v: usize) {
+            self.index[v] = Some(self.next_index);
+            self.low[v] = self.next_index;
+            self.next_index += 1;
+            self.stack.push(v);
+            self.on_stack[v] = true;
+
+            for i in 0..self.adjacency[v].len() {
+                let w = self.adjacency[v][i];
+                match self.index[w] {
+                    None => {
+                        self.visit(w);
+                        self.low[v] = self.low[v].min(self.low[w]);
+                    }
+                    Some(w_index) if self.on_stack[w] => {
+                        self.low[v] = self.low[v].min(w_index);
+                    }
+                    _ => {}
+                }
+            }
+
+            if Some(self.low[v]) == self.index[v] {
+                let mut component = Vec::new();
+                while let Some(w) = self.stack.pop() {
+                    self.on_stack[w] = false;
+                    component.push(self.nodes[w].clone());
+                    if w == v {
+                        break;
+                    }
+                }
+                if component.len() > 1 {
+                    component.sort();
+                    self.components.push(component);
+                }
+            }
+        }
+    }
+
+    let position: HashMap<&str, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.as_str(), i))
+        .collect();
+    let mut adjacency = vec![Vec::new(); nodes.len()];
+    for edge in edges {
+        if edge.from == edge.to {
+            continue;
+        }
+        if let (Some(&from), Some(&to)) = (
+            position.get(edge.from.as_str()),
+            position.get(edge.to.as_str()),
+        ) {
+            adjacency[from].push(to);
+        }
+    }
+
+    let mut tarjan = Tarjan {
+        adjacency,
+        nodes,
+        index: vec![None; nodes.len()],
+        low: vec![0; nodes.len()],
+        on_stack: vec![false; nodes.len()],
+        stack: Vec::new(),
+        next_index: 0,
+        components: Vec::new(),
+    };
+    for v in 0..nodes.len() {
+        if tarjan.index[v].is_none() {
+            tarjan.visit(v);
+        }
+    }
+    tarjan.components.sort();
+    tarjan.components
+}
+
 pub fn list_indexes(
     profile: &ConnectionProfile,
     schema: &str,
     table: &str,
+    order: ListOrder,
 ) -> Result<Vec<BTreeMap<String, String>>, String> {
     let schema = schema.to_string();
     let table = table.to_string();
     pool::with_temp_connection(profile, |conn| {
-        let sql = "SELECT INDEX_NAME, NON_UNIQUE, GROUP_CONCAT(COLUMN_NAME ORDER BY SEQ_IN_INDEX) AS COLUMNS, INDEX_TYPE FROM INFORMATION_SCHEMA.STATISTICS WHERE TABLE_SCHEMA = :schema AND TABLE_NAME = :table GROUP BY INDEX_NAME, NON_UNIQUE, INDEX_TYPE";
+        let sql = index_list_sql(order);
         let rows: Vec<(String, i64, Option<String>, Option<String>)> = conn
             .exec(sql, params! {"schema" => &schema, "table" => &table})
             .map_err(|e| format!("Query failed: {e}"))?;
@@ -723,31 +1745,175 @@ pub fn list_indexes(
     })
 }
 
-pub fn list_triggers(
+// Ordinal 时主键排在最前，其余按索引名
+fn index_list_sql(order: ListOrder) -> String {
+    let (column_order, index_order) = match order {
+        ListOrder::Ordinal => ("SEQ_IN_INDEX", "INDEX_NAME <> 'PRIMARY', INDEX_NAME"),
+        ListOrder::Name => ("COLUMN_NAME", "INDEX_NAME"),
+    };
+    format!("SELECT INDEX_NAME, NON_UNIQUE, GROUP_CONCAT(COLUMN_NAME ORDER BY {column_order}) AS COLUMNS, INDEX_TYPE FROM INFORMATION_SCHEMA.STATISTICS WHERE TABLE_SCHEMA = :schema AND TABLE_NAME = :table GROUP BY INDEX_NAME, NON_UNIQUE, INDEX_TYPE ORDER BY {index_order}")
+}
+
+/// 基于 performance_schema 的索引 I/O 统计找出自上次服务器启动以来从未使用过的二级索引。
+/// 统计在重启后清零，运行时间较短时结果仅供参考；唯一索引仍承担约束作用，删除前需确认
+pub fn find_unused_indexes(
     profile: &ConnectionProfile,
     schema: &str,
-    table: &str,
-) -> Result<Vec<BTreeMap<String, String>>, String> {
+) -> Result<Vec<UnusedIndex>, String> {
     let schema = schema.to_string();
-    let table = table.to_string();
     pool::with_temp_connection(profile, |conn| {
-        let sql = "SELECT TRIGGER_NAME, ACTION_TIMING, EVENT_MANIPULATION, ACTION_STATEMENT FROM INFORMATION_SCHEMA.TRIGGERS WHERE EVENT_OBJECT_SCHEMA = :schema AND EVENT_OBJECT_TABLE = :table";
-        let rows: Vec<(String, String, String, String)> = conn
-            .exec(sql, params! {"schema" => &schema, "table" => &table})
-            .map_err(|e| format!("Query failed: {e}"))?;
-        let mut result = Vec::new();
-        for (name, timing, event, statement) in rows {
-            let mut map = BTreeMap::new();
-            map.insert("TRIGGER_NAME".to_string(), name);
-            map.insert("ACTION_TIMING".to_string(), timing);
-            map.insert("EVENT_MANIPULATION".to_string(), event);
-            map.insert("ACTION_STATEMENT".to_string(), statement);
-            result.push(map);
+        let enabled: Option<i64> = conn
+            .query_first("SELECT @@GLOBAL.performance_schema")
+            .unwrap_or(None);
+        if enabled != Some(1) {
+            return Err(PERFORMANCE_SCHEMA_DISABLED.to_string());
         }
-        Ok(result)
+
+        let rows: Vec<UnusedIndexRow> = conn
+            .exec(UNUSED_INDEX_SQL, params! {"schema" => &schema})
+            .map_err(|e| unused_index_query_error(&e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(table, index_name, columns, non_unique)| UnusedIndex {
+                table,
+                index_name,
+                columns: columns
+                    .map(|c| c.split(',').map(|s| s.to_string()).collect())
+                    .unwrap_or_default(),
+                unique: non_unique == Some(0),
+            })
+            .collect())
     })
 }
 
+type UnusedIndexRow = (String, String, Option<String>, Option<i64>);
+
+const UNUSED_INDEX_SQL: &str = "SELECT u.OBJECT_NAME, u.INDEX_NAME, GROUP_CONCAT(s.COLUMN_NAME ORDER BY s.SEQ_IN_INDEX SEPARATOR ','), MIN(s.NON_UNIQUE) FROM performance_schema.table_io_waits_summary_by_index_usage u JOIN INFORMATION_SCHEMA.STATISTICS s ON s.TABLE_SCHEMA = u.OBJECT_SCHEMA AND s.TABLE_NAME = u.OBJECT_NAME AND s.INDEX_NAME = u.INDEX_NAME WHERE u.OBJECT_SCHEMA = :schema AND u.INDEX_NAME IS NOT NULL AND u.INDEX_NAME <> 'PRIMARY' AND u.COUNT_STAR = 0 GROUP BY u.OBJECT_NAME, u.INDEX_NAME ORDER BY u.OBJECT_NAME, u.INDEX_NAME";
+
+const PERFORMANCE_SCHEMA_DISABLED: &str =
+    "performance_schema is disabled or not accessible; enable it (performance_schema=ON) and grant SELECT on performance_schema to detect unused indexes";
+
+fn unused_index_query_error(err: &mysql::Error) -> String {
+    match err {
+        // 1142: 无表权限；1146: 表不存在；1049: 库不存在
+        mysql::Error::MySqlError(e) if matches!(e.code, 1142 | 1146 | 1049) => {
+            PERFORMANCE_SCHEMA_DISABLED.to_string()
+        }
+        _ => format!("Query failed: {err}"),
+    }
+}
+
+pub fn list_indexes_detailed(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<IndexModel>, String> {
+    pool::with_temp_connection(profile, |conn| load_indexes_detailed(conn, schema, table))
+}
+
+fn load_indexes_detailed(
+    conn: &mut mysql::Conn,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<IndexModel>, String> {
+    // IS_VISIBLE 仅在 MySQL 8.0+ 存在，旧版本回退为全部可见
+    let sql = "SELECT INDEX_NAME, NON_UNIQUE, INDEX_TYPE, COLUMN_NAME, SUB_PART, COLLATION, INDEX_COMMENT, IS_VISIBLE FROM INFORMATION_SCHEMA.STATISTICS WHERE TABLE_SCHEMA = :schema AND TABLE_NAME = :table ORDER BY INDEX_NAME <> 'PRIMARY', INDEX_NAME, SEQ_IN_INDEX";
+    let fallback_sql = "SELECT INDEX_NAME, NON_UNIQUE, INDEX_TYPE, COLUMN_NAME, SUB_PART, COLLATION, INDEX_COMMENT, 'YES' AS IS_VISIBLE FROM INFORMATION_SCHEMA.STATISTICS WHERE TABLE_SCHEMA = :schema AND TABLE_NAME = :table ORDER BY INDEX_NAME <> 'PRIMARY', INDEX_NAME, SEQ_IN_INDEX";
+    let rows: Vec<mysql::Row> = match conn.exec(sql, params! {"schema" => schema, "table" => table})
+    {
+        Ok(rows) => rows,
+        // 1054: 未知列，即不支持 IS_VISIBLE 的旧版本
+        Err(mysql::Error::MySqlError(e)) if e.code == 1054 => conn
+            .exec(fallback_sql, params! {"schema" => schema, "table" => table})
+            .map_err(|e| format!("Query failed: {e}"))?,
+        Err(e) => return Err(format!("Query failed: {e}")),
+    };
+    Ok(group_index_rows(rows))
+}
+
+// STATISTICS 按 INDEX_NAME、SEQ_IN_INDEX 排序，相邻同名行属于同一索引
+fn group_index_rows(rows: Vec<mysql::Row>) -> Vec<IndexModel> {
+    let mut result: Vec<IndexModel> = Vec::new();
+    for row in rows {
+        let name = row
+            .get::<Option<String>, _>("INDEX_NAME")
+            .flatten()
+            .unwrap_or_default();
+        let non_unique = row
+            .get::<Option<i64>, _>("NON_UNIQUE")
+            .flatten()
+            .unwrap_or(1);
+        let column = IndexColumn {
+            // 函数索引（8.0.13+）没有列名
+            name: row
+                .get::<Option<String>, _>("COLUMN_NAME")
+                .flatten()
+                .unwrap_or_default(),
+            sub_part: row.get::<Option<u64>, _>("SUB_PART").flatten(),
+            collation: row.get::<Option<String>, _>("COLLATION").flatten(),
+        };
+
+        match result.last_mut() {
+            Some(last) if last.name == name => last.columns.push(column),
+            _ => result.push(IndexModel {
+                name,
+                unique: non_unique == 0,
+                index_type: row
+                    .get::<Option<String>, _>("INDEX_TYPE")
+                    .flatten()
+                    .unwrap_or_default(),
+                columns: vec![column],
+                visible: row
+                    .get::<Option<String>, _>("IS_VISIBLE")
+                    .flatten()
+                    .map(|v| !v.eq_ignore_ascii_case("NO"))
+                    .unwrap_or(true),
+                comment: row
+                    .get::<Option<String>, _>("INDEX_COMMENT")
+                    .flatten()
+                    .unwrap_or_default(),
+            }),
+        }
+    }
+    result
+}
+
+pub fn list_triggers(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    order: ListOrder,
+) -> Result<Vec<BTreeMap<String, String>>, String> {
+    pool::with_temp_connection(profile, |conn| load_triggers(conn, schema, table, order))
+}
+
+fn load_triggers(
+    conn: &mut mysql::Conn,
+    schema: &str,
+    table: &str,
+    order: ListOrder,
+) -> Result<Vec<BTreeMap<String, String>>, String> {
+    let order_by = match order {
+        ListOrder::Ordinal => "EVENT_MANIPULATION, ACTION_TIMING, ACTION_ORDER",
+        ListOrder::Name => "TRIGGER_NAME",
+    };
+    let sql = format!("SELECT TRIGGER_NAME, ACTION_TIMING, EVENT_MANIPULATION, ACTION_STATEMENT FROM INFORMATION_SCHEMA.TRIGGERS WHERE EVENT_OBJECT_SCHEMA = :schema AND EVENT_OBJECT_TABLE = :table ORDER BY {order_by}");
+    let rows: Vec<(String, String, String, String)> = conn
+        .exec(sql, params! {"schema" => schema, "table" => table})
+        .map_err(|e| format!("Query failed: {e}"))?;
+    let mut result = Vec::new();
+    for (name, timing, event, statement) in rows {
+        let mut map = BTreeMap::new();
+        map.insert("TRIGGER_NAME".to_string(), name);
+        map.insert("ACTION_TIMING".to_string(), timing);
+        map.insert("EVENT_MANIPULATION".to_string(), event);
+        map.insert("ACTION_STATEMENT".to_string(), statement);
+        result.push(map);
+    }
+    Ok(result)
+}
+
 pub fn list_checks(
     profile: &ConnectionProfile,
     schema: &str,
@@ -768,24 +1934,1197 @@ pub fn list_checks(
             map.insert("ENFORCED".to_string(), enforced);
             result.push(map);
         }
-        Ok(result)
+        Ok(result)
+    })
+}
+
+pub fn load_ddl(profile: &ConnectionProfile, schema: &str, table: &str) -> Result<String, String> {
+    let schema = schema.to_string();
+    let table = table.to_string();
+    pool::with_temp_connection(profile, |conn| {
+        let sql = format!(
+            "SHOW CREATE TABLE `{}`.`{}`",
+            escape_identifier(&schema),
+            escape_identifier(&table)
+        );
+        let row: Option<(String, String)> = conn
+            .query_first(sql)
+            .map_err(|e| format!("Query failed: {e}"))?;
+        Ok(row.map(|(_, ddl)| ddl).unwrap_or_default())
+    })
+}
+
+/// 读取表当前的列/索引/外键定义，结构与设计器提交的 TableModel 一致
+pub fn load_table_model(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+) -> Result<TableModel, String> {
+    let indexes = list_indexes_detailed(profile, schema, table)?;
+    let schema = schema.to_string();
+    let table = table.to_string();
+    pool::with_temp_connection(profile, |conn| {
+        let sql = "SELECT COLUMN_NAME, COLUMN_TYPE, IS_NULLABLE, COLUMN_DEFAULT, EXTRA, CHARACTER_SET_NAME, COLLATION_NAME, COLUMN_COMMENT FROM INFORMATION_SCHEMA.COLUMNS WHERE TABLE_SCHEMA = :schema AND TABLE_NAME = :table ORDER BY ORDINAL_POSITION";
+        let rows: Vec<mysql::Row> = conn
+            .exec(sql, params! {"schema" => &schema, "table" => &table})
+            .map_err(|e| format!("Query failed: {e}"))?;
+        let mut columns = Vec::with_capacity(rows.len());
+        for row in rows {
+            let extra = row
+                .get::<Option<String>, _>("EXTRA")
+                .flatten()
+                .unwrap_or_default();
+            let default_value = row
+                .get::<Option<String>, _>("COLUMN_DEFAULT")
+                .flatten()
+                .map(|value| normalize_column_default(&value, &extra));
+            columns.push(ColumnModel {
+                name: row
+                    .get::<Option<String>, _>("COLUMN_NAME")
+                    .flatten()
+                    .unwrap_or_default(),
+                original_name: None,
+                column_type: row
+                    .get::<Option<String>, _>("COLUMN_TYPE")
+                    .flatten()
+                    .unwrap_or_default(),
+                nullable: row
+                    .get::<Option<String>, _>("IS_NULLABLE")
+                    .flatten()
+                    .is_some_and(|v| v.eq_ignore_ascii_case("YES")),
+                default_value,
+                auto_increment: extra.to_ascii_lowercase().contains("auto_increment"),
+                on_update: parse_on_update(&extra),
+                charset: row.get::<Option<String>, _>("CHARACTER_SET_NAME").flatten(),
+                collation: row.get::<Option<String>, _>("COLLATION_NAME").flatten(),
+                comment: row
+                    .get::<Option<String>, _>("COLUMN_COMMENT")
+                    .flatten()
+                    .unwrap_or_default(),
+            });
+        }
+
+        let fk_sql = "SELECT k.CONSTRAINT_NAME, k.COLUMN_NAME, k.REFERENCED_TABLE_SCHEMA, k.REFERENCED_TABLE_NAME, k.REFERENCED_COLUMN_NAME, r.UPDATE_RULE, r.DELETE_RULE FROM INFORMATION_SCHEMA.KEY_COLUMN_USAGE k JOIN INFORMATION_SCHEMA.REFERENTIAL_CONSTRAINTS r ON r.CONSTRAINT_SCHEMA = k.CONSTRAINT_SCHEMA AND r.CONSTRAINT_NAME = k.CONSTRAINT_NAME AND r.TABLE_NAME = k.TABLE_NAME WHERE k.TABLE_SCHEMA = :schema AND k.TABLE_NAME = :table AND k.REFERENCED_TABLE_NAME IS NOT NULL ORDER BY k.CONSTRAINT_NAME, k.ORDINAL_POSITION";
+        let fk_rows: Vec<FkRuleRow> = conn
+            .exec(fk_sql, params! {"schema" => &schema, "table" => &table})
+            .map_err(|e| format!("Query failed: {e}"))?;
+        let mut foreign_keys: Vec<ForeignKeyModel> = Vec::new();
+        for (name, column, ref_schema, ref_table, ref_column, on_update, on_delete) in fk_rows {
+            match foreign_keys.last_mut() {
+                Some(last) if last.name == name => {
+                    last.columns.push(column);
+                    last.referenced_columns.push(ref_column);
+                }
+                _ => foreign_keys.push(ForeignKeyModel {
+                    name,
+                    columns: vec![column],
+                    referenced_schema: Some(ref_schema),
+                    referenced_table: ref_table,
+                    referenced_columns: vec![ref_column],
+                    on_update,
+                    on_delete,
+                }),
+            }
+        }
+
+        Ok((columns, foreign_keys))
+    })
+    .map(|(columns, foreign_keys)| TableModel {
+        columns,
+        indexes,
+        foreign_keys,
+    })
+}
+
+/// 对比当前表定义与设计器中的目标定义，生成将执行的 ALTER 语句；
+/// 删除列、收窄类型、改为 NOT NULL 等可能丢失数据的修改会给出警告
+pub fn preview_table_changes(
+    profile: &ConnectionProfile,
+    schema: &str,
+    table: &str,
+    proposed: &TableModel,
+) -> Result<TableChangePreview, String> {
+    let current = load_table_model(profile, schema, table)?;
+    if current.columns.is_empty() {
+        return Err(format!("Table `{schema}`.`{table}` not found"));
+    }
+    Ok(diff_table_models(schema, table, &current, proposed))
+}
+
+fn diff_table_models(
+    schema: &str,
+    table: &str,
+    current: &TableModel,
+    proposed: &TableModel,
+) -> TableChangePreview {
+    let target = format!(
+        "`{}`.`{}`",
+        escape_identifier(schema),
+        escape_identifier(table)
+    );
+    let mut statements: Vec<String> = Vec::new();
+    let mut warnings: Vec<String> = Vec::new();
+
+    // 外键先删后建，避免修改列/索引时被外键约束阻止
+    let fk_schema =
+        |fk: &ForeignKeyModel| fk.referenced_schema.clone().unwrap_or(schema.to_string());
+    let fk_same = |a: &ForeignKeyModel, b: &ForeignKeyModel| {
+        fk_schema(a) == fk_schema(b)
+            && a.referenced_table == b.referenced_table
+            && a.columns == b.columns
+            && a.referenced_columns == b.referenced_columns
+            && a.on_update.eq_ignore_ascii_case(&b.on_update)
+            && a.on_delete.eq_ignore_ascii_case(&b.on_delete)
+    };
+    let mut added_fks: Vec<&ForeignKeyModel> = Vec::new();
+    for fk in &current.foreign_keys {
+        let kept = proposed
+            .foreign_keys
+            .iter()
+            .any(|p| p.name == fk.name && fk_same(fk, p));
+        if !kept {
+            statements.push(format!(
+                "ALTER TABLE {target} DROP FOREIGN KEY `{}`;",
+                escape_identifier(&fk.name)
+            ));
+        }
+    }
+    for fk in &proposed.foreign_keys {
+        let kept = current
+            .foreign_keys
+            .iter()
+            .any(|c| c.name == fk.name && fk_same(c, fk));
+        if !kept {
+            added_fks.push(fk);
+        }
+    }
+
+    let mut added_indexes: Vec<&IndexModel> = Vec::new();
+    for index in &current.indexes {
+        if !proposed.indexes.iter().any(|p| p == index) {
+            statements.push(if index.name == "PRIMARY" {
+                format!("ALTER TABLE {target} DROP PRIMARY KEY;")
+            } else {
+                format!(
+                    "ALTER TABLE {target} DROP INDEX `{}`;",
+                    escape_identifier(&index.name)
+                )
+            });
+        }
+    }
+    for index in &proposed.indexes {
+        if !current.indexes.iter().any(|c| c == index) {
+            added_indexes.push(index);
+        }
+    }
+
+    let proposed_sources: BTreeSet<&str> = proposed
+        .columns
+        .iter()
+        .map(|c| c.original_name.as_deref().unwrap_or(&c.name))
+        .collect();
+    for column in &current.columns {
+        if !proposed_sources.contains(column.name.as_str()) {
+            statements.push(format!(
+                "ALTER TABLE {target} DROP COLUMN `{}`;",
+                escape_identifier(&column.name)
+            ));
+            warnings.push(format!(
+                "Dropping column `{}` permanently deletes its data",
+                column.name
+            ));
+        }
+    }
+
+    let mut previous: Option<&str> = None;
+    for column in &proposed.columns {
+        let source = column.original_name.as_deref().unwrap_or(&column.name);
+        let position = match previous {
+            Some(name) => format!(" AFTER `{}`", escape_identifier(name)),
+            None => " FIRST".to_string(),
+        };
+        previous = Some(&column.name);
+        let Some(existing) = current.columns.iter().find(|c| c.name == source) else {
+            statements.push(format!(
+                "ALTER TABLE {target} ADD COLUMN {}{position};",
+                build_column_definition(column)
+            ));
+            continue;
+        };
+
+        // 未指定字符集/排序规则时沿用原值，避免 MODIFY 重置为表默认值
+        let mut column = column.clone();
+        if column.charset.is_none() && column.collation.is_none() {
+            column.charset.clone_from(&existing.charset);
+            column.collation.clone_from(&existing.collation);
+        }
+        let renamed = existing.name != column.name;
+        if !renamed && build_column_definition(existing) == build_column_definition(&column) {
+            continue;
+        }
+        if renamed {
+            statements.push(format!(
+                "ALTER TABLE {target} CHANGE COLUMN `{}` {};",
+                escape_identifier(&existing.name),
+                build_column_definition(&column)
+            ));
+        } else {
+            statements.push(format!(
+                "ALTER TABLE {target} MODIFY COLUMN {};",
+                build_column_definition(&column)
+            ));
+        }
+        if let Some(reason) = type_narrowing_reason(&existing.column_type, &column.column_type) {
+            warnings.push(format!(
+                "Changing column `{}` from {} to {} may lose data: {}",
+                existing.name, existing.column_type, column.column_type, reason
+            ));
+        }
+        if existing.nullable && !column.nullable {
+            warnings.push(format!(
+                "Column `{}` becomes NOT NULL; existing NULL values will be rejected or replaced",
+                existing.name
+            ));
+        }
+    }
+
+    for index in added_indexes {
+        statements.push(format!(
+            "ALTER TABLE {target} ADD {};",
+            build_index_definition(index)
+        ));
+    }
+    for fk in added_fks {
+        let referenced = match fk.referenced_schema.as_deref() {
+            Some(ref_schema) if ref_schema != schema => format!(
+                "`{}`.`{}`",
+                escape_identifier(ref_schema),
+                escape_identifier(&fk.referenced_table)
+            ),
+            _ => format!("`{}`", escape_identifier(&fk.referenced_table)),
+        };
+        statements.push(format!(
+            "ALTER TABLE {target} ADD CONSTRAINT `{}` FOREIGN KEY ({}) REFERENCES {referenced} ({}) ON UPDATE {} ON DELETE {};",
+            escape_identifier(&fk.name),
+            quote_column_list(&fk.columns),
+            quote_column_list(&fk.referenced_columns),
+            fk.on_update.to_ascii_uppercase(),
+            fk.on_delete.to_ascii_uppercase()
+        ));
+    }
+
+    TableChangePreview {
+        sql: statements.join("\n"),
+        warnings,
+    }
+}
+
+fn quote_column_list(columns: &[String]) -> String {
+    columns
+        .iter()
+        .map(|c| format!("`{}`", escape_identifier(c)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn build_column_definition(column: &ColumnModel) -> String {
+    let mut def = format!(
+        "`{}` {}",
+        escape_identifier(&column.name),
+        column.column_type
+    );
+    if let Some(charset) = column.charset.as_deref().filter(|v| !v.is_empty()) {
+        def.push_str(&format!(" CHARACTER SET {charset}"));
+    }
+    if let Some(collation) = column.collation.as_deref().filter(|v| !v.is_empty()) {
+        def.push_str(&format!(" COLLATE {collation}"));
+    }
+    def.push_str(if column.nullable {
+        " NULL"
+    } else {
+        " NOT NULL"
+    });
+    let is_string_type = matches!(
+        parse_type_capacity(&column.column_type),
+        TypeCapacity::Text(_) | TypeCapacity::Binary(_) | TypeCapacity::Enum(_)
+    );
+    // 非字符串列的空默认值视为未设置
+    let default = column
+        .default_value
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| is_string_type || !v.is_empty());
+    if let Some(default) = default {
+        let upper = default.to_ascii_uppercase();
+        let is_numeric =
+            default.parse::<f64>().is_ok() && !default.contains(|c: char| c.is_ascii_alphabetic());
+        if upper == "NULL" || upper.starts_with("CURRENT_TIMESTAMP") {
+            def.push_str(&format!(" DEFAULT {upper}"));
+        } else if default.starts_with('(') || (is_numeric && !is_string_type) {
+            def.push_str(&format!(" DEFAULT {default}"));
+        } else {
+            def.push_str(&format!(" DEFAULT '{}'", escape_string(default)));
+        }
+    }
+    if column.auto_increment {
+        def.push_str(" AUTO_INCREMENT");
+    }
+    if let Some(on_update) = column.on_update.as_deref().filter(|v| !v.is_empty()) {
+        def.push_str(&format!(" ON UPDATE {on_update}"));
+    }
+    if !column.comment.is_empty() {
+        def.push_str(&format!(" COMMENT '{}'", escape_string(&column.comment)));
+    }
+    def
+}
+
+fn build_index_definition(index: &IndexModel) -> String {
+    let columns = index
+        .columns
+        .iter()
+        .map(|c| {
+            let mut part = format!("`{}`", escape_identifier(&c.name));
+            if let Some(len) = c.sub_part {
+                part.push_str(&format!("({len})"));
+            }
+            if c.collation.as_deref() == Some("D") {
+                part.push_str(" DESC");
+            }
+            part
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let index_type = index.index_type.to_ascii_uppercase();
+    let mut def = if index.name == "PRIMARY" {
+        format!("PRIMARY KEY ({columns})")
+    } else {
+        let kind = match index_type.as_str() {
+            "FULLTEXT" => "FULLTEXT ",
+            "SPATIAL" => "SPATIAL ",
+            _ if index.unique => "UNIQUE ",
+            _ => "",
+        };
+        format!(
+            "{kind}INDEX `{}` ({columns})",
+            escape_identifier(&index.name)
+        )
+    };
+    if matches!(index_type.as_str(), "BTREE" | "HASH") {
+        def.push_str(&format!(" USING {index_type}"));
+    }
+    if !index.comment.is_empty() {
+        def.push_str(&format!(" COMMENT '{}'", escape_string(&index.comment)));
+    }
+    if !index.visible {
+        def.push_str(" INVISIBLE");
+    }
+    def
+}
+
+// MariaDB 的 COLUMN_DEFAULT 带引号；MySQL 8 的表达式默认值需用括号包裹才能原样回写
+fn normalize_column_default(value: &str, extra: &str) -> String {
+    if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+        return value[1..value.len() - 1].replace("''", "'");
+    }
+    let is_expression = extra.to_ascii_uppercase().contains("DEFAULT_GENERATED")
+        && !value.to_ascii_uppercase().starts_with("CURRENT_TIMESTAMP");
+    if is_expression {
+        format!("({value})")
+    } else {
+        value.to_string()
+    }
+}
+
+fn parse_on_update(extra: &str) -> Option<String> {
+    let lowered = extra.to_ascii_lowercase();
+    let start = lowered.find("on update ")? + "on update ".len();
+    let value = extra[start..].split_whitespace().next()?;
+    Some(value.to_ascii_uppercase())
+}
+
+fn parse_type_capacity(column_type: &str) -> TypeCapacity {
+    let lowered = column_type.trim().to_ascii_lowercase();
+    let base_end = lowered
+        .find(|c: char| c == '(' || c.is_whitespace())
+        .unwrap_or(lowered.len());
+    let base = &lowered[..base_end];
+    let args = lowered[base_end..]
+        .trim_start()
+        .strip_prefix('(')
+        .and_then(|rest| rest.rfind(')').map(|end| &rest[..end]))
+        .unwrap_or("");
+    let column_args = &column_type.trim()[base_end..];
+    let mut numbers = args.split(',').map(|v| v.trim().parse::<u64>().ok());
+    let first = numbers.next().flatten();
+    let second = numbers.next().flatten();
+    let unsigned = lowered.contains("unsigned");
+    match base {
+        "char" | "varchar" => TypeCapacity::Text(first.unwrap_or(1)),
+        "tinytext" => TypeCapacity::Text(255),
+        "text" => TypeCapacity::Text(65_535),
+        "mediumtext" => TypeCapacity::Text(16_777_215),
+        "longtext" => TypeCapacity::Text(4_294_967_295),
+        "binary" | "varbinary" => TypeCapacity::Binary(first.unwrap_or(1)),
+        "tinyblob" => TypeCapacity::Binary(255),
+        "blob" => TypeCapacity::Binary(65_535),
+        "mediumblob" => TypeCapacity::Binary(16_777_215),
+        "longblob" => TypeCapacity::Binary(4_294_967_295),
+        "tinyint" | "bool" | "boolean" => TypeCapacity::Integer { bytes: 1, unsigned },
+        "smallint" => TypeCapacity::Integer { bytes: 2, unsigned },
+        "mediumint" => TypeCapacity::Integer { bytes: 3, unsigned },
+        "int" | "integer" => TypeCapacity::Integer { bytes: 4, unsigned },
+        "bigint" => TypeCapacity::Integer { bytes: 8, unsigned },
+        "decimal" | "numeric" | "dec" | "fixed" => TypeCapacity::Decimal {
+            precision: first.unwrap_or(10),
+            scale: second.unwrap_or(0),
+        },
+        "float" => TypeCapacity::Float(4),
+        "double" | "real" => TypeCapacity::Float(8),
+        "enum" | "set" => TypeCapacity::Enum(parse_quoted_list(column_args)),
+        _ => TypeCapacity::Other(base.to_string()),
+    }
+}
+
+// 解析 enum/set 的取值列表，保留原始大小写
+fn parse_quoted_list(args: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut in_quote = false;
+    let mut chars = args.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' if in_quote && chars.peek() == Some(&'\'') => {
+                current.push('\'');
+                chars.next();
+            }
+            '\'' if in_quote => {
+                values.push(std::mem::take(&mut current));
+                in_quote = false;
+            }
+            '\'' => in_quote = true,
+            _ if in_quote => current.push(c),
+            _ => {}
+        }
+    }
+    values
+}
+
+fn type_narrowing_reason(old_type: &str, new_type: &str) -> Option<String> {
+    let old = parse_type_capacity(old_type);
+    let new = parse_type_capacity(new_type);
+    match (&old, &new) {
+        (TypeCapacity::Text(a), TypeCapacity::Text(b))
+        | (TypeCapacity::Binary(a), TypeCapacity::Binary(b)) => {
+            (b < a).then(|| "longer values will be truncated or rejected".to_string())
+        }
+        (
+            TypeCapacity::Integer {
+                bytes: a,
+                unsigned: ua,
+            },
+            TypeCapacity::Integer {
+                bytes: b,
+                unsigned: ub,
+            },
+        ) => (b < a || ua != ub)
+            .then(|| "out-of-range values will be clipped or rejected".to_string()),
+        (
+            TypeCapacity::Decimal {
+                precision: p1,
+                scale: s1,
+            },
+            TypeCapacity::Decimal {
+                precision: p2,
+                scale: s2,
+            },
+        ) => (p2.saturating_sub(*s2) < p1.saturating_sub(*s1) || s2 < s1)
+            .then(|| "precision or scale is reduced".to_string()),
+        (TypeCapacity::Float(a), TypeCapacity::Float(b)) => {
+            (b < a).then(|| "floating-point precision is reduced".to_string())
+        }
+        (TypeCapacity::Enum(a), TypeCapacity::Enum(b)) => {
+            let removed: Vec<&str> = a
+                .iter()
+                .filter(|v| !b.contains(v))
+                .map(String::as_str)
+                .collect();
+            (!removed.is_empty()).then(|| format!("values removed: {}", removed.join(", ")))
+        }
+        (TypeCapacity::Other(a), TypeCapacity::Other(b)) if a == b => None,
+        _ if std::mem::discriminant(&old) == std::mem::discriminant(&new) => None,
+        _ => Some("existing values will be converted to a different type".to_string()),
+    }
+}
+
+pub fn export_schema_snapshot(profile: &ConnectionProfile, schema: &str) -> Result<String, String> {
+    let snapshot = collect_schema_snapshot(profile, schema)?;
+    serde_json::to_string_pretty(&snapshot).map_err(|e| format!("Serialize failed: {e}"))
+}
+
+/// 将实时库结构与快照比较；"added" 表示实时库中有而快照中没有
+pub fn diff_against_snapshot(
+    profile: &ConnectionProfile,
+    schema: &str,
+    snapshot_json: &str,
+) -> Result<SchemaDiff, String> {
+    let snapshot: SchemaSnapshot =
+        serde_json::from_str(snapshot_json).map_err(|e| format!("Invalid snapshot: {e}"))?;
+    let live = collect_schema_snapshot(profile, schema)?;
+    Ok(diff_schema_snapshots(&snapshot, &live))
+}
+
+// 所有表在同一条连接上读取，表名不走缓存以反映当前结构
+fn collect_schema_snapshot(
+    profile: &ConnectionProfile,
+    schema: &str,
+) -> Result<SchemaSnapshot, String> {
+    pool::with_temp_connection(profile, |conn| {
+        let names: Vec<String> = conn
+            .exec_map(
+                BASE_TABLES_SQL,
+                params! {"schema" => schema},
+                |name: String| name,
+            )
+            .map_err(|e| format!("Query failed: {e}"))?;
+        let mut tables = Vec::with_capacity(names.len());
+        for table in names {
+            tables.push(TableSnapshot {
+                columns: load_columns(conn, schema, &table)?,
+                indexes: load_indexes_detailed(conn, schema, &table)?,
+                foreign_keys: load_foreign_keys(conn, schema, &table, ListOrder::Ordinal)?,
+                triggers: load_triggers(conn, schema, &table, ListOrder::Ordinal)?,
+                name: table,
+            });
+        }
+        Ok(SchemaSnapshot {
+            schema: schema.to_string(),
+            tables,
+        })
+    })
+}
+
+fn diff_schema_snapshots(base: &SchemaSnapshot, live: &SchemaSnapshot) -> SchemaDiff {
+    let base_tables: BTreeMap<&str, &TableSnapshot> =
+        base.tables.iter().map(|t| (t.name.as_str(), t)).collect();
+    let live_tables: BTreeMap<&str, &TableSnapshot> =
+        live.tables.iter().map(|t| (t.name.as_str(), t)).collect();
+
+    let mut diff = SchemaDiff::default();
+    for (name, live_table) in &live_tables {
+        match base_tables.get(name) {
+            None => diff.added_tables.push(name.to_string()),
+            Some(base_table) => {
+                let table_diff = diff_table_snapshots(base_table, live_table);
+                if !table_diff.is_empty() {
+                    diff.changed_tables.push(table_diff);
+                }
+            }
+        }
+    }
+    for name in base_tables.keys() {
+        if !live_tables.contains_key(name) {
+            diff.removed_tables.push(name.to_string());
+        }
+    }
+    diff
+}
+
+fn diff_table_snapshots(base: &TableSnapshot, live: &TableSnapshot) -> TableDiff {
+    let mut diff = TableDiff {
+        name: live.name.clone(),
+        ..Default::default()
+    };
+
+    let base_columns = keyed_maps(&base.columns, "COLUMN_NAME");
+    let live_columns = keyed_maps(&live.columns, "COLUMN_NAME");
+    diff_keyed(
+        &base_columns,
+        &live_columns,
+        &mut diff.added_columns,
+        &mut diff.removed_columns,
+        &mut diff.changed_columns,
+    );
+
+    let base_indexes: BTreeMap<&str, &IndexModel> =
+        base.indexes.iter().map(|i| (i.name.as_str(), i)).collect();
+    let live_indexes: BTreeMap<&str, &IndexModel> =
+        live.indexes.iter().map(|i| (i.name.as_str(), i)).collect();
+    diff_keyed(
+        &base_indexes,
+        &live_indexes,
+        &mut diff.added_indexes,
+        &mut diff.removed_indexes,
+        &mut diff.changed_indexes,
+    );
+
+    // 外键与触发器只比较名称
+    let base_fks: BTreeSet<&str> = keyed_maps(&base.foreign_keys, "CONSTRAINT_NAME")
+        .into_keys()
+        .collect();
+    let live_fks: BTreeSet<&str> = keyed_maps(&live.foreign_keys, "CONSTRAINT_NAME")
+        .into_keys()
+        .collect();
+    diff.added_foreign_keys = live_fks
+        .difference(&base_fks)
+        .map(|s| s.to_string())
+        .collect();
+    diff.removed_foreign_keys = base_fks
+        .difference(&live_fks)
+        .map(|s| s.to_string())
+        .collect();
+
+    let base_triggers: BTreeSet<&str> = keyed_maps(&base.triggers, "TRIGGER_NAME")
+        .into_keys()
+        .collect();
+    let live_triggers: BTreeSet<&str> = keyed_maps(&live.triggers, "TRIGGER_NAME")
+        .into_keys()
+        .collect();
+    diff.added_triggers = live_triggers
+        .difference(&base_triggers)
+        .map(|s| s.to_string())
+        .collect();
+    diff.removed_triggers = base_triggers
+        .difference(&live_triggers)
+        .map(|s| s.to_string())
+        .collect();
+
+    diff
+}
+
+fn keyed_maps<'a>(
+    items: &'a [BTreeMap<String, String>],
+    key: &str,
+) -> BTreeMap<&'a str, &'a BTreeMap<String, String>> {
+    items
+        .iter()
+        .filter_map(|item| item.get(key).map(|k| (k.as_str(), item)))
+        .collect()
+}
+
+fn diff_keyed<T: PartialEq>(
+    base: &BTreeMap<&str, T>,
+    live: &BTreeMap<&str, T>,
+    added: &mut Vec<String>,
+    removed: &mut Vec<String>,
+    changed: &mut Vec<String>,
+) {
+    for (name, live_item) in live {
+        match base.get(name) {
+            None => added.push(name.to_string()),
+            Some(base_item) if base_item != live_item => changed.push(name.to_string()),
+            Some(_) => {}
+        }
+    }
+    for name in base.keys() {
+        if !live.contains_key(name) {
+            removed.push(name.to_string());
+        }
+    }
+}
+
+pub fn rename_table(
+    profile: &ConnectionProfile,
+    schema: &str,
+    old_name: &str,
+    new_name: &str,
+) -> Result<RenameTableResult, String> {
+    let schema = schema.to_string();
+    let old_name = old_name.to_string();
+    let new_name = new_name.trim().to_string();
+    if new_name.is_empty() {
+        return Err("New table name is empty".to_string());
+    }
+    let result = pool::with_temp_connection(profile, |conn| {
+        // 仅提示，不阻止重命名
+        let warnings = find_table_references(conn, &schema, &old_name)?;
+        conn.query_drop(build_rename_table_sql(&schema, &old_name, &new_name))
+            .map_err(|e| format!("Rename failed: {e}"))?;
+        Ok(RenameTableResult {
+            success: true,
+            warnings,
+        })
+    })?;
+    invalidate_metadata_cache(profile);
+    Ok(result)
+}
+
+fn build_rename_table_sql(schema: &str, old_name: &str, new_name: &str) -> String {
+    format!(
+        "RENAME TABLE `{}`.`{}` TO `{}`.`{}`",
+        escape_identifier(schema),
+        escape_identifier(old_name),
+        escape_identifier(schema),
+        escape_identifier(new_name)
+    )
+}
+
+fn find_table_references(
+    conn: &mut mysql::Conn,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<String>, String> {
+    // 限定名在任意库中都算引用；只写表名时仅同库对象才指向这张表
+    let needle = format!("`{}`", escape_identifier(table));
+    let qualified = format!("`{}`.{needle}", escape_identifier(schema));
+    let mut warnings = Vec::new();
+
+    let views_sql = "SELECT TABLE_SCHEMA, TABLE_NAME FROM INFORMATION_SCHEMA.VIEWS WHERE LOCATE(:qualified, VIEW_DEFINITION) > 0 OR (TABLE_SCHEMA = :schema AND LOCATE(:needle, VIEW_DEFINITION) > 0) ORDER BY TABLE_SCHEMA, TABLE_NAME";
+    let views: Vec<(String, String)> = conn
+        .exec(
+            views_sql,
+            params! {"schema" => schema, "needle" => &needle, "qualified" => &qualified},
+        )
+        .map_err(|e| format!("Query failed: {e}"))?;
+    for (view_schema, view_name) in views {
+        warnings.push(format!(
+            "View {view_schema}.{view_name} references {schema}.{table}"
+        ));
+    }
+
+    let fk_sql = "SELECT DISTINCT TABLE_SCHEMA, TABLE_NAME, CONSTRAINT_NAME FROM INFORMATION_SCHEMA.KEY_COLUMN_USAGE WHERE REFERENCED_TABLE_SCHEMA = :schema AND REFERENCED_TABLE_NAME = :table AND NOT (TABLE_SCHEMA = :schema AND TABLE_NAME = :table) ORDER BY TABLE_SCHEMA, TABLE_NAME, CONSTRAINT_NAME";
+    let fks: Vec<(String, String, String)> = conn
+        .exec(fk_sql, params! {"schema" => schema, "table" => table})
+        .map_err(|e| format!("Query failed: {e}"))?;
+    for (fk_schema, fk_table, constraint) in fks {
+        warnings.push(format!(
+            "Foreign key {constraint} on {fk_schema}.{fk_table} references {schema}.{table}"
+        ));
+    }
+
+    let triggers_sql = "SELECT TRIGGER_SCHEMA, TRIGGER_NAME FROM INFORMATION_SCHEMA.TRIGGERS WHERE NOT (EVENT_OBJECT_SCHEMA = :schema AND EVENT_OBJECT_TABLE = :table) AND (LOCATE(:qualified, ACTION_STATEMENT) > 0 OR (TRIGGER_SCHEMA = :schema AND LOCATE(:needle, ACTION_STATEMENT) > 0)) ORDER BY TRIGGER_SCHEMA, TRIGGER_NAME";
+    let triggers: Vec<(String, String)> = conn
+        .exec(
+            triggers_sql,
+            params! {"schema" => schema, "table" => table, "needle" => &needle, "qualified" => &qualified},
+        )
+        .map_err(|e| format!("Query failed: {e}"))?;
+    for (trigger_schema, trigger_name) in triggers {
+        warnings.push(format!(
+            "Trigger {trigger_schema}.{trigger_name} references {schema}.{table}"
+        ));
+    }
+
+    Ok(warnings)
+}
+
+/// 复制整个库：表结构与数据出错即中止，视图/存储过程/触发器失败仅记为警告
+pub fn clone_database(
+    profile: &ConnectionProfile,
+    source_schema: &str,
+    target_schema: &str,
+    with_data: bool,
+) -> Result<CloneDatabaseResult, String> {
+    let source = source_schema.to_string();
+    let target = target_schema.trim().to_string();
+    if target.is_empty() {
+        return Err("Target database name is empty".to_string());
+    }
+    if source == target {
+        return Err("Target database must differ from source".to_string());
+    }
+
+    let result = pool::with_temp_connection(profile, |conn| {
+        let charset: Option<(String, String)> = conn
+            .exec_first(
+                "SELECT DEFAULT_CHARACTER_SET_NAME, DEFAULT_COLLATION_NAME FROM INFORMATION_SCHEMA.SCHEMATA WHERE SCHEMA_NAME = :schema",
+                params! {"schema" => &source},
+            )
+            .map_err(|e| format!("Query failed: {e}"))?;
+        let (charset, collation) = charset.ok_or_else(|| format!("Database {source} not found"))?;
+        conn.query_drop(format!(
+            "CREATE DATABASE `{}` DEFAULT CHARACTER SET {} COLLATE {}",
+            escape_identifier(&target),
+            charset,
+            collation
+        ))
+        .map_err(|e| format!("Create database failed: {e}"))?;
+        conn.query_drop(format!("USE `{}`", escape_identifier(&target)))
+            .map_err(|e| format!("Use database failed: {e}"))?;
+
+        let tables: Vec<String> = conn
+            .exec(
+                "SELECT TABLE_NAME FROM INFORMATION_SCHEMA.TABLES WHERE TABLE_SCHEMA = :schema AND TABLE_TYPE = 'BASE TABLE' ORDER BY TABLE_NAME",
+                params! {"schema" => &source},
+            )
+            .map_err(|e| format!("Query failed: {e}"))?;
+        let edges: Vec<(String, String)> = conn
+            .exec(
+                "SELECT DISTINCT TABLE_NAME, REFERENCED_TABLE_NAME FROM INFORMATION_SCHEMA.KEY_COLUMN_USAGE WHERE TABLE_SCHEMA = :schema AND REFERENCED_TABLE_SCHEMA = :schema AND REFERENCED_TABLE_NAME IS NOT NULL",
+                params! {"schema" => &source},
+            )
+            .map_err(|e| format!("Query failed: {e}"))?;
+        let ordered_tables = topo_sort_tables(&tables, &edges);
+
+        let mut result = CloneDatabaseResult::default();
+        conn.query_drop("SET FOREIGN_KEY_CHECKS = 0")
+            .map_err(|e| format!("Execute failed: {e}"))?;
+        let copy_result = clone_tables(
+            conn,
+            &source,
+            &target,
+            &ordered_tables,
+            with_data,
+            &mut result,
+        );
+        let _ = conn.query_drop("SET FOREIGN_KEY_CHECKS = 1");
+        copy_result?;
+
+        clone_views(conn, &source, &target, &mut result)?;
+        clone_routines(conn, &source, &target, &mut result)?;
+        clone_triggers(conn, &source, &target, &mut result)?;
+        Ok(result)
+    });
+    // 中途失败时目标库可能已部分建好，同样需要刷新
+    invalidate_metadata_cache(profile);
+    result
+}
+
+fn clone_tables(
+    conn: &mut mysql::Conn,
+    source: &str,
+    target: &str,
+    tables: &[String],
+    with_data: bool,
+    result: &mut CloneDatabaseResult,
+) -> Result<(), String> {
+    for table in tables {
+        // CREATE TABLE ... LIKE 不会复制外键，因此直接在目标库执行源表 DDL
+        let row: Option<(String, String)> = conn
+            .query_first(format!(
+                "SHOW CREATE TABLE `{}`.`{}`",
+                escape_identifier(source),
+                escape_identifier(table)
+            ))
+            .map_err(|e| format!("SHOW CREATE TABLE failed for {table}: {e}"))?;
+        let ddl = row
+            .map(|(_, ddl)| ddl)
+            .ok_or_else(|| format!("Table {table} not found"))?;
+        conn.query_drop(&ddl)
+            .map_err(|e| format!("Create table {table} failed: {e}"))?;
+        result.tables += 1;
+
+        if with_data {
+            let columns: Vec<String> = conn
+                .exec(
+                    "SELECT COLUMN_NAME FROM INFORMATION_SCHEMA.COLUMNS WHERE TABLE_SCHEMA = :schema AND TABLE_NAME = :table AND EXTRA NOT LIKE '%GENERATED%' ORDER BY ORDINAL_POSITION",
+                    params! {"schema" => source, "table" => table},
+                )
+                .map_err(|e| format!("Query failed: {e}"))?;
+            conn.query_drop(build_copy_rows_sql(source, target, table, &columns))
+                .map_err(|e| format!("Copy data for {table} failed: {e}"))?;
+            result.rows_copied += conn.affected_rows();
+        }
+    }
+    Ok(())
+}
+
+fn build_copy_rows_sql(source: &str, target: &str, table: &str, columns: &[String]) -> String {
+    let column_list = columns
+        .iter()
+        .map(|c| format!("`{}`", escape_identifier(c)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "INSERT INTO `{}`.`{}` ({}) SELECT {} FROM `{}`.`{}`",
+        escape_identifier(target),
+        escape_identifier(table),
+        column_list,
+        column_list,
+        escape_identifier(source),
+        escape_identifier(table)
+    )
+}
+
+fn clone_views(
+    conn: &mut mysql::Conn,
+    source: &str,
+    target: &str,
+    result: &mut CloneDatabaseResult,
+) -> Result<(), String> {
+    let views: Vec<String> = conn
+        .exec(
+            "SELECT TABLE_NAME FROM INFORMATION_SCHEMA.VIEWS WHERE TABLE_SCHEMA = :schema ORDER BY TABLE_NAME",
+            params! {"schema" => source},
+        )
+        .map_err(|e| format!("Query failed: {e}"))?;
+
+    let mut pending: Vec<(String, String)> = Vec::new();
+    for view in views {
+        let row: Option<mysql::Row> = conn
+            .query_first(format!(
+                "SHOW CREATE VIEW `{}`.`{}`",
+                escape_identifier(source),
+                escape_identifier(&view)
+            ))
+            .map_err(|e| format!("SHOW CREATE VIEW failed for {view}: {e}"))?;
+        match row.and_then(|r| r.get::<String, _>("Create View")) {
+            Some(ddl) => pending.push((view, retarget_ddl(&ddl, source, target))),
+            None => result
+                .warnings
+                .push(format!("View {view}: definition not found")),
+        }
+    }
+
+    // 视图之间可能互相依赖，按轮次重试直到没有进展
+    loop {
+        let created_before = result.views;
+        let mut failed = Vec::new();
+        let mut errors = Vec::new();
+        for (view, ddl) in pending.drain(..) {
+            match conn.query_drop(&ddl) {
+                Ok(()) => result.views += 1,
+                Err(e) => {
+                    errors.push(format!("View {view}: {e}"));
+                    failed.push((view, ddl));
+                }
+            }
+        }
+        if failed.is_empty() {
+            break;
+        }
+        if result.views == created_before {
+            result.warnings.extend(errors);
+            break;
+        }
+        pending = failed;
+    }
+    Ok(())
+}
+
+fn clone_routines(
+    conn: &mut mysql::Conn,
+    source: &str,
+    target: &str,
+    result: &mut CloneDatabaseResult,
+) -> Result<(), String> {
+    let routines: Vec<(String, String)> = conn
+        .exec(
+            "SELECT ROUTINE_NAME, ROUTINE_TYPE FROM INFORMATION_SCHEMA.ROUTINES WHERE ROUTINE_SCHEMA = :schema ORDER BY ROUTINE_TYPE, ROUTINE_NAME",
+            params! {"schema" => source},
+        )
+        .map_err(|e| format!("Query failed: {e}"))?;
+
+    for (name, routine_type) in routines {
+        let (keyword, column) = if routine_type.eq_ignore_ascii_case("PROCEDURE") {
+            ("PROCEDURE", "Create Procedure")
+        } else {
+            ("FUNCTION", "Create Function")
+        };
+        let row: Option<mysql::Row> = conn
+            .query_first(format!(
+                "SHOW CREATE {keyword} `{}`.`{}`",
+                escape_identifier(source),
+                escape_identifier(&name)
+            ))
+            .map_err(|e| format!("SHOW CREATE {keyword} failed for {name}: {e}"))?;
+        let Some(ddl) = row.and_then(|r| r.get::<Option<String>, _>(column).flatten()) else {
+            result
+                .warnings
+                .push(format!("{keyword} {name}: definition not visible"));
+            continue;
+        };
+        match conn.query_drop(retarget_ddl(&ddl, source, target)) {
+            Ok(()) => result.routines += 1,
+            Err(e) => result.warnings.push(format!("{keyword} {name}: {e}")),
+        }
+    }
+    Ok(())
+}
+
+fn clone_triggers(
+    conn: &mut mysql::Conn,
+    source: &str,
+    target: &str,
+    result: &mut CloneDatabaseResult,
+) -> Result<(), String> {
+    let triggers: Vec<String> = conn
+        .exec(
+            "SELECT TRIGGER_NAME FROM INFORMATION_SCHEMA.TRIGGERS WHERE TRIGGER_SCHEMA = :schema ORDER BY EVENT_OBJECT_TABLE, ACTION_ORDER",
+            params! {"schema" => source},
+        )
+        .map_err(|e| format!("Query failed: {e}"))?;
+
+    for name in triggers {
+        let row: Option<mysql::Row> = conn
+            .query_first(format!(
+                "SHOW CREATE TRIGGER `{}`.`{}`",
+                escape_identifier(source),
+                escape_identifier(&name)
+            ))
+            .map_err(|e| format!("SHOW CREATE TRIGGER failed for {name}: {e}"))?;
+        let Some(ddl) = row.and_then(|r| r.get::<String, _>("SQL Original Statement")) else {
+            result
+                .warnings
+                .push(format!("Trigger {name}: definition not found"));
+            continue;
+        };
+        match conn.query_drop(retarget_ddl(&ddl, source, target)) {
+            Ok(()) => result.triggers += 1,
+            Err(e) => result.warnings.push(format!("Trigger {name}: {e}")),
+        }
+    }
+    Ok(())
+}
+
+// 去掉 DEFINER 子句并把源库限定名替换为目标库
+fn retarget_ddl(ddl: &str, source: &str, target: &str) -> String {
+    let mut output = ddl.to_string();
+    if let Some(start) = output.find("DEFINER=") {
+        let mut end = start + "DEFINER=".len();
+        let mut quote: Option<char> = None;
+        for ch in output[end..].chars() {
+            match quote {
+                Some(q) if ch == q => quote = None,
+                Some(_) => {}
+                None if ch == '`' || ch == '\'' || ch == '"' => quote = Some(ch),
+                None if ch.is_whitespace() => break,
+                None => {}
+            }
+            end += ch.len_utf8();
+        }
+        output.replace_range(start..end, "");
+    }
+    sqlutils::replace_schema_qualifier(&output, source, target)
+}
+
+/// 将给定表按同库内的外键依赖排序，被引用的表在前
+pub fn order_tables_by_dependencies(
+    profile: &ConnectionProfile,
+    schema: &str,
+    tables: &[String],
+) -> Result<Vec<String>, String> {
+    let edges: Vec<(String, String)> = pool::with_temp_connection(profile, |conn| {
+        conn.exec(
+            "SELECT DISTINCT TABLE_NAME, REFERENCED_TABLE_NAME FROM INFORMATION_SCHEMA.KEY_COLUMN_USAGE WHERE TABLE_SCHEMA = :schema AND REFERENCED_TABLE_SCHEMA = :schema AND REFERENCED_TABLE_NAME IS NOT NULL",
+            params! {"schema" => schema},
+        )
+        .map_err(|e| format!("Query failed: {e}"))
+    })?;
+    Ok(topo_sort_tables(tables, &edges))
+}
+
+/// 按外键依赖排序，被引用的表在前；存在环时剩余表保持原顺序追加
+fn topo_sort_tables(tables: &[String], edges: &[(String, String)]) -> Vec<String> {
+    let mut remaining: Vec<String> = tables.to_vec();
+    let mut ordered: Vec<String> = Vec::with_capacity(tables.len());
+    loop {
+        let ready: Vec<String> = remaining
+            .iter()
+            .filter(|table| {
+                !edges.iter().any(|(child, parent)| {
+                    child == *table && parent != *table && remaining.contains(parent)
+                })
+            })
+            .cloned()
+            .collect();
+        if ready.is_empty() {
+            break;
+        }
+        remaining.retain(|table| !ready.contains(table));
+        ordered.extend(ready);
+    }
+    ordered.extend(remaining);
+    ordered
+}
+
+/// 生成指定类型对象的 DROP 脚本：触发器、事件、存储过程先删，随后视图，最后按外键依赖逆序删表
+pub fn generate_drop_script(
+    profile: &ConnectionProfile,
+    schema: &str,
+    object_kinds: &[ObjectKind],
+) -> Result<String, String> {
+    let schema = schema.to_string();
+    pool::with_temp_connection(profile, |conn| {
+        let mut targets = DropTargets::default();
+        if object_kinds.contains(&ObjectKind::Table) {
+            let tables: Vec<String> = conn
+                .exec(
+                    "SELECT TABLE_NAME FROM INFORMATION_SCHEMA.TABLES WHERE TABLE_SCHEMA = :schema AND TABLE_TYPE = 'BASE TABLE' ORDER BY TABLE_NAME",
+                    params! {"schema" => &schema},
+                )
+                .map_err(|e| format!("Query failed: {e}"))?;
+            let edges: Vec<(String, String)> = conn
+                .exec(
+                    "SELECT DISTINCT TABLE_NAME, REFERENCED_TABLE_NAME FROM INFORMATION_SCHEMA.KEY_COLUMN_USAGE WHERE TABLE_SCHEMA = :schema AND REFERENCED_TABLE_SCHEMA = :schema AND REFERENCED_TABLE_NAME IS NOT NULL",
+                    params! {"schema" => &schema},
+                )
+                .map_err(|e| format!("Query failed: {e}"))?;
+            targets.tables = topo_sort_tables(&tables, &edges);
+            targets.tables.reverse();
+        }
+        if object_kinds.contains(&ObjectKind::View) {
+            targets.views = conn
+                .exec(
+                    "SELECT TABLE_NAME FROM INFORMATION_SCHEMA.VIEWS WHERE TABLE_SCHEMA = :schema ORDER BY TABLE_NAME",
+                    params! {"schema" => &schema},
+                )
+                .map_err(|e| format!("Query failed: {e}"))?;
+        }
+        if object_kinds.contains(&ObjectKind::Routine) {
+            targets.routines = conn
+                .exec(
+                    "SELECT ROUTINE_NAME, ROUTINE_TYPE FROM INFORMATION_SCHEMA.ROUTINES WHERE ROUTINE_SCHEMA = :schema ORDER BY ROUTINE_TYPE, ROUTINE_NAME",
+                    params! {"schema" => &schema},
+                )
+                .map_err(|e| format!("Query failed: {e}"))?;
+        }
+        if object_kinds.contains(&ObjectKind::Trigger) {
+            targets.triggers = conn
+                .exec(
+                    "SELECT TRIGGER_NAME FROM INFORMATION_SCHEMA.TRIGGERS WHERE TRIGGER_SCHEMA = :schema ORDER BY EVENT_OBJECT_TABLE, ACTION_ORDER",
+                    params! {"schema" => &schema},
+                )
+                .map_err(|e| format!("Query failed: {e}"))?;
+        }
+        if object_kinds.contains(&ObjectKind::Event) {
+            targets.events = conn
+                .exec(
+                    "SELECT EVENT_NAME FROM INFORMATION_SCHEMA.EVENTS WHERE EVENT_SCHEMA = :schema ORDER BY EVENT_NAME",
+                    params! {"schema" => &schema},
+                )
+                .map_err(|e| format!("Query failed: {e}"))?;
+        }
+        Ok(build_drop_script(&schema, &targets))
     })
 }
 
-pub fn load_ddl(profile: &ConnectionProfile, schema: &str, table: &str) -> Result<String, String> {
-    let schema = schema.to_string();
-    let table = table.to_string();
-    pool::with_temp_connection(profile, |conn| {
-        let sql = format!(
-            "SHOW CREATE TABLE `{}`.`{}`",
-            escape_identifier(&schema),
-            escape_identifier(&table)
-        );
-        let row: Option<(String, String)> = conn
-            .query_first(sql)
-            .map_err(|e| format!("Query failed: {e}"))?;
-        Ok(row.map(|(_, ddl)| ddl).unwrap_or_default())
-    })
+fn build_drop_script(schema: &str, targets: &DropTargets) -> String {
+    let qualified = |name: &str| {
+        format!(
+            "`{}`.`{}`",
+            escape_identifier(schema),
+            escape_identifier(name)
+        )
+    };
+    let mut script = String::new();
+    script.push_str(&format!("-- Drop script for `{}`\n", schema));
+    script.push_str(&format!(
+        "-- Generated at: {}\n\n",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+    ));
+    script.push_str("SET FOREIGN_KEY_CHECKS = 0;\n\n");
+    for name in &targets.triggers {
+        script.push_str(&format!("DROP TRIGGER IF EXISTS {};\n", qualified(name)));
+    }
+    for name in &targets.events {
+        script.push_str(&format!("DROP EVENT IF EXISTS {};\n", qualified(name)));
+    }
+    for (name, routine_type) in &targets.routines {
+        let keyword = if routine_type.eq_ignore_ascii_case("PROCEDURE") {
+            "PROCEDURE"
+        } else {
+            "FUNCTION"
+        };
+        script.push_str(&format!("DROP {keyword} IF EXISTS {};\n", qualified(name)));
+    }
+    for name in &targets.views {
+        script.push_str(&format!("DROP VIEW IF EXISTS {};\n", qualified(name)));
+    }
+    for name in &targets.tables {
+        script.push_str(&format!("DROP TABLE IF EXISTS {};\n", qualified(name)));
+    }
+    script.push_str("\nSET FOREIGN_KEY_CHECKS = 1;\n");
+    script
 }
 
 /// 生成ER图的SQL导出（通用SQL格式）
@@ -1070,6 +3409,77 @@ pub fn get_user_model(
     })
 }
 
+/// 汇总所有账户的结构化权限。filter.privilege 只保留持有该权限的账户
+/// （全局授予或任一作用域授予均算）；filter.database 只保留该库下的作用域，
+/// 且账户须在该库上有授权或拥有全局权限
+pub fn audit_privileges(
+    profile: &ConnectionProfile,
+    filter: Option<PrivFilter>,
+) -> Result<Vec<UserPrivilegeSummary>, String> {
+    let filter = filter.unwrap_or_default();
+    let privilege = filter
+        .privilege
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+    let database = filter
+        .database
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty());
+
+    pool::with_temp_connection(profile, |conn| {
+        let users: Vec<(String, String)> = conn
+            .query("SELECT User, Host FROM mysql.user ORDER BY User, Host")
+            .map_err(|e| format!("Query failed: {e}"))?;
+
+        let mut result = Vec::new();
+        for (username, host) in users {
+            let server_privileges = load_server_privileges(conn, &username, &host)
+                .map_err(|e| format!("Query failed: {e}"))?;
+            let database_privileges = load_database_privileges(conn, &username, &host)
+                .map_err(|e| format!("Query failed: {e}"))?;
+            let summary = UserPrivilegeSummary {
+                username,
+                host,
+                server_privileges,
+                database_privileges,
+            };
+            result.extend(apply_priv_filter(
+                summary,
+                privilege.as_deref(),
+                database.as_deref(),
+            ));
+        }
+        Ok(result)
+    })
+}
+
+fn apply_priv_filter(
+    mut summary: UserPrivilegeSummary,
+    privilege: Option<&str>,
+    database: Option<&str>,
+) -> Option<UserPrivilegeSummary> {
+    if let Some(db) = database {
+        summary.database_privileges.retain(|scope, _| {
+            parse_scope_key(scope).is_some_and(|(scope_db, _, _)| scope_db == db)
+        });
+        if summary.database_privileges.is_empty() && summary.server_privileges.is_empty() {
+            return None;
+        }
+    }
+
+    if let Some(wanted) = privilege {
+        let holds = summary
+            .server_privileges
+            .iter()
+            .chain(summary.database_privileges.values().flatten())
+            .any(|p| privilege_eq(p, wanted));
+        if !holds {
+            return None;
+        }
+    }
+    Some(summary)
+}
+
 pub fn get_all_databases(profile: &ConnectionProfile) -> Result<Vec<String>, String> {
     pool::with_temp_connection(profile, |conn| {
         let dbs: Vec<String> = conn
@@ -1097,12 +3507,107 @@ pub fn generate_user_sql(
     generate_user_sql_payload(&current_payload, is_new_user, original_payload.as_ref())
 }
 
+pub fn get_sql_mode(profile: &ConnectionProfile, global: bool) -> Result<Vec<String>, String> {
+    let sql = if global {
+        "SELECT @@GLOBAL.sql_mode"
+    } else {
+        "SELECT @@SESSION.sql_mode"
+    };
+    pool::with_temp_connection(profile, |conn| {
+        let value: Option<Option<String>> = conn
+            .query_first(sql)
+            .map_err(|e| format!("Query failed: {e}"))?;
+        Ok(parse_sql_mode(&value.flatten().unwrap_or_default()))
+    })
+}
+
+/// 校验后设置 sql_mode，返回实际执行的语句。
+/// 全局设置通过临时连接执行；会话级设置作用于 `connection` 指定的 (pool_id, conn_id) 查询连接，
+/// 并记入该连接的会话变量，重连后自动恢复。
+pub fn set_sql_mode(
+    profile: &ConnectionProfile,
+    modes: &[String],
+    global: bool,
+    connection: Option<(u64, u64)>,
+) -> Result<String, String> {
+    let sql = build_set_sql_mode_sql(modes, global)?;
+    if global {
+        return pool::with_temp_connection(profile, |conn| {
+            conn.query_drop(&sql)
+                .map_err(|e| format!("Execute failed: {e}"))?;
+            Ok(sql.clone())
+        });
+    }
+    let (pool_id, conn_id) =
+        connection.ok_or_else(|| "Session sql_mode requires a query connection".to_string())?;
+    pool::execute(pool_id, conn_id, &sql, None)?;
+    Ok(sql)
+}
+
+fn parse_sql_mode(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|mode| mode.trim().to_ascii_uppercase())
+        .filter(|mode| !mode.is_empty())
+        .collect()
+}
+
+fn build_set_sql_mode_sql(modes: &[String], global: bool) -> Result<String, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for mode in modes {
+        let mode = mode.trim().to_ascii_uppercase();
+        if mode.is_empty() {
+            continue;
+        }
+        if !KNOWN_SQL_MODES.contains(&mode.as_str()) {
+            return Err(format!("Unknown sql_mode: {mode}"));
+        }
+        if !normalized.contains(&mode) {
+            normalized.push(mode);
+        }
+    }
+    let scope = if global { "GLOBAL" } else { "SESSION" };
+    Ok(format!("SET {scope} sql_mode = '{}'", normalized.join(",")))
+}
+
+/// 读取事件调度器状态，DISABLED（启动参数禁用）视为关闭
+pub fn get_event_scheduler_status(profile: &ConnectionProfile) -> Result<bool, String> {
+    pool::with_temp_connection(profile, |conn| {
+        let value: Option<Option<String>> = conn
+            .query_first("SELECT @@GLOBAL.event_scheduler")
+            .map_err(|e| format!("Query failed: {e}"))?;
+        Ok(parse_event_scheduler_status(
+            &value.flatten().unwrap_or_default(),
+        ))
+    })
+}
+
+/// 开关事件调度器（需要 SYSTEM_VARIABLES_ADMIN 或 SUPER 权限），返回实际执行的语句
+pub fn set_event_scheduler(profile: &ConnectionProfile, enabled: bool) -> Result<String, String> {
+    let sql = build_set_event_scheduler_sql(enabled);
+    pool::with_temp_connection(profile, |conn| {
+        conn.query_drop(&sql)
+            .map_err(|e| format!("Execute failed: {e}"))?;
+        Ok(sql.clone())
+    })
+}
+
+fn parse_event_scheduler_status(value: &str) -> bool {
+    matches!(value.trim().to_ascii_uppercase().as_str(), "ON" | "1")
+}
+
+fn build_set_event_scheduler_sql(enabled: bool) -> String {
+    let state = if enabled { "ON" } else { "OFF" };
+    format!("SET GLOBAL event_scheduler = {state}")
+}
+
 pub fn execute_sql(
     profile: &ConnectionProfile,
     sql: &str,
     database_override: Option<&str>,
 ) -> Result<(), String> {
     let sql = sql.to_string();
+    let schema = database_override.or(profile.database.as_deref());
     pool::with_temp_connection_database(profile, database_override, |conn| {
         let statements = sqlutils::split_sql_statements(&sql, DbType::Mysql);
 
@@ -1111,12 +3616,20 @@ pub fn execute_sql(
             if trimmed.is_empty() {
                 continue;
             }
-            conn.query_drop(trimmed)
-                .map_err(|e| format!("Execute failed: {e}\nStatement: {trimmed}"))?;
+            let result = conn.query_drop(trimmed).map_err(|e| e.to_string());
+            audit::log_statement(
+                &profile.username,
+                schema,
+                trimmed,
+                result.as_ref().err().map(String::as_str),
+            );
+            result.map_err(|e| format!("Execute failed: {e}\nStatement: {trimmed}"))?;
         }
 
         Ok(())
-    })
+    })?;
+    invalidate_metadata_cache(profile);
+    Ok(())
 }
 
 fn escape_identifier(input: &str) -> String {
@@ -1668,3 +4181,770 @@ fn generate_user_sql_payload(
 fn escape_sql(value: &str) -> String {
     value.replace('\\', "\\\\").replace('\'', "''")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::test_support::{fresh_schema, mysql_profile, profile, row, run_sql, text};
+    use mysql::Value;
+
+    fn statistics_row(index: &str, column: &str, sub_part: Option<u64>) -> mysql::Row {
+        row(
+            &[
+                "INDEX_NAME",
+                "NON_UNIQUE",
+                "INDEX_TYPE",
+                "COLUMN_NAME",
+                "SUB_PART",
+                "COLLATION",
+                "INDEX_COMMENT",
+                "IS_VISIBLE",
+            ],
+            vec![
+                text(index),
+                Value::Int(if index == "PRIMARY" { 0 } else { 1 }),
+                text("BTREE"),
+                text(column),
+                sub_part.map(Value::UInt).unwrap_or(Value::NULL),
+                text("A"),
+                text(""),
+                text(if index == "idx_hidden" { "NO" } else { "YES" }),
+            ],
+        )
+    }
+
+    #[test]
+    fn groups_multi_column_index_with_prefix() {
+        let indexes = group_index_rows(vec![
+            statistics_row("PRIMARY", "id", None),
+            statistics_row("idx_name_email", "name", None),
+            statistics_row("idx_name_email", "email", Some(10)),
+            statistics_row("idx_hidden", "created_at", None),
+        ]);
+
+        assert_eq!(indexes.len(), 3);
+        assert!(indexes[0].unique);
+        let composite = &indexes[1];
+        assert_eq!(composite.name, "idx_name_email");
+        assert!(!composite.unique);
+        assert_eq!(composite.index_type, "BTREE");
+        let columns: Vec<(&str, Option<u64>)> = composite
+            .columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.sub_part))
+            .collect();
+        assert_eq!(columns, vec![("name", None), ("email", Some(10))]);
+        assert!(composite.visible);
+        assert!(!indexes[2].visible);
+    }
+
+    #[test]
+    fn rename_table_sql_escapes_identifiers() {
+        assert_eq!(
+            build_rename_table_sql("shop", "orders", "order`s_2024"),
+            "RENAME TABLE `shop`.`orders` TO `shop`.`order``s_2024`"
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn rename_table_warns_about_referencing_view() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "rename_table");
+        // 其它库里同名表的视图不应被误报
+        let other = fresh_schema(&profile, "rename_table_other");
+        run_sql(
+            &profile,
+            &[
+                &format!("CREATE TABLE `{schema}`.`orders` (id INT PRIMARY KEY)"),
+                &format!(
+                    "CREATE VIEW `{schema}`.`recent_orders` AS SELECT id FROM `{schema}`.`orders`"
+                ),
+                &format!("CREATE TABLE `{other}`.`orders` (id INT PRIMARY KEY)"),
+                &format!(
+                    "CREATE VIEW `{other}`.`open_orders` AS SELECT id FROM `{other}`.`orders`"
+                ),
+            ],
+        );
+
+        let result = rename_table(&profile, &schema, "orders", "orders_archive").unwrap();
+        assert!(result.success);
+        assert_eq!(
+            result.warnings,
+            vec![format!(
+                "View {schema}.recent_orders references {schema}.orders"
+            )]
+        );
+        run_sql(
+            &profile,
+            &[
+                &format!("DROP DATABASE `{schema}`"),
+                &format!("DROP DATABASE `{other}`"),
+            ],
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn schema_snapshot_contains_table_columns_and_index() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "snapshot");
+        run_sql(
+            &profile,
+            &[&format!(
+                "CREATE TABLE `{schema}`.`users` (id INT PRIMARY KEY, email VARCHAR(128) NOT NULL, KEY idx_email (email))"
+            )],
+        );
+
+        let json = export_schema_snapshot(&profile, &schema).unwrap();
+        let snapshot: SchemaSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(snapshot.schema, schema);
+        assert_eq!(snapshot.tables.len(), 1);
+        let table = &snapshot.tables[0];
+        assert_eq!(table.name, "users");
+        let columns: Vec<&str> = table
+            .columns
+            .iter()
+            .map(|c| c["COLUMN_NAME"].as_str())
+            .collect();
+        assert_eq!(columns, vec!["id", "email"]);
+        let indexes: Vec<&str> = table.indexes.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(indexes, vec!["PRIMARY", "idx_email"]);
+        assert_eq!(export_schema_snapshot(&profile, &schema).unwrap(), json);
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+    }
+
+    fn column(name: &str, column_type: &str) -> BTreeMap<String, String> {
+        BTreeMap::from([
+            ("COLUMN_NAME".to_string(), name.to_string()),
+            ("COLUMN_TYPE".to_string(), column_type.to_string()),
+        ])
+    }
+
+    fn snapshot(columns: Vec<BTreeMap<String, String>>) -> SchemaSnapshot {
+        SchemaSnapshot {
+            schema: "shop".to_string(),
+            tables: vec![TableSnapshot {
+                name: "users".to_string(),
+                columns,
+                indexes: Vec::new(),
+                foreign_keys: Vec::new(),
+                triggers: Vec::new(),
+            }],
+        }
+    }
+
+    #[test]
+    fn column_missing_from_snapshot_is_reported_as_added() {
+        let base = snapshot(vec![column("id", "int")]);
+        let live = snapshot(vec![column("id", "int"), column("email", "varchar(128)")]);
+
+        let diff = diff_schema_snapshots(&base, &live);
+        assert!(diff.added_tables.is_empty());
+        assert!(diff.removed_tables.is_empty());
+        assert_eq!(diff.changed_tables.len(), 1);
+        let table = &diff.changed_tables[0];
+        assert_eq!(table.name, "users");
+        assert_eq!(table.added_columns, vec!["email"]);
+        assert!(table.removed_columns.is_empty());
+        assert!(table.changed_columns.is_empty());
+
+        assert!(diff_schema_snapshots(&live, &live)
+            .changed_tables
+            .is_empty());
+    }
+
+    #[test]
+    fn parses_and_builds_sql_mode() {
+        assert_eq!(
+            parse_sql_mode("STRICT_TRANS_TABLES, no_zero_date,,ONLY_FULL_GROUP_BY"),
+            vec!["STRICT_TRANS_TABLES", "NO_ZERO_DATE", "ONLY_FULL_GROUP_BY"]
+        );
+        assert!(parse_sql_mode("").is_empty());
+
+        let modes = vec![
+            "strict_trans_tables".to_string(),
+            "ANSI_QUOTES".to_string(),
+            "STRICT_TRANS_TABLES".to_string(),
+        ];
+        assert_eq!(
+            build_set_sql_mode_sql(&modes, false).unwrap(),
+            "SET SESSION sql_mode = 'STRICT_TRANS_TABLES,ANSI_QUOTES'"
+        );
+        assert_eq!(
+            build_set_sql_mode_sql(&[], true).unwrap(),
+            "SET GLOBAL sql_mode = ''"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_sql_mode() {
+        let modes = vec![
+            "STRICT_TRANS_TABLES".to_string(),
+            "NO_SUCH_MODE".to_string(),
+        ];
+        assert_eq!(
+            build_set_sql_mode_sql(&modes, false).unwrap_err(),
+            "Unknown sql_mode: NO_SUCH_MODE"
+        );
+        let injected = vec!["ANSI'; DROP TABLE users; --".to_string()];
+        assert!(build_set_sql_mode_sql(&injected, false).is_err());
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn session_sql_mode_applies_to_the_query_connection() {
+        let profile = mysql_profile();
+        let modes = vec!["ANSI_QUOTES".to_string()];
+        assert_eq!(
+            set_sql_mode(&profile, &modes, false, None).unwrap_err(),
+            "Session sql_mode requires a query connection"
+        );
+
+        let pool_id = pool::create_pool(&profile).unwrap();
+        let conn_id = pool::get_connection(pool_id, None).unwrap();
+        let sql = set_sql_mode(&profile, &modes, false, Some((pool_id, conn_id))).unwrap();
+        let current = pool::query(pool_id, conn_id, "SELECT @@SESSION.sql_mode", None);
+        let diagnostics = pool::get_connection_diagnostics(pool_id, conn_id);
+        pool::release_connection(pool_id, conn_id).unwrap();
+        pool::close_pool(pool_id);
+
+        assert_eq!(sql, "SET SESSION sql_mode = 'ANSI_QUOTES'");
+        assert_eq!(
+            current.unwrap().rows[0][0],
+            serde_json::Value::from("ANSI_QUOTES")
+        );
+        assert_eq!(diagnostics.unwrap().session_settings, [sql]);
+    }
+
+    #[test]
+    fn clone_orders_parent_before_child_and_copies_rows() {
+        let tables = vec!["order_items".to_string(), "orders".to_string()];
+        let edges = vec![("order_items".to_string(), "orders".to_string())];
+        assert_eq!(
+            topo_sort_tables(&tables, &edges),
+            vec!["orders", "order_items"]
+        );
+        assert_eq!(
+            build_copy_rows_sql("shop", "shop_copy", "orders", &["id".to_string(), "note".to_string()]),
+            "INSERT INTO `shop_copy`.`orders` (`id`, `note`) SELECT `id`, `note` FROM `shop`.`orders`"
+        );
+    }
+
+    #[test]
+    fn retarget_ddl_keeps_literals_that_mention_source_schema() {
+        let ddl = "CREATE DEFINER=`root`@`%` SQL SECURITY DEFINER VIEW `shop`.`v` AS select `shop`.`t`.`id` AS `id`,'`shop`.`t`' AS `label` from `shop`.`t`";
+        assert_eq!(
+            retarget_ddl(ddl, "shop", "shop_copy"),
+            "CREATE  SQL SECURITY DEFINER VIEW `shop_copy`.`v` AS select `shop_copy`.`t`.`id` AS `id`,'`shop`.`t`' AS `label` from `shop_copy`.`t`"
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn clone_database_copies_fk_tables_in_order() {
+        let profile = mysql_profile();
+        let source = fresh_schema(&profile, "clone_src");
+        let target = format!("{source}_copy");
+        run_sql(
+            &profile,
+            &[
+                &format!("DROP DATABASE IF EXISTS `{target}`"),
+                &format!("CREATE TABLE `{source}`.`orders` (id INT PRIMARY KEY, note VARCHAR(32) DEFAULT '{source}.x')"),
+                &format!("CREATE TABLE `{source}`.`order_items` (id INT PRIMARY KEY, order_id INT, FOREIGN KEY (order_id) REFERENCES `orders` (id))"),
+                &format!("INSERT INTO `{source}`.`orders` (id) VALUES (1)"),
+                &format!("INSERT INTO `{source}`.`order_items` VALUES (10, 1)"),
+            ],
+        );
+
+        let result = clone_database(&profile, &source, &target, true).unwrap();
+        assert_eq!(result.tables, 2);
+        assert_eq!(result.rows_copied, 2);
+        let fks = list_foreign_keys(&profile, &target, "order_items", ListOrder::Ordinal).unwrap();
+        assert_eq!(fks.len(), 1);
+        run_sql(
+            &profile,
+            &[
+                &format!("DROP DATABASE `{target}`"),
+                &format!("DROP DATABASE `{source}`"),
+            ],
+        );
+    }
+
+    #[test]
+    fn builds_event_scheduler_statement() {
+        assert_eq!(
+            build_set_event_scheduler_sql(true),
+            "SET GLOBAL event_scheduler = ON"
+        );
+        assert_eq!(
+            build_set_event_scheduler_sql(false),
+            "SET GLOBAL event_scheduler = OFF"
+        );
+    }
+
+    #[test]
+    fn parses_event_scheduler_status() {
+        assert!(parse_event_scheduler_status("ON"));
+        assert!(parse_event_scheduler_status(" on "));
+        assert!(parse_event_scheduler_status("1"));
+        assert!(!parse_event_scheduler_status("OFF"));
+        assert!(!parse_event_scheduler_status("DISABLED"));
+        assert!(!parse_event_scheduler_status(""));
+    }
+
+    #[test]
+    fn drop_script_drops_views_before_tables_inside_fk_wrappers() {
+        let targets = DropTargets {
+            tables: vec!["order_items".to_string(), "orders".to_string()],
+            views: vec!["recent_orders".to_string()],
+            routines: vec![("refresh".to_string(), "PROCEDURE".to_string())],
+            ..Default::default()
+        };
+        let script = build_drop_script("shop", &targets);
+        let statements: Vec<&str> = script
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with("--"))
+            .collect();
+        assert_eq!(
+            statements,
+            vec![
+                "SET FOREIGN_KEY_CHECKS = 0;",
+                "DROP PROCEDURE IF EXISTS `shop`.`refresh`;",
+                "DROP VIEW IF EXISTS `shop`.`recent_orders`;",
+                "DROP TABLE IF EXISTS `shop`.`order_items`;",
+                "DROP TABLE IF EXISTS `shop`.`orders`;",
+                "SET FOREIGN_KEY_CHECKS = 1;",
+            ]
+        );
+    }
+
+    #[test]
+    fn warmed_object_lists_are_served_from_cache() {
+        let profile = profile("cache-test.invalid", 3306, "root", "");
+        let key = metadata_cache_key(&profile, "shop", "tables");
+        let queries = std::cell::Cell::new(0);
+        let load = || {
+            queries.set(queries.get() + 1);
+            Ok(vec!["orders".to_string(), "users".to_string()])
+        };
+
+        // 预热后的列表调用不再查询数据库
+        cached_names(key.clone(), load).unwrap();
+        let names = cached_names(key.clone(), load).unwrap();
+        assert_eq!(names, vec!["orders".to_string(), "users".to_string()]);
+        assert_eq!(queries.get(), 1);
+
+        invalidate_metadata_cache(&profile);
+        cached_names(key.clone(), load).unwrap();
+        assert_eq!(queries.get(), 2);
+
+        // 连接池执行 DDL 按连接键清除，前端全局刷新清除全部
+        invalidate_metadata_cache_for_key(&key.0);
+        cached_names(key.clone(), load).unwrap();
+        assert_eq!(queries.get(), 3);
+        clear_metadata_cache();
+        cached_names(key, load).unwrap();
+        assert_eq!(queries.get(), 4);
+    }
+
+    #[test]
+    fn storage_breakdown_sums_sizes_and_sorts_descending() {
+        let rows = vec![
+            ("small".to_string(), Some(512), Some(0), None),
+            (
+                "large".to_string(),
+                Some(1024 * 1024),
+                Some(512 * 1024),
+                Some(0),
+            ),
+            ("medium".to_string(), Some(2048), None, Some(1024)),
+        ];
+
+        let tables = build_storage_breakdown(rows);
+        let summary: Vec<(&str, u64, &str)> = tables
+            .iter()
+            .map(|t| (t.table.as_str(), t.total, t.total_display.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("large", 1536 * 1024, "1.5 MB"),
+                ("medium", 3072, "3.0 KB"),
+                ("small", 512, "512 B"),
+            ]
+        );
+        assert_eq!(tables[1].data_free, 1024);
+    }
+
+    fn row_values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn single_primary_key_locator_uses_bound_value() {
+        let columns = vec![("id".to_string(), true), ("name".to_string(), false)];
+        let row = row_values(&[("id", "7"), ("name", "O'Brien")]);
+
+        let locator = locate_row("shop", "users", &columns, &row).unwrap();
+        assert_eq!(locator.locator, "`shop`.`users` WHERE `id` = '7'");
+        assert!(locator.warnings.is_empty());
+
+        let (sql, params) = build_row_lookup_query("shop", "users", &locator.keys).unwrap();
+        assert_eq!(sql, "SELECT * FROM `shop`.`users` WHERE `id` = ? LIMIT 1");
+        assert_eq!(params, vec![Value::from("7")]);
+    }
+
+    #[test]
+    fn composite_primary_key_locator_keeps_key_order() {
+        let columns = vec![
+            ("order_id".to_string(), true),
+            ("line".to_string(), true),
+            ("sku".to_string(), false),
+        ];
+        let row = row_values(&[("order_id", "42"), ("line", "1' OR '1'='1"), ("sku", "A")]);
+
+        let locator = locate_row("shop", "order_lines", &columns, &row).unwrap();
+        assert_eq!(
+            locator.locator,
+            "`shop`.`order_lines` WHERE `order_id` = '42' AND `line` = '1'' OR ''1''=''1'"
+        );
+        let (sql, params) = build_row_lookup_query("shop", "order_lines", &locator.keys).unwrap();
+        assert_eq!(
+            sql,
+            "SELECT * FROM `shop`.`order_lines` WHERE `order_id` = ? AND `line` = ? LIMIT 1"
+        );
+        assert_eq!(params, vec![Value::from("42"), Value::from("1' OR '1'='1")]);
+
+        let missing = row_values(&[("order_id", "42")]);
+        assert_eq!(
+            locate_row("shop", "order_lines", &columns, &missing)
+                .err()
+                .as_deref(),
+            Some("Row is missing primary key column: line")
+        );
+    }
+
+    #[test]
+    fn large_tables_are_sampled_with_bounded_ratio() {
+        assert_eq!(
+            build_sample_rows_sql("shop", "events", 100, 10_000_000),
+            "SELECT * FROM `shop`.`events` WHERE RAND() < 0.00003000 LIMIT 100"
+        );
+        // 小表直接 ORDER BY RAND()
+        assert_eq!(
+            build_sample_rows_sql("shop", "tiny", 100, 500),
+            "SELECT * FROM `shop`.`tiny` ORDER BY RAND() LIMIT 100"
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn server_overview_counts_tables_per_schema() {
+        let profile = mysql_profile();
+        let populated = fresh_schema(&profile, "overview_populated");
+        let empty = fresh_schema(&profile, "overview_empty");
+        run_sql(
+            &profile,
+            &[
+                &format!("CREATE TABLE `{populated}`.`a` (id INT)"),
+                &format!("CREATE TABLE `{populated}`.`b` (id INT)"),
+                &format!("CREATE VIEW `{populated}`.`v` AS SELECT 1 AS x"),
+            ],
+        );
+
+        let overview = get_server_overview(&profile);
+        run_sql(
+            &profile,
+            &[
+                &format!("DROP DATABASE `{populated}`"),
+                &format!("DROP DATABASE `{empty}`"),
+            ],
+        );
+
+        let overview = overview.unwrap();
+        let count = |name: &str| {
+            overview
+                .databases
+                .iter()
+                .find(|db| db.name == name)
+                .map(|db| db.table_count)
+        };
+        // 视图不计入表数量，没有表的库也要出现
+        assert_eq!(count(&populated), Some(2));
+        assert_eq!(count(&empty), Some(0));
+    }
+
+    fn routine_param(
+        name: &str,
+        param_type: &str,
+        mode: &str,
+        charset: Option<&str>,
+    ) -> RoutineParam {
+        RoutineParam {
+            name: name.to_string(),
+            param_type: param_type.to_string(),
+            mode: Some(mode.to_string()),
+            character_set: charset.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn reconstructs_procedure_signature_with_inout_and_deterministic() {
+        let detail = RoutineDetail {
+            name: "bump".to_string(),
+            routine_type: "PROCEDURE".to_string(),
+            return_type: None,
+            return_character_set: None,
+            params: vec![
+                routine_param("counter", "int", "INOUT", None),
+                routine_param("label", "varchar(20)", "IN", Some("utf8mb4")),
+            ],
+            deterministic: true,
+            sql_data_access: Some("MODIFIES SQL DATA".to_string()),
+            security_type: Some("INVOKER".to_string()),
+            comment: Some("it's fine".to_string()),
+        };
+        assert_eq!(
+            generate_routine_header_sql(&detail),
+            "CREATE PROCEDURE `bump`(INOUT `counter` int, IN `label` varchar(20) CHARSET utf8mb4)\n    \
+             DETERMINISTIC\n    \
+             MODIFIES SQL DATA\n    \
+             SQL SECURITY INVOKER\n    \
+             COMMENT 'it''s fine'"
+        );
+    }
+
+    #[test]
+    fn index_listing_orders_columns_by_sequence_unless_name_requested() {
+        let ordinal = index_list_sql(ListOrder::Ordinal);
+        assert!(ordinal.contains("GROUP_CONCAT(COLUMN_NAME ORDER BY SEQ_IN_INDEX)"));
+        assert!(ordinal.ends_with("ORDER BY INDEX_NAME <> 'PRIMARY', INDEX_NAME"));
+        let by_name = index_list_sql(ListOrder::Name);
+        assert!(by_name.contains("GROUP_CONCAT(COLUMN_NAME ORDER BY COLUMN_NAME)"));
+        assert!(foreign_key_list_sql(ListOrder::Ordinal)
+            .ends_with("ORDER BY CONSTRAINT_NAME, ORDINAL_POSITION"));
+        assert_eq!(
+            serde_json::from_str::<ListOrder>("\"name\"").unwrap(),
+            ListOrder::Name
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn composite_index_columns_keep_sequence_order() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "index_order");
+        run_sql(
+            &profile,
+            &[&format!(
+                "CREATE TABLE `{schema}`.`t` (a INT, b INT, c INT, KEY `idx_cba` (c, b, a))"
+            )],
+        );
+
+        let ordinal = list_indexes(&profile, &schema, "t", ListOrder::Ordinal);
+        let by_name = list_indexes(&profile, &schema, "t", ListOrder::Name);
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert_eq!(ordinal.unwrap()[0]["COLUMNS"], "c,b,a");
+        assert_eq!(by_name.unwrap()[0]["COLUMNS"], "a,b,c");
+    }
+
+    fn privilege_summary(
+        username: &str,
+        server: &[&str],
+        scopes: &[(&str, &[&str])],
+    ) -> UserPrivilegeSummary {
+        UserPrivilegeSummary {
+            username: username.to_string(),
+            host: "%".to_string(),
+            server_privileges: server.iter().map(|p| p.to_string()).collect(),
+            database_privileges: scopes
+                .iter()
+                .map(|(scope, privs)| {
+                    (
+                        scope.to_string(),
+                        privs.iter().map(|p| p.to_string()).collect(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn privilege_filter_keeps_only_holders() {
+        let holders = |privilege: &str, database: Option<&str>| -> Vec<String> {
+            [
+                privilege_summary("admin", &["Super", "Process"], &[]),
+                privilege_summary("app", &[], &[("shop", &["Select", "Insert"])]),
+                privilege_summary("report", &[], &[("shop.orders", &["Select"])]),
+            ]
+            .into_iter()
+            .filter_map(|user| apply_priv_filter(user, Some(privilege), database))
+            .map(|user| user.username)
+            .collect()
+        };
+
+        assert_eq!(holders("SUPER", None), ["admin"]);
+        assert_eq!(holders("select", None), ["app", "report"]);
+        assert_eq!(holders("INSERT", Some("shop")), ["app"]);
+        assert!(holders("DROP", None).is_empty());
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn audit_filtered_by_privilege_returns_only_holders() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "audit");
+        run_sql(
+            &profile,
+            &[
+                "DROP USER IF EXISTS 'dbw_audit_ro'@'%', 'dbw_audit_rw'@'%'",
+                "CREATE USER 'dbw_audit_ro'@'%' IDENTIFIED BY 'pw'",
+                "CREATE USER 'dbw_audit_rw'@'%' IDENTIFIED BY 'pw'",
+                &format!("GRANT SELECT ON `{schema}`.* TO 'dbw_audit_ro'@'%'"),
+                &format!("GRANT SELECT, DELETE ON `{schema}`.* TO 'dbw_audit_rw'@'%'"),
+            ],
+        );
+
+        let result = audit_privileges(
+            &profile,
+            Some(PrivFilter {
+                privilege: Some("DELETE".to_string()),
+                database: Some(schema.clone()),
+            }),
+        );
+        run_sql(
+            &profile,
+            &[
+                "DROP USER 'dbw_audit_ro'@'%', 'dbw_audit_rw'@'%'",
+                &format!("DROP DATABASE `{schema}`"),
+            ],
+        );
+
+        let users: Vec<String> = result
+            .unwrap()
+            .into_iter()
+            .map(|s| s.username)
+            .filter(|u| u.starts_with("dbw_audit_"))
+            .collect();
+        assert_eq!(users, ["dbw_audit_rw"]);
+    }
+
+    #[test]
+    fn unused_index_query_skips_primary_and_used_indexes() {
+        assert!(UNUSED_INDEX_SQL
+            .contains("FROM performance_schema.table_io_waits_summary_by_index_usage u"));
+        assert!(UNUSED_INDEX_SQL.contains("u.OBJECT_SCHEMA = :schema"));
+        assert!(UNUSED_INDEX_SQL.contains("u.INDEX_NAME IS NOT NULL"));
+        assert!(UNUSED_INDEX_SQL.contains("u.INDEX_NAME <> 'PRIMARY'"));
+        assert!(UNUSED_INDEX_SQL.contains("u.COUNT_STAR = 0"));
+    }
+
+    fn server_error(code: u16) -> mysql::Error {
+        mysql::Error::MySqlError(mysql::MySqlError {
+            state: "42S02".to_string(),
+            message: "denied".to_string(),
+            code,
+        })
+    }
+
+    #[test]
+    fn unavailable_performance_schema_reports_how_to_enable_it() {
+        for code in [1142, 1146, 1049] {
+            assert_eq!(
+                unused_index_query_error(&server_error(code)),
+                PERFORMANCE_SCHEMA_DISABLED
+            );
+        }
+        assert!(unused_index_query_error(&server_error(1064)).starts_with("Query failed: "));
+    }
+
+    fn fk_edge(from: &str, to: &str) -> FkEdge {
+        FkEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            constraint_name: format!("fk_{from}_{to}"),
+            columns: vec![format!("{to}_id")],
+            referenced_columns: vec!["id".to_string()],
+        }
+    }
+
+    #[test]
+    fn fk_cycles_report_two_table_cycle_but_not_self_reference() {
+        let nodes: Vec<String> = ["customers", "employees", "orders", "regions"]
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+        let edges = vec![
+            fk_edge("orders", "customers"),
+            fk_edge("customers", "orders"),
+            fk_edge("employees", "employees"),
+            fk_edge("employees", "regions"),
+        ];
+
+        let cycles = find_fk_cycles(&nodes, &edges);
+        assert_eq!(cycles, vec![vec!["customers", "orders"]]);
+    }
+
+    fn model_column(name: &str, column_type: &str) -> ColumnModel {
+        ColumnModel {
+            name: name.to_string(),
+            original_name: None,
+            column_type: column_type.to_string(),
+            nullable: true,
+            default_value: None,
+            auto_increment: false,
+            on_update: None,
+            charset: None,
+            collation: None,
+            comment: String::new(),
+        }
+    }
+
+    #[test]
+    fn narrowing_varchar_produces_modify_and_data_loss_warning() {
+        let current = TableModel {
+            columns: vec![
+                model_column("id", "int"),
+                model_column("name", "varchar(64)"),
+            ],
+            ..Default::default()
+        };
+        let proposed = TableModel {
+            columns: vec![
+                model_column("id", "int"),
+                model_column("name", "varchar(16)"),
+            ],
+            ..Default::default()
+        };
+
+        let preview = diff_table_models("shop", "users", &current, &proposed);
+        assert_eq!(
+            preview.sql,
+            "ALTER TABLE `shop`.`users` MODIFY COLUMN `name` varchar(16) NULL;"
+        );
+        assert_eq!(preview.warnings.len(), 1);
+        assert!(
+            preview.warnings[0].starts_with(
+                "Changing column `name` from varchar(64) to varchar(16) may lose data: "
+            ),
+            "{}",
+            preview.warnings[0]
+        );
+
+        let widened = TableModel {
+            columns: vec![
+                model_column("id", "int"),
+                model_column("name", "varchar(255)"),
+            ],
+            ..Default::default()
+        };
+        assert!(diff_table_models("shop", "users", &current, &widened)
+            .warnings
+            .is_empty());
+    }
+}
@@ -1,6 +1,9 @@
 pub mod app_config;
+pub mod audit;
 pub mod backup;
+pub mod bundle;
 pub mod config;
+pub mod convert;
 pub mod executor;
 pub mod export;
 pub mod favorites;
@@ -9,5 +12,8 @@ pub mod json;
 pub mod metadata;
 pub mod models;
 pub mod pool;
+pub mod spatial;
 pub mod sqlutils;
 pub mod ssl;
+#[cfg(test)]
+mod test_support;
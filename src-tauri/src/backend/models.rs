@@ -14,8 +14,12 @@ pub struct ConnectionProfile {
     pub timeout: Option<u64>, // 空闲超时（wait_timeout），默认 28800 秒（8小时）
     #[serde(rename = "connectionTimeout")]
     pub connection_timeout: Option<u64>, // 连接超时，默认 30 秒
+    #[serde(rename = "readTimeout")]
+    pub read_timeout: Option<u64>, // 读取超时（秒），默认不限制
     #[serde(rename = "autoReconnect")]
     pub auto_reconnect: Option<bool>, // 自动重连，默认 None（使用全局设置）
+    #[serde(rename = "keepaliveInterval")]
+    pub keepalive_interval: Option<u64>, // 心跳间隔（秒），默认 30 秒
     pub ssl: Option<bool>,
     #[serde(rename = "sslMode")]
     pub ssl_mode: Option<String>,
@@ -25,6 +29,24 @@ pub struct ConnectionProfile {
     pub ssl_cert_path: Option<String>,
     #[serde(rename = "sslKeyPath")]
     pub ssl_key_path: Option<String>,
+    #[serde(rename = "defaultRowLimit")]
+    pub default_row_limit: Option<u64>, // 无 LIMIT 的 SELECT 默认最多返回的行数
+    #[serde(rename = "initSql", default)]
+    pub init_sql: Vec<String>, // 用户自定义会话初始化语句，在内置语句之后执行
+    #[serde(
+        rename = "envTemplates",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub env_templates: BTreeMap<String, String>, // 字段 -> 加载时展开前的 ${VAR} 模板，保存时写回
+}
+
+/// 命令中引用连接的方式：完整配置，或已保存连接的名称（由后端从本地存储解析）
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ProfileRef {
+    Inline(Box<ConnectionProfile>),
+    Named(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -40,6 +62,8 @@ pub struct FavoriteItem {
     pub id: Option<String>,
     pub name: String,
     pub description: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
     #[serde(rename = "type")]
     pub favorite_type: FavoriteType,
     pub content: Option<String>,
@@ -1,4 +1,9 @@
-use crate::backend::models::{ConnectionProfile, SqlParam};
+use crate::backend::app_config;
+use crate::backend::audit;
+use crate::backend::metadata;
+use crate::backend::models::{ConnectionProfile, DbType, SqlParam};
+use crate::backend::spatial::{self, SpatialFormat};
+use crate::backend::sqlutils;
 use crate::backend::ssl::{
     apply_ssl_mode_to_builder, parse_ssl_mode, ssl_mode_to_session_value, SslMode,
 };
@@ -14,7 +19,8 @@ use mysql::{Conn, Opts, OptsBuilder, Params, Value};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 use tokio::runtime::Runtime;
@@ -30,13 +36,26 @@ static TOKIO_RUNTIME: Lazy<Runtime> = Lazy::new(|| {
         .expect("Failed to build tokio runtime")
 });
 
+pub(crate) fn runtime() -> &'static Runtime {
+    &TOKIO_RUNTIME
+}
+
 // NEW: 全局心跳管理器，默认 30 秒间隔（类似 Navicat）
 static KEEPALIVE_MANAGER: Lazy<KeepaliveManager> = Lazy::new(|| KeepaliveManager::new(30));
 
 static CONN_ID_COUNTER: AtomicU64 = AtomicU64::new(1);
 static POOL_ID_COUNTER: AtomicU64 = AtomicU64::new(1);
 const DEFAULT_QUERY_PAGE_SIZE: u64 = 200;
+// 每个连接池最多缓存的分页列元数据条目数，超出后整体清空
+const COLUMN_CACHE_MAX_ENTRIES: usize = 64;
+const ACTIVITY_LOG_CAPACITY: usize = 200;
+const ACTIVITY_DETAIL_MAX_CHARS: usize = 200;
+// 已释放连接的时间线保留个数，释放后仍可查看
+const RELEASED_ACTIVITY_KEEP: usize = 16;
 const MAX_QUERY_PAGE_SIZE: u64 = 2000;
+// 单次查询最多返回给前端的行数，防止异常查询撑爆内存；可通过 app_config 覆盖
+const MAX_RESULT_ROWS_KEY: &str = "query.max_rows";
+const DEFAULT_MAX_RESULT_ROWS: u64 = 100_000;
 // Avoid probing connection health for every statement; probe only after idle window.
 const HEALTH_CHECK_IDLE_THRESHOLD_SECS: u64 = 3;
 
@@ -59,11 +78,15 @@ pub struct PoolConfig {
     pub idle_timeout_ms: Option<u64>,
     pub max_lifetime_ms: Option<u64>,
     pub connection_timeout_ms: Option<u64>,
+    pub read_timeout_secs: Option<u64>,
     pub create_timeout_ms: Option<u64>,
     pub recycle_timeout_ms: Option<u64>,
     pub current_database: Option<String>, // NEW: 跟踪当前数据库
     pub keepalive_interval_secs: Option<u64>, // NEW: 心跳间隔（秒），默认 30
     pub auto_reconnect: bool,             // NEW: 自动重连，默认 false（安全优先）
+    pub default_row_limit: Option<u64>,
+    #[serde(default)]
+    pub init_sql: Vec<String>,
 }
 
 impl PoolConfig {
@@ -87,11 +110,14 @@ impl PoolConfig {
             max_lifetime_ms: Some(1_800_000),
             // MODIFIED: 使用 profile 中的 connection_timeout，默认 30 秒
             connection_timeout_ms: Some(profile.connection_timeout.unwrap_or(30) * 1000),
+            read_timeout_secs: profile.read_timeout.filter(|v| *v > 0),
             create_timeout_ms: None,
             recycle_timeout_ms: None,
             current_database: profile.database.clone(), // NEW: 使用 profile 中的数据库作为初始值
-            keepalive_interval_secs: Some(30),          // NEW: 默认 30 秒心跳间隔（类似 Navicat）
+            keepalive_interval_secs: Some(profile.keepalive_interval.unwrap_or(30)), // NEW: 默认 30 秒心跳间隔（类似 Navicat）
             auto_reconnect: profile.auto_reconnect.unwrap_or(false), // NEW: 默认 false（安全优先）
+            default_row_limit: profile.default_row_limit.filter(|v| *v > 0),
+            init_sql: profile.init_sql.clone(),
         }
     }
 
@@ -131,20 +157,37 @@ impl Manager for MysqlManager {
             }
         };
 
+        self.run_init_sqls(&mut conn)?;
+        Ok(conn)
+    }
+
+    // 归还的连接可能带着 autocommit=0、临时表、会话变量等状态，复用前重置会话
+    // （COM_RESET_CONNECTION，同时回滚未提交事务）并重新执行初始化 SQL
+    async fn recycle(&self, conn: &mut Self::Type, _: &Metrics) -> RecycleResult<Self::Error> {
+        conn.reset().map_err(RecycleError::Backend)?;
+        self.run_init_sqls(conn).map_err(RecycleError::Backend)
+    }
+}
+
+impl MysqlManager {
+    fn run_init_sqls(&self, conn: &mut Conn) -> Result<(), mysql::Error> {
         for sql in &self.init_sqls {
             if let Err(err) = conn.query_drop(sql) {
                 if sql.starts_with("SET SESSION ssl_mode") {
                     continue;
                 }
-                return Err(err);
+                // 附带出错的语句，便于定位用户自定义的初始化 SQL
+                return Err(match err {
+                    mysql::Error::MySqlError(mut server_err) => {
+                        server_err.message =
+                            format!("Init SQL failed: {sql}: {}", server_err.message);
+                        mysql::Error::MySqlError(server_err)
+                    }
+                    other => other,
+                });
             }
         }
-
-        Ok(conn)
-    }
-
-    async fn recycle(&self, conn: &mut Self::Type, _: &Metrics) -> RecycleResult<Self::Error> {
-        conn.query_drop("SELECT 1").map_err(RecycleError::Backend)
+        Ok(())
     }
 }
 
@@ -162,6 +205,10 @@ struct ConnectionState {
     in_transaction: AtomicU64,       // 事务嵌套计数（0表示不在事务中）
     has_temporary_tables: AtomicU64, // 临时表计数
     auto_reconnect: bool,            // 此连接是否启用自动重连
+    // 用户执行过的会话级 SET：(变量名, 原语句)，同名变量只保留最后一次，重连后按序重放
+    session_settings: Vec<(String, String)>,
+    // 连接活动时间线，重连时随状态迁移
+    activity: ActivityLog,
 }
 
 impl ConnectionState {
@@ -180,9 +227,21 @@ impl ConnectionState {
             in_transaction: AtomicU64::new(0),
             has_temporary_tables: AtomicU64::new(0),
             auto_reconnect,
+            session_settings: Vec::new(),
+            activity: ActivityLog::default(),
         }
     }
 
+    fn with_session_settings(mut self, session_settings: Vec<(String, String)>) -> Self {
+        self.session_settings = session_settings;
+        self
+    }
+
+    fn with_activity(mut self, activity: ActivityLog) -> Self {
+        self.activity = activity;
+        self
+    }
+
     // NEW: 记录连接使用
     fn record_use(&self) {
         self.use_count.fetch_add(1, Ordering::SeqCst);
@@ -224,6 +283,11 @@ impl ConnectionState {
             return (false, Some(format!("Active transaction detected (nesting level: {}). Auto-reconnect disabled to prevent data inconsistency.", txn_count)));
         }
 
+        // autocommit 关闭时随时可能有未提交的修改，重连会静默丢弃
+        if self.autocommit_disabled() {
+            return (false, Some("Autocommit is disabled and uncommitted changes may be pending. Auto-reconnect disabled to prevent data inconsistency.".to_string()));
+        }
+
         // 检查是否有临时表
         let temp_table_count = self.has_temporary_tables.load(Ordering::SeqCst);
         if temp_table_count > 0 {
@@ -233,14 +297,19 @@ impl ConnectionState {
         (true, None)
     }
 
-    // NEW: 开始事务（预留方法，用于未来跟踪事务状态）
-    #[allow(dead_code)]
+    fn autocommit_disabled(&self) -> bool {
+        self.session_settings
+            .iter()
+            .find(|(name, _)| name == "autocommit")
+            .is_some_and(|(_, statement)| is_autocommit_off(statement))
+    }
+
+    // 开始事务，计数大于 0 期间禁止自动重连
     fn begin_transaction(&self) {
         self.in_transaction.fetch_add(1, Ordering::SeqCst);
     }
 
-    // NEW: 提交/回滚事务（预留方法，用于未来跟踪事务状态）
-    #[allow(dead_code)]
+    // 提交/回滚事务
     fn end_transaction(&self) {
         let current = self.in_transaction.load(Ordering::SeqCst);
         if current > 0 {
@@ -248,8 +317,7 @@ impl ConnectionState {
         }
     }
 
-    // NEW: 创建临时表（预留方法，用于未来跟踪临时表状态）
-    #[allow(dead_code)]
+    // NEW: 创建临时表，之后禁止自动重连以免临时表丢失
     fn add_temporary_table(&self) {
         self.has_temporary_tables.fetch_add(1, Ordering::SeqCst);
     }
@@ -263,6 +331,26 @@ impl ConnectionState {
         }
     }
 
+    fn diagnostics(&self) -> ConnDiag {
+        let now = Self::now_unix_secs();
+        let (_, reconnect_blocked_reason) = self.can_safely_reconnect();
+        ConnDiag {
+            exists: true,
+            current_database: self.current_database.clone(),
+            session_settings: self
+                .session_settings
+                .iter()
+                .map(|(_, statement)| statement.clone())
+                .collect(),
+            in_transaction: self.in_transaction.load(Ordering::SeqCst),
+            has_temp_tables: self.has_temporary_tables.load(Ordering::SeqCst),
+            use_count: self.use_count.load(Ordering::SeqCst),
+            age_secs: now.saturating_sub(self.created_at),
+            last_used_secs_ago: now.saturating_sub(self.last_used_at.load(Ordering::SeqCst)),
+            reconnect_blocked_reason,
+        }
+    }
+
     #[allow(dead_code)]
     fn get_stats(&self) -> ConnectionUsageStats {
         ConnectionUsageStats {
@@ -340,7 +428,12 @@ impl KeepaliveManager {
                 // 执行心跳检测
                 let should_stop = {
                     if let Some(mut entry) = pool.in_use.get_mut(&conn_id) {
-                        match entry.conn.query_drop("SELECT 1") {
+                        let ping = entry.conn.query_drop("SELECT 1");
+                        match &ping {
+                            Ok(_) => entry.activity.push(ActivityKind::KeepalivePing, "ok"),
+                            Err(err) => entry.activity.push(ActivityKind::KeepalivePing, &err.to_string()),
+                        }
+                        match ping {
                             Ok(_) => {
                                 // 心跳成功，继续
                                 false
@@ -359,26 +452,28 @@ impl KeepaliveManager {
                                     } else {
                                         // 连接已断开，尝试重连并恢复上下文
                                         let current_db = entry.current_database.clone();
+                                        let session_settings = entry.session_settings.clone();
                                         drop(entry); // 释放锁
                                         
-                                        // 移除旧连接
-                                        pool.in_use.remove(&conn_id);
+                                        // 移除旧连接，保留活动时间线
+                                        let activity = pool.in_use.remove(&conn_id).map(|(_, state)| state.activity).unwrap_or_default();
                                         
                                         // 获取新连接
                                         match TOKIO_RUNTIME.block_on(pool.pool.get()) {
                                             Ok(new_conn) => {
-                                                // 恢复数据库上下文
+                                                // 恢复数据库上下文和会话变量
                                                 let mut temp_conn = new_conn;
                                                 let mut restored = true;
-                                                if let Some(ref db) = current_db {
-                                                    if let Err(e) = temp_conn.query_drop(format!("USE `{}`", escape_identifier(db))) {
-                                                        eprintln!("Keepalive: Failed to restore database context '{}': {}", db, e);
-                                                        restored = false;
-                                                    }
+                                                if let Err(e) = restore_session_context(&mut temp_conn, current_db.as_deref(), &session_settings) {
+                                                    eprintln!("Keepalive: {}", e);
+                                                    restored = false;
                                                 }
                                                 
                                                 if restored {
-                                                    let new_state = ConnectionState::new(temp_conn, current_db.clone(), pool.auto_reconnect);
+                                                    let new_state = ConnectionState::new(temp_conn, current_db.clone(), pool.auto_reconnect)
+                                                        .with_session_settings(session_settings)
+                                                        .with_activity(activity);
+                                                    new_state.activity.push(ActivityKind::Reconnect, "keepalive ping failed");
                                                     pool.in_use.insert(conn_id, new_state);
                                                     println!("Keepalive: Connection {} restored successfully", conn_id);
                                                     false // 继续心跳
@@ -450,6 +545,17 @@ struct ConnectionPool {
     pool: Pool<MysqlManager>,
     in_use: DashMap<u64, ConnectionState>, // MODIFIED: 使用 ConnectionState 替代 DeadpoolObject
     auto_reconnect: bool,                  // NEW: 此连接池的自动重连配置
+    default_row_limit: Option<u64>,
+    username: String,
+    connection_key: String,
+    result_format: RwLock<ResultFormat>,
+    column_cache: DashMap<(u64, String), Arc<CachedColumns>>, // 分页查询的列元数据，按 (conn_id, sql) 缓存
+    released_activity: std::sync::Mutex<VecDeque<(u64, Vec<ActivityEvent>)>>,
+}
+
+struct CachedColumns {
+    columns: Vec<ColumnMeta>,
+    type_hints: Vec<(String, u8, bool)>,
 }
 
 impl ConnectionPool {
@@ -474,6 +580,9 @@ impl ConnectionPool {
         }
 
         builder = builder.prefer_socket(false).stmt_cache_size(250);
+        if let Some(secs) = config.read_timeout_secs {
+            builder = builder.read_timeout(Some(Duration::from_secs(secs)));
+        }
 
         let ssl_mode = parse_ssl_mode(config.ssl_mode.as_deref());
         let fallback_opts = if matches!(ssl_mode, SslMode::Preferred) {
@@ -495,12 +604,17 @@ impl ConnectionPool {
                 collation: config.collation.clone(),
                 timeout: config.timeout_seconds,
                 connection_timeout: config.connection_timeout_ms.map(|ms| ms / 1000),
+                read_timeout: config.read_timeout_secs,
                 auto_reconnect: Some(config.auto_reconnect), // NEW: 传递自动重连配置
+                keepalive_interval: config.keepalive_interval_secs,
                 ssl: None,
                 ssl_mode: config.ssl_mode.clone(),
                 ssl_ca_path: config.ssl_ca_path.clone(),
                 ssl_cert_path: config.ssl_cert_path.clone(),
                 ssl_key_path: config.ssl_key_path.clone(),
+                default_row_limit: config.default_row_limit,
+                init_sql: config.init_sql.clone(),
+                env_templates: Default::default(),
             },
         )?;
 
@@ -556,9 +670,95 @@ impl ConnectionPool {
             pool,
             in_use: DashMap::new(),
             auto_reconnect: config.auto_reconnect, // NEW: 保存自动重连配置
+            default_row_limit: config.default_row_limit,
+            username: config.username.clone(),
+            connection_key: config.connection_key(),
+            result_format: RwLock::new(ResultFormat::default()),
+            column_cache: DashMap::new(),
+            released_activity: std::sync::Mutex::new(VecDeque::new()),
         })
     }
 
+    fn result_format(&self) -> ResultFormat {
+        self.result_format
+            .read()
+            .map(|format| *format)
+            .unwrap_or_default()
+    }
+
+    fn cached_columns(&self, conn_id: u64, sql: &str) -> Option<Arc<CachedColumns>> {
+        self.column_cache
+            .get(&(conn_id, sql.to_string()))
+            .map(|entry| Arc::clone(entry.value()))
+    }
+
+    fn cache_columns(&self, conn_id: u64, sql: &str, cached: Arc<CachedColumns>) {
+        if self.column_cache.len() >= COLUMN_CACHE_MAX_ENTRIES {
+            self.column_cache.clear();
+        }
+        self.column_cache.insert((conn_id, sql.to_string()), cached);
+    }
+
+    fn invalidate_column_cache(&self, conn_id: u64) {
+        self.column_cache.retain(|(id, _), _| *id != conn_id);
+    }
+
+    // 非只读语句可能改变表结构，丢弃该连接的列缓存
+    fn note_statement(&self, conn_id: u64, sql: &str) {
+        if !is_read_only_sql(sql) {
+            self.invalidate_column_cache(conn_id);
+        }
+    }
+
+    // 用户执行的 USE / 会话级 SET 语句成功后同步跟踪，保证重连时恢复正确的库和会话变量
+    fn track_session_statements(&self, conn_id: u64, sql: &str) {
+        let statements = sqlutils::split_sql_statements(sql, DbType::Mysql);
+        let database = statements
+            .iter()
+            .rev()
+            .find_map(|statement| parse_use_database(statement));
+        let settings: Vec<(String, String)> = statements
+            .iter()
+            .flat_map(|statement| parse_session_settings(statement))
+            .collect();
+        let transaction = statements
+            .iter()
+            .rev()
+            .find_map(|statement| transaction_effect(statement));
+        // 建表、删表、改名等会改变对象列表，清掉该连接的元数据缓存
+        if statements
+            .iter()
+            .any(|statement| changes_schema_objects(statement))
+        {
+            metadata::invalidate_metadata_cache_for_key(&self.connection_key);
+        }
+        if database.is_none() && settings.is_empty() && transaction.is_none() {
+            return;
+        }
+        if let Some(mut entry) = self.in_use.get_mut(&conn_id) {
+            if let Some(database) = database.clone() {
+                entry.current_database = Some(database);
+            }
+            // 通过普通执行发出的 BEGIN/COMMIT/ROLLBACK 及隐式提交的 DDL 同步事务计数
+            match transaction {
+                Some(TransactionStep::Begin) => entry.in_transaction.store(1, Ordering::SeqCst),
+                Some(TransactionStep::Commit | TransactionStep::Rollback) => {
+                    entry.in_transaction.store(0, Ordering::SeqCst)
+                }
+                None => {}
+            }
+            for (name, statement) in settings {
+                entry
+                    .session_settings
+                    .retain(|(existing, _)| *existing != name);
+                entry.session_settings.push((name, statement));
+            }
+        }
+        if database.is_some() {
+            self.invalidate_column_cache(conn_id);
+        }
+    }
+
     fn get_connection(&self, initial_database: Option<String>) -> Result<u64, String> {
         let conn = TOKIO_RUNTIME
             .block_on(self.pool.timeout_get(&self.pool.timeouts()))
@@ -566,10 +766,57 @@ impl ConnectionPool {
         let conn_id = CONN_ID_COUNTER.fetch_add(1, Ordering::SeqCst);
         // NEW: 使用 ConnectionState::new 创建连接状态，传入 auto_reconnect 配置
         let state = ConnectionState::new(conn, initial_database, self.auto_reconnect);
+        state.activity.push(
+            ActivityKind::Connect,
+            state.current_database.as_deref().unwrap_or(""),
+        );
         self.in_use.insert(conn_id, state);
         Ok(conn_id)
     }
 
+    fn record_activity(&self, conn_id: u64, kind: ActivityKind, detail: &str) {
+        if let Some(entry) = self.in_use.get(&conn_id) {
+            entry.activity.push(kind, detail);
+        }
+    }
+
+    // 语句类活动：detail 为 SQL（口令字面量已脱敏），失败时附带错误信息；同时写入审计日志（若已开启）
+    fn record_statement<T>(
+        &self,
+        conn_id: u64,
+        kind: ActivityKind,
+        sql: &str,
+        result: &Result<T, String>,
+    ) {
+        let schema = self
+            .in_use
+            .get(&conn_id)
+            .and_then(|entry| entry.current_database.clone());
+        audit::log_statement(
+            &self.username,
+            schema.as_deref(),
+            sql,
+            result.as_ref().err().map(String::as_str),
+        );
+        let sql = audit::scrub_secrets(sql.trim());
+        match result {
+            Ok(_) => self.record_activity(conn_id, kind, &sql),
+            Err(err) => self.record_activity(conn_id, kind, &format!("{sql} -- error: {err}")),
+        }
+    }
+
+    fn activity(&self, conn_id: u64) -> Option<Vec<ActivityEvent>> {
+        if let Some(entry) = self.in_use.get(&conn_id) {
+            return Some(entry.activity.events());
+        }
+        self.released_activity.lock().ok().and_then(|released| {
+            released
+                .iter()
+                .find(|(id, _)| *id == conn_id)
+                .map(|(_, events)| events.clone())
+        })
+    }
+
     // NEW: 获取活跃连接列表
     fn get_active_connections(&self) -> Vec<ActiveConnectionInfo> {
         self.in_use
@@ -584,10 +831,38 @@ impl ConnectionPool {
     }
 
     fn release_connection(&self, conn_id: u64) -> Result<(), String> {
-        if self.in_use.remove(&conn_id).is_some() {
-            Ok(())
-        } else {
-            Err("Connection not found".to_string())
+        self.invalidate_column_cache(conn_id);
+        match self.in_use.remove(&conn_id) {
+            Some((_, mut state)) => {
+                // 未结束的事务（含 autocommit=0 下的修改）会一直持有锁直到连接被复用时重置，
+                // 归还前先回滚；回滚失败则丢弃连接
+                let pending =
+                    state.in_transaction.load(Ordering::SeqCst) > 0 || state.autocommit_disabled();
+                let rollback = pending.then(|| state.conn.query_drop("ROLLBACK"));
+                match &rollback {
+                    Some(Ok(())) => state.activity.push(
+                        ActivityKind::Execute,
+                        "ROLLBACK (transaction open on release)",
+                    ),
+                    Some(Err(err)) => state.activity.push(
+                        ActivityKind::Release,
+                        &format!("ROLLBACK failed, connection discarded: {err}"),
+                    ),
+                    None => {}
+                }
+                state.activity.push(ActivityKind::Release, "");
+                if let Ok(mut released) = self.released_activity.lock() {
+                    if released.len() >= RELEASED_ACTIVITY_KEEP {
+                        released.pop_front();
+                    }
+                    released.push_back((conn_id, state.activity.events()));
+                }
+                if let Some(Err(_)) = rollback {
+                    drop(Object::take(state.conn));
+                }
+                Ok(())
+            }
+            None => Err("Connection not found".to_string()),
         }
     }
 
@@ -597,13 +872,18 @@ impl ConnectionPool {
     {
         // MODIFIED: 先保存当前数据库状态，以便重连后恢复
         // MODIFIED: 获取当前数据库和检查是否可以安全重连
-        let (current_db, can_reconnect, reconnect_reason) = {
+        let (current_db, session_settings, can_reconnect, reconnect_reason) = {
             let entry = self
                 .in_use
                 .get(&conn_id)
                 .ok_or_else(|| "Connection not found".to_string())?;
             let (can_reconnect, reason) = entry.can_safely_reconnect();
-            (entry.current_database.clone(), can_reconnect, reason)
+            (
+                entry.current_database.clone(),
+                entry.session_settings.clone(),
+                can_reconnect,
+                reason,
+            )
         };
 
         let should_reconnect_before_action = {
@@ -646,27 +926,27 @@ impl ConnectionPool {
                 ));
             }
 
-            self.in_use.remove(&conn_id);
+            let activity = self
+                .in_use
+                .remove(&conn_id)
+                .map(|(_, state)| state.activity)
+                .unwrap_or_default();
 
             let new_conn = TOKIO_RUNTIME
                 .block_on(self.pool.timeout_get(&self.pool.timeouts()))
                 .map_err(|e| format!("Connection was stale and reconnection failed: {e}"))?;
 
-            // NEW: 恢复数据库上下文
-            if let Some(ref db) = current_db {
-                let mut temp_conn = new_conn;
-                if let Err(err) = temp_conn.query_drop(format!("USE `{}`", escape_identifier(db))) {
-                    return Err(format!(
-                        "Reconnected but failed to restore database context '{}': {}",
-                        db, err
-                    ));
-                }
-                let state = ConnectionState::new(temp_conn, Some(db.clone()), self.auto_reconnect);
-                self.in_use.insert(conn_id, state);
-            } else {
-                let state = ConnectionState::new(new_conn, None, self.auto_reconnect);
-                self.in_use.insert(conn_id, state);
-            }
+            // NEW: 恢复数据库上下文和会话变量
+            let mut new_conn = new_conn;
+            restore_session_context(&mut new_conn, current_db.as_deref(), &session_settings)?;
+            let state = ConnectionState::new(new_conn, current_db.clone(), self.auto_reconnect)
+                .with_session_settings(session_settings.clone())
+                .with_activity(activity);
+            state.activity.push(
+                ActivityKind::Reconnect,
+                "stale connection detected by health check",
+            );
+            self.in_use.insert(conn_id, state);
         }
 
         let first_error = {
@@ -711,7 +991,11 @@ impl ConnectionPool {
             ));
         }
 
-        self.in_use.remove(&conn_id);
+        let activity = self
+            .in_use
+            .remove(&conn_id)
+            .map(|(_, state)| state.activity)
+            .unwrap_or_default();
 
         let new_conn = TOKIO_RUNTIME
             .block_on(self.pool.timeout_get(&self.pool.timeouts()))
@@ -721,21 +1005,17 @@ impl ConnectionPool {
                 )
             })?;
 
-        // NEW: 恢复数据库上下文（第二次重连）
-        if let Some(ref db) = current_db {
-            let mut temp_conn = new_conn;
-            if let Err(err) = temp_conn.query_drop(format!("USE `{}`", escape_identifier(db))) {
-                return Err(format!(
-                    "Reconnected but failed to restore database context '{}': {}",
-                    db, err
-                ));
-            }
-            let state = ConnectionState::new(temp_conn, Some(db.clone()), self.auto_reconnect);
-            self.in_use.insert(conn_id, state);
-        } else {
-            let state = ConnectionState::new(new_conn, None, self.auto_reconnect);
-            self.in_use.insert(conn_id, state);
-        }
+        // NEW: 恢复数据库上下文和会话变量（第二次重连）
+        let mut new_conn = new_conn;
+        restore_session_context(&mut new_conn, current_db.as_deref(), &session_settings)?;
+        let state = ConnectionState::new(new_conn, current_db, self.auto_reconnect)
+            .with_session_settings(session_settings)
+            .with_activity(activity);
+        state.activity.push(
+            ActivityKind::Reconnect,
+            &format!("after error: {first_error}"),
+        );
+        self.in_use.insert(conn_id, state);
 
         let mut entry = self
             .in_use
@@ -799,6 +1079,29 @@ impl ConnectionPool {
         }
     }
 
+    // 预先建立连接，使空闲连接数达到 target_idle（受 max_size 限制）
+    fn warmup(&self, target_idle: usize) -> Result<(), String> {
+        let status = self.pool.status();
+        let in_use = status.size.saturating_sub(status.available);
+        let capacity = status.max_size.saturating_sub(in_use);
+        let target = target_idle.min(capacity);
+        if target <= status.available {
+            return Ok(());
+        }
+        // 同时持有 target 条：先取出的是已有空闲连接，其余才会新建。
+        // 只取缺少的条数会反复拿回同一批空闲连接，空闲数不会增加
+        let mut warm = Vec::with_capacity(target);
+        for _ in 0..target {
+            let conn = TOKIO_RUNTIME
+                .block_on(self.pool.timeout_get(&self.pool.timeouts()))
+                .map_err(|e| format!("Failed to warm connection: {e}"))?;
+            warm.push(conn);
+        }
+        // 全部归还后成为空闲连接
+        drop(warm);
+        Ok(())
+    }
+
     fn close(&self) {
         self.in_use.clear();
         self.pool.close();
@@ -896,7 +1199,94 @@ pub struct ActiveConnectionInfo {
     pub created_at: u64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ActivityKind {
+    Connect,
+    Query,
+    Execute,
+    Reconnect,
+    KeepalivePing,
+    Release,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityEvent {
+    // Unix 毫秒时间戳
+    pub at: u64,
+    pub kind: ActivityKind,
+    pub detail: String,
+}
+
+// 每个连接只保留最近的活动，超出后丢弃最早的记录
+#[derive(Default)]
+struct ActivityLog(std::sync::Mutex<VecDeque<ActivityEvent>>);
+
+impl ActivityLog {
+    fn push(&self, kind: ActivityKind, detail: &str) {
+        let at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let detail = if detail.chars().count() > ACTIVITY_DETAIL_MAX_CHARS {
+            let mut truncated: String = detail.chars().take(ACTIVITY_DETAIL_MAX_CHARS).collect();
+            truncated.push('…');
+            truncated
+        } else {
+            detail.to_string()
+        };
+        if let Ok(mut events) = self.0.lock() {
+            if events.len() >= ACTIVITY_LOG_CAPACITY {
+                events.pop_front();
+            }
+            events.push_back(ActivityEvent { at, kind, detail });
+        }
+    }
+
+    fn events(&self) -> Vec<ActivityEvent> {
+        self.0
+            .lock()
+            .map(|events| events.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+// 连接诊断信息，用于排查连接为何一直被占用
+#[derive(Debug, Default, Serialize)]
+pub struct ConnDiag {
+    pub exists: bool,
+    pub current_database: Option<String>,
+    // 重连后会重放的会话级 SET 语句
+    pub session_settings: Vec<String>,
+    pub in_transaction: u64,
+    pub has_temp_tables: u64,
+    pub use_count: u64,
+    pub age_secs: u64,
+    pub last_used_secs_ago: u64,
+    pub reconnect_blocked_reason: Option<String>,
+}
+
+// 二进制列（BINARY/VARBINARY/BLOB）在查询结果中的展示方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BinaryDisplay {
+    Utf8Lossy,
+    #[default]
+    Base64,
+    Hex,
+}
+
+// 查询结果中特殊列的展示方式，按连接池设置
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResultFormat {
+    pub binary_display: BinaryDisplay,
+    // 开启后 JSON 列以嵌套 JSON 值而不是字符串返回
+    pub pretty_json: bool,
+    // 设置后空间列以 WKT/GeoJSON 文本返回，否则按二进制展示
+    pub spatial_format: Option<SpatialFormat>,
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct ColumnMeta {
     pub name: String,
     pub label: String,
@@ -909,6 +1299,7 @@ pub struct QueryResult {
     pub rows: Vec<Vec<JsonValue>>,
     pub query_time_secs: f64,
     pub fetch_time_secs: f64,
+    pub truncated: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -933,6 +1324,23 @@ pub struct MultiQueryResult {
     pub fetch_time_secs: f64,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatementKind {
+    Query,
+    Update,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatementOutcome {
+    pub index: usize,
+    pub kind: StatementKind,
+    pub result_set: Option<QueryResult>,
+    pub affected_rows: u64,
+    pub last_insert_id: u64,
+    pub duration_ms: u64,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ExecResult {
     pub affected_rows: u64,
@@ -940,6 +1348,14 @@ pub struct ExecResult {
     pub query_time_secs: f64,
 }
 
+/// 基于 EXPLAIN FORMAT=JSON 的执行前估算，rows 为预计读取的总行数（嵌套循环按前序表的行数放大）
+#[derive(Debug, Default, Serialize)]
+pub struct QueryEstimate {
+    pub estimated_rows: u64,
+    pub estimated_cost: Option<f64>,
+    pub full_table_scan: bool,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ConnectionProperties {
     pub connection_status: bool,
@@ -995,6 +1411,7 @@ pub fn set_connection_database(
         Some(pool) => {
             if let Some(mut entry) = pool.in_use.get_mut(&conn_id) {
                 entry.current_database = database;
+                pool.invalidate_column_cache(conn_id);
                 Ok(())
             } else {
                 Err("Connection not found".to_string())
@@ -1004,6 +1421,94 @@ pub fn set_connection_database(
     }
 }
 
+/// 返回跟踪的当前库；`verify` 时用 `SELECT DATABASE()` 向服务器确认并回写
+pub fn get_current_database(
+    pool_id: u64,
+    conn_id: u64,
+    verify: bool,
+) -> Result<Option<String>, String> {
+    let manager = POOL_MANAGER
+        .read()
+        .map_err(|_| "Pool manager lock failed".to_string())?;
+    let pool = manager
+        .get_pool(pool_id)
+        .ok_or_else(|| "Pool not found".to_string())?;
+    if !verify {
+        return pool
+            .in_use
+            .get(&conn_id)
+            .map(|entry| entry.current_database.clone())
+            .ok_or_else(|| "Connection not found".to_string());
+    }
+    let database: Option<String> = pool.with_connection(conn_id, |conn| {
+        conn.query_first::<Option<String>, _>("SELECT DATABASE()")
+            .map(Option::flatten)
+            .map_err(|e| format!("Query failed: {e}"))
+    })?;
+    if let Some(mut entry) = pool.in_use.get_mut(&conn_id) {
+        if entry.current_database != database {
+            entry.current_database = database.clone();
+            pool.invalidate_column_cache(conn_id);
+        }
+    }
+    Ok(database)
+}
+
+/// 切换连接的 autocommit。状态随会话变量一起跟踪，重连后重放；
+/// 关闭期间视为可能处于事务中，自动重连被禁止
+pub fn set_autocommit(pool_id: u64, conn_id: u64, enabled: bool) -> Result<(), String> {
+    let manager = POOL_MANAGER
+        .read()
+        .map_err(|_| "Pool manager lock failed".to_string())?;
+    let pool = manager
+        .get_pool(pool_id)
+        .ok_or_else(|| "Pool not found".to_string())?;
+    let statement = autocommit_statement(enabled);
+    let result = pool.with_connection(conn_id, |conn| {
+        conn.query_drop(statement)
+            .map_err(|e| format!("Failed to set autocommit: {e}"))
+    });
+    pool.record_statement(conn_id, ActivityKind::Execute, statement, &result);
+    result?;
+    pool.track_session_statements(conn_id, statement);
+    Ok(())
+}
+
+fn autocommit_statement(enabled: bool) -> &'static str {
+    if enabled {
+        "SET autocommit = 1"
+    } else {
+        "SET autocommit = 0"
+    }
+}
+
+/// 连接不在使用列表中时返回 `exists: false`
+pub fn get_connection_diagnostics(pool_id: u64, conn_id: u64) -> Result<ConnDiag, String> {
+    let manager = POOL_MANAGER
+        .read()
+        .map_err(|_| "Pool manager lock failed".to_string())?;
+    match manager.get_pool(pool_id) {
+        Some(pool) => Ok(pool
+            .in_use
+            .get(&conn_id)
+            .map(|entry| entry.diagnostics())
+            .unwrap_or_default()),
+        None => Err("Pool not found".to_string()),
+    }
+}
+
+/// 连接的活动时间线（按时间先后）；释放后的最近若干连接仍可查询
+pub fn get_activity(pool_id: u64, conn_id: u64) -> Result<Vec<ActivityEvent>, String> {
+    let manager = POOL_MANAGER
+        .read()
+        .map_err(|_| "Pool manager lock failed".to_string())?;
+    let pool = manager
+        .get_pool(pool_id)
+        .ok_or_else(|| "Pool not found".to_string())?;
+    pool.activity(conn_id)
+        .ok_or_else(|| "Connection not found".to_string())
+}
+
 pub fn release_connection(pool_id: u64, conn_id: u64) -> Result<bool, String> {
     // NEW: 停止心跳任务
     KEEPALIVE_MANAGER.stop(conn_id);
@@ -1032,6 +1537,10 @@ pub fn test_connection(profile: &ConnectionProfile) -> Result<bool, String> {
         }
     }
 
+    if let Some(secs) = profile.read_timeout.filter(|v| *v > 0) {
+        builder = builder.read_timeout(Some(Duration::from_secs(secs)));
+    }
+
     let fallback_opts = if matches!(ssl_mode, SslMode::Preferred) {
         Some(Opts::from(builder.clone()))
     } else {
@@ -1060,7 +1569,7 @@ pub fn test_connection(profile: &ConnectionProfile) -> Result<bool, String> {
             if sql.starts_with("SET SESSION ssl_mode") {
                 continue;
             }
-            return Err(format!("Connection init failed: {err}"));
+            return Err(format!("Connection init failed: {err}\nStatement: {sql}"));
         }
     }
 
@@ -1077,6 +1586,36 @@ pub fn get_stats(pool_id: u64) -> Result<PoolStats, String> {
     }
 }
 
+pub fn warmup(pool_id: u64, target_idle: usize) -> Result<PoolStats, String> {
+    let manager = POOL_MANAGER
+        .read()
+        .map_err(|_| "Pool manager lock failed".to_string())?;
+    match manager.get_pool(pool_id) {
+        Some(pool) => {
+            pool.warmup(target_idle)?;
+            Ok(pool.get_stats())
+        }
+        None => Err("Pool not found".to_string()),
+    }
+}
+
+/// 调整连接池上限；缩小时多余的空闲连接会被关闭，使用中的连接归还后再回收
+pub fn resize(pool_id: u64, max_size: usize) -> Result<PoolStats, String> {
+    if max_size == 0 {
+        return Err("max_size must be greater than 0".to_string());
+    }
+    let manager = POOL_MANAGER
+        .read()
+        .map_err(|_| "Pool manager lock failed".to_string())?;
+    match manager.get_pool(pool_id) {
+        Some(pool) => {
+            pool.pool.resize(max_size);
+            Ok(pool.get_stats())
+        }
+        None => Err("Pool not found".to_string()),
+    }
+}
+
 pub fn get_detailed_stats(pool_id: u64) -> Result<DetailedPoolStats, String> {
     let manager = POOL_MANAGER
         .read()
@@ -1239,12 +1778,46 @@ pub fn get_connection_properties(
     })
 }
 
-pub fn query(pool_id: u64, conn_id: u64, sql: &str) -> Result<QueryResult, String> {
+/// timeout_ms：SELECT 带 MAX_EXECUTION_TIME 提示，其它语句超时后在另一条连接上 KILL QUERY；
+/// 语句被中断时返回 "Query timed out after N ms"
+pub fn query(
+    pool_id: u64,
+    conn_id: u64,
+    sql: &str,
+    timeout_ms: Option<u64>,
+) -> Result<QueryResult, String> {
     let manager = POOL_MANAGER
         .read()
         .map_err(|_| "Pool manager lock failed".to_string())?;
     match manager.get_pool(pool_id) {
-        Some(pool) => pool.with_connection(conn_id, |conn| execute_query(conn, sql, None)),
+        Some(pool) => {
+            pool.note_statement(conn_id, sql);
+            // SELECT 由服务端按 MAX_EXECUTION_TIME 中断，监视线程兜底其它语句和不支持该提示的服务端
+            let hinted = timeout_ms.and_then(|ms| apply_max_execution_time(sql, ms));
+            let statement = hinted.as_deref().unwrap_or(sql);
+            let result = with_query_timeout(&pool, timeout_ms, |running| {
+                match pool.default_row_limit.and_then(|limit| {
+                    apply_default_row_limit(statement, limit).map(|sql| (limit, sql))
+                }) {
+                    Some((limit, limited_sql)) => pool.with_connection(conn_id, |conn| {
+                        running.store(conn.connection_id(), Ordering::SeqCst);
+                        let mut result =
+                            execute_query(conn, &limited_sql, None, pool.result_format())?;
+                        truncate_to_row_limit(&mut result, limit);
+                        Ok(result)
+                    }),
+                    None => pool.with_connection(conn_id, |conn| {
+                        running.store(conn.connection_id(), Ordering::SeqCst);
+                        execute_query(conn, statement, None, pool.result_format())
+                    }),
+                }
+            });
+            pool.record_statement(conn_id, ActivityKind::Query, sql, &result);
+            if result.is_ok() {
+                pool.track_session_statements(conn_id, sql);
+            }
+            result
+        }
         None => Err("Pool not found".to_string()),
     }
 }
@@ -1261,9 +1834,26 @@ pub fn query_page(
         .read()
         .map_err(|_| "Pool manager lock failed".to_string())?;
     match manager.get_pool(pool_id) {
-        Some(pool) => pool.with_connection(conn_id, |conn| {
-            execute_query_page(conn, sql, page, page_size, include_total)
-        }),
+        Some(pool) => {
+            let cached = pool.cached_columns(conn_id, sql);
+            let outcome = pool.with_connection(conn_id, |conn| {
+                execute_query_page(
+                    conn,
+                    sql,
+                    page,
+                    page_size,
+                    include_total,
+                    pool.result_format(),
+                    cached.clone(),
+                )
+            });
+            pool.record_statement(conn_id, ActivityKind::Query, sql, &outcome);
+            let (result, columns) = outcome?;
+            if !cached.is_some_and(|cached| Arc::ptr_eq(&cached, &columns)) {
+                pool.cache_columns(conn_id, sql, columns);
+            }
+            Ok(result)
+        }
         None => Err("Pool not found".to_string()),
     }
 }
@@ -1273,77 +1863,446 @@ pub fn query_multi(pool_id: u64, conn_id: u64, sql: &str) -> Result<MultiQueryRe
         .read()
         .map_err(|_| "Pool manager lock failed".to_string())?;
     match manager.get_pool(pool_id) {
-        Some(pool) => pool.with_connection(conn_id, |conn| execute_query_multi(conn, sql, None)),
+        Some(pool) => {
+            pool.invalidate_column_cache(conn_id);
+            let result = pool.with_connection(conn_id, |conn| {
+                execute_query_multi(conn, sql, None, pool.result_format())
+            });
+            pool.record_statement(conn_id, ActivityKind::Query, sql, &result);
+            if result.is_ok() {
+                pool.track_session_statements(conn_id, sql);
+            }
+            result
+        }
         None => Err("Pool not found".to_string()),
     }
 }
 
-pub fn query_prepared_multi(
+/// 逐条执行脚本，返回每条语句各自的结果与耗时；遇到错误时中止并标明语句序号
+pub fn query_multi_detailed(
     pool_id: u64,
     conn_id: u64,
     sql: &str,
-    params: Vec<SqlParam>,
-) -> Result<MultiQueryResult, String> {
-    let params = convert_params(params)?;
-    let manager = POOL_MANAGER
-        .read()
-        .map_err(|_| "Pool manager lock failed".to_string())?;
-    match manager.get_pool(pool_id) {
-        Some(pool) => pool.with_connection(conn_id, |conn| {
-            execute_query_multi(conn, sql, Some(params.clone()))
-        }),
-        None => Err("Pool not found".to_string()),
+) -> Result<Vec<StatementOutcome>, String> {
+    let statements = sqlutils::split_sql_statements(sql, DbType::Mysql);
+    if statements.is_empty() {
+        return Err("SQL query cannot be empty".to_string());
     }
-}
-
-pub fn execute(pool_id: u64, conn_id: u64, sql: &str) -> Result<ExecResult, String> {
     let manager = POOL_MANAGER
         .read()
         .map_err(|_| "Pool manager lock failed".to_string())?;
     match manager.get_pool(pool_id) {
-        Some(pool) => pool.with_connection(conn_id, |conn| execute_update(conn, sql, None)),
+        Some(pool) => {
+            pool.invalidate_column_cache(conn_id);
+            let mut outcomes = Vec::with_capacity(statements.len());
+            for (index, statement) in statements.iter().enumerate() {
+                let outcome = pool
+                    .with_connection(conn_id, |conn| {
+                        execute_statement_detailed(conn, index, statement, pool.result_format())
+                    })
+                    .map_err(|e| format!("Statement {} failed: {e}", index + 1));
+                pool.record_statement(conn_id, ActivityKind::Query, statement, &outcome);
+                let outcome = outcome?;
+                pool.track_session_statements(conn_id, statement);
+                outcomes.push(outcome);
+            }
+            Ok(outcomes)
+        }
         None => Err("Pool not found".to_string()),
     }
 }
 
-pub fn query_prepared(
+pub fn query_prepared_multi(
     pool_id: u64,
     conn_id: u64,
     sql: &str,
     params: Vec<SqlParam>,
-) -> Result<QueryResult, String> {
+) -> Result<MultiQueryResult, String> {
     let params = convert_params(params)?;
     let manager = POOL_MANAGER
         .read()
         .map_err(|_| "Pool manager lock failed".to_string())?;
     match manager.get_pool(pool_id) {
-        Some(pool) => pool.with_connection(conn_id, |conn| {
-            execute_query(conn, sql, Some(params.clone()))
-        }),
+        Some(pool) => {
+            pool.invalidate_column_cache(conn_id);
+            let result = pool.with_connection(conn_id, |conn| {
+                execute_query_multi(conn, sql, Some(params.clone()), pool.result_format())
+            });
+            pool.record_statement(conn_id, ActivityKind::Query, sql, &result);
+            result
+        }
         None => Err("Pool not found".to_string()),
     }
 }
 
-pub fn execute_prepared(
+pub fn execute(
     pool_id: u64,
     conn_id: u64,
     sql: &str,
-    params: Vec<SqlParam>,
+    timeout_ms: Option<u64>,
 ) -> Result<ExecResult, String> {
-    let params = convert_params(params)?;
     let manager = POOL_MANAGER
         .read()
         .map_err(|_| "Pool manager lock failed".to_string())?;
     match manager.get_pool(pool_id) {
-        Some(pool) => pool.with_connection(conn_id, |conn| {
-            execute_update(conn, sql, Some(params.clone()))
-        }),
+        Some(pool) => {
+            pool.invalidate_column_cache(conn_id);
+            let result = with_query_timeout(&pool, timeout_ms, |running| {
+                pool.with_connection(conn_id, |conn| {
+                    running.store(conn.connection_id(), Ordering::SeqCst);
+                    execute_update(conn, sql, None)
+                })
+            });
+            pool.record_statement(conn_id, ActivityKind::Execute, sql, &result);
+            if result.is_ok() {
+                pool.track_session_statements(conn_id, sql);
+            }
+            result
+        }
         None => Err("Pool not found".to_string()),
     }
 }
 
-pub fn close_pool(pool_id: u64) {
-    // NEW: 停止所有相关连接的心跳任务
+// 监视线程在超时后通过池中另一条连接 KILL QUERY 正在执行语句的会话。会话 id 由 action
+// 在拿到连接后写入 running，重连后的新会话也能被正确中断。
+// 只有服务端报告语句被中断（1317 被 KILL / 3024 超过 MAX_EXECUTION_TIME）才按超时返回，
+// 恰好在超时边界完成的语句照常返回结果。返回前等待监视线程退出，保证 KILL 不会落到下一条语句上
+fn with_query_timeout<T, F>(
+    pool: &Arc<ConnectionPool>,
+    timeout_ms: Option<u64>,
+    action: F,
+) -> Result<T, String>
+where
+    F: FnOnce(&AtomicU32) -> Result<T, String>,
+{
+    let running = Arc::new(AtomicU32::new(0));
+    let Some(timeout_ms) = timeout_ms.filter(|ms| *ms > 0) else {
+        return action(&running);
+    };
+
+    let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+    let watchdog_pool = Arc::clone(pool);
+    let watchdog_running = Arc::clone(&running);
+    let watchdog = std::thread::spawn(move || {
+        let timeout = std::time::Duration::from_millis(timeout_ms);
+        if done_rx.recv_timeout(timeout) != Err(std::sync::mpsc::RecvTimeoutError::Timeout) {
+            return false;
+        }
+        let thread_id = watchdog_running.load(Ordering::SeqCst);
+        thread_id != 0
+            && watchdog_pool
+                .with_pooled_connection(|conn| {
+                    conn.query_drop(format!("KILL QUERY {thread_id}"))
+                        .map_err(|e| e.to_string())
+                })
+                .is_ok()
+    });
+
+    let result = action(&running);
+    let _ = done_tx.send(());
+    let killed = watchdog.join().unwrap_or(false);
+    match result {
+        Err(err) if (killed && err.contains("ERROR 1317 ")) || err.contains("ERROR 3024 ") => {
+            Err(format!("Query timed out after {timeout_ms} ms"))
+        }
+        result => result,
+    }
+}
+
+// 单条 SELECT 加上 MAX_EXECUTION_TIME 优化器提示（MySQL 5.7.8+，其它服务端当作注释忽略）。
+// 一个查询块只认第一个提示注释，已带提示的语句不改写
+fn apply_max_execution_time(sql: &str, timeout_ms: u64) -> Option<String> {
+    let trimmed = sql.trim_start();
+    let keyword = trimmed.get(..6)?;
+    let rest = &trimmed[6..];
+    if !keyword.eq_ignore_ascii_case("select")
+        || !rest.starts_with(char::is_whitespace)
+        || rest.trim_start().starts_with("/*+")
+        || sqlutils::split_sql_statements(sql, DbType::Mysql).len() != 1
+    {
+        return None;
+    }
+    Some(format!(
+        "SELECT /*+ MAX_EXECUTION_TIME({timeout_ms}) */{rest}"
+    ))
+}
+
+/// 在 conn_id 上显式开启事务，之后的语句直到 commit/rollback 前都不会自动提交
+pub fn begin_transaction(pool_id: u64, conn_id: u64) -> Result<(), String> {
+    run_transaction_statement(pool_id, conn_id, TransactionStep::Begin)
+}
+
+pub fn commit(pool_id: u64, conn_id: u64) -> Result<(), String> {
+    run_transaction_statement(pool_id, conn_id, TransactionStep::Commit)
+}
+
+pub fn rollback(pool_id: u64, conn_id: u64) -> Result<(), String> {
+    run_transaction_statement(pool_id, conn_id, TransactionStep::Rollback)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TransactionStep {
+    Begin,
+    Commit,
+    Rollback,
+}
+
+fn run_transaction_statement(
+    pool_id: u64,
+    conn_id: u64,
+    step: TransactionStep,
+) -> Result<(), String> {
+    let sql = match step {
+        TransactionStep::Begin => "START TRANSACTION",
+        TransactionStep::Commit => "COMMIT",
+        TransactionStep::Rollback => "ROLLBACK",
+    };
+    let manager = POOL_MANAGER
+        .read()
+        .map_err(|_| "Pool manager lock failed".to_string())?;
+    let pool = manager
+        .get_pool(pool_id)
+        .ok_or_else(|| "Pool not found".to_string())?;
+    {
+        let entry = pool
+            .in_use
+            .get(&conn_id)
+            .ok_or_else(|| "Connection not found".to_string())?;
+        let active = entry.in_transaction.load(Ordering::SeqCst) > 0;
+        // START TRANSACTION 会隐式提交当前事务，不允许嵌套
+        if step == TransactionStep::Begin && active {
+            return Err("A transaction is already active on this connection".to_string());
+        }
+        if step != TransactionStep::Begin && !active {
+            return Err("No active transaction on this connection".to_string());
+        }
+    }
+
+    let result = pool.with_connection(conn_id, |conn| {
+        conn.query_drop(sql)
+            .map_err(|e| format!("{sql} failed: {e}"))
+    });
+    pool.record_statement(conn_id, ActivityKind::Execute, sql, &result);
+    if let Some(entry) = pool.in_use.get(&conn_id) {
+        match step {
+            TransactionStep::Begin if result.is_ok() => entry.begin_transaction(),
+            TransactionStep::Begin => {}
+            // 提交失败时事务仍可回滚；回滚失败通常是连接已断开，服务端已丢弃事务
+            TransactionStep::Commit if result.is_err() => {}
+            TransactionStep::Commit | TransactionStep::Rollback => entry.end_transaction(),
+        }
+    }
+    result
+}
+
+pub fn query_prepared(
+    pool_id: u64,
+    conn_id: u64,
+    sql: &str,
+    params: Vec<SqlParam>,
+) -> Result<QueryResult, String> {
+    let params = convert_params(params)?;
+    let manager = POOL_MANAGER
+        .read()
+        .map_err(|_| "Pool manager lock failed".to_string())?;
+    match manager.get_pool(pool_id) {
+        Some(pool) => {
+            pool.note_statement(conn_id, sql);
+            let result = pool.with_connection(conn_id, |conn| {
+                execute_query(conn, sql, Some(params.clone()), pool.result_format())
+            });
+            pool.record_statement(conn_id, ActivityKind::Query, sql, &result);
+            result
+        }
+        None => Err("Pool not found".to_string()),
+    }
+}
+
+pub fn query_prepared_named(
+    pool_id: u64,
+    conn_id: u64,
+    sql: &str,
+    params: HashMap<String, SqlParam>,
+) -> Result<QueryResult, String> {
+    let params = convert_named_params(params)?;
+    let manager = POOL_MANAGER
+        .read()
+        .map_err(|_| "Pool manager lock failed".to_string())?;
+    match manager.get_pool(pool_id) {
+        Some(pool) => {
+            pool.note_statement(conn_id, sql);
+            let result = pool.with_connection(conn_id, |conn| {
+                execute_query_params(
+                    conn,
+                    sql,
+                    Params::Named(params.clone()),
+                    pool.result_format(),
+                    max_result_rows(),
+                )
+            });
+            pool.record_statement(conn_id, ActivityKind::Query, sql, &result);
+            result
+        }
+        None => Err("Pool not found".to_string()),
+    }
+}
+
+pub fn execute_prepared(
+    pool_id: u64,
+    conn_id: u64,
+    sql: &str,
+    params: Vec<SqlParam>,
+) -> Result<ExecResult, String> {
+    let params = convert_params(params)?;
+    let manager = POOL_MANAGER
+        .read()
+        .map_err(|_| "Pool manager lock failed".to_string())?;
+    match manager.get_pool(pool_id) {
+        Some(pool) => {
+            pool.invalidate_column_cache(conn_id);
+            let result = pool.with_connection(conn_id, |conn| {
+                execute_update(conn, sql, Some(params.clone()))
+            });
+            pool.record_statement(conn_id, ActivityKind::Execute, sql, &result);
+            result
+        }
+        None => Err("Pool not found".to_string()),
+    }
+}
+
+/// 执行前估算 SELECT 的代价与扫描行数，供界面在大查询前提示确认
+pub fn estimate(pool_id: u64, conn_id: u64, sql: &str) -> Result<QueryEstimate, String> {
+    let normalized = normalize_query_sql(sql)?;
+    let lowered = normalized
+        .trim_start_matches(|c: char| c.is_whitespace() || c == '(')
+        .to_ascii_lowercase();
+    if !(lowered.starts_with("select") || lowered.starts_with("with")) {
+        return Err("Only SELECT statements can be estimated".to_string());
+    }
+
+    let explain_sql = format!("EXPLAIN FORMAT=JSON {normalized}");
+    let plan: Option<String> = with_connection(pool_id, conn_id, |conn| {
+        conn.query_first(&explain_sql)
+            .map_err(|e| format!("Explain failed: {e}"))
+    })?;
+    let plan = plan.ok_or_else(|| "Explain returned no plan".to_string())?;
+    parse_explain_estimate(&plan)
+}
+
+/// 将查询结果保存为新表（`CREATE [TEMPORARY] TABLE ... AS SELECT`）
+pub fn result_to_table(
+    pool_id: u64,
+    conn_id: u64,
+    select_sql: &str,
+    target_schema: Option<&str>,
+    target_table: &str,
+    temporary: bool,
+) -> Result<ExecResult, String> {
+    let sql = build_result_to_table_sql(select_sql, target_schema, target_table, temporary)?;
+    let manager = POOL_MANAGER
+        .read()
+        .map_err(|_| "Pool manager lock failed".to_string())?;
+    match manager.get_pool(pool_id) {
+        Some(pool) => {
+            pool.invalidate_column_cache(conn_id);
+            let result = pool.with_connection(conn_id, |conn| execute_update(conn, &sql, None));
+            pool.record_statement(conn_id, ActivityKind::Execute, &sql, &result);
+            let result = result?;
+            if temporary {
+                if let Some(entry) = pool.in_use.get(&conn_id) {
+                    entry.add_temporary_table();
+                }
+            }
+            Ok(result)
+        }
+        None => Err("Pool not found".to_string()),
+    }
+}
+
+fn build_result_to_table_sql(
+    select_sql: &str,
+    target_schema: Option<&str>,
+    target_table: &str,
+    temporary: bool,
+) -> Result<String, String> {
+    let select_sql = select_sql.trim().trim_end_matches(';').trim();
+    let lowered = select_sql
+        .trim_start_matches('(')
+        .trim_start()
+        .to_ascii_lowercase();
+    if !(lowered.starts_with("select") || lowered.starts_with("with")) {
+        return Err("Only SELECT statements can be saved as a table".to_string());
+    }
+    if sqlutils::split_sql_statements(select_sql, DbType::Mysql).len() > 1 {
+        return Err("Only a single SELECT statement can be saved as a table".to_string());
+    }
+    let target_table = target_table.trim();
+    if target_table.is_empty() {
+        return Err("Target table name is empty".to_string());
+    }
+    let target = match target_schema.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(schema) => format!(
+            "`{}`.`{}`",
+            escape_identifier(schema),
+            escape_identifier(target_table)
+        ),
+        None => format!("`{}`", escape_identifier(target_table)),
+    };
+    let keyword = if temporary {
+        "CREATE TEMPORARY TABLE"
+    } else {
+        "CREATE TABLE"
+    };
+    Ok(format!("{keyword} {target} AS {select_sql}"))
+}
+
+pub fn set_binary_display(pool_id: u64, mode: BinaryDisplay) -> Result<(), String> {
+    let manager = POOL_MANAGER
+        .read()
+        .map_err(|_| "Pool manager lock failed".to_string())?;
+    let pool = manager
+        .get_pool(pool_id)
+        .ok_or_else(|| "Pool not found".to_string())?;
+    let mut current = pool
+        .result_format
+        .write()
+        .map_err(|_| "Result format lock failed".to_string())?;
+    current.binary_display = mode;
+    Ok(())
+}
+
+pub fn set_pretty_json(pool_id: u64, enabled: bool) -> Result<(), String> {
+    let manager = POOL_MANAGER
+        .read()
+        .map_err(|_| "Pool manager lock failed".to_string())?;
+    let pool = manager
+        .get_pool(pool_id)
+        .ok_or_else(|| "Pool not found".to_string())?;
+    let mut current = pool
+        .result_format
+        .write()
+        .map_err(|_| "Result format lock failed".to_string())?;
+    current.pretty_json = enabled;
+    Ok(())
+}
+
+pub fn set_spatial_format(pool_id: u64, format: Option<SpatialFormat>) -> Result<(), String> {
+    let manager = POOL_MANAGER
+        .read()
+        .map_err(|_| "Pool manager lock failed".to_string())?;
+    let pool = manager
+        .get_pool(pool_id)
+        .ok_or_else(|| "Pool not found".to_string())?;
+    let mut current = pool
+        .result_format
+        .write()
+        .map_err(|_| "Result format lock failed".to_string())?;
+    current.spatial_format = format;
+    Ok(())
+}
+
+pub fn close_pool(pool_id: u64) {
+    // NEW: 停止所有相关连接的心跳任务
     if let Ok(manager) = POOL_MANAGER.read() {
         if let Some(pool) = manager.get_pool(pool_id) {
             // 获取所有活跃连接的 ID 并停止它们的心跳
@@ -1378,6 +2337,20 @@ pub fn get_or_create_pool(profile: &ConnectionProfile) -> Result<u64, String> {
     manager.get_or_create_pool(config)
 }
 
+/// 在已借出的连接上执行操作，连接丢失时按该连接的重连策略恢复
+pub fn with_connection<T, F>(pool_id: u64, conn_id: u64, action: F) -> Result<T, String>
+where
+    F: FnMut(&mut Conn) -> Result<T, String>,
+{
+    let manager = POOL_MANAGER
+        .read()
+        .map_err(|_| "Pool manager lock failed".to_string())?;
+    let pool = manager
+        .get_pool(pool_id)
+        .ok_or_else(|| "Pool not found".to_string())?;
+    pool.with_connection(conn_id, action)
+}
+
 pub fn with_temp_connection<T, F>(profile: &ConnectionProfile, action: F) -> Result<T, String>
 where
     F: FnOnce(&mut Conn) -> Result<T, String>,
@@ -1478,6 +2451,14 @@ fn build_session_init_sql(config: &PoolConfig) -> Vec<String> {
         sqls.push(format!("SET SESSION ssl_mode = '{}'", mode_value));
     }
 
+    sqls.extend(
+        config
+            .init_sql
+            .iter()
+            .map(|sql| sql.trim().trim_end_matches(';').trim().to_string())
+            .filter(|sql| !sql.is_empty()),
+    );
+
     sqls
 }
 
@@ -1521,10 +2502,27 @@ fn derive_timeouts(config: &PoolConfig) -> (Option<u64>, Option<u64>, Option<u64
     )
 }
 
-fn execute_query(
+pub fn execute_query(
     conn: &mut Conn,
     sql: &str,
     params: Option<Vec<Value>>,
+    format: ResultFormat,
+) -> Result<QueryResult, String> {
+    execute_query_params(
+        conn,
+        sql,
+        params.map(Params::Positional).unwrap_or(Params::Empty),
+        format,
+        max_result_rows(),
+    )
+}
+
+fn execute_query_params(
+    conn: &mut Conn,
+    sql: &str,
+    params: Params,
+    format: ResultFormat,
+    max_rows: u64,
 ) -> Result<QueryResult, String> {
     let statement_start = Instant::now();
     let mut result = QueryResult {
@@ -1532,15 +2530,12 @@ fn execute_query(
         rows: Vec::new(),
         query_time_secs: 0.0,
         fetch_time_secs: 0.0,
+        truncated: false,
     };
 
-    let mut rows = if let Some(p) = params {
-        conn.exec_iter(sql, Params::Positional(p))
-            .map_err(|e| format!("Query failed: {e}"))?
-    } else {
-        conn.exec_iter(sql, Params::Empty)
-            .map_err(|e| format!("Query failed: {e}"))?
-    };
+    let mut rows = conn
+        .exec_iter(sql, params)
+        .map_err(|e| format!("Query failed: {e}"))?;
 
     let columns_binding = rows.columns();
     let columns = columns_binding.as_ref();
@@ -1554,18 +2549,23 @@ fn execute_query(
         })
         .collect();
 
-    let column_type_hints: Vec<(String, u8)> = columns
-        .iter()
-        .map(|c| (format!("{:?}", c.column_type()), c.decimals()))
-        .collect();
+    let column_type_hints = build_column_type_hints(columns);
 
     let query_elapsed = statement_start.elapsed().as_secs_f64();
     let fetch_start = Instant::now();
 
     for row in rows.by_ref() {
         let row = row.map_err(|e| format!("Row read failed: {e}"))?;
-        result.rows.push(row_to_json(row, &column_type_hints));
+        if result.rows.len() as u64 >= max_rows {
+            result.truncated = true;
+            break;
+        }
+        result
+            .rows
+            .push(row_to_json(row, &column_type_hints, format));
     }
+    // 提前退出时剩余行由 drop 读完丢弃，连接可继续使用
+    drop(rows);
 
     result.query_time_secs = query_elapsed;
     result.fetch_time_secs = fetch_start.elapsed().as_secs_f64();
@@ -1573,13 +2573,59 @@ fn execute_query(
     Ok(result)
 }
 
+fn max_result_rows() -> u64 {
+    app_config::get_property(MAX_RESULT_ROWS_KEY, "")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(DEFAULT_MAX_RESULT_ROWS)
+}
+
+fn describe_columns(columns: &[mysql::Column]) -> CachedColumns {
+    CachedColumns {
+        columns: columns
+            .iter()
+            .map(|c: &mysql::Column| ColumnMeta {
+                name: c.name_str().to_string(),
+                label: c.name_str().to_string(),
+                type_name: format!("{:?}", c.column_type()),
+            })
+            .collect(),
+        type_hints: build_column_type_hints(columns),
+    }
+}
+
+// 缓存的列名或类型与实际结果不一致时（表结构已被其他会话修改）重新生成
+fn reuse_or_describe_columns(
+    cached: Option<Arc<CachedColumns>>,
+    columns: &[mysql::Column],
+    describe: impl FnOnce(&[mysql::Column]) -> CachedColumns,
+) -> Arc<CachedColumns> {
+    match cached {
+        Some(cached) if cached_columns_match(&cached, columns) => cached,
+        _ => Arc::new(describe(columns)),
+    }
+}
+
+fn cached_columns_match(cached: &CachedColumns, columns: &[mysql::Column]) -> bool {
+    cached.columns.len() == columns.len()
+        && cached
+            .columns
+            .iter()
+            .zip(columns)
+            .all(|(meta, c)| meta.name == c.name_str())
+        && cached.type_hints == build_column_type_hints(columns)
+}
+
 fn execute_query_page(
     conn: &mut Conn,
     sql: &str,
     page: Option<u64>,
     page_size: Option<u64>,
     include_total: Option<bool>,
-) -> Result<QueryPageResult, String> {
+    format: ResultFormat,
+    cached_columns: Option<Arc<CachedColumns>>,
+) -> Result<(QueryPageResult, Arc<CachedColumns>), String> {
     let normalized_sql = normalize_query_sql(sql)?;
     let safe_page = page.unwrap_or(1).max(1);
     let safe_page_size = page_size
@@ -1619,19 +2665,8 @@ fn execute_query_page(
     let columns_binding = rows.columns();
     let columns = columns_binding.as_ref();
 
-    let result_columns: Vec<ColumnMeta> = columns
-        .iter()
-        .map(|c: &mysql::Column| ColumnMeta {
-            name: c.name_str().to_string(),
-            label: c.name_str().to_string(),
-            type_name: format!("{:?}", c.column_type()),
-        })
-        .collect();
-
-    let column_type_hints: Vec<(String, u8)> = columns
-        .iter()
-        .map(|c| (format!("{:?}", c.column_type()), c.decimals()))
-        .collect();
+    let cached_columns = reuse_or_describe_columns(cached_columns, columns, describe_columns);
+    let column_type_hints = &cached_columns.type_hints;
 
     let fetch_start = Instant::now();
     let mut result_rows: Vec<Vec<JsonValue>> = Vec::new();
@@ -1640,7 +2675,7 @@ fn execute_query_page(
     for row in rows.by_ref() {
         let row = row.map_err(|e| format!("Row read failed: {e}"))?;
         if (result_rows.len() as u64) < safe_page_size {
-            result_rows.push(row_to_json(row, &column_type_hints));
+            result_rows.push(row_to_json(row, column_type_hints, format));
         } else {
             has_more = true;
             break;
@@ -1651,17 +2686,20 @@ fn execute_query_page(
     let total_elapsed = started_at.elapsed().as_secs_f64();
     let query_time_secs = (total_elapsed - fetch_time_secs).max(0.0);
 
-    Ok(QueryPageResult {
-        columns: result_columns,
-        rows: result_rows,
-        page: safe_page,
-        page_size: safe_page_size,
-        has_more,
-        total_rows,
-        total_pages,
-        query_time_secs,
-        fetch_time_secs,
-    })
+    Ok((
+        QueryPageResult {
+            columns: cached_columns.columns.clone(),
+            rows: result_rows,
+            page: safe_page,
+            page_size: safe_page_size,
+            has_more,
+            total_rows,
+            total_pages,
+            query_time_secs,
+            fetch_time_secs,
+        },
+        cached_columns,
+    ))
 }
 
 fn build_paged_sql(normalized_sql: &str, offset: u64, fetch_size: u64) -> String {
@@ -1687,43 +2725,467 @@ fn build_paged_sql(normalized_sql: &str, offset: u64, fetch_size: u64) -> String
     }
 }
 
-fn normalize_query_sql(sql: &str) -> Result<String, String> {
-    let mut normalized = sql.trim().trim_end_matches(';').trim().to_string();
-    if normalized.is_empty() {
-        return Err("SQL query cannot be empty".to_string());
+// 解析 `USE db` / ``USE `db` ``，返回库名
+fn parse_use_database(statement: &str) -> Option<String> {
+    let trimmed = statement.trim().trim_end_matches(';').trim();
+    let keyword = trimmed.get(..3)?;
+    if !keyword.eq_ignore_ascii_case("use") {
+        return None;
+    }
+    let rest = &trimmed[3..];
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let name = rest.trim();
+    if let Some(quoted) = name.strip_prefix('`') {
+        let inner = quoted.strip_suffix('`')?;
+        return Some(inner.replace("``", "`")).filter(|db| !db.is_empty());
+    }
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return None;
     }
+    Some(name.to_string())
+}
 
-    // Prevent accidental multi-statements from being wrapped into derived tables.
-    if normalized.contains(';') {
-        return Err("Paginated query only supports a single SQL statement".to_string());
+// 语句对显式事务的影响：START TRANSACTION/BEGIN 开启新事务（同时隐式提交旧事务），
+// COMMIT/ROLLBACK 结束事务，DDL、LOCK TABLES、GRANT 等会隐式提交。临时表的增删不提交
+fn transaction_effect(statement: &str) -> Option<TransactionStep> {
+    let lowered = statement.trim().trim_end_matches(';').to_ascii_lowercase();
+    let words: Vec<&str> = lowered.split_whitespace().collect();
+    match words.as_slice() {
+        ["begin"] | ["begin", "work"] | ["start", "transaction", ..] => {
+            Some(TransactionStep::Begin)
+        }
+        ["commit", ..] => Some(TransactionStep::Commit),
+        ["rollback", rest @ ..] if !rest.contains(&"to") => Some(TransactionStep::Rollback),
+        ["create" | "drop", "temporary", ..] => None,
+        ["alter" | "create" | "drop" | "rename" | "truncate" | "grant" | "revoke", ..]
+        | ["lock", "tables" | "table", ..]
+        | ["analyze" | "optimize" | "repair", ..] => Some(TransactionStep::Commit),
+        _ => None,
     }
+}
 
-    normalized.shrink_to_fit();
-    Ok(normalized)
+// CREATE/ALTER/DROP/RENAME 可能增删或改名库、表、视图、存储过程；临时表不出现在对象列表中
+fn changes_schema_objects(statement: &str) -> bool {
+    let lowered = statement.trim().to_ascii_lowercase();
+    let words: Vec<&str> = lowered.split_whitespace().take(2).collect();
+    match words.as_slice() {
+        ["create" | "drop", "temporary"] => false,
+        ["create" | "alter" | "drop" | "rename", ..] => true,
+        _ => false,
+    }
 }
 
-fn execute_query_multi(
+// 重连后先 USE 原来的库，再按原顺序重放会话级 SET
+fn restore_session_context(
     conn: &mut Conn,
-    sql: &str,
-    params: Option<Vec<Value>>,
-) -> Result<MultiQueryResult, String> {
-    let statement_start = Instant::now();
-    let mut result_sets: Vec<QueryResult> = Vec::new();
+    current_database: Option<&str>,
+    session_settings: &[(String, String)],
+) -> Result<(), String> {
+    if let Some(db) = current_database {
+        conn.query_drop(format!("USE `{}`", escape_identifier(db)))
+            .map_err(|err| {
+                format!(
+                    "Reconnected but failed to restore database context '{}': {}",
+                    db, err
+                )
+            })?;
+    }
+    for (_, statement) in session_settings {
+        conn.query_drop(statement).map_err(|err| {
+            format!(
+                "Reconnected but failed to restore session setting '{}': {}",
+                statement, err
+            )
+        })?;
+    }
+    Ok(())
+}
 
-    // Use exec_iter to get a QueryResult that supports multiple result sets
-    let mut rows = if let Some(p) = params {
-        conn.exec_iter(sql, Params::Positional(p))
-            .map_err(|e| format!("Query failed: {e}"))?
-    } else {
-        conn.exec_iter(sql, Params::Empty)
-            .map_err(|e| format!("Query failed: {e}"))?
+// 识别会话级 SET，按赋值拆成 (变量名, 语句)；单个赋值时保留原语句，多个赋值时每项为 `SET 赋值`。
+// 作用域修饰符沿用到后续未写修饰符的赋值（与 MySQL 一致）。
+// GLOBAL/PERSIST、用户变量 @x、SET TRANSACTION、SET PASSWORD 等不跟踪
+fn parse_session_settings(statement: &str) -> Vec<(String, String)> {
+    let trimmed = statement.trim().trim_end_matches(';').trim();
+    let Some(keyword) = trimmed.get(..3) else {
+        return Vec::new();
     };
+    if !keyword.eq_ignore_ascii_case("set") || !trimmed[3..].starts_with(char::is_whitespace) {
+        return Vec::new();
+    }
+    let body = trimmed[3..].trim_start();
+    let first_word = body
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if matches!(
+        first_word.as_str(),
+        "transaction" | "password" | "role" | "default" | "resource"
+    ) {
+        return Vec::new();
+    }
 
-    // Get affected rows and last insert id before processing result sets
-    // These need to be retrieved before the rows iterator is consumed
-    let affected_rows = rows.affected_rows();
-    let last_insert_id = rows.last_insert_id().unwrap_or(0);
-    let query_elapsed = statement_start.elapsed().as_secs_f64();
+    let assignments = split_set_assignments(body);
+    let single = assignments.len() == 1;
+    let mut session_scope = true;
+    let mut settings = Vec::new();
+    for assignment in assignments {
+        let mut assignment = assignment.trim();
+        let word = assignment
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match word.as_str() {
+            "global" | "persist" | "persist_only" => {
+                session_scope = false;
+                assignment = assignment[word.len()..].trim_start();
+            }
+            "session" | "local" => {
+                session_scope = true;
+                assignment = assignment[word.len()..].trim_start();
+            }
+            _ => {}
+        }
+        let lowered = assignment.to_ascii_lowercase();
+        // 显式 @@ 前缀优先于沿用的作用域；单个 @ 为用户变量
+        let session = if let Some(name) = lowered.strip_prefix("@@") {
+            !name.starts_with("global.")
+                && !name.starts_with("persist.")
+                && !name.starts_with("persist_only.")
+        } else {
+            session_scope && !lowered.starts_with('@')
+        };
+        if !session {
+            continue;
+        }
+
+        let name = match lowered.split_whitespace().next().unwrap_or_default() {
+            "names" | "charset" | "character" => "names",
+            _ => {
+                let Some(name_end) =
+                    lowered.find(|c: char| c == '=' || c == ':' || c.is_whitespace())
+                else {
+                    continue;
+                };
+                lowered[..name_end]
+                    .trim_start_matches("@@session.")
+                    .trim_start_matches("@@local.")
+                    .trim_start_matches("@@")
+            }
+        };
+        if name.is_empty() {
+            continue;
+        }
+        let statement = if single {
+            trimmed.to_string()
+        } else {
+            format!("SET {assignment}")
+        };
+        settings.push((name.to_string(), statement));
+    }
+    settings
+}
+
+// 按顶层逗号拆分 SET 的赋值列表，跳过引号内和括号内的逗号
+fn split_set_assignments(body: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (index, c) in body.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' && q != '`' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '\'' | '"' | '`' => quote = Some(c),
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&body[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&body[start..]);
+    parts
+}
+
+// 解析已跟踪的 `SET autocommit = ...` 语句取值，0/OFF/FALSE 视为关闭
+fn is_autocommit_off(statement: &str) -> bool {
+    let lowered = statement.to_ascii_lowercase();
+    let Some(pos) = lowered.find("autocommit") else {
+        return false;
+    };
+    let value = lowered[pos + "autocommit".len()..]
+        .trim_start()
+        .trim_start_matches(":=")
+        .trim_start_matches('=')
+        .trim_start();
+    let value = value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .next()
+        .unwrap_or_default()
+        .trim_matches(|c| c == '\'' || c == '"');
+    matches!(value, "0" | "off" | "false")
+}
+
+fn is_read_only_sql(sql: &str) -> bool {
+    let lowered = sql
+        .trim_start_matches(|c: char| c.is_whitespace() || c == '(')
+        .to_ascii_lowercase();
+    ["select", "show", "describe", "desc", "explain", "with"]
+        .iter()
+        .any(|keyword| {
+            lowered.starts_with(keyword)
+                && !lowered[keyword.len()..].starts_with(|c: char| c.is_alphanumeric() || c == '_')
+        })
+}
+
+fn parse_explain_estimate(plan: &str) -> Result<QueryEstimate, String> {
+    let plan: JsonValue =
+        serde_json::from_str(plan).map_err(|e| format!("Invalid explain output: {e}"))?;
+    let query_block = plan.get("query_block").unwrap_or(&plan);
+    let mut estimate = QueryEstimate {
+        estimated_cost: query_block
+            .get("cost_info")
+            .and_then(|info| info.get("query_cost"))
+            .or_else(|| query_block.get("cost"))
+            .and_then(explain_number),
+        ..Default::default()
+    };
+    // f64 转 u64 超出范围时饱和，不会溢出
+    estimate.estimated_rows = collect_explain_tables(query_block, &mut estimate) as u64;
+    Ok(estimate)
+}
+
+// 递归遍历计划树中的所有 "table" 节点（嵌套循环、子查询、union 等都会嵌套），返回预计读取的行数。
+// nested_loop 中每张表都要对前序表产出的每一行扫描一次，因此按前面累计的产出行数放大
+fn collect_explain_tables(node: &JsonValue, estimate: &mut QueryEstimate) -> f64 {
+    match node {
+        JsonValue::Object(map) => {
+            let mut rows = 0.0;
+            for (key, value) in map {
+                match (key.as_str(), value) {
+                    ("table", JsonValue::Object(_)) => {
+                        rows += scan_explain_table(value, 1.0, estimate).0
+                    }
+                    ("nested_loop", JsonValue::Array(items)) => {
+                        let mut fanout = 1.0;
+                        for item in items {
+                            match item.get("table").filter(|table| table.is_object()) {
+                                Some(table) => {
+                                    let (examined, produced) =
+                                        scan_explain_table(table, fanout, estimate);
+                                    rows += examined;
+                                    fanout = produced;
+                                }
+                                None => rows += fanout * collect_explain_tables(item, estimate),
+                            }
+                        }
+                    }
+                    _ => rows += collect_explain_tables(value, estimate),
+                }
+            }
+            rows
+        }
+        JsonValue::Array(items) => items
+            .iter()
+            .map(|item| collect_explain_tables(item, estimate))
+            .sum(),
+        _ => 0.0,
+    }
+}
+
+// 返回 (该表在 fanout 次扫描中读取的行数, 连接到该表为止产出的行数)
+fn scan_explain_table(table: &JsonValue, fanout: f64, estimate: &mut QueryEstimate) -> (f64, f64) {
+    // MySQL 为 rows_examined_per_scan，MariaDB 为 rows
+    let per_scan = table
+        .get("rows_examined_per_scan")
+        .or_else(|| table.get("rows"))
+        .and_then(explain_number)
+        .unwrap_or(0.0);
+    // ALL 为全表扫描，index 为全索引扫描，两者都会读遍整张表
+    if matches!(
+        table.get("access_type").and_then(JsonValue::as_str),
+        Some("ALL" | "index")
+    ) {
+        estimate.full_table_scan = true;
+    }
+    // 表节点下挂的子查询、物化表等按独立计划累加
+    let nested = collect_explain_tables(table, estimate);
+    // MySQL 直接给出累计产出行数；MariaDB 没有，按 filtered 百分比推算
+    let produced = table
+        .get("rows_produced_per_join")
+        .and_then(explain_number)
+        .unwrap_or_else(|| {
+            let filtered = table
+                .get("filtered")
+                .and_then(explain_number)
+                .unwrap_or(100.0);
+            fanout * per_scan * filtered / 100.0
+        });
+    (fanout * per_scan + nested, produced)
+}
+
+// MySQL 的 cost_info 数值以字符串输出（如 "12.50"）
+fn explain_number(value: &JsonValue) -> Option<f64> {
+    match value {
+        JsonValue::Number(number) => number.as_f64(),
+        JsonValue::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+// 为不带 LIMIT 的单条 SELECT 追加 LIMIT limit+1，多取的一行用于判断结果是否被截断
+fn apply_default_row_limit(sql: &str, limit: u64) -> Option<String> {
+    let normalized = sql.trim().trim_end_matches(';').trim();
+    if normalized.is_empty() || normalized.contains(';') {
+        return None;
+    }
+
+    let lowered = normalized.to_ascii_lowercase();
+    let tokens: Vec<&str> = lowered.split_whitespace().collect();
+    let has_tokens = |needle: &[&str]| tokens.windows(needle.len()).any(|w| w == needle);
+    let is_select = lowered.starts_with("select");
+    // 锁定子句必须位于 LIMIT 之后，注释可能吞掉追加的 LIMIT，这些情况保持原样
+    if !is_select
+        || tokens.contains(&"limit")
+        || has_tokens(&["for", "update"])
+        || has_tokens(&["for", "share"])
+        || has_tokens(&["lock", "in", "share", "mode"])
+        || has_tokens(&["into", "outfile"])
+        || has_tokens(&["into", "dumpfile"])
+        || lowered.contains("--")
+        || lowered.contains('#')
+        || lowered.contains("/*")
+    {
+        return None;
+    }
+
+    Some(format!("{} LIMIT {}", normalized, limit.saturating_add(1)))
+}
+
+fn truncate_to_row_limit(result: &mut QueryResult, limit: u64) {
+    if result.rows.len() as u64 > limit {
+        result.rows.truncate(limit as usize);
+        result.truncated = true;
+    }
+}
+
+fn normalize_query_sql(sql: &str) -> Result<String, String> {
+    let mut normalized = sql.trim().trim_end_matches(';').trim().to_string();
+    if normalized.is_empty() {
+        return Err("SQL query cannot be empty".to_string());
+    }
+
+    // Prevent accidental multi-statements from being wrapped into derived tables.
+    if normalized.contains(';') {
+        return Err("Paginated query only supports a single SQL statement".to_string());
+    }
+
+    normalized.shrink_to_fit();
+    Ok(normalized)
+}
+
+fn execute_statement_detailed(
+    conn: &mut Conn,
+    index: usize,
+    statement: &str,
+    format: ResultFormat,
+) -> Result<StatementOutcome, String> {
+    let statement_start = Instant::now();
+    let mut rows = conn
+        .query_iter(statement)
+        .map_err(|e| format!("Query failed: {e}"))?;
+    let affected_rows = rows.affected_rows();
+    let last_insert_id = rows.last_insert_id().unwrap_or(0);
+    let query_elapsed = statement_start.elapsed().as_secs_f64();
+    let fetch_start = Instant::now();
+
+    let mut result_set: Option<QueryResult> = None;
+    while let Some(set) = rows.iter() {
+        let columns_binding = set.columns();
+        let columns = columns_binding.as_ref();
+        if columns.is_empty() {
+            continue;
+        }
+        let column_type_hints = build_column_type_hints(columns);
+        let mut result = QueryResult {
+            columns: columns
+                .iter()
+                .map(|c: &mysql::Column| ColumnMeta {
+                    name: c.name_str().to_string(),
+                    label: c.name_str().to_string(),
+                    type_name: format!("{:?}", c.column_type()),
+                })
+                .collect(),
+            rows: Vec::new(),
+            query_time_secs: query_elapsed,
+            fetch_time_secs: 0.0,
+            truncated: false,
+        };
+        for row in set {
+            let row = row.map_err(|e| format!("Row read failed: {e}"))?;
+            result
+                .rows
+                .push(row_to_json(row, &column_type_hints, format));
+        }
+        result.fetch_time_secs = fetch_start.elapsed().as_secs_f64();
+        // 存储过程可能返回多个结果集，只保留第一个
+        if result_set.is_none() {
+            result_set = Some(result);
+        }
+    }
+
+    Ok(StatementOutcome {
+        index,
+        kind: if result_set.is_some() {
+            StatementKind::Query
+        } else {
+            StatementKind::Update
+        },
+        result_set,
+        affected_rows,
+        last_insert_id,
+        duration_ms: statement_start.elapsed().as_millis() as u64,
+    })
+}
+
+fn execute_query_multi(
+    conn: &mut Conn,
+    sql: &str,
+    params: Option<Vec<Value>>,
+    format: ResultFormat,
+) -> Result<MultiQueryResult, String> {
+    let statement_start = Instant::now();
+    let mut result_sets: Vec<QueryResult> = Vec::new();
+
+    // Use exec_iter to get a QueryResult that supports multiple result sets
+    let mut rows = if let Some(p) = params {
+        conn.exec_iter(sql, Params::Positional(p))
+            .map_err(|e| format!("Query failed: {e}"))?
+    } else {
+        conn.exec_iter(sql, Params::Empty)
+            .map_err(|e| format!("Query failed: {e}"))?
+    };
+
+    // Get affected rows and last insert id before processing result sets
+    // These need to be retrieved before the rows iterator is consumed
+    let affected_rows = rows.affected_rows();
+    let last_insert_id = rows.last_insert_id().unwrap_or(0);
+    let query_elapsed = statement_start.elapsed().as_secs_f64();
     let fetch_start = Instant::now();
 
     // Use QueryResult::iter to iterate over all result sets
@@ -1734,6 +3196,7 @@ fn execute_query_multi(
             rows: Vec::new(),
             query_time_secs: 0.0,
             fetch_time_secs: 0.0,
+            truncated: false,
         };
 
         let columns_binding = result_set.columns();
@@ -1749,15 +3212,14 @@ fn execute_query_multi(
             })
             .collect();
 
-        let column_type_hints: Vec<(String, u8)> = columns
-            .iter()
-            .map(|c| (format!("{:?}", c.column_type()), c.decimals()))
-            .collect();
+        let column_type_hints = build_column_type_hints(columns);
 
         // Collect all rows for this result set
         for row in result_set {
             let row = row.map_err(|e| format!("Row read failed: {e}"))?;
-            result.rows.push(row_to_json(row, &column_type_hints));
+            result
+                .rows
+                .push(row_to_json(row, &column_type_hints, format));
         }
 
         // Skip empty result sets (no columns and no rows)
@@ -1804,20 +3266,85 @@ fn execute_update(
     })
 }
 
-fn row_to_json(row: mysql::Row, column_type_hints: &[(String, u8)]) -> Vec<JsonValue> {
+// build_column_type_hints 中 JSON 列的类型名
+const JSON_TYPE_NAME: &str = "MYSQL_TYPE_JSON";
+const GEOMETRY_TYPE_NAME: &str = "MYSQL_TYPE_GEOMETRY";
+
+// 63 为 binary 字符集，用于区分 BINARY/VARBINARY/BLOB 与文本列
+const BINARY_CHARSET_ID: u16 = 63;
+
+fn build_column_type_hints(columns: &[mysql::Column]) -> Vec<(String, u8, bool)> {
+    columns
+        .iter()
+        .map(|c| {
+            let is_binary = c.character_set() == BINARY_CHARSET_ID
+                && matches!(
+                    c.column_type(),
+                    mysql::consts::ColumnType::MYSQL_TYPE_STRING
+                        | mysql::consts::ColumnType::MYSQL_TYPE_VAR_STRING
+                        | mysql::consts::ColumnType::MYSQL_TYPE_VARCHAR
+                        | mysql::consts::ColumnType::MYSQL_TYPE_TINY_BLOB
+                        | mysql::consts::ColumnType::MYSQL_TYPE_MEDIUM_BLOB
+                        | mysql::consts::ColumnType::MYSQL_TYPE_LONG_BLOB
+                        | mysql::consts::ColumnType::MYSQL_TYPE_BLOB
+                        | mysql::consts::ColumnType::MYSQL_TYPE_GEOMETRY
+                );
+            (format!("{:?}", c.column_type()), c.decimals(), is_binary)
+        })
+        .collect()
+}
+
+fn row_to_json(
+    row: mysql::Row,
+    column_type_hints: &[(String, u8, bool)],
+    format: ResultFormat,
+) -> Vec<JsonValue> {
     row.unwrap()
         .into_iter()
         .enumerate()
         .map(|(index, value)| {
-            let (type_name, datetime_precision) = column_type_hints
+            let (type_name, datetime_precision, is_binary) = column_type_hints
                 .get(index)
-                .map(|(name, precision)| (name.as_str(), *precision))
-                .unwrap_or(("", 0));
-            value_to_json(value, type_name, datetime_precision)
+                .map(|(name, precision, is_binary)| (name.as_str(), *precision, *is_binary))
+                .unwrap_or(("", 0, false));
+            match value {
+                Value::Bytes(bytes) if is_binary => format
+                    .spatial_format
+                    .filter(|_| type_name == GEOMETRY_TYPE_NAME)
+                    .and_then(|spatial| spatial::render_geometry(&bytes, spatial))
+                    .map(JsonValue::String)
+                    // 非空间列或无法解析的空间值按普通二进制展示
+                    .unwrap_or_else(|| binary_to_json(&bytes, format.binary_display)),
+                Value::Bytes(bytes) if format.pretty_json && type_name == JSON_TYPE_NAME => {
+                    json_column_to_json(bytes)
+                }
+                value => value_to_json(value, type_name, datetime_precision),
+            }
         })
         .collect()
 }
 
+// JSON 列解析为嵌套值输出，前端可按树展示；解析失败时退回原始字符串
+fn json_column_to_json(bytes: Vec<u8>) -> JsonValue {
+    serde_json::from_slice(&bytes)
+        .unwrap_or_else(|_| JsonValue::String(String::from_utf8_lossy(&bytes).to_string()))
+}
+
+fn binary_to_json(bytes: &[u8], binary_display: BinaryDisplay) -> JsonValue {
+    match binary_display {
+        BinaryDisplay::Utf8Lossy => JsonValue::String(String::from_utf8_lossy(bytes).to_string()),
+        BinaryDisplay::Base64 => JsonValue::String(STANDARD.encode(bytes)),
+        BinaryDisplay::Hex => {
+            let mut rendered = String::with_capacity(2 + bytes.len() * 2);
+            rendered.push_str("0x");
+            for byte in bytes {
+                rendered.push_str(&format!("{byte:02X}"));
+            }
+            JsonValue::String(rendered)
+        }
+    }
+}
+
 fn value_to_json(value: Value, type_name: &str, datetime_precision: u8) -> JsonValue {
     match value {
         Value::NULL => JsonValue::Null,
@@ -1882,6 +3409,45 @@ fn convert_params(params: Vec<SqlParam>) -> Result<Vec<Value>, String> {
     Ok(result)
 }
 
+fn is_decimal_literal(text: &str) -> bool {
+    let unsigned = text.strip_prefix(['-', '+']).unwrap_or(text);
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((m, e)) => (m, Some(e)),
+        None => (unsigned, None),
+    };
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits_ok = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if int_part.is_empty() && frac_part.is_empty() {
+        return false;
+    }
+    if !digits_ok(int_part) || !digits_ok(frac_part) {
+        return false;
+    }
+    match exponent {
+        Some(exp) => {
+            let exp = exp.strip_prefix(['-', '+']).unwrap_or(exp);
+            !exp.is_empty() && digits_ok(exp)
+        }
+        None => true,
+    }
+}
+
+// 参数名不含前导冒号，与 SQL 中的 `:name` 对应
+fn convert_named_params(
+    params: HashMap<String, SqlParam>,
+) -> Result<HashMap<Vec<u8>, Value>, String> {
+    let mut result = HashMap::with_capacity(params.len());
+    for (name, item) in params {
+        let key = name.trim().trim_start_matches(':').to_string();
+        if key.is_empty() {
+            return Err("Named param has empty name".to_string());
+        }
+        let value = to_mysql_value(item).map_err(|e| format!("{e}: {key}"))?;
+        result.insert(key.into_bytes(), value);
+    }
+    Ok(result)
+}
+
 fn to_mysql_value(param: SqlParam) -> Result<Value, String> {
     let t = param.param_type.to_ascii_lowercase();
     match t.as_str() {
@@ -1893,7 +3459,42 @@ fn to_mysql_value(param: SqlParam) -> Result<Value, String> {
             .value
             .as_i64()
             .map(Value::Int)
+            .or_else(|| param.value.as_u64().map(Value::UInt)) // 超出 i64 时按无符号处理
             .ok_or_else(|| "Invalid integer param".to_string()),
+        "uint" | "unsigned" => param
+            .value
+            .as_u64()
+            .or_else(|| {
+                param
+                    .value
+                    .as_str()
+                    .and_then(|s| s.trim().parse::<u64>().ok())
+            })
+            .map(Value::UInt)
+            .ok_or_else(|| "Invalid unsigned param".to_string()),
+        "decimal" => {
+            // 以字符串传递，避免经过 f64 丢失精度
+            let text = match &param.value {
+                JsonValue::String(s) => s.trim().to_string(),
+                JsonValue::Number(n) => n.to_string(),
+                _ => return Err("Invalid decimal param".to_string()),
+            };
+            if !is_decimal_literal(&text) {
+                return Err(format!("Invalid decimal param: {text}"));
+            }
+            Ok(Value::Bytes(text.into_bytes()))
+        }
+        "json" => {
+            let text = match &param.value {
+                JsonValue::String(s) => {
+                    serde_json::from_str::<JsonValue>(s)
+                        .map_err(|e| format!("Invalid json param: {e}"))?;
+                    s.clone()
+                }
+                other => other.to_string(),
+            };
+            Ok(Value::Bytes(text.into_bytes()))
+        }
         "double" => param
             .value
             .as_f64()
@@ -1977,3 +3578,987 @@ fn parse_datetime_to_value(text: &str) -> Result<Value, String> {
 
     Ok(Value::Date(year, month, day, hour, minute, second, micros))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::test_support::{fresh_schema, mysql_profile, profile, run_sql, typed_row};
+    use mysql::consts::ColumnType;
+    use mysql::Column;
+
+    fn result_with_rows(count: usize) -> QueryResult {
+        QueryResult {
+            columns: Vec::new(),
+            rows: (0..count).map(|i| vec![JsonValue::from(i)]).collect(),
+            query_time_secs: 0.0,
+            fetch_time_secs: 0.0,
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn default_row_limit_caps_unbounded_select() {
+        assert_eq!(
+            apply_default_row_limit("  SELECT * FROM t ;", 1000).as_deref(),
+            Some("SELECT * FROM t LIMIT 1001")
+        );
+
+        let mut result = result_with_rows(1001);
+        truncate_to_row_limit(&mut result, 1000);
+        assert_eq!(result.rows.len(), 1000);
+        assert!(result.truncated);
+
+        let mut result = result_with_rows(1000);
+        truncate_to_row_limit(&mut result, 1000);
+        assert_eq!(result.rows.len(), 1000);
+        assert!(!result.truncated);
+    }
+
+    #[test]
+    fn default_row_limit_passes_through_other_statements() {
+        for sql in [
+            "SELECT * FROM t LIMIT 10",
+            "select * from t limit 5, 10",
+            "SELECT * FROM t FOR UPDATE",
+            "SELECT * FROM t WHERE id = 1\nFOR   UPDATE",
+            "SELECT * FROM t FOR SHARE",
+            "SELECT * FROM t LOCK IN SHARE MODE",
+            "SELECT * FROM t INTO OUTFILE '/tmp/t.csv'",
+            "SELECT * FROM t -- newest first",
+            "SELECT * FROM t # trailing note",
+            "SELECT * FROM t /* hint */",
+            "SELECT 1; SELECT 2",
+            "UPDATE t SET a = 1",
+            "SHOW TABLES",
+            "",
+        ] {
+            assert_eq!(apply_default_row_limit(sql, 100), None, "{sql}");
+        }
+    }
+
+    #[test]
+    fn user_init_sql_runs_after_builtin_statements_in_order() {
+        let mut profile = profile("127.0.0.1", 3306, "root", "");
+        profile.database = Some("shop".to_string());
+        profile.charset = Some("utf8mb4".to_string());
+        profile.ssl_mode = Some("disabled".to_string());
+        profile.init_sql = vec![
+            "SET SESSION time_zone = '+00:00';".to_string(),
+            "  ".to_string(),
+            "SET @tenant = 42".to_string(),
+        ];
+        let mut config = PoolConfig::from_profile(&profile);
+        config.current_database = profile.database.clone();
+
+        let sqls = build_session_init_sql(&config);
+        assert_eq!(sqls[0], "USE `shop`");
+        assert_eq!(sqls[1], "SET NAMES utf8mb4");
+        assert_eq!(
+            &sqls[sqls.len() - 2..],
+            ["SET SESSION time_zone = '+00:00'", "SET @tenant = 42"]
+        );
+    }
+
+    fn param(param_type: &str, value: JsonValue) -> SqlParam {
+        SqlParam {
+            param_type: param_type.to_string(),
+            value,
+        }
+    }
+
+    #[test]
+    fn converts_two_named_params_including_null() {
+        let params = HashMap::from([
+            (
+                ":name".to_string(),
+                param("string", JsonValue::from("Alice")),
+            ),
+            ("deleted_at".to_string(), param("null", JsonValue::Null)),
+        ]);
+        let converted = convert_named_params(params).unwrap();
+        assert_eq!(converted.len(), 2);
+        assert_eq!(
+            converted[b"name".as_slice()],
+            Value::Bytes(b"Alice".to_vec())
+        );
+        assert_eq!(converted[b"deleted_at".as_slice()], Value::NULL);
+
+        let empty = HashMap::from([(":".to_string(), param("null", JsonValue::Null))]);
+        assert!(convert_named_params(empty).is_err());
+    }
+
+    #[test]
+    fn converts_large_unsigned_and_precise_decimal_params() {
+        let max = serde_json::json!(u64::MAX);
+        assert_eq!(
+            to_mysql_value(param("int", max.clone())).unwrap(),
+            Value::UInt(u64::MAX)
+        );
+        assert_eq!(
+            to_mysql_value(param("unsigned", JsonValue::from("18446744073709551615"))).unwrap(),
+            Value::UInt(u64::MAX)
+        );
+        assert!(to_mysql_value(param("unsigned", JsonValue::from(-1))).is_err());
+
+        let decimal = "12345678901234567890.123456789012345678";
+        assert_eq!(
+            to_mysql_value(param("decimal", JsonValue::from(decimal))).unwrap(),
+            Value::Bytes(decimal.as_bytes().to_vec())
+        );
+        assert!(to_mysql_value(param("decimal", JsonValue::from("1.2.3"))).is_err());
+        assert!(to_mysql_value(param("decimal", JsonValue::from("1e"))).is_err());
+    }
+
+    #[test]
+    fn validates_json_params() {
+        assert_eq!(
+            to_mysql_value(param("json", serde_json::json!({"a": [1, 2]}))).unwrap(),
+            Value::Bytes(br#"{"a":[1,2]}"#.to_vec())
+        );
+        assert_eq!(
+            to_mysql_value(param("json", JsonValue::from("[true]"))).unwrap(),
+            Value::Bytes(b"[true]".to_vec())
+        );
+        let err = to_mysql_value(param("json", JsonValue::from("{not json"))).unwrap_err();
+        assert!(err.starts_with("Invalid json param:"), "{err}");
+    }
+
+    fn binary_row(bytes: &[u8]) -> (mysql::Row, Vec<(String, u8, bool)>) {
+        let columns = vec![
+            Column::new(ColumnType::MYSQL_TYPE_BLOB)
+                .with_name(b"payload")
+                .with_character_set(BINARY_CHARSET_ID),
+            Column::new(ColumnType::MYSQL_TYPE_VAR_STRING)
+                .with_name(b"label")
+                .with_character_set(255),
+        ];
+        let hints = build_column_type_hints(&columns);
+        let row = typed_row(
+            columns,
+            vec![Value::Bytes(bytes.to_vec()), Value::Bytes(b"ok".to_vec())],
+        );
+        (row, hints)
+    }
+
+    #[test]
+    fn renders_binary_columns_per_display_mode() {
+        let bytes = [0x00, 0xFF, b'A'];
+        let cases = [
+            (BinaryDisplay::Hex, JsonValue::from("0x00FF41")),
+            (BinaryDisplay::Base64, JsonValue::from("AP9B")),
+            (BinaryDisplay::Utf8Lossy, JsonValue::from("\u{0}\u{FFFD}A")),
+        ];
+        for (binary_display, expected) in cases {
+            let (row, hints) = binary_row(&bytes);
+            let format = ResultFormat {
+                binary_display,
+                ..Default::default()
+            };
+            assert_eq!(
+                row_to_json(row, &hints, format),
+                vec![expected, JsonValue::from("ok")]
+            );
+        }
+    }
+
+    #[test]
+    fn json_column_is_emitted_as_structured_value_when_pretty_json_is_on() {
+        let json = br#"{"tags": ["a", "b"], "n": 1}"#;
+        let render = |pretty_json: bool| {
+            let columns = vec![Column::new(ColumnType::MYSQL_TYPE_JSON)
+                .with_name(b"doc")
+                .with_character_set(BINARY_CHARSET_ID)];
+            let hints = build_column_type_hints(&columns);
+            let row = typed_row(columns, vec![Value::Bytes(json.to_vec())]);
+            let format = ResultFormat {
+                pretty_json,
+                ..Default::default()
+            };
+            row_to_json(row, &hints, format)
+        };
+
+        assert_eq!(
+            render(true),
+            vec![serde_json::json!({"tags": ["a", "b"], "n": 1})]
+        );
+        assert_eq!(
+            render(false),
+            vec![JsonValue::from(r#"{"tags": ["a", "b"], "n": 1}"#)]
+        );
+    }
+
+    #[test]
+    fn geometry_column_is_rendered_when_spatial_format_is_set() {
+        // SRID 0 + 小端 WKB POINT(1 2)
+        let mut point = vec![0, 0, 0, 0, 1, 1, 0, 0, 0];
+        point.extend(1.0f64.to_le_bytes());
+        point.extend(2.0f64.to_le_bytes());
+        let render = |spatial_format: Option<SpatialFormat>, bytes: &[u8]| {
+            let columns = vec![Column::new(ColumnType::MYSQL_TYPE_GEOMETRY)
+                .with_name(b"location")
+                .with_character_set(BINARY_CHARSET_ID)];
+            let hints = build_column_type_hints(&columns);
+            let row = typed_row(columns, vec![Value::Bytes(bytes.to_vec())]);
+            let format = ResultFormat {
+                binary_display: BinaryDisplay::Hex,
+                spatial_format,
+                ..Default::default()
+            };
+            row_to_json(row, &hints, format)
+        };
+
+        assert_eq!(
+            render(Some(SpatialFormat::Wkt), &point),
+            vec![JsonValue::from("POINT(1 2)")]
+        );
+        assert_eq!(
+            render(Some(SpatialFormat::GeoJson), &point),
+            vec![JsonValue::from(
+                r#"{"coordinates":[1.0,2.0],"type":"Point"}"#
+            )]
+        );
+        assert_eq!(
+            render(None, &point[..9]),
+            vec![JsonValue::from("0x000000000101000000")]
+        );
+        // 无法解析时退回二进制展示
+        assert_eq!(
+            render(Some(SpatialFormat::Wkt), &[0xAB]),
+            vec![JsonValue::from("0xAB")]
+        );
+    }
+
+    #[test]
+    fn paged_fetches_reuse_cached_column_metadata() {
+        let columns = vec![
+            Column::new(ColumnType::MYSQL_TYPE_LONG).with_name(b"id"),
+            Column::new(ColumnType::MYSQL_TYPE_VAR_STRING).with_name(b"name"),
+        ];
+        let describe_calls = std::cell::Cell::new(0);
+        let counting = |columns: &[Column]| {
+            describe_calls.set(describe_calls.get() + 1);
+            describe_columns(columns)
+        };
+
+        let first = reuse_or_describe_columns(None, &columns, counting);
+        let second = reuse_or_describe_columns(Some(Arc::clone(&first)), &columns, counting);
+        let third = reuse_or_describe_columns(Some(Arc::clone(&second)), &columns, counting);
+        assert_eq!(describe_calls.get(), 1);
+        assert!(Arc::ptr_eq(&first, &third));
+        assert_eq!(first.columns[1].name, "name");
+
+        // 列数变化说明表结构已变，必须重新描述
+        let widened = [
+            columns.clone(),
+            vec![Column::new(ColumnType::MYSQL_TYPE_BLOB)],
+        ]
+        .concat();
+        let refreshed = reuse_or_describe_columns(Some(first), &widened, counting);
+        assert_eq!(describe_calls.get(), 2);
+        assert_eq!(refreshed.columns.len(), 3);
+
+        // 列数不变但列被改名或改类型，同样不能复用旧的元数据
+        let renamed = vec![
+            Column::new(ColumnType::MYSQL_TYPE_LONG).with_name(b"id"),
+            Column::new(ColumnType::MYSQL_TYPE_VAR_STRING).with_name(b"title"),
+        ];
+        let refreshed = reuse_or_describe_columns(Some(Arc::clone(&second)), &renamed, counting);
+        assert_eq!(describe_calls.get(), 3);
+        assert_eq!(refreshed.columns[1].name, "title");
+
+        let retyped = vec![
+            Column::new(ColumnType::MYSQL_TYPE_LONG).with_name(b"id"),
+            Column::new(ColumnType::MYSQL_TYPE_DATETIME).with_name(b"name"),
+        ];
+        let refreshed = reuse_or_describe_columns(Some(second), &retyped, counting);
+        assert_eq!(describe_calls.get(), 4);
+        assert_eq!(refreshed.type_hints[1].0, "MYSQL_TYPE_DATETIME");
+    }
+
+    #[test]
+    fn parses_use_statements() {
+        assert_eq!(
+            parse_use_database("USE other_db;"),
+            Some("other_db".to_string())
+        );
+        assert_eq!(
+            parse_use_database("use `my``db`"),
+            Some("my`db".to_string())
+        );
+        assert_eq!(parse_use_database("USER_FUNC()"), None);
+        assert_eq!(parse_use_database("SELECT 1"), None);
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn use_statement_updates_tracked_database() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "use_tracking");
+        let pool_id = create_pool(&profile).unwrap();
+        let conn_id = get_connection(pool_id, None).unwrap();
+
+        execute(pool_id, conn_id, &format!("USE `{schema}`"), None).unwrap();
+        let tracked = get_current_database(pool_id, conn_id, false).unwrap();
+        let verified = get_current_database(pool_id, conn_id, true).unwrap();
+
+        release_connection(pool_id, conn_id).unwrap();
+        close_pool(pool_id);
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+        assert_eq!(tracked.as_deref(), Some(schema.as_str()));
+        assert_eq!(verified, tracked);
+    }
+
+    #[test]
+    fn builds_create_table_as_select() {
+        assert_eq!(
+            build_result_to_table_sql(
+                "SELECT id FROM users WHERE id > 1;",
+                Some("shop"),
+                "top",
+                false
+            )
+            .unwrap(),
+            "CREATE TABLE `shop`.`top` AS SELECT id FROM users WHERE id > 1"
+        );
+        assert_eq!(
+            build_result_to_table_sql("WITH t AS (SELECT 1) SELECT * FROM t", None, "tmp`x", true)
+                .unwrap(),
+            "CREATE TEMPORARY TABLE `tmp``x` AS WITH t AS (SELECT 1) SELECT * FROM t"
+        );
+        assert!(build_result_to_table_sql("DELETE FROM users", None, "t", false).is_err());
+        assert!(build_result_to_table_sql("SELECT 1; SELECT 2", None, "t", false).is_err());
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn mixed_script_reports_query_and_update_outcomes() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "multi_detailed");
+        run_sql(
+            &profile,
+            &[&format!(
+                "CREATE TABLE `{schema}`.`t` (id INT AUTO_INCREMENT PRIMARY KEY, v INT)"
+            )],
+        );
+        let pool_id = create_pool(&profile).unwrap();
+        let conn_id = get_connection(pool_id, Some(schema.clone())).unwrap();
+        execute(pool_id, conn_id, &format!("USE `{schema}`"), None).unwrap();
+
+        let outcomes = query_multi_detailed(
+            pool_id,
+            conn_id,
+            "SELECT 1 AS one; INSERT INTO t (v) VALUES (10), (20);",
+        );
+
+        release_connection(pool_id, conn_id).unwrap();
+        close_pool(pool_id);
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+        let outcomes = outcomes.unwrap();
+        assert_eq!(outcomes.len(), 2);
+        assert!(matches!(outcomes[0].kind, StatementKind::Query));
+        assert_eq!(
+            outcomes[0].result_set.as_ref().unwrap().rows,
+            vec![vec![JsonValue::from("1")]]
+        );
+        assert!(matches!(outcomes[1].kind, StatementKind::Update));
+        assert_eq!(outcomes[1].index, 1);
+        assert_eq!(outcomes[1].affected_rows, 2);
+        assert!(outcomes[1].result_set.is_none());
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn warmup_fills_idle_connections_and_resize_updates_max() {
+        let profile = mysql_profile();
+        let pool_id = create_pool(&profile).unwrap();
+        let before = get_stats(pool_id).unwrap();
+        let target = (before.idle_connections + 2).min(before.max_size);
+
+        // 已有空闲连接时再次预热，仍应补足到目标数
+        let first = warmup(pool_id, before.idle_connections + 1);
+        let warmed = warmup(pool_id, target);
+        let resized = resize(pool_id, 3);
+        let invalid = resize(pool_id, 0);
+        close_pool(pool_id);
+
+        assert_eq!(
+            first.unwrap().idle_connections,
+            (before.idle_connections + 1).min(before.max_size)
+        );
+        let warmed = warmed.unwrap();
+        assert_eq!(warmed.idle_connections, target);
+        assert!(warmed.idle_connections >= before.idle_connections);
+        assert_eq!(resized.unwrap().max_size, 3);
+        assert_eq!(invalid.unwrap_err(), "max_size must be greater than 0");
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn diagnostics_reflect_database_and_use_count() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "conn_diag");
+        let pool_id = create_pool(&profile).unwrap();
+        let conn_id = get_connection(pool_id, Some(schema.clone())).unwrap();
+        query(pool_id, conn_id, "SELECT 1", None).unwrap();
+        query(pool_id, conn_id, "SELECT 2", None).unwrap();
+
+        let diag = get_connection_diagnostics(pool_id, conn_id).unwrap();
+        release_connection(pool_id, conn_id).unwrap();
+        let released = get_connection_diagnostics(pool_id, conn_id).unwrap();
+        close_pool(pool_id);
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert!(diag.exists);
+        assert_eq!(diag.current_database.as_deref(), Some(schema.as_str()));
+        assert_eq!(diag.use_count, 2);
+        assert_eq!(diag.in_transaction, 0);
+        assert_eq!(diag.reconnect_blocked_reason, None);
+        assert!(!released.exists);
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn rows_beyond_cap_are_truncated_and_connection_stays_usable() {
+        let profile = mysql_profile();
+        let sql = "WITH RECURSIVE seq(n) AS (SELECT 1 UNION ALL SELECT n + 1 FROM seq WHERE n < 50) SELECT n FROM seq";
+        let (result, next) = with_temp_connection(&profile, |conn| {
+            let result =
+                execute_query_params(conn, sql, Params::Empty, ResultFormat::default(), 10)?;
+            let next: Option<u32> = conn.query_first("SELECT 42").map_err(|e| e.to_string())?;
+            Ok((result, next))
+        })
+        .unwrap();
+
+        assert!(result.truncated);
+        assert_eq!(result.rows.len(), 10);
+        assert_eq!(next, Some(42));
+    }
+
+    #[test]
+    fn tracks_session_level_set_statements_only() {
+        let names = |sql: &str| -> Vec<String> {
+            parse_session_settings(sql)
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect()
+        };
+        assert_eq!(
+            parse_session_settings("SET SESSION sql_mode = 'ANSI_QUOTES';"),
+            vec![(
+                "sql_mode".to_string(),
+                "SET SESSION sql_mode = 'ANSI_QUOTES'".to_string()
+            )]
+        );
+        assert_eq!(names("set @@session.time_zone='+00:00'"), ["time_zone"]);
+        assert_eq!(names("SET NAMES utf8mb4"), ["names"]);
+        assert!(names("SET GLOBAL sql_mode = ''").is_empty());
+        assert!(names("SET @@global.max_connections = 10").is_empty());
+        assert!(names("SET TRANSACTION ISOLATION LEVEL READ COMMITTED").is_empty());
+        assert!(names("SET @batch = 1").is_empty());
+        assert!(names("SELECT 1").is_empty());
+    }
+
+    #[test]
+    fn multi_assignment_set_is_split_per_variable() {
+        assert_eq!(
+            parse_session_settings(
+                "SET sql_mode = 'ANSI,NO_ZERO_DATE', @tag = 'a,b', time_zone = '+08:00'"
+            ),
+            vec![
+                (
+                    "sql_mode".to_string(),
+                    "SET sql_mode = 'ANSI,NO_ZERO_DATE'".to_string()
+                ),
+                (
+                    "time_zone".to_string(),
+                    "SET time_zone = '+08:00'".to_string()
+                ),
+            ]
+        );
+        // GLOBAL 沿用到下一个赋值，@@ 前缀显式指定会话作用域
+        assert_eq!(
+            parse_session_settings(
+                "SET GLOBAL max_connections = 10, wait_timeout = 60, @@session.autocommit = 0"
+            ),
+            vec![(
+                "autocommit".to_string(),
+                "SET @@session.autocommit = 0".to_string()
+            )]
+        );
+        assert_eq!(
+            parse_session_settings("SET NAMES utf8mb4, SESSION lock_wait_timeout = IF(1, 5, 10)")
+                .into_iter()
+                .map(|(_, statement)| statement)
+                .collect::<Vec<_>>(),
+            ["SET NAMES utf8mb4", "SET lock_wait_timeout = IF(1, 5, 10)"]
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn session_sql_mode_is_reapplied_after_reconnect() {
+        let mut profile = mysql_profile();
+        profile.auto_reconnect = Some(true);
+        let pool_id = create_pool(&profile).unwrap();
+        let conn_id = get_connection(pool_id, None).unwrap();
+
+        execute(
+            pool_id,
+            conn_id,
+            "SET SESSION sql_mode = 'ANSI_QUOTES'",
+            None,
+        )
+        .unwrap();
+        let before = query(pool_id, conn_id, "SELECT CONNECTION_ID()", None).unwrap();
+        let session_id = before.rows[0][0].as_str().unwrap().to_string();
+        // 从另一个连接杀掉当前会话，模拟网络中断
+        run_sql(&profile, &[&format!("KILL {session_id}")]);
+
+        let after = query(
+            pool_id,
+            conn_id,
+            "SELECT @@SESSION.sql_mode, CONNECTION_ID()",
+            None,
+        );
+        let diagnostics = get_connection_diagnostics(pool_id, conn_id);
+        release_connection(pool_id, conn_id).unwrap();
+        close_pool(pool_id);
+
+        let after = after.unwrap();
+        assert_eq!(after.rows[0][0], JsonValue::from("ANSI_QUOTES"));
+        assert_ne!(after.rows[0][1], JsonValue::from(session_id.as_str()));
+        assert_eq!(
+            diagnostics.unwrap().session_settings,
+            ["SET SESSION sql_mode = 'ANSI_QUOTES'"]
+        );
+    }
+
+    #[test]
+    fn autocommit_statement_round_trips_through_tracking() {
+        assert_eq!(autocommit_statement(false), "SET autocommit = 0");
+        assert_eq!(autocommit_statement(true), "SET autocommit = 1");
+        assert_eq!(
+            parse_session_settings(autocommit_statement(false)),
+            vec![("autocommit".to_string(), "SET autocommit = 0".to_string())]
+        );
+        assert!(is_autocommit_off(autocommit_statement(false)));
+        assert!(!is_autocommit_off(autocommit_statement(true)));
+        assert!(is_autocommit_off("SET SESSION autocommit := OFF"));
+        assert!(parse_session_settings("SET sql_mode = '', autocommit = 0")
+            .into_iter()
+            .find(|(name, _)| name == "autocommit")
+            .is_some_and(|(_, statement)| is_autocommit_off(&statement)));
+        assert!(!is_autocommit_off("SET @@session.autocommit='ON'"));
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn disabling_autocommit_blocks_auto_reconnect() {
+        let mut profile = mysql_profile();
+        profile.auto_reconnect = Some(true);
+        let pool_id = create_pool(&profile).unwrap();
+        let conn_id = get_connection(pool_id, None).unwrap();
+
+        set_autocommit(pool_id, conn_id, false).unwrap();
+        let disabled = get_connection_diagnostics(pool_id, conn_id).unwrap();
+        let session = query(pool_id, conn_id, "SELECT @@SESSION.autocommit", None).unwrap();
+        set_autocommit(pool_id, conn_id, true).unwrap();
+        let enabled = get_connection_diagnostics(pool_id, conn_id).unwrap();
+        release_connection(pool_id, conn_id).unwrap();
+        close_pool(pool_id);
+
+        assert_eq!(session.rows[0][0], JsonValue::from("0"));
+        assert!(disabled
+            .reconnect_blocked_reason
+            .as_deref()
+            .is_some_and(|reason| reason.starts_with("Autocommit is disabled")));
+        assert_eq!(enabled.reconnect_blocked_reason, None);
+        assert_eq!(enabled.session_settings, ["SET autocommit = 1"]);
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn saving_result_as_temporary_table_blocks_auto_reconnect() {
+        let mut profile = mysql_profile();
+        profile.auto_reconnect = Some(true);
+        let pool_id = create_pool(&profile).unwrap();
+        let conn_id = get_connection(pool_id, None).unwrap();
+
+        let before = get_connection_diagnostics(pool_id, conn_id).unwrap();
+        result_to_table(pool_id, conn_id, "SELECT 1 AS n", None, "tmp_result", true).unwrap();
+        let after = get_connection_diagnostics(pool_id, conn_id).unwrap();
+        let saved = query(pool_id, conn_id, "SELECT n FROM tmp_result", None).unwrap();
+        release_connection(pool_id, conn_id).unwrap();
+        close_pool(pool_id);
+
+        assert_eq!(before.has_temp_tables, 0);
+        assert_eq!(before.reconnect_blocked_reason, None);
+        assert_eq!(after.has_temp_tables, 1);
+        assert!(after
+            .reconnect_blocked_reason
+            .as_deref()
+            .is_some_and(|reason| reason.starts_with("Temporary tables exist (count: 1)")));
+        assert_eq!(saved.rows[0][0], JsonValue::from("1"));
+    }
+
+    #[test]
+    fn activity_log_keeps_latest_events_in_order() {
+        let log = ActivityLog::default();
+        log.push(ActivityKind::Connect, "shop");
+        for i in 0..ACTIVITY_LOG_CAPACITY {
+            log.push(ActivityKind::Query, &format!("SELECT {i}"));
+        }
+        log.push(
+            ActivityKind::Release,
+            &"x".repeat(ACTIVITY_DETAIL_MAX_CHARS + 10),
+        );
+
+        let events = log.events();
+        assert_eq!(events.len(), ACTIVITY_LOG_CAPACITY);
+        assert_eq!(events[0].kind, ActivityKind::Query);
+        assert_eq!(events[0].detail, "SELECT 1");
+        assert!(events.windows(2).all(|pair| pair[0].at <= pair[1].at));
+        let last = events.last().unwrap();
+        assert_eq!(last.kind, ActivityKind::Release);
+        assert_eq!(last.detail.chars().count(), ACTIVITY_DETAIL_MAX_CHARS + 1);
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn connection_operations_produce_ordered_timeline() {
+        let profile = mysql_profile();
+        let pool_id = create_pool(&profile).unwrap();
+        let conn_id = get_connection(pool_id, None).unwrap();
+
+        query(pool_id, conn_id, "SELECT 1", None).unwrap();
+        execute(pool_id, conn_id, "SET @dbw_activity_token = 'abc'", None).unwrap();
+        release_connection(pool_id, conn_id).unwrap();
+        let events = get_activity(pool_id, conn_id);
+        close_pool(pool_id);
+
+        let events = events.unwrap();
+        let kinds: Vec<ActivityKind> = events.iter().map(|event| event.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                ActivityKind::Connect,
+                ActivityKind::Query,
+                ActivityKind::Execute,
+                ActivityKind::Release,
+            ]
+        );
+        assert_eq!(events[1].detail, "SELECT 1");
+        // 与审计日志一样，口令类字面量不出现在时间线中
+        assert_eq!(events[2].detail, "SET @dbw_activity_token = '***'");
+        assert!(events.windows(2).all(|pair| pair[0].at <= pair[1].at));
+    }
+
+    #[test]
+    fn explain_json_is_parsed_into_estimate_with_full_scan_flag() {
+        let plan = r#"{
+          "query_block": {
+            "select_id": 1,
+            "cost_info": {"query_cost": "5123.40"},
+            "nested_loop": [
+              {"table": {"table_name": "orders", "access_type": "ALL",
+                         "rows_examined_per_scan": 50000, "rows_produced_per_join": 50000,
+                         "cost_info": {"read_cost": "100.00"}}},
+              {"table": {"table_name": "customers", "access_type": "eq_ref",
+                         "rows_examined_per_scan": 1, "rows_produced_per_join": 50000}}
+            ]
+          }
+        }"#;
+        let estimate = parse_explain_estimate(plan).unwrap();
+        // customers 对 orders 的每一行各查一次
+        assert_eq!(estimate.estimated_rows, 100000);
+        assert_eq!(estimate.estimated_cost, Some(5123.4));
+        assert!(estimate.full_table_scan);
+
+        let indexed = r#"{"query_block": {"cost_info": {"query_cost": "0.35"},
+            "table": {"table_name": "orders", "access_type": "const", "rows_examined_per_scan": 1}}}"#;
+        let estimate = parse_explain_estimate(indexed).unwrap();
+        assert_eq!(estimate.estimated_rows, 1);
+        assert!(!estimate.full_table_scan);
+
+        assert!(parse_explain_estimate("not json").is_err());
+    }
+
+    #[test]
+    fn explain_estimate_multiplies_nested_loop_fanout() {
+        let plan = r#"{"query_block": {"nested_loop": [
+            {"table": {"table_name": "a", "access_type": "ALL",
+                       "rows_examined_per_scan": 1000, "rows_produced_per_join": 100}},
+            {"table": {"table_name": "b", "access_type": "ALL",
+                       "rows_examined_per_scan": 200, "rows_produced_per_join": 20000}},
+            {"table": {"table_name": "c", "access_type": "ref",
+                       "rows_examined_per_scan": 3, "rows_produced_per_join": 60000}}
+        ]}}"#;
+        let estimate = parse_explain_estimate(plan).unwrap();
+        assert_eq!(estimate.estimated_rows, 1000 + 100 * 200 + 20000 * 3);
+
+        // MariaDB 没有 rows_produced_per_join，按 filtered 推算
+        let mariadb = r#"{"query_block": {"nested_loop": [
+            {"table": {"table_name": "a", "access_type": "ALL", "rows": 1000, "filtered": 10}},
+            {"table": {"table_name": "b", "access_type": "ALL", "rows": 200, "filtered": 100}}
+        ]}}"#;
+        let estimate = parse_explain_estimate(mariadb).unwrap();
+        assert_eq!(estimate.estimated_rows, 1000 + 100 * 200);
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn rollback_discards_and_commit_keeps_transaction_writes() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "pool_txn");
+        run_sql(
+            &profile,
+            &[&format!("CREATE TABLE `{schema}`.`t` (id INT PRIMARY KEY)")],
+        );
+        let pool_id = create_pool(&profile).unwrap();
+        let conn_id = get_connection(pool_id, Some(schema.clone())).unwrap();
+        execute(pool_id, conn_id, &format!("USE `{schema}`"), None).unwrap();
+        let count = || {
+            query(pool_id, conn_id, "SELECT COUNT(*) FROM t", None)
+                .unwrap()
+                .rows[0][0]
+                .clone()
+        };
+
+        let no_transaction = rollback(pool_id, conn_id);
+        begin_transaction(pool_id, conn_id).unwrap();
+        let nested = begin_transaction(pool_id, conn_id);
+        execute(pool_id, conn_id, "INSERT INTO t VALUES (1)", None).unwrap();
+        let inside = count();
+        rollback(pool_id, conn_id).unwrap();
+        let rolled_back = count();
+        begin_transaction(pool_id, conn_id).unwrap();
+        execute(pool_id, conn_id, "INSERT INTO t VALUES (2)", None).unwrap();
+        commit(pool_id, conn_id).unwrap();
+        let committed = count();
+        let diag = get_connection_diagnostics(pool_id, conn_id).unwrap();
+        release_connection(pool_id, conn_id).unwrap();
+        close_pool(pool_id);
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert_eq!(
+            no_transaction.unwrap_err(),
+            "No active transaction on this connection"
+        );
+        assert_eq!(
+            nested.unwrap_err(),
+            "A transaction is already active on this connection"
+        );
+        assert_eq!(inside, JsonValue::from("1"));
+        assert_eq!(rolled_back, JsonValue::from("0"));
+        assert_eq!(committed, JsonValue::from("1"));
+        assert_eq!(diag.in_transaction, 0);
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn open_transaction_blocks_auto_reconnect() {
+        let mut profile = mysql_profile();
+        profile.auto_reconnect = Some(true);
+        let pool_id = create_pool(&profile).unwrap();
+        let conn_id = get_connection(pool_id, None).unwrap();
+
+        begin_transaction(pool_id, conn_id).unwrap();
+        let diag = get_connection_diagnostics(pool_id, conn_id).unwrap();
+        let before = query(pool_id, conn_id, "SELECT CONNECTION_ID()", None).unwrap();
+        let session_id = before.rows[0][0].as_str().unwrap().to_string();
+        // 会话断开后重连会丢失未提交的修改，必须报错而不是静默换连接
+        run_sql(&profile, &[&format!("KILL {session_id}")]);
+        let after = query(pool_id, conn_id, "SELECT CONNECTION_ID()", None);
+        let _ = release_connection(pool_id, conn_id);
+        close_pool(pool_id);
+
+        assert_eq!(diag.in_transaction, 1);
+        assert!(diag
+            .reconnect_blocked_reason
+            .as_deref()
+            .is_some_and(|reason| reason.starts_with("Active transaction detected")));
+        let err = after.unwrap_err();
+        assert!(
+            err.starts_with("Connection lost and auto-reconnect is disabled: Active transaction"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn sleep_past_timeout_is_killed_and_connection_stays_usable() {
+        let profile = mysql_profile();
+        let pool_id = create_pool(&profile).unwrap();
+        let conn_id = get_connection(pool_id, None).unwrap();
+
+        let start = std::time::Instant::now();
+        // SLEEP 作为整条查询时被中断返回 1 而不报错；作为条件的一部分时查询报错
+        let slept = query(pool_id, conn_id, "SELECT SLEEP(5)", Some(500));
+        let filtered = query(
+            pool_id,
+            conn_id,
+            "SELECT COUNT(*) FROM (SELECT 1 AS x) AS t WHERE SLEEP(5) = 0",
+            Some(500),
+        );
+        let executed = execute(
+            pool_id,
+            conn_id,
+            "DO (SELECT COUNT(*) FROM (SELECT 1 AS x) AS t WHERE SLEEP(5) = 0)",
+            Some(500),
+        );
+        let elapsed = start.elapsed();
+        let next = query(pool_id, conn_id, "SELECT 42", Some(500));
+        release_connection(pool_id, conn_id).unwrap();
+        close_pool(pool_id);
+
+        assert_eq!(slept.unwrap().rows, vec![vec![JsonValue::from("1")]]);
+        assert_eq!(filtered.unwrap_err(), "Query timed out after 500 ms");
+        assert_eq!(executed.unwrap_err(), "Query timed out after 500 ms");
+        assert!(elapsed < std::time::Duration::from_secs(4), "{elapsed:?}");
+        assert_eq!(next.unwrap().rows, vec![vec![JsonValue::from("42")]]);
+    }
+
+    #[test]
+    fn max_execution_time_hint_is_added_to_single_selects_only() {
+        assert_eq!(
+            apply_max_execution_time("  select id FROM t", 500).as_deref(),
+            Some("SELECT /*+ MAX_EXECUTION_TIME(500) */ id FROM t")
+        );
+        assert_eq!(
+            apply_max_execution_time("SELECT /*+ NO_INDEX(t) */ id FROM t", 500),
+            None
+        );
+        assert_eq!(apply_max_execution_time("UPDATE t SET a = 1", 500), None);
+        assert_eq!(apply_max_execution_time("SELECT 1; SELECT 2", 500), None);
+        assert_eq!(apply_max_execution_time("selection", 500), None);
+    }
+
+    #[test]
+    fn transaction_effect_tracks_explicit_and_implicit_commits() {
+        let effect = |sql: &str| transaction_effect(sql);
+        assert_eq!(
+            effect("START TRANSACTION READ ONLY"),
+            Some(TransactionStep::Begin)
+        );
+        assert_eq!(effect("begin;"), Some(TransactionStep::Begin));
+        assert_eq!(effect("COMMIT WORK"), Some(TransactionStep::Commit));
+        assert_eq!(effect("ROLLBACK"), Some(TransactionStep::Rollback));
+        assert_eq!(effect("ROLLBACK TO SAVEPOINT sp1"), None);
+        assert_eq!(
+            effect("ALTER TABLE t ADD c INT"),
+            Some(TransactionStep::Commit)
+        );
+        assert_eq!(effect("truncate table t"), Some(TransactionStep::Commit));
+        assert_eq!(effect("LOCK TABLES t WRITE"), Some(TransactionStep::Commit));
+        assert_eq!(effect("CREATE TEMPORARY TABLE tmp (id INT)"), None);
+        assert_eq!(effect("DROP TEMPORARY TABLE tmp"), None);
+        assert_eq!(effect("INSERT INTO t VALUES (1)"), None);
+        assert_eq!(effect("BEGIN NOT ATOMIC SELECT 1; END"), None);
+    }
+
+    #[test]
+    fn schema_changing_statements_are_detected() {
+        assert!(changes_schema_objects("CREATE TABLE t (id INT)"));
+        assert!(changes_schema_objects("  drop view v"));
+        assert!(changes_schema_objects("ALTER TABLE t RENAME TO u"));
+        assert!(changes_schema_objects("RENAME TABLE t TO u"));
+        assert!(!changes_schema_objects(
+            "CREATE TEMPORARY TABLE tmp (id INT)"
+        ));
+        assert!(!changes_schema_objects("INSERT INTO t VALUES (1)"));
+        assert!(!changes_schema_objects("SELECT 'create'"));
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn release_rolls_back_and_plain_commit_clears_transaction() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "pool_release_txn");
+        run_sql(
+            &profile,
+            &[&format!("CREATE TABLE `{schema}`.`t` (id INT PRIMARY KEY)")],
+        );
+        let pool_id = create_pool(&profile).unwrap();
+        let conn_id = get_connection(pool_id, Some(schema.clone())).unwrap();
+        execute(pool_id, conn_id, &format!("USE `{schema}`"), None).unwrap();
+
+        begin_transaction(pool_id, conn_id).unwrap();
+        execute(pool_id, conn_id, "INSERT INTO t VALUES (1)", None).unwrap();
+        execute(pool_id, conn_id, "COMMIT", None).unwrap();
+        let after_commit = get_connection_diagnostics(pool_id, conn_id).unwrap();
+        let second_begin = begin_transaction(pool_id, conn_id);
+        execute(pool_id, conn_id, "INSERT INTO t VALUES (2)", None).unwrap();
+        release_connection(pool_id, conn_id).unwrap();
+
+        let next_id = get_connection(pool_id, Some(schema.clone())).unwrap();
+        execute(pool_id, next_id, &format!("USE `{schema}`"), None).unwrap();
+        execute(pool_id, next_id, "INSERT INTO t VALUES (3)", None).unwrap();
+        release_connection(pool_id, next_id).unwrap();
+        close_pool(pool_id);
+        let rows: Vec<i32> = with_temp_connection(&profile, |conn| {
+            conn.query(format!("SELECT id FROM `{schema}`.`t` ORDER BY id"))
+                .map_err(|e| e.to_string())
+        })
+        .unwrap();
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert_eq!(after_commit.in_transaction, 0);
+        assert!(second_begin.is_ok());
+        // 2 随归还时的回滚丢弃；3 在新借用的连接上自动提交
+        assert_eq!(rows, vec![1, 3]);
+    }
+
+    #[test]
+    #[ignore = "requires MySQL (DBW_TEST_MYSQL_URL)"]
+    fn released_connection_is_reset_before_reuse() {
+        let profile = mysql_profile();
+        let schema = fresh_schema(&profile, "pool_reset");
+        run_sql(
+            &profile,
+            &[&format!("CREATE TABLE `{schema}`.`t` (id INT PRIMARY KEY)")],
+        );
+        let pool_id = create_pool(&profile).unwrap();
+        // 只保留一条物理连接，保证下一次借用拿到的是刚归还的那条
+        resize(pool_id, 1).unwrap();
+        let conn_id = get_connection(pool_id, Some(schema.clone())).unwrap();
+        let first = query(pool_id, conn_id, "SELECT CONNECTION_ID()", None).unwrap();
+        execute(pool_id, conn_id, "SET sql_mode = '', autocommit = 0", None).unwrap();
+        let diag = get_connection_diagnostics(pool_id, conn_id).unwrap();
+        let insert = format!("INSERT INTO `{schema}`.`t` VALUES (1)");
+        execute(pool_id, conn_id, &insert, None).unwrap();
+        let temporary = format!("CREATE TEMPORARY TABLE `{schema}`.`tmp` (id INT)");
+        execute(pool_id, conn_id, &temporary, None).unwrap();
+        release_connection(pool_id, conn_id).unwrap();
+
+        let next_id = get_connection(pool_id, Some(schema.clone())).unwrap();
+        let state = query(
+            pool_id,
+            next_id,
+            &format!(
+                "SELECT CONNECTION_ID(), @@SESSION.autocommit, (SELECT COUNT(*) FROM `{schema}`.`t`)"
+            ),
+            None,
+        )
+        .unwrap();
+        let temporary = query(
+            pool_id,
+            next_id,
+            &format!("SELECT * FROM `{schema}`.`tmp`"),
+            None,
+        );
+        release_connection(pool_id, next_id).unwrap();
+        close_pool(pool_id);
+        run_sql(&profile, &[&format!("DROP DATABASE `{schema}`")]);
+
+        assert!(diag
+            .reconnect_blocked_reason
+            .as_deref()
+            .is_some_and(|reason| reason.starts_with("Autocommit is disabled")));
+        assert_eq!(state.rows[0][0], first.rows[0][0]);
+        assert_eq!(
+            state.rows[0][1..],
+            [JsonValue::from("1"), JsonValue::from("0")]
+        );
+        assert!(temporary.is_err());
+    }
+}
@@ -0,0 +1,315 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+
+/// 空间列（GEOMETRY/POINT 等）的文本表示
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpatialFormat {
+    Wkt,
+    GeoJson,
+}
+
+impl SpatialFormat {
+    /// 在 SELECT 中完成转换时使用的服务端函数
+    pub fn sql_function(self) -> &'static str {
+        match self {
+            SpatialFormat::Wkt => "ST_AsText",
+            SpatialFormat::GeoJson => "ST_AsGeoJSON",
+        }
+    }
+}
+
+type Coord = (f64, f64);
+
+enum Geometry {
+    Point(Coord),
+    LineString(Vec<Coord>),
+    Polygon(Vec<Vec<Coord>>),
+    MultiPoint(Vec<Coord>),
+    MultiLineString(Vec<Vec<Coord>>),
+    MultiPolygon(Vec<Vec<Vec<Coord>>>),
+    Collection(Vec<Geometry>),
+}
+
+/// 将 MySQL 内部的空间值（4 字节 SRID + WKB）转为 WKT 或 GeoJSON；
+/// 无法解析时返回 None，由调用方按普通二进制展示
+pub fn render_geometry(bytes: &[u8], format: SpatialFormat) -> Option<String> {
+    let mut reader = WkbReader {
+        bytes: bytes.get(4..)?,
+        pos: 0,
+        little_endian: true,
+    };
+    let geometry = reader.geometry()?;
+    if reader.pos != reader.bytes.len() {
+        return None;
+    }
+    Some(match format {
+        SpatialFormat::Wkt => to_wkt(&geometry),
+        SpatialFormat::GeoJson => to_geojson(&geometry).to_string(),
+    })
+}
+
+struct WkbReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    little_endian: bool,
+}
+
+impl WkbReader<'_> {
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let chunk = self.bytes.get(self.pos..self.pos + N)?;
+        self.pos += N;
+        chunk.try_into().ok()
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let raw = self.take::<4>()?;
+        Some(if self.little_endian {
+            u32::from_le_bytes(raw)
+        } else {
+            u32::from_be_bytes(raw)
+        })
+    }
+
+    fn f64(&mut self) -> Option<f64> {
+        let raw = self.take::<8>()?;
+        Some(if self.little_endian {
+            f64::from_le_bytes(raw)
+        } else {
+            f64::from_be_bytes(raw)
+        })
+    }
+
+    // 元素个数来自数据本身，先按剩余字节数校验，避免损坏的值触发超大分配
+    fn count(&mut self, min_item_len: usize) -> Option<usize> {
+        let count = self.u32()? as usize;
+        let remaining = self.bytes.len() - self.pos;
+        (count.checked_mul(min_item_len)? <= remaining).then_some(count)
+    }
+
+    fn coord(&mut self) -> Option<Coord> {
+        Some((self.f64()?, self.f64()?))
+    }
+
+    fn coords(&mut self) -> Option<Vec<Coord>> {
+        let count = self.count(16)?;
+        (0..count).map(|_| self.coord()).collect()
+    }
+
+    fn rings(&mut self) -> Option<Vec<Vec<Coord>>> {
+        let count = self.count(4)?;
+        (0..count).map(|_| self.coords()).collect()
+    }
+
+    fn header(&mut self) -> Option<u32> {
+        self.little_endian = match self.take::<1>()?[0] {
+            0 => false,
+            1 => true,
+            _ => return None,
+        };
+        self.u32()
+    }
+
+    // Multi* 中的每个成员都是带字节序和类型头的完整 WKB，且类型必须一致
+    fn members<T>(
+        &mut self,
+        expected_type: u32,
+        read: impl Fn(&mut Self) -> Option<T>,
+    ) -> Option<Vec<T>> {
+        let count = self.count(5)?;
+        (0..count)
+            .map(|_| {
+                if self.header()? != expected_type {
+                    return None;
+                }
+                read(self)
+            })
+            .collect()
+    }
+
+    fn geometry(&mut self) -> Option<Geometry> {
+        Some(match self.header()? {
+            1 => Geometry::Point(self.coord()?),
+            2 => Geometry::LineString(self.coords()?),
+            3 => Geometry::Polygon(self.rings()?),
+            4 => Geometry::MultiPoint(self.members(1, Self::coord)?),
+            5 => Geometry::MultiLineString(self.members(2, Self::coords)?),
+            6 => Geometry::MultiPolygon(self.members(3, Self::rings)?),
+            7 => {
+                let count = self.count(5)?;
+                Geometry::Collection((0..count).map(|_| self.geometry()).collect::<Option<_>>()?)
+            }
+            _ => return None,
+        })
+    }
+}
+
+fn wkt_coord((x, y): &Coord) -> String {
+    format!("{x} {y}")
+}
+
+fn wkt_coords(coords: &[Coord]) -> String {
+    let parts: Vec<String> = coords.iter().map(wkt_coord).collect();
+    format!("({})", parts.join(","))
+}
+
+fn wkt_rings(rings: &[Vec<Coord>]) -> String {
+    let parts: Vec<String> = rings.iter().map(|ring| wkt_coords(ring)).collect();
+    format!("({})", parts.join(","))
+}
+
+// 与 MySQL ST_AsText 的写法一致：逗号后无空格，MULTIPOINT 的每个点带括号
+fn to_wkt(geometry: &Geometry) -> String {
+    let (name, body) = match geometry {
+        Geometry::Point(coord) => ("POINT", format!("({})", wkt_coord(coord))),
+        Geometry::LineString(coords) => ("LINESTRING", wkt_coords(coords)),
+        Geometry::Polygon(rings) => ("POLYGON", wkt_rings(rings)),
+        Geometry::MultiPoint(points) => {
+            let parts: Vec<String> = points
+                .iter()
+                .map(|point| format!("({})", wkt_coord(point)))
+                .collect();
+            ("MULTIPOINT", format!("({})", parts.join(",")))
+        }
+        Geometry::MultiLineString(lines) => ("MULTILINESTRING", wkt_rings(lines)),
+        Geometry::MultiPolygon(polygons) => {
+            let parts: Vec<String> = polygons.iter().map(|rings| wkt_rings(rings)).collect();
+            ("MULTIPOLYGON", format!("({})", parts.join(",")))
+        }
+        Geometry::Collection(members) => {
+            let parts: Vec<String> = members.iter().map(to_wkt).collect();
+            ("GEOMETRYCOLLECTION", format!("({})", parts.join(",")))
+        }
+    };
+    if body == "()" {
+        format!("{name} EMPTY")
+    } else {
+        format!("{name}{body}")
+    }
+}
+
+fn geojson_coords(coords: &[Coord]) -> JsonValue {
+    coords.iter().map(|(x, y)| json!([x, y])).collect()
+}
+
+fn geojson_rings(rings: &[Vec<Coord>]) -> JsonValue {
+    rings.iter().map(|ring| geojson_coords(ring)).collect()
+}
+
+fn to_geojson(geometry: &Geometry) -> JsonValue {
+    let (kind, coordinates) = match geometry {
+        Geometry::Point((x, y)) => ("Point", json!([x, y])),
+        Geometry::LineString(coords) => ("LineString", geojson_coords(coords)),
+        Geometry::Polygon(rings) => ("Polygon", geojson_rings(rings)),
+        Geometry::MultiPoint(points) => ("MultiPoint", geojson_coords(points)),
+        Geometry::MultiLineString(lines) => ("MultiLineString", geojson_rings(lines)),
+        Geometry::MultiPolygon(polygons) => (
+            "MultiPolygon",
+            polygons.iter().map(|rings| geojson_rings(rings)).collect(),
+        ),
+        Geometry::Collection(members) => {
+            let geometries: Vec<JsonValue> = members.iter().map(to_geojson).collect();
+            return json!({"type": "GeometryCollection", "geometries": geometries});
+        }
+    };
+    json!({"type": kind, "coordinates": coordinates})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 构造 SRID 0 的小端 WKB
+    struct Wkb(Vec<u8>);
+
+    impl Wkb {
+        fn new() -> Self {
+            Wkb(vec![0; 4])
+        }
+
+        fn header(mut self, kind: u32) -> Self {
+            self.0.push(1);
+            self.0.extend(kind.to_le_bytes());
+            self
+        }
+
+        fn count(mut self, count: u32) -> Self {
+            self.0.extend(count.to_le_bytes());
+            self
+        }
+
+        fn coords(mut self, coords: &[Coord]) -> Self {
+            for (x, y) in coords {
+                self.0.extend(x.to_le_bytes());
+                self.0.extend(y.to_le_bytes());
+            }
+            self
+        }
+    }
+
+    #[test]
+    fn renders_point_and_polygon_as_wkt_and_geojson() {
+        let point = Wkb::new().header(1).coords(&[(1.5, -2.0)]).0;
+        assert_eq!(
+            render_geometry(&point, SpatialFormat::Wkt).as_deref(),
+            Some("POINT(1.5 -2)")
+        );
+        assert_eq!(
+            render_geometry(&point, SpatialFormat::GeoJson).as_deref(),
+            Some(r#"{"coordinates":[1.5,-2.0],"type":"Point"}"#)
+        );
+
+        let square = [(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 0.0)];
+        let polygon = Wkb::new().header(3).count(1).count(4).coords(&square).0;
+        assert_eq!(
+            render_geometry(&polygon, SpatialFormat::Wkt).as_deref(),
+            Some("POLYGON((0 0,4 0,4 4,0 0))")
+        );
+    }
+
+    #[test]
+    fn renders_multi_geometries_and_collections() {
+        let multipoint = Wkb::new()
+            .header(4)
+            .count(2)
+            .header(1)
+            .coords(&[(1.0, 1.0)])
+            .header(1)
+            .coords(&[(2.0, 2.0)])
+            .0;
+        assert_eq!(
+            render_geometry(&multipoint, SpatialFormat::Wkt).as_deref(),
+            Some("MULTIPOINT((1 1),(2 2))")
+        );
+
+        let collection = Wkb::new()
+            .header(7)
+            .count(2)
+            .header(1)
+            .coords(&[(1.0, 2.0)])
+            .header(2)
+            .count(2)
+            .coords(&[(0.0, 0.0), (3.0, 3.0)])
+            .0;
+        assert_eq!(
+            render_geometry(&collection, SpatialFormat::Wkt).as_deref(),
+            Some("GEOMETRYCOLLECTION(POINT(1 2),LINESTRING(0 0,3 3))")
+        );
+        let empty = Wkb::new().header(7).count(0).0;
+        assert_eq!(
+            render_geometry(&empty, SpatialFormat::Wkt).as_deref(),
+            Some("GEOMETRYCOLLECTION EMPTY")
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_or_unknown_values() {
+        let point = Wkb::new().header(1).coords(&[(1.0, 2.0)]).0;
+        assert!(render_geometry(&point[..point.len() - 1], SpatialFormat::Wkt).is_none());
+        assert!(render_geometry(&Wkb::new().header(99).0, SpatialFormat::Wkt).is_none());
+        // 声明的点数远超实际数据
+        let bogus = Wkb::new().header(2).count(u32::MAX).0;
+        assert!(render_geometry(&bogus, SpatialFormat::Wkt).is_none());
+        assert!(render_geometry(b"abc", SpatialFormat::Wkt).is_none());
+    }
+}
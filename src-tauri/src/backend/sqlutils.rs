@@ -1,4 +1,6 @@
-use crate::backend::models::DbType;
+use crate::backend::models::{DbType, SqlParam};
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
 use sqlparser::ast::Statement;
 use sqlparser::dialect::{
     GenericDialect, MsSqlDialect, MySqlDialect, PostgreSqlDialect, SQLiteDialect,
@@ -36,6 +38,111 @@ pub fn split_sql_statements(sql: &str, db_type: DbType) -> Vec<String> {
     split_sql_statements_inner(sql, db_type)
 }
 
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Like,
+    NotLike,
+    In,
+    NotIn,
+    IsNull,
+    IsNotNull,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FilterCondition {
+    pub column: String,
+    pub op: FilterOp,
+    // IN/NOT IN 为数组，IS NULL/IS NOT NULL 忽略
+    #[serde(default)]
+    pub value: JsonValue,
+}
+
+/// 生成以 AND 连接的条件（不含 WHERE 关键字），值全部作为 `?` 参数返回，供 query_prepared 使用
+pub fn build_where(
+    conditions: Vec<FilterCondition>,
+    db_type: DbType,
+) -> Result<(String, Vec<SqlParam>), String> {
+    let mut clauses = Vec::with_capacity(conditions.len());
+    let mut params = Vec::new();
+    for condition in conditions {
+        let column = quote_identifier(&condition.column, db_type)?;
+        let clause = match condition.op {
+            FilterOp::IsNull => format!("{column} IS NULL"),
+            FilterOp::IsNotNull => format!("{column} IS NOT NULL"),
+            FilterOp::In | FilterOp::NotIn => {
+                let JsonValue::Array(values) = condition.value else {
+                    return Err(format!(
+                        "Filter on {} needs an array value",
+                        condition.column
+                    ));
+                };
+                let negated = matches!(condition.op, FilterOp::NotIn);
+                // 空列表：IN 恒假，NOT IN 恒真
+                if values.is_empty() {
+                    clauses.push(if negated { "1 = 1" } else { "1 = 0" }.to_string());
+                    continue;
+                }
+                let placeholders = vec!["?"; values.len()].join(", ");
+                params.extend(values.into_iter().map(json_to_sql_param));
+                let keyword = if negated { "NOT IN" } else { "IN" };
+                format!("{column} {keyword} ({placeholders})")
+            }
+            op => {
+                let operator = match op {
+                    FilterOp::Eq => "=",
+                    FilterOp::Ne => "<>",
+                    FilterOp::Lt => "<",
+                    FilterOp::Le => "<=",
+                    FilterOp::Gt => ">",
+                    FilterOp::Ge => ">=",
+                    FilterOp::Like => "LIKE",
+                    _ => "NOT LIKE",
+                };
+                params.push(json_to_sql_param(condition.value));
+                format!("{column} {operator} ?")
+            }
+        };
+        clauses.push(clause);
+    }
+    Ok((clauses.join(" AND "), params))
+}
+
+fn quote_identifier(name: &str, db_type: DbType) -> Result<String, String> {
+    if name.trim().is_empty() {
+        return Err("Filter column is empty".to_string());
+    }
+    Ok(match db_type {
+        DbType::Mysql => format!("`{}`", name.replace('`', "``")),
+        DbType::SqlServer => format!("[{}]", name.replace(']', "]]")),
+        DbType::PostgreSql | DbType::Sqlite | DbType::Oracle => {
+            format!("\"{}\"", name.replace('"', "\"\""))
+        }
+    })
+}
+
+fn json_to_sql_param(value: JsonValue) -> SqlParam {
+    let param_type = match &value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "bool",
+        JsonValue::Number(n) if n.is_i64() => "int",
+        JsonValue::Number(n) if n.is_u64() => "uint",
+        JsonValue::Number(_) => "double",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) | JsonValue::Object(_) => "json",
+    };
+    SqlParam {
+        param_type: param_type.to_string(),
+        value,
+    }
+}
+
 fn select_dialect(db_type: DbType) -> Box<dyn sqlparser::dialect::Dialect> {
     match db_type {
         DbType::Mysql => Box::new(MySqlDialect {}),
@@ -250,6 +357,87 @@ fn split_sql_statements_inner(sql: &str, db_type: &str) -> Vec<String> {
     statements
 }
 
+/// 把 `source`. 形式的库名限定改写为 `target`.，与语句拆分使用相同的引号与注释规则，
+/// 字符串、注释以及不带点号的同名标识符保持不变（MySQL 方言）
+pub(crate) fn replace_schema_qualifier(sql: &str, source: &str, target: &str) -> String {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut output = String::with_capacity(sql.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let ch = chars[i];
+        let next_ch = chars.get(i + 1).copied().unwrap_or('\0');
+        let end = match ch {
+            '\'' | '"' | '`' => find_quote_end(&chars, i),
+            '-' if next_ch == '-' => find_line_end(&chars, i),
+            '#' => find_line_end(&chars, i),
+            // /*! ... */ 为可执行注释，其中内容照常处理
+            '/' if next_ch == '*' && chars.get(i + 2) != Some(&'!') => {
+                find_block_comment_end(&chars, i)
+            }
+            _ => {
+                output.push(ch);
+                i += 1;
+                continue;
+            }
+        };
+        let quoted = &chars[i..end];
+        if ch == '`'
+            && chars.get(end) == Some(&'.')
+            && unquote_backtick(quoted).as_deref() == Some(source)
+        {
+            output.push_str(&format!("`{}`", target.replace('`', "``")));
+        } else {
+            output.extend(quoted);
+        }
+        i = end;
+    }
+    output
+}
+
+// 返回引号结束后的位置；单/双引号支持反斜杠转义，三种引号均支持连写两次转义
+fn find_quote_end(chars: &[char], start: usize) -> usize {
+    let quote = chars[start];
+    let mut i = start + 1;
+    while i < chars.len() {
+        if chars[i] == '\\' && quote != '`' {
+            i += 2;
+            continue;
+        }
+        if chars[i] == quote {
+            if chars.get(i + 1) == Some(&quote) {
+                i += 2;
+                continue;
+            }
+            return i + 1;
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+fn find_block_comment_end(chars: &[char], start: usize) -> usize {
+    let mut i = start + 2;
+    while i + 1 < chars.len() {
+        if chars[i] == '*' && chars[i + 1] == '/' {
+            return i + 2;
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+fn unquote_backtick(quoted: &[char]) -> Option<String> {
+    if quoted.len() < 2 || quoted[quoted.len() - 1] != '`' {
+        return None;
+    }
+    Some(
+        quoted[1..quoted.len() - 1]
+            .iter()
+            .collect::<String>()
+            .replace("``", "`"),
+    )
+}
+
 fn find_line_end(chars: &[char], start: usize) -> usize {
     let mut i = start;
     while i < chars.len() {
@@ -356,3 +544,85 @@ fn is_compound_block_opener(word: &str, chars: &[char], index: usize) -> bool {
         _ => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_schema_qualifier_outside_quotes() {
+        let sql = "INSERT INTO `shop`.`orders` VALUES (1, 'see `shop`.`orders`', \"`shop`.x\") -- `shop`.`t`\n/* `shop`.`t` */ SELECT * FROM `shop`.`items` JOIN `shop` ON 1 = 1";
+        assert_eq!(
+            replace_schema_qualifier(sql, "shop", "shop_copy"),
+            "INSERT INTO `shop_copy`.`orders` VALUES (1, 'see `shop`.`orders`', \"`shop`.x\") -- `shop`.`t`\n/* `shop`.`t` */ SELECT * FROM `shop_copy`.`items` JOIN `shop` ON 1 = 1"
+        );
+    }
+
+    #[test]
+    fn schema_qualifier_handles_escapes() {
+        let sql = "SELECT 'it\\'s `a`.b', `we``ird`.`t` FROM `a`.`t` /*!40101 JOIN `a`.`u` */";
+        assert_eq!(
+            replace_schema_qualifier(sql, "a", "b"),
+            "SELECT 'it\\'s `a`.b', `we``ird`.`t` FROM `b`.`t` /*!40101 JOIN `b`.`u` */"
+        );
+        assert_eq!(
+            replace_schema_qualifier("SELECT * FROM `we``ird`.`t`", "we`ird", "x"),
+            "SELECT * FROM `x`.`t`"
+        );
+    }
+
+    fn condition(column: &str, op: FilterOp, value: JsonValue) -> FilterCondition {
+        FilterCondition {
+            column: column.to_string(),
+            op,
+            value,
+        }
+    }
+
+    #[test]
+    fn builds_parameterized_where_clause() {
+        let conditions = vec![
+            condition("id", FilterOp::Eq, serde_json::json!(7)),
+            condition("name", FilterOp::Like, serde_json::json!("a%'; DROP")),
+            condition("status", FilterOp::In, serde_json::json!(["new", "paid"])),
+            condition("deleted_at", FilterOp::IsNull, JsonValue::Null),
+        ];
+
+        let (clause, params) = build_where(conditions, DbType::Mysql).unwrap();
+        assert_eq!(
+            clause,
+            "`id` = ? AND `name` LIKE ? AND `status` IN (?, ?) AND `deleted_at` IS NULL"
+        );
+        let params: Vec<(&str, &JsonValue)> = params
+            .iter()
+            .map(|p| (p.param_type.as_str(), &p.value))
+            .collect();
+        assert_eq!(
+            params,
+            vec![
+                ("int", &serde_json::json!(7)),
+                ("string", &serde_json::json!("a%'; DROP")),
+                ("string", &serde_json::json!("new")),
+                ("string", &serde_json::json!("paid")),
+            ]
+        );
+    }
+
+    #[test]
+    fn where_clause_rejects_non_array_in_and_handles_empty_list() {
+        let err = build_where(
+            vec![condition("id", FilterOp::In, serde_json::json!(1))],
+            DbType::Mysql,
+        )
+        .unwrap_err();
+        assert_eq!(err, "Filter on id needs an array value");
+
+        let (clause, params) = build_where(
+            vec![condition("id", FilterOp::In, serde_json::json!([]))],
+            DbType::Mysql,
+        )
+        .unwrap();
+        assert_eq!(clause, "1 = 0");
+        assert!(params.is_empty());
+    }
+}
@@ -2,15 +2,20 @@ mod backend;
 
 use backend::app_config;
 use backend::backup;
+use backend::bundle;
 use backend::config;
+use backend::convert;
 use backend::executor;
 use backend::export as export_mod;
 use backend::favorites;
 use backend::import as import_mod;
 use backend::json as json_mod;
 use backend::metadata;
-use backend::models::{ConnectionProfile, DbType, FavoriteItem, FavoriteType, SqlParam, UserModel};
+use backend::models::{
+    ConnectionProfile, DbType, FavoriteItem, FavoriteType, ProfileRef, SqlParam, UserModel,
+};
 use backend::pool;
+use backend::spatial;
 use backend::sqlutils;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -255,7 +260,8 @@ async fn warmup_country_detection_cache() {
 }
 
 #[tauri::command]
-fn pool_create(profile: ConnectionProfile) -> Result<u64, String> {
+fn pool_create(profile: ProfileRef) -> Result<u64, String> {
+    let profile = config::resolve_ref(profile)?;
     pool::create_pool(&profile)
 }
 
@@ -264,18 +270,67 @@ fn pool_get_connection(pool_id: u64, initial_database: Option<String>) -> Result
     pool::get_connection(pool_id, initial_database)
 }
 
+#[tauri::command]
+fn pool_result_to_table(
+    pool_id: u64,
+    conn_id: u64,
+    select_sql: String,
+    target_schema: Option<String>,
+    target_table: String,
+    temporary: bool,
+) -> Result<pool::ExecResult, String> {
+    pool::result_to_table(
+        pool_id,
+        conn_id,
+        &select_sql,
+        target_schema.as_deref(),
+        &target_table,
+        temporary,
+    )
+}
+
 #[tauri::command]
 fn pool_set_database(pool_id: u64, conn_id: u64, database: Option<String>) -> Result<(), String> {
     pool::set_connection_database(pool_id, conn_id, database)
 }
 
+#[tauri::command]
+fn pool_get_current_database(
+    pool_id: u64,
+    conn_id: u64,
+    verify: Option<bool>,
+) -> Result<Option<String>, String> {
+    pool::get_current_database(pool_id, conn_id, verify.unwrap_or(false))
+}
+
+#[tauri::command]
+fn pool_set_autocommit(pool_id: u64, conn_id: u64, enabled: bool) -> Result<(), String> {
+    pool::set_autocommit(pool_id, conn_id, enabled)
+}
+
+#[tauri::command]
+fn pool_estimate(pool_id: u64, conn_id: u64, sql: String) -> Result<pool::QueryEstimate, String> {
+    pool::estimate(pool_id, conn_id, &sql)
+}
+
+#[tauri::command]
+fn pool_get_connection_diagnostics(pool_id: u64, conn_id: u64) -> Result<pool::ConnDiag, String> {
+    pool::get_connection_diagnostics(pool_id, conn_id)
+}
+
+#[tauri::command]
+fn pool_get_activity(pool_id: u64, conn_id: u64) -> Result<Vec<pool::ActivityEvent>, String> {
+    pool::get_activity(pool_id, conn_id)
+}
+
 #[tauri::command]
 fn pool_release_connection(pool_id: u64, conn_id: u64) -> Result<bool, String> {
     pool::release_connection(pool_id, conn_id)
 }
 
 #[tauri::command]
-fn pool_test_connection(profile: ConnectionProfile) -> Result<bool, String> {
+fn pool_test_connection(profile: ProfileRef) -> Result<bool, String> {
+    let profile = config::resolve_ref(profile)?;
     pool::test_connection(&profile)
 }
 
@@ -284,6 +339,16 @@ fn pool_get_stats(pool_id: u64) -> Result<pool::PoolStats, String> {
     pool::get_stats(pool_id)
 }
 
+#[tauri::command]
+fn pool_warmup(pool_id: u64, target_idle: usize) -> Result<pool::PoolStats, String> {
+    pool::warmup(pool_id, target_idle)
+}
+
+#[tauri::command]
+fn pool_resize(pool_id: u64, max_size: usize) -> Result<pool::PoolStats, String> {
+    pool::resize(pool_id, max_size)
+}
+
 // NEW: 获取详细统计信息
 #[tauri::command]
 fn pool_get_detailed_stats(pool_id: u64) -> Result<pool::DetailedPoolStats, String> {
@@ -332,8 +397,13 @@ fn pool_get_connection_properties(
 }
 
 #[tauri::command]
-fn pool_query(pool_id: u64, conn_id: u64, sql: String) -> Result<pool::QueryResult, String> {
-    pool::query(pool_id, conn_id, &sql)
+fn pool_query(
+    pool_id: u64,
+    conn_id: u64,
+    sql: String,
+    timeout_ms: Option<u64>,
+) -> Result<pool::QueryResult, String> {
+    pool::query(pool_id, conn_id, &sql, timeout_ms)
 }
 
 #[tauri::command]
@@ -358,8 +428,37 @@ fn pool_query_multi(
 }
 
 #[tauri::command]
-fn pool_execute(pool_id: u64, conn_id: u64, sql: String) -> Result<pool::ExecResult, String> {
-    pool::execute(pool_id, conn_id, &sql)
+fn pool_query_multi_detailed(
+    pool_id: u64,
+    conn_id: u64,
+    sql: String,
+) -> Result<Vec<pool::StatementOutcome>, String> {
+    pool::query_multi_detailed(pool_id, conn_id, &sql)
+}
+
+#[tauri::command]
+fn pool_execute(
+    pool_id: u64,
+    conn_id: u64,
+    sql: String,
+    timeout_ms: Option<u64>,
+) -> Result<pool::ExecResult, String> {
+    pool::execute(pool_id, conn_id, &sql, timeout_ms)
+}
+
+#[tauri::command]
+fn pool_begin_transaction(pool_id: u64, conn_id: u64) -> Result<(), String> {
+    pool::begin_transaction(pool_id, conn_id)
+}
+
+#[tauri::command]
+fn pool_commit(pool_id: u64, conn_id: u64) -> Result<(), String> {
+    pool::commit(pool_id, conn_id)
+}
+
+#[tauri::command]
+fn pool_rollback(pool_id: u64, conn_id: u64) -> Result<(), String> {
+    pool::rollback(pool_id, conn_id)
 }
 
 #[tauri::command]
@@ -372,6 +471,34 @@ fn pool_query_prepared(
     pool::query_prepared(pool_id, conn_id, &sql, params)
 }
 
+#[tauri::command]
+fn pool_query_prepared_named(
+    pool_id: u64,
+    conn_id: u64,
+    sql: String,
+    params: HashMap<String, SqlParam>,
+) -> Result<pool::QueryResult, String> {
+    pool::query_prepared_named(pool_id, conn_id, &sql, params)
+}
+
+#[tauri::command]
+fn pool_set_binary_display(pool_id: u64, mode: pool::BinaryDisplay) -> Result<(), String> {
+    pool::set_binary_display(pool_id, mode)
+}
+
+#[tauri::command]
+fn pool_set_pretty_json(pool_id: u64, enabled: bool) -> Result<(), String> {
+    pool::set_pretty_json(pool_id, enabled)
+}
+
+#[tauri::command]
+fn pool_set_spatial_format(
+    pool_id: u64,
+    format: Option<spatial::SpatialFormat>,
+) -> Result<(), String> {
+    pool::set_spatial_format(pool_id, format)
+}
+
 #[tauri::command]
 fn pool_query_prepared_multi(
     pool_id: u64,
@@ -403,168 +530,338 @@ fn pool_close_all() {
 }
 
 #[tauri::command]
-fn metadata_list_databases(profile: ConnectionProfile) -> Result<Vec<String>, String> {
+fn metadata_list_databases(profile: ProfileRef) -> Result<Vec<String>, String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::list_databases(&profile)
 }
 
 #[tauri::command]
-fn metadata_list_tables(
-    profile: ConnectionProfile,
-    database: String,
-) -> Result<Vec<String>, String> {
+fn metadata_list_tables(profile: ProfileRef, database: String) -> Result<Vec<String>, String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::list_tables(&profile, &database)
 }
 
 #[tauri::command]
 fn metadata_list_table_details(
-    profile: ConnectionProfile,
+    profile: ProfileRef,
     database: String,
 ) -> Result<Vec<metadata::TableDetail>, String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::list_table_details(&profile, &database)
 }
 
 #[tauri::command]
-fn metadata_list_views(
-    profile: ConnectionProfile,
-    database: String,
-) -> Result<Vec<String>, String> {
+fn metadata_list_views(profile: ProfileRef, database: String) -> Result<Vec<String>, String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::list_views(&profile, &database)
 }
 
 #[tauri::command]
 fn metadata_list_view_details(
-    profile: ConnectionProfile,
+    profile: ProfileRef,
     database: String,
 ) -> Result<Vec<metadata::ViewDetail>, String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::list_view_details(&profile, &database)
 }
 
 #[tauri::command]
-fn metadata_list_functions(
-    profile: ConnectionProfile,
-    database: String,
-) -> Result<Vec<String>, String> {
+fn metadata_list_functions(profile: ProfileRef, database: String) -> Result<Vec<String>, String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::list_functions(&profile, &database)
 }
 
+#[tauri::command]
+fn metadata_get_server_overview(profile: ProfileRef) -> Result<metadata::ServerOverview, String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::get_server_overview(&profile)
+}
+
+#[tauri::command]
+fn metadata_get_storage_breakdown(
+    profile: ProfileRef,
+    database: String,
+) -> Result<Vec<metadata::TableStorage>, String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::get_storage_breakdown(&profile, &database)
+}
+
+#[tauri::command]
+fn metadata_build_row_locator(
+    profile: ProfileRef,
+    database: String,
+    table: String,
+    row: std::collections::HashMap<String, String>,
+) -> Result<metadata::RowLocator, String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::build_row_locator(&profile, &database, &table, &row)
+}
+
+#[tauri::command]
+fn metadata_fetch_row_by_locator(
+    profile: ProfileRef,
+    database: String,
+    table: String,
+    keys: Vec<metadata::RowKey>,
+) -> Result<Option<std::collections::BTreeMap<String, Option<String>>>, String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::fetch_row_by_locator(&profile, &database, &table, &keys)
+}
+
+#[tauri::command]
+fn metadata_sample_rows(
+    profile: ProfileRef,
+    database: String,
+    table: String,
+    n: Option<u64>,
+) -> Result<pool::QueryResult, String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::sample_rows(&profile, &database, &table, n.unwrap_or(100))
+}
+
+#[tauri::command]
+fn metadata_warm_schema(
+    app_handle: tauri::AppHandle,
+    profile: ProfileRef,
+    database: String,
+) -> Result<(), String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::warm_schema(profile, database, app_handle);
+    Ok(())
+}
+
+#[tauri::command]
+fn metadata_invalidate_cache(profile: ProfileRef) -> Result<(), String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::invalidate_metadata_cache(&profile);
+    Ok(())
+}
+
+#[tauri::command]
+fn metadata_clear_cache() {
+    metadata::clear_metadata_cache();
+}
+
 #[tauri::command]
 fn metadata_list_routines_with_details(
-    profile: ConnectionProfile,
+    profile: ProfileRef,
     database: String,
 ) -> Result<Vec<metadata::RoutineDetail>, String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::list_routines_with_details(&profile, &database)
 }
 
 #[tauri::command]
 fn metadata_list_function_details(
-    profile: ConnectionProfile,
+    profile: ProfileRef,
     database: String,
 ) -> Result<Vec<metadata::FunctionDetail>, String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::list_function_details(&profile, &database)
 }
 
 #[tauri::command]
 fn metadata_list_columns(
-    profile: ConnectionProfile,
+    profile: ProfileRef,
     database: String,
     table: String,
 ) -> Result<Vec<std::collections::BTreeMap<String, String>>, String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::list_columns(&profile, &database, &table)
 }
 
 #[tauri::command]
 fn metadata_list_foreign_keys(
-    profile: ConnectionProfile,
+    profile: ProfileRef,
     database: String,
     table: String,
+    order: Option<metadata::ListOrder>,
 ) -> Result<Vec<std::collections::BTreeMap<String, String>>, String> {
-    metadata::list_foreign_keys(&profile, &database, &table)
+    let profile = config::resolve_ref(profile)?;
+    metadata::list_foreign_keys(&profile, &database, &table, order.unwrap_or_default())
 }
 
 #[tauri::command]
 fn metadata_get_er_diagram_data(
-    profile: ConnectionProfile,
+    profile: ProfileRef,
     database: String,
 ) -> Result<metadata::ErDiagramData, String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::get_er_diagram_data(&profile, &database)
 }
 
 #[tauri::command]
-fn metadata_export_er_diagram_sql(
-    profile: ConnectionProfile,
+fn metadata_get_fk_graph(
+    profile: ProfileRef,
     database: String,
-) -> Result<String, String> {
+) -> Result<metadata::FkGraph, String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::get_fk_graph(&profile, &database)
+}
+
+#[tauri::command]
+fn metadata_export_er_diagram_sql(profile: ProfileRef, database: String) -> Result<String, String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::export_er_diagram_sql(&profile, &database)
 }
 
 #[tauri::command]
 fn metadata_list_indexes(
-    profile: ConnectionProfile,
+    profile: ProfileRef,
     database: String,
     table: String,
+    order: Option<metadata::ListOrder>,
 ) -> Result<Vec<std::collections::BTreeMap<String, String>>, String> {
-    metadata::list_indexes(&profile, &database, &table)
+    let profile = config::resolve_ref(profile)?;
+    metadata::list_indexes(&profile, &database, &table, order.unwrap_or_default())
+}
+
+#[tauri::command]
+fn metadata_find_unused_indexes(
+    profile: ProfileRef,
+    database: String,
+) -> Result<Vec<metadata::UnusedIndex>, String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::find_unused_indexes(&profile, &database)
+}
+
+#[tauri::command]
+fn metadata_list_indexes_detailed(
+    profile: ProfileRef,
+    database: String,
+    table: String,
+) -> Result<Vec<metadata::IndexModel>, String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::list_indexes_detailed(&profile, &database, &table)
+}
+
+#[tauri::command]
+fn metadata_preview_table_changes(
+    profile: ProfileRef,
+    database: String,
+    table: String,
+    proposed: metadata::TableModel,
+) -> Result<metadata::TableChangePreview, String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::preview_table_changes(&profile, &database, &table, &proposed)
 }
 
 #[tauri::command]
 fn metadata_list_triggers(
-    profile: ConnectionProfile,
+    profile: ProfileRef,
     database: String,
     table: String,
+    order: Option<metadata::ListOrder>,
 ) -> Result<Vec<std::collections::BTreeMap<String, String>>, String> {
-    metadata::list_triggers(&profile, &database, &table)
+    let profile = config::resolve_ref(profile)?;
+    metadata::list_triggers(&profile, &database, &table, order.unwrap_or_default())
 }
 
 #[tauri::command]
 fn metadata_list_checks(
-    profile: ConnectionProfile,
+    profile: ProfileRef,
     database: String,
     table: String,
 ) -> Result<Vec<std::collections::BTreeMap<String, String>>, String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::list_checks(&profile, &database, &table)
 }
 
 #[tauri::command]
 fn metadata_load_ddl(
-    profile: ConnectionProfile,
+    profile: ProfileRef,
     database: String,
     table: String,
 ) -> Result<String, String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::load_ddl(&profile, &database, &table)
 }
 
 #[tauri::command]
-fn metadata_get_current_user_info(profile: ConnectionProfile) -> Result<String, String> {
+fn metadata_export_schema_snapshot(
+    profile: ProfileRef,
+    database: String,
+) -> Result<String, String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::export_schema_snapshot(&profile, &database)
+}
+
+#[tauri::command]
+fn metadata_diff_against_snapshot(
+    profile: ProfileRef,
+    database: String,
+    snapshot_json: String,
+) -> Result<metadata::SchemaDiff, String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::diff_against_snapshot(&profile, &database, &snapshot_json)
+}
+
+#[tauri::command]
+fn metadata_clone_database(
+    profile: ProfileRef,
+    source_database: String,
+    target_database: String,
+    with_data: bool,
+) -> Result<metadata::CloneDatabaseResult, String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::clone_database(&profile, &source_database, &target_database, with_data)
+}
+
+#[tauri::command]
+fn metadata_rename_table(
+    profile: ProfileRef,
+    database: String,
+    old_name: String,
+    new_name: String,
+) -> Result<metadata::RenameTableResult, String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::rename_table(&profile, &database, &old_name, &new_name)
+}
+
+#[tauri::command]
+fn metadata_get_current_user_info(profile: ProfileRef) -> Result<String, String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::get_current_user_info(&profile)
 }
 
 #[tauri::command]
-fn metadata_get_all_users(
-    profile: ConnectionProfile,
-) -> Result<Vec<metadata::UserSummary>, String> {
+fn metadata_get_all_users(profile: ProfileRef) -> Result<Vec<metadata::UserSummary>, String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::get_all_users(&profile)
 }
 
 #[tauri::command]
 fn metadata_get_user_detail(
-    profile: ConnectionProfile,
+    profile: ProfileRef,
     username: String,
     host: String,
 ) -> Result<String, String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::get_user_detail(&profile, &username, &host)
 }
 
 #[tauri::command]
 fn metadata_get_user_model(
-    profile: ConnectionProfile,
+    profile: ProfileRef,
     username: String,
     host: String,
 ) -> Result<metadata::UserModelPayload, String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::get_user_model(&profile, &username, &host)
 }
 
 #[tauri::command]
-fn metadata_get_all_databases(profile: ConnectionProfile) -> Result<Vec<String>, String> {
+fn metadata_audit_privileges(
+    profile: ProfileRef,
+    filter: Option<metadata::PrivFilter>,
+) -> Result<Vec<metadata::UserPrivilegeSummary>, String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::audit_privileges(&profile, filter)
+}
+
+#[tauri::command]
+fn metadata_get_all_databases(profile: ProfileRef) -> Result<Vec<String>, String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::get_all_databases(&profile)
 }
 
@@ -577,34 +874,88 @@ fn metadata_generate_user_sql(
     metadata::generate_user_sql(&user, is_new_user, original.as_ref())
 }
 
+#[tauri::command]
+fn metadata_get_sql_mode(profile: ProfileRef, global: bool) -> Result<Vec<String>, String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::get_sql_mode(&profile, global)
+}
+
+#[tauri::command]
+fn metadata_set_sql_mode(
+    profile: ProfileRef,
+    modes: Vec<String>,
+    global: bool,
+    pool_id: Option<u64>,
+    conn_id: Option<u64>,
+) -> Result<String, String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::set_sql_mode(&profile, &modes, global, pool_id.zip(conn_id))
+}
+
+#[tauri::command]
+fn metadata_generate_drop_script(
+    profile: ProfileRef,
+    database: String,
+    object_kinds: Vec<metadata::ObjectKind>,
+) -> Result<String, String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::generate_drop_script(&profile, &database, &object_kinds)
+}
+
+#[tauri::command]
+fn metadata_get_event_scheduler_status(profile: ProfileRef) -> Result<bool, String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::get_event_scheduler_status(&profile)
+}
+
+#[tauri::command]
+fn metadata_set_event_scheduler(profile: ProfileRef, enabled: bool) -> Result<String, String> {
+    let profile = config::resolve_ref(profile)?;
+    metadata::set_event_scheduler(&profile, enabled)
+}
+
 #[tauri::command]
 fn metadata_execute_sql(
-    profile: ConnectionProfile,
+    profile: ProfileRef,
     sql: String,
     database: Option<String>,
 ) -> Result<(), String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::execute_sql(&profile, &sql, database.as_deref())
 }
 
 #[tauri::command]
 fn metadata_get_function_ddl(
-    profile: ConnectionProfile,
+    profile: ProfileRef,
     database: String,
     name: String,
     routine_type: String,
 ) -> Result<String, String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::get_function_ddl(&profile, &database, &name, &routine_type)
 }
 
 #[tauri::command]
 fn metadata_get_routine_params(
-    profile: ConnectionProfile,
+    profile: ProfileRef,
     database: String,
     name: String,
 ) -> Result<Vec<metadata::RoutineParam>, String> {
+    let profile = config::resolve_ref(profile)?;
     metadata::get_routine_params(&profile, &database, &name)
 }
 
+#[tauri::command]
+fn metadata_get_routine_header_sql(
+    profile: ProfileRef,
+    database: String,
+    name: String,
+) -> Result<String, String> {
+    let profile = config::resolve_ref(profile)?;
+    let detail = metadata::get_routine_detail(&profile, &database, &name)?;
+    Ok(metadata::generate_routine_header_sql(&detail))
+}
+
 #[tauri::command]
 fn config_load_connections() -> Result<Vec<ConnectionProfile>, String> {
     config::load_connections()
@@ -616,8 +967,21 @@ fn config_save_connections(profiles: Vec<ConnectionProfile>) -> Result<(), Strin
 }
 
 #[tauri::command]
-fn config_import_connections(file_path: String) -> Result<Vec<ConnectionProfile>, String> {
-    config::import_connections(std::path::Path::new(&file_path))
+fn config_test_all_connections(
+    profiles: Vec<ConnectionProfile>,
+) -> Vec<config::ConnectionTestStatus> {
+    config::test_all(profiles)
+}
+
+#[tauri::command]
+fn config_import_connections(
+    file_path: String,
+    missing_env: Option<config::MissingEnvVar>,
+) -> Result<Vec<ConnectionProfile>, String> {
+    config::import_connections(
+        std::path::Path::new(&file_path),
+        missing_env.unwrap_or_default(),
+    )
 }
 
 #[tauri::command]
@@ -643,6 +1007,32 @@ fn app_config_flush() -> Result<(), String> {
     app_config::flush()
 }
 
+#[tauri::command]
+fn app_export_bundle(
+    file_path: String,
+    passphrase: String,
+    include_passwords: Option<bool>,
+) -> Result<(), String> {
+    bundle::export_bundle(
+        std::path::Path::new(&file_path),
+        &passphrase,
+        include_passwords.unwrap_or(false),
+    )
+}
+
+#[tauri::command]
+fn app_import_bundle(
+    file_path: String,
+    passphrase: String,
+    merge: Option<bool>,
+) -> Result<bundle::BundleImportResult, String> {
+    bundle::import_bundle(
+        std::path::Path::new(&file_path),
+        &passphrase,
+        merge.unwrap_or(true),
+    )
+}
+
 #[tauri::command]
 fn favorites_get_all() -> Result<Vec<FavoriteItem>, String> {
     favorites::get_all()
@@ -708,6 +1098,14 @@ fn sql_extract_view_select(ddl: String, db_type: DbType) -> Result<Option<String
     sqlutils::extract_view_select(&ddl, db_type)
 }
 
+#[tauri::command]
+fn sql_build_where(
+    conditions: Vec<sqlutils::FilterCondition>,
+    db_type: DbType,
+) -> Result<(String, Vec<SqlParam>), String> {
+    sqlutils::build_where(conditions, db_type)
+}
+
 #[tauri::command]
 fn sql_split_statements(sql: String, db_type: DbType) -> Vec<String> {
     sqlutils::split_sql_statements(&sql, db_type)
@@ -1000,7 +1398,7 @@ fn pool_execute_statement_page_impl(
                     }),
                 }
             } else {
-                match pool::query(pool_id, conn_id, &sql) {
+                match pool::query(pool_id, conn_id, &sql, None) {
                     Ok(query_result) => entries.push(ScriptExecutePageEntry {
                         statement_index,
                         sql,
@@ -1061,7 +1459,7 @@ fn pool_execute_statement_page_impl(
         }
 
         let mut branch_had_error = false;
-        match pool::execute(pool_id, conn_id, &sql) {
+        match pool::execute(pool_id, conn_id, &sql, None) {
             Ok(exec_result) => entries.push(ScriptExecutePageEntry {
                 statement_index,
                 sql,
@@ -1165,6 +1563,28 @@ fn json_parse_canonical(json: String) -> Result<String, String> {
     json_mod::parse_to_canonical_json(&json)
 }
 
+#[tauri::command]
+fn import_preview_rows(
+    profile: ProfileRef,
+    database: String,
+    table: String,
+    file_path: String,
+    format: String,
+    limit: Option<usize>,
+) -> Result<import_mod::PreviewResult, String> {
+    let profile = config::resolve_ref(profile)?;
+    let format = import_mod::ImportFormat::from_str(&format)
+        .ok_or_else(|| format!("Unsupported import format: {format}"))?;
+    import_mod::preview_rows(
+        &profile,
+        &database,
+        &table,
+        std::path::Path::new(&file_path),
+        format,
+        limit.unwrap_or(20),
+    )
+}
+
 // Legacy import/export commands for backward compatibility
 #[tauri::command]
 fn import_from_csv(
@@ -1213,22 +1633,63 @@ fn import_from_jsonl(
 
 // New unified import command
 #[tauri::command]
-fn import_table(
-    profile: ConnectionProfile,
+async fn import_table(
+    app_handle: tauri::AppHandle,
+    profile: ProfileRef,
     database: String,
     table: String,
     file_path: String,
     format: String,
+    options: Option<import_mod::ImportOptions>,
 ) -> import_mod::ImportResult {
+    let profile = match config::resolve_ref(profile) {
+        Ok(profile) => profile,
+        Err(err) => return import_mod::ImportResult::failed(err),
+    };
     let import_format =
         import_mod::ImportFormat::from_str(&format).unwrap_or(import_mod::ImportFormat::Csv);
-    import_mod::import_table(
-        &profile,
-        &database,
-        &table,
-        std::path::Path::new(&file_path),
-        import_format,
-    )
+    let mut options = options.unwrap_or_default();
+    options.progress = import_progress_callback(&app_handle, options.import_id.as_deref());
+    // 放到阻塞线程执行，导入期间 import_cancel 才能被处理
+    tauri::async_runtime::spawn_blocking(move || {
+        import_mod::import_table_with_options(
+            &profile,
+            &database,
+            &table,
+            std::path::Path::new(&file_path),
+            import_format,
+            &options,
+        )
+    })
+    .await
+    .unwrap_or_else(|err| {
+        import_mod::ImportResult::failed(format!("failed to join import task: {err}"))
+    })
+}
+
+#[tauri::command]
+async fn import_multi_table_json(
+    profile: ProfileRef,
+    database: String,
+    file_path: String,
+    options: Option<import_mod::ImportOptions>,
+) -> Result<Vec<import_mod::TableImportResult>, String> {
+    let profile = config::resolve_ref(profile)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        import_mod::import_multi_table_json(
+            &profile,
+            &database,
+            std::path::Path::new(&file_path),
+            &options.unwrap_or_default(),
+        )
+    })
+    .await
+    .map_err(|err| format!("failed to join import task: {err}"))?
+}
+
+#[tauri::command]
+fn import_cancel(import_id: String) -> bool {
+    import_mod::cancel_import(&import_id)
 }
 
 #[tauri::command]
@@ -1262,32 +1723,147 @@ fn export_to_jsonl(
 }
 
 // New unified export commands
+// 表导出在后台线程执行，导出期间仍可调用 export_cancel
 #[tauri::command]
-fn export_table(
-    profile: ConnectionProfile,
+async fn export_table(
+    app_handle: tauri::AppHandle,
+    profile: ProfileRef,
     database: String,
     table: String,
     file_path: String,
     format: String,
+    options: Option<export_mod::TableExportOptions>,
 ) -> export_mod::ExportResult {
+    let profile = match config::resolve_ref(profile) {
+        Ok(profile) => profile,
+        Err(err) => return failed_export_result(file_path, err),
+    };
     let export_format =
         export_mod::ExportFormat::from_str(&format).unwrap_or(export_mod::ExportFormat::Csv);
-    export_mod::export_table(
-        &profile,
-        &database,
-        &table,
-        std::path::Path::new(&file_path),
-        export_format,
-    )
+    let options = options.unwrap_or_default();
+    watch_export_progress(&app_handle, options.job_id.as_deref());
+    let path = file_path.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        export_mod::export_table_with_csv_options(
+            &profile,
+            &database,
+            &table,
+            std::path::Path::new(&path),
+            export_format,
+            &options,
+            &export_mod::CsvOptions::default(),
+        )
+    })
+    .await
+    .unwrap_or_else(|err| {
+        failed_export_result(file_path, format!("failed to join export task: {err}"))
+    })
+}
+
+#[tauri::command]
+async fn export_table_csv_opts(
+    app_handle: tauri::AppHandle,
+    profile: ProfileRef,
+    database: String,
+    table: String,
+    file_path: String,
+    csv_options: export_mod::CsvOptions,
+    options: Option<export_mod::TableExportOptions>,
+) -> export_mod::ExportResult {
+    let profile = match config::resolve_ref(profile) {
+        Ok(profile) => profile,
+        Err(err) => return failed_export_result(file_path, err),
+    };
+    let options = options.unwrap_or_default();
+    watch_export_progress(&app_handle, options.job_id.as_deref());
+    let path = file_path.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        export_mod::export_table_with_csv_options(
+            &profile,
+            &database,
+            &table,
+            std::path::Path::new(&path),
+            export_mod::ExportFormat::Csv,
+            &options,
+            &csv_options,
+        )
+    })
+    .await
+    .unwrap_or_else(|err| {
+        failed_export_result(file_path, format!("failed to join export task: {err}"))
+    })
+}
+
+#[tauri::command]
+fn export_cancel(job_id: String) -> bool {
+    export_mod::cancel_export(&job_id)
+}
+
+// 导出进度以 `export-progress:<jobId>` 事件发送，payload 为 { rowsExported, done }
+fn watch_export_progress(app_handle: &tauri::AppHandle, job_id: Option<&str>) {
+    let Some(job_id) = job_id else {
+        return;
+    };
+    let event = format!("export-progress:{job_id}");
+    let app_handle = app_handle.clone();
+    export_mod::watch_export(
+        job_id,
+        std::sync::Arc::new(move |progress: &export_mod::ExportProgress| {
+            let _ = app_handle.emit(&event, progress.clone());
+        }),
+    );
+}
+
+// 导入进度以 `import-progress:<importId>` 事件发送，payload 为 { rowsImported, done }
+fn import_progress_callback(
+    app_handle: &tauri::AppHandle,
+    import_id: Option<&str>,
+) -> Option<import_mod::ImportProgressFn> {
+    let import_id = import_id.filter(|id| !id.is_empty())?;
+    let event = format!("import-progress:{import_id}");
+    let app_handle = app_handle.clone();
+    Some(std::sync::Arc::new(
+        move |progress: &import_mod::ImportProgress| {
+            let _ = app_handle.emit(&event, progress.clone());
+        },
+    ))
+}
+
+fn failed_export_result(file_path: String, err: String) -> export_mod::ExportResult {
+    export_mod::ExportResult {
+        success: false,
+        rows_exported: 0,
+        file_path,
+        duration_ms: 0,
+        rows_per_sec: 0.0,
+        bytes_per_sec: 0.0,
+        checksum: None,
+        error: Some(err),
+    }
+}
+
+#[tauri::command]
+fn export_table_tab(
+    profile: ProfileRef,
+    database: String,
+    table: String,
+    out_dir: String,
+) -> Result<export_mod::TabExportResult, String> {
+    let profile = config::resolve_ref(profile)?;
+    if out_dir.trim().is_empty() {
+        return Err("导出目录不能为空".to_string());
+    }
+    export_mod::export_table_tab(&profile, &database, &table, std::path::Path::new(&out_dir))
 }
 
 #[tauri::command]
 fn export_query_result(
     file_path: String,
     headers: Vec<String>,
-    rows: Vec<Vec<String>>,
+    rows: Vec<Vec<Option<String>>>,
     format: String,
     table_name: Option<String>,
+    options: Option<export_mod::QueryExportOptions>,
 ) -> Result<export_mod::ExportResult, String> {
     let export_format =
         export_mod::ExportFormat::from_str(&format).unwrap_or(export_mod::ExportFormat::Csv);
@@ -1303,7 +1879,126 @@ fn export_query_result(
         }
     }
 
-    export_mod::export_query_result(path, &headers, &rows, export_format, table_name.as_deref())
+    let mut options = options.unwrap_or_default();
+    if options.table_name.is_none() {
+        options.table_name = table_name;
+    }
+    export_mod::export_query_result(path, &headers, &rows, export_format, &options)
+}
+
+#[tauri::command]
+fn export_query_result_to_string(
+    headers: Vec<String>,
+    rows: Vec<Vec<Option<String>>>,
+    format: String,
+    table_name: Option<String>,
+    options: Option<export_mod::QueryExportOptions>,
+) -> Result<String, String> {
+    let export_format =
+        export_mod::ExportFormat::from_str(&format).unwrap_or(export_mod::ExportFormat::Csv);
+    let mut options = options.unwrap_or_default();
+    if options.table_name.is_none() {
+        options.table_name = table_name;
+    }
+    export_mod::export_query_result_to_string(&headers, &rows, export_format, &options)
+}
+
+#[tauri::command]
+async fn pool_query_to_file(
+    app_handle: tauri::AppHandle,
+    pool_id: u64,
+    conn_id: u64,
+    sql: String,
+    file_path: String,
+    format: String,
+    options: Option<export_mod::QueryExportOptions>,
+) -> Result<export_mod::ExportResult, String> {
+    let export_format =
+        export_mod::ExportFormat::from_str(&format).unwrap_or(export_mod::ExportFormat::Csv);
+
+    if file_path.trim().is_empty() {
+        return Err("导出路径不能为空".to_string());
+    }
+
+    let path = std::path::PathBuf::from(&file_path);
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("创建导出目录失败: {e}"))?;
+        }
+    }
+
+    let options = options.unwrap_or_default();
+    watch_export_progress(&app_handle, options.job_id.as_deref());
+    tauri::async_runtime::spawn_blocking(move || {
+        export_mod::export_query_to_file(pool_id, conn_id, &sql, &path, export_format, &options)
+    })
+    .await
+    .map_err(|err| format!("failed to join export task: {err}"))
+}
+
+// 按原 SQL 在当前会话连接上重新执行并流式写入文件，结果网格无需整表传回后端
+#[tauri::command]
+async fn export_active_query(
+    app_handle: tauri::AppHandle,
+    pool_id: u64,
+    conn_id: u64,
+    sql: String,
+    file_path: String,
+    format: String,
+    options: Option<export_mod::QueryExportOptions>,
+) -> Result<export_mod::ExportResult, String> {
+    pool_query_to_file(
+        app_handle, pool_id, conn_id, sql, file_path, format, options,
+    )
+    .await
+}
+
+#[tauri::command]
+fn export_to_chart_series(
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    x_col: String,
+    y_cols: Vec<String>,
+    agg: String,
+) -> Result<export_mod::ChartData, String> {
+    let aggregation = export_mod::ChartAggregation::from_str(&agg)
+        .ok_or_else(|| format!("Unsupported aggregation: {agg}"))?;
+    export_mod::to_chart_series(&headers, &rows, &x_col, &y_cols, aggregation)
+}
+
+#[tauri::command]
+fn export_transform_cells(
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    rules: Vec<export_mod::ReplaceRule>,
+) -> Result<Vec<Vec<String>>, String> {
+    export_mod::transform_cells(&headers, rows, &rules)
+}
+
+#[tauri::command]
+fn convert_file_to_file(
+    input_path: String,
+    input_format: String,
+    output_path: String,
+    output_format: String,
+) -> Result<export_mod::ExportResult, String> {
+    let input = import_mod::ImportFormat::from_str(&input_format)
+        .ok_or_else(|| format!("Unsupported input format: {input_format}"))?;
+    let output = export_mod::ExportFormat::from_str(&output_format)
+        .ok_or_else(|| format!("Unsupported output format: {output_format}"))?;
+
+    if output_path.trim().is_empty() {
+        return Err("导出路径不能为空".to_string());
+    }
+
+    let path = std::path::Path::new(&output_path);
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("创建导出目录失败: {e}"))?;
+        }
+    }
+
+    convert::file_to_file(std::path::Path::new(&input_path), input, path, output)
 }
 
 // Legacy export command for backward compatibility
@@ -1360,8 +2055,13 @@ fn backup_execute(req: backup::BackupRequest) -> Result<backup::BackupResult, St
 }
 
 #[tauri::command]
-fn restore_execute(req: backup::RestoreRequest) -> Result<backup::RestoreResult, String> {
-    backup::restore_execute(req)
+fn restore_execute(
+    window: tauri::Window,
+    req: backup::RestoreRequest,
+) -> Result<backup::RestoreResult, String> {
+    backup::restore_execute_with_progress(req, |progress| {
+        let _ = window.emit("restore-progress", progress.clone());
+    })
 }
 
 #[tauri::command]
@@ -1371,6 +2071,12 @@ fn incremental_backup(
     backup::incremental_backup(req)
 }
 
+#[tauri::command]
+fn backup_get_binlog_status(profile: ProfileRef) -> Result<backup::BinlogStatus, String> {
+    let profile = config::resolve_ref(profile)?;
+    backup::get_binlog_status(&profile)
+}
+
 #[tauri::command]
 fn schedule_add(req: backup::ScheduleRequest) -> Result<bool, String> {
     backup::schedule_add(req)
@@ -1556,19 +2262,35 @@ pub fn run() {
             pool_create,
             pool_get_connection,
             pool_set_database,
+            pool_get_current_database,
+            pool_set_autocommit,
+            pool_result_to_table,
+            pool_get_connection_diagnostics,
+            pool_get_activity,
             pool_release_connection,
             pool_test_connection,
             pool_get_stats,
+            pool_warmup,
+            pool_resize,
             pool_get_detailed_stats,
             pool_get_active_connections,
             pool_get_all_active_connections,
             pool_get_connection_properties,
             pool_query,
             pool_query_page,
+            pool_estimate,
             pool_query_multi,
+            pool_query_multi_detailed,
             pool_execute_statement_page,
             pool_execute,
+            pool_begin_transaction,
+            pool_commit,
+            pool_rollback,
             pool_query_prepared,
+            pool_query_prepared_named,
+            pool_set_binary_display,
+            pool_set_pretty_json,
+            pool_set_spatial_format,
             pool_query_prepared_multi,
             pool_execute_prepared,
             pool_close,
@@ -1579,32 +2301,58 @@ pub fn run() {
             metadata_list_views,
             metadata_list_view_details,
             metadata_list_functions,
+            metadata_get_server_overview,
+            metadata_get_storage_breakdown,
+            metadata_build_row_locator,
+            metadata_fetch_row_by_locator,
+            metadata_sample_rows,
+            metadata_warm_schema,
+            metadata_invalidate_cache,
+            metadata_clear_cache,
             metadata_list_routines_with_details,
             metadata_list_function_details,
             metadata_list_columns,
             metadata_list_foreign_keys,
             metadata_get_er_diagram_data,
+            metadata_get_fk_graph,
             metadata_export_er_diagram_sql,
             metadata_list_indexes,
+            metadata_list_indexes_detailed,
+            metadata_find_unused_indexes,
+            metadata_preview_table_changes,
             metadata_list_triggers,
             metadata_list_checks,
             metadata_load_ddl,
+            metadata_rename_table,
+            metadata_clone_database,
+            metadata_export_schema_snapshot,
+            metadata_diff_against_snapshot,
             metadata_get_current_user_info,
             metadata_get_all_users,
             metadata_get_user_detail,
             metadata_get_user_model,
+            metadata_audit_privileges,
             metadata_get_all_databases,
             metadata_generate_user_sql,
             metadata_execute_sql,
+            metadata_get_sql_mode,
+            metadata_set_sql_mode,
+            metadata_get_event_scheduler_status,
+            metadata_generate_drop_script,
+            metadata_set_event_scheduler,
             metadata_get_function_ddl,
             metadata_get_routine_params,
+            metadata_get_routine_header_sql,
             config_load_connections,
             config_save_connections,
             config_import_connections,
+            config_test_all_connections,
             config_export_connections,
             app_config_get,
             app_config_set,
             app_config_flush,
+            app_export_bundle,
+            app_import_bundle,
             favorites_get_all,
             favorites_get_by_type,
             favorites_search,
@@ -1618,6 +2366,7 @@ pub fn run() {
             favorites_stats,
             sql_format,
             sql_extract_view_select,
+            sql_build_where,
             sql_split_statements,
             sql_split_statements_create,
             sql_split_statements_page,
@@ -1627,14 +2376,27 @@ pub fn run() {
             import_from_json,
             import_from_jsonl,
             import_table,
+            import_multi_table_json,
+            import_cancel,
+            import_preview_rows,
             export_to_csv,
             export_to_jsonl,
             export_table,
+            export_table_csv_opts,
+            export_cancel,
+            export_table_tab,
             export_query_result,
+            export_query_result_to_string,
+            pool_query_to_file,
+            export_active_query,
+            export_to_chart_series,
+            export_transform_cells,
+            convert_file_to_file,
             export_query_result_csv,
             backup_execute,
             restore_execute,
             incremental_backup,
+            backup_get_binlog_status,
             schedule_add,
             schedule_remove,
             schedule_list,
@@ -84,7 +84,8 @@ export const FavoritesDialog: React.FC<FavoritesDialogProps> = ({
       filtered = filtered.filter(
         (item) =>
           item.name.toLowerCase().includes(keyword) ||
-          item.description?.toLowerCase().includes(keyword)
+          item.description?.toLowerCase().includes(keyword) ||
+          item.author?.toLowerCase().includes(keyword)
       );
     }
 
@@ -360,6 +361,7 @@ const FavoriteEditDialog: React.FC<FavoriteEditDialogProps> = ({
   const { connections, activeConnectionId } = useConnectionStore();
   const [name, setName] = useState('');
   const [description, setDescription] = useState('');
+  const [author, setAuthor] = useState('');
   const [type, setType] = useState<FavoriteType>('SQL_QUERY');
   const [sqlText, setSqlText] = useState('');
   const [sqlFilePath, setSqlFilePath] = useState('');
@@ -394,6 +396,7 @@ const FavoriteEditDialog: React.FC<FavoriteEditDialogProps> = ({
     if (editItem) {
       setName(editItem.name);
       setDescription(editItem.description || '');
+      setAuthor(editItem.author || '');
       setType(editItem.type);
       resetFields();
 
@@ -419,6 +422,7 @@ const FavoriteEditDialog: React.FC<FavoriteEditDialogProps> = ({
     } else {
       setName('');
       setDescription('');
+      setAuthor('');
       setType(defaultType);
       resetFields();
     }
@@ -648,6 +652,7 @@ const FavoriteEditDialog: React.FC<FavoriteEditDialogProps> = ({
       await onSave({
         name: name.trim(),
         description: description.trim() || undefined,
+        author: author.trim() || undefined,
         type,
         content: buildFavoriteContent(),
         createdTime: editItem?.createdTime || Date.now(),
@@ -712,6 +717,19 @@ const FavoriteEditDialog: React.FC<FavoriteEditDialogProps> = ({
             />
           </div>
 
+          <div className="favorite-form-row">
+            <label className="favorite-form-label">
+              <Icon icon="person" size={14} />
+              {t('dialog.favorites.authorLabel')}
+            </label>
+            <InputGroup
+              placeholder={t('dialog.favorites.authorPlaceholder')}
+              value={author}
+              onChange={(e) => setAuthor(e.target.value)}
+              className="favorite-form-input"
+            />
+          </div>
+
           {type === 'SQL_QUERY' && (
             <>
               <div className="favorite-form-row">
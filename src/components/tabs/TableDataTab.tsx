@@ -383,6 +383,7 @@ export const TableDataTab: React.FC<TableDataTabProps> = ({
   const getFileExtension = (format: ExportFormat): string => {
     switch (format) {
       case 'csv': return 'csv';
+      case 'tsv': return 'tsv';
       case 'txt': return 'txt';
       case 'json': return 'json';
       case 'html': return 'html';
@@ -390,6 +391,8 @@ export const TableDataTab: React.FC<TableDataTabProps> = ({
       case 'sql': return 'sql';
       case 'jsonl': return 'jsonl';
       case 'xlsx': return 'xlsx';
+      case 'parquet': return 'parquet';
+      case 'markdown': return 'md';
       default: return 'csv';
     }
   };
@@ -473,7 +476,8 @@ export const TableDataTab: React.FC<TableDataTabProps> = ({
       
       const selectedPath = await open({
         title: t('tableDataTab.import.title', { format: importFormatValue.toUpperCase() }),
-        filters: getFileFilter(importFormatValue),
+        // gzip 压缩的文本文件（如 data.csv.gz）由后端按扩展名自动解压
+        filters: [{ name: importFormatValue.toUpperCase(), extensions: [getFileExtension(importFormatValue), 'gz'] }],
         canCreateDirectories: false,
       });
 
@@ -1040,6 +1044,7 @@ export const TableDataTab: React.FC<TableDataTabProps> = ({
             onChange={(e) => setExportFormat(e.target.value as ExportFormat)}
             options={[
               { value: 'csv', label: t('tableDataTab.format.csv') },
+              { value: 'tsv', label: t('tableDataTab.format.tsv') },
               { value: 'txt', label: t('tableDataTab.format.txt') },
               { value: 'json', label: t('tableDataTab.format.json') },
               { value: 'html', label: t('tableDataTab.format.html') },
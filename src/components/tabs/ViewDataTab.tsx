@@ -509,6 +509,7 @@ export const ViewDataTab: React.FC<ViewDataTabProps> = ({
   const getFileExtension = (format: ExportFormat): string => {
     switch (format) {
       case 'csv': return 'csv';
+      case 'tsv': return 'tsv';
       case 'txt': return 'txt';
       case 'json': return 'json';
       case 'html': return 'html';
@@ -516,6 +517,8 @@ export const ViewDataTab: React.FC<ViewDataTabProps> = ({
       case 'sql': return 'sql';
       case 'jsonl': return 'jsonl';
       case 'xlsx': return 'xlsx';
+      case 'parquet': return 'parquet';
+      case 'markdown': return 'md';
       default: return 'csv';
     }
   };
@@ -1170,6 +1173,7 @@ export const ViewDataTab: React.FC<ViewDataTabProps> = ({
             onChange={(e) => setExportFormat(e.target.value as ExportFormat)}
             options={[
               { value: 'csv', label: t('tableDataTab.format.csv') },
+              { value: 'tsv', label: t('tableDataTab.format.tsv') },
               { value: 'txt', label: t('tableDataTab.format.txt') },
               { value: 'json', label: t('tableDataTab.format.json') },
               { value: 'html', label: t('tableDataTab.format.html') },
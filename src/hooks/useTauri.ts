@@ -1,12 +1,22 @@
 import { invoke } from '@tauri-apps/api/core';
 import type {
   ConnectionProfile,
+  ListOrder,
   PoolStats,
+  ConnectionDiagnostics,
+  ActivityEvent,
+  QueryEstimate,
   ConnectionProperties,
   QueryResult,
   ExecResult,
   SqlParam,
+  FilterCondition,
+  BundleImportResult,
+  BinaryDisplay,
   TableDetail,
+  TableStorage,
+  RowLocator,
+  ServerOverview,
   ViewDetail,
   FunctionDetail,
   RoutineDetail,
@@ -14,6 +24,11 @@ import type {
   MetadataRecord,
   UserSummary,
   UserModelPayload,
+  UnusedIndex,
+  TableModel,
+  TableChangePreview,
+  PrivFilter,
+  UserPrivilegeSummary,
   UserModel,
   FavoriteItem,
   FavoriteType,
@@ -26,7 +41,11 @@ import type {
   IncrementalResult,
   ScheduleRequest,
   ExportResult,
+  TabExportResult,
   ImportResult,
+  ImportPreviewResult,
+  TableImportResult,
+  ImportOptions,
 } from '../types';
 
 export interface ErDiagramColumnRecord {
@@ -51,6 +70,21 @@ export interface ErDiagramData {
   foreignKeys: ErDiagramForeignKeyRecord[];
 }
 
+export interface FkEdge {
+  from: string;
+  to: string;
+  constraintName: string;
+  columns: string[];
+  referencedColumns: string[];
+}
+
+export interface FkGraph {
+  nodes: string[];
+  edges: FkEdge[];
+  selfReferences: string[];
+  cycles: string[][];
+}
+
 type MetadataInvokeArgs = Record<string, unknown>;
 
 interface MetadataCacheEntry {
@@ -86,11 +120,17 @@ const getMetadataCacheKey = (command: string, args: MetadataInvokeArgs): string
   return `${command}:${JSON.stringify(normalizeMetadataArgs(args))}`;
 };
 
-export const clearMetadataCache = (): void => {
+const clearLocalMetadataCache = (): void => {
   metadataResultCache.clear();
   metadataInFlightCache.clear();
 };
 
+// 同时清除后端的对象列表缓存，否则刷新后仍可能拿到 60 秒内的旧列表
+export const clearMetadataCache = (): void => {
+  clearLocalMetadataCache();
+  invoke('metadata_clear_cache').catch(() => undefined);
+};
+
 if (typeof window !== 'undefined' && !(window as Window & { __dbwMetadataCacheHooked?: boolean }).__dbwMetadataCacheHooked) {
   window.addEventListener('dbw:global-refresh', clearMetadataCache);
   (window as Window & { __dbwMetadataCacheHooked?: boolean }).__dbwMetadataCacheHooked = true;
@@ -141,6 +181,18 @@ export const poolApi = {
   getConnection: (poolId: number): Promise<number> =>
     invoke('pool_get_connection', { poolId }),
   
+  getCurrentDatabase: (poolId: number, connId: number, verify?: boolean): Promise<string | null> =>
+    invoke('pool_get_current_database', { poolId, connId, verify }),
+
+  setAutocommit: (poolId: number, connId: number, enabled: boolean): Promise<void> =>
+    invoke('pool_set_autocommit', { poolId, connId, enabled }),
+
+  getConnectionDiagnostics: (poolId: number, connId: number): Promise<ConnectionDiagnostics> =>
+    invoke('pool_get_connection_diagnostics', { poolId, connId }),
+
+  getActivity: (poolId: number, connId: number): Promise<ActivityEvent[]> =>
+    invoke('pool_get_activity', { poolId, connId }),
+
   releaseConnection: (poolId: number, connId: number): Promise<boolean> =>
     invoke('pool_release_connection', { poolId, connId }),
   
@@ -150,20 +202,55 @@ export const poolApi = {
   getStats: (poolId: number): Promise<PoolStats> =>
     invoke('pool_get_stats', { poolId }),
 
+  warmup: (poolId: number, targetIdle: number): Promise<PoolStats> =>
+    invoke('pool_warmup', { poolId, targetIdle }),
+
+  resize: (poolId: number, maxSize: number): Promise<PoolStats> =>
+    invoke('pool_resize', { poolId, maxSize }),
+
   getConnectionProperties: (poolId: number, database?: string | null): Promise<ConnectionProperties> =>
     invoke('pool_get_connection_properties', { poolId, database: database ?? null }),
   
-  query: (poolId: number, connId: number, sql: string): Promise<QueryResult> =>
-    invoke('pool_query', { poolId, connId, sql }),
+  // timeoutMs 超时后服务端 KILL QUERY，返回 "Query timed out after N ms"
+  query: (poolId: number, connId: number, sql: string, timeoutMs?: number): Promise<QueryResult> =>
+    invoke('pool_query', { poolId, connId, sql, timeoutMs: timeoutMs ?? null }),
   
-  execute: (poolId: number, connId: number, sql: string): Promise<ExecResult> =>
-    invoke('pool_execute', { poolId, connId, sql }),
+  // 基于 EXPLAIN 的执行前估算，仅支持 SELECT
+  estimate: (poolId: number, connId: number, sql: string): Promise<QueryEstimate> =>
+    invoke('pool_estimate', { poolId, connId, sql }),
+
+  execute: (poolId: number, connId: number, sql: string, timeoutMs?: number): Promise<ExecResult> =>
+    invoke('pool_execute', { poolId, connId, sql, timeoutMs: timeoutMs ?? null }),
+
+  // 显式事务：begin 之后的语句直到 commit/rollback 前不自动提交，期间连接不会自动重连
+  beginTransaction: (poolId: number, connId: number): Promise<void> =>
+    invoke('pool_begin_transaction', { poolId, connId }),
+
+  commit: (poolId: number, connId: number): Promise<void> =>
+    invoke('pool_commit', { poolId, connId }),
+
+  rollback: (poolId: number, connId: number): Promise<void> =>
+    invoke('pool_rollback', { poolId, connId }),
+  
+  resultToTable: (poolId: number, connId: number, selectSql: string, targetSchema: string | null, targetTable: string, temporary: boolean): Promise<ExecResult> =>
+    invoke('pool_result_to_table', { poolId, connId, selectSql, targetSchema, targetTable, temporary }),
   
   queryPrepared: (poolId: number, connId: number, sql: string, params: SqlParam[]): Promise<QueryResult> =>
     invoke('pool_query_prepared', { poolId, connId, sql, params }),
   
   executePrepared: (poolId: number, connId: number, sql: string, params: SqlParam[]): Promise<ExecResult> =>
     invoke('pool_execute_prepared', { poolId, connId, sql, params }),
+
+  setBinaryDisplay: (poolId: number, mode: BinaryDisplay): Promise<void> =>
+    invoke('pool_set_binary_display', { poolId, mode }),
+  
+  // 开启后 JSON 列在结果中为嵌套值而非字符串
+  setPrettyJson: (poolId: number, enabled: boolean): Promise<void> =>
+    invoke('pool_set_pretty_json', { poolId, enabled }),
+  
+  // 空间列以 WKT/GeoJSON 文本返回，null 时按二进制展示
+  setSpatialFormat: (poolId: number, format: 'wkt' | 'geojson' | null): Promise<void> =>
+    invoke('pool_set_spatial_format', { poolId, format }),
   
   close: (poolId: number): Promise<void> =>
     invoke('pool_close', { poolId }),
@@ -184,6 +271,22 @@ export const metadataApi = {
   listTableDetails: (profile: ConnectionProfile, database: string): Promise<TableDetail[]> =>
     invokeMetadataCached('metadata_list_table_details', { profile, database }),
   
+  getServerOverview: (profile: ConnectionProfile): Promise<ServerOverview> =>
+    invokeMetadataCached('metadata_get_server_overview', { profile }),
+  
+  getStorageBreakdown: (profile: ConnectionProfile, database: string): Promise<TableStorage[]> =>
+    invoke('metadata_get_storage_breakdown', { profile, database }),
+  
+  // 随机抽样，大表为近似结果
+  sampleRows: (profile: ConnectionProfile, database: string, table: string, n?: number): Promise<QueryResult> =>
+    invoke('metadata_sample_rows', { profile, database, table, n }),
+  
+  buildRowLocator: (profile: ConnectionProfile, database: string, table: string, row: Record<string, string>): Promise<RowLocator> =>
+    invoke('metadata_build_row_locator', { profile, database, table, row }),
+  
+  fetchRowByLocator: (profile: ConnectionProfile, locator: RowLocator): Promise<Record<string, string | null> | null> =>
+    invoke('metadata_fetch_row_by_locator', { profile, database: locator.schema, table: locator.table, keys: locator.keys }),
+  
   listViews: (profile: ConnectionProfile, database: string): Promise<string[]> =>
     invokeMetadataCached('metadata_list_views', { profile, database }),
   
@@ -193,6 +296,15 @@ export const metadataApi = {
   listFunctions: (profile: ConnectionProfile, database: string): Promise<string[]> =>
     invokeMetadataCached('metadata_list_functions', { profile, database }),
   
+  // 后台预取，进度通过 schema-warmed 事件返回
+  warmSchema: (profile: ConnectionProfile, database: string): Promise<void> =>
+    invoke('metadata_warm_schema', { profile, database }),
+
+  invalidateCache: (profile: ConnectionProfile): Promise<void> => {
+    clearLocalMetadataCache();
+    return invoke('metadata_invalidate_cache', { profile });
+  },
+  
   listRoutinesWithDetails: (profile: ConnectionProfile, database: string): Promise<RoutineDetail[]> =>
     invokeMetadataCached('metadata_list_routines_with_details', { profile, database }),
   
@@ -205,20 +317,35 @@ export const metadataApi = {
   getRoutineParams: (profile: ConnectionProfile, database: string, name: string): Promise<RoutineParamInfo[]> =>
     invokeMetadataCached('metadata_get_routine_params', { profile, database, name }),
   
+  // 重建 CREATE PROCEDURE/FUNCTION 签名及特性子句，不含例程体
+  getRoutineHeaderSql: (profile: ConnectionProfile, database: string, name: string): Promise<string> =>
+    invokeMetadataCached('metadata_get_routine_header_sql', { profile, database, name }),
+  
   listColumns: (profile: ConnectionProfile, database: string, table: string): Promise<MetadataRecord[]> =>
     invokeMetadataCached('metadata_list_columns', { profile, database, table }),
   
-  listForeignKeys: (profile: ConnectionProfile, database: string, table: string): Promise<MetadataRecord[]> =>
-    invokeMetadataCached('metadata_list_foreign_keys', { profile, database, table }),
+  listForeignKeys: (profile: ConnectionProfile, database: string, table: string, order?: ListOrder): Promise<MetadataRecord[]> =>
+    invokeMetadataCached('metadata_list_foreign_keys', { profile, database, table, order }),
 
   getErDiagramData: (profile: ConnectionProfile, database: string): Promise<ErDiagramData> =>
     invokeMetadataCached('metadata_get_er_diagram_data', { profile, database }),
   
-  listIndexes: (profile: ConnectionProfile, database: string, table: string): Promise<MetadataRecord[]> =>
-    invokeMetadataCached('metadata_list_indexes', { profile, database, table }),
+  getFkGraph: (profile: ConnectionProfile, database: string): Promise<FkGraph> =>
+    invokeMetadataCached('metadata_get_fk_graph', { profile, database }),
+  
+  listIndexes: (profile: ConnectionProfile, database: string, table: string, order?: ListOrder): Promise<MetadataRecord[]> =>
+    invokeMetadataCached('metadata_list_indexes', { profile, database, table, order }),
+  
+  // 使用统计随运行时变化，不走元数据缓存
+  findUnusedIndexes: (profile: ConnectionProfile, database: string): Promise<UnusedIndex[]> =>
+    invoke('metadata_find_unused_indexes', { profile, database }),
+
+  // 设计器应用修改前预览将执行的 ALTER 语句及数据丢失警告
+  previewTableChanges: (profile: ConnectionProfile, database: string, table: string, proposed: TableModel): Promise<TableChangePreview> =>
+    invoke('metadata_preview_table_changes', { profile, database, table, proposed }),
   
-  listTriggers: (profile: ConnectionProfile, database: string, table: string): Promise<MetadataRecord[]> =>
-    invokeMetadataCached('metadata_list_triggers', { profile, database, table }),
+  listTriggers: (profile: ConnectionProfile, database: string, table: string, order?: ListOrder): Promise<MetadataRecord[]> =>
+    invokeMetadataCached('metadata_list_triggers', { profile, database, table, order }),
   
   listChecks: (profile: ConnectionProfile, database: string, table: string): Promise<MetadataRecord[]> =>
     invokeMetadataCached('metadata_list_checks', { profile, database, table }),
@@ -238,6 +365,9 @@ export const metadataApi = {
   getUserModel: (profile: ConnectionProfile, username: string, host: string): Promise<UserModelPayload> =>
     invokeMetadataCached('metadata_get_user_model', { profile, username, host }),
   
+  auditPrivileges: (profile: ConnectionProfile, filter?: PrivFilter): Promise<UserPrivilegeSummary[]> =>
+    invoke('metadata_audit_privileges', { profile, filter: filter ?? null }),
+  
   getAllDatabases: (profile: ConnectionProfile): Promise<string[]> =>
     invokeMetadataCached('metadata_get_all_databases', { profile }),
   
@@ -246,7 +376,7 @@ export const metadataApi = {
   
   executeSql: (profile: ConnectionProfile, sql: string, database?: string): Promise<void> =>
     invoke('metadata_execute_sql', { profile, sql, database }).then(() => {
-      clearMetadataCache();
+      clearLocalMetadataCache();
     }),
 };
 
@@ -259,8 +389,9 @@ export const configApi = {
   saveConnections: (profiles: ConnectionProfile[]): Promise<void> =>
     invoke('config_save_connections', { profiles }),
   
-  importConnections: (filePath: string): Promise<ConnectionProfile[]> =>
-    invoke('config_import_connections', { filePath }),
+  // missingEnv: 引用的 ${VAR} 未设置时报错（默认）、替换为空或保留原样
+  importConnections: (filePath: string, missingEnv?: 'error' | 'empty' | 'keep'): Promise<ConnectionProfile[]> =>
+    invoke('config_import_connections', { filePath, missingEnv }),
   
   exportConnections: (filePath: string, profiles: ConnectionProfile[]): Promise<void> =>
     invoke('config_export_connections', { filePath, profiles }),
@@ -277,6 +408,14 @@ export const appConfigApi = {
   
   flush: (): Promise<void> =>
     invoke('app_config_flush'),
+
+  // 连接、收藏、应用配置和备份计划打包为单个加密迁移文件；includePasswords 默认 false
+  exportBundle: (filePath: string, passphrase: string, includePasswords?: boolean): Promise<void> =>
+    invoke('app_export_bundle', { filePath, passphrase, includePasswords }),
+
+  // merge 默认 true：同名连接/同 id 收藏/同键配置以迁移包为准；false 时整体替换
+  importBundle: (filePath: string, passphrase: string, merge?: boolean): Promise<BundleImportResult> =>
+    invoke('app_import_bundle', { filePath, passphrase, merge }),
 };
 
 // ============ 收藏夹 API ============
@@ -327,6 +466,10 @@ export const sqlUtilsApi = {
   
   splitStatements: (sql: string, dbType: DbType): Promise<string[]> =>
     invoke('sql_split_statements', { sql, dbType }),
+
+  // 返回 [条件 SQL（不含 WHERE）, 参数]，配合 poolApi.queryPrepared 使用
+  buildWhere: (conditions: FilterCondition[], dbType: DbType): Promise<[string, SqlParam[]]> =>
+    invoke('sql_build_where', { conditions, dbType }),
 };
 
 // ============ JSON 工具 API ============
@@ -338,7 +481,7 @@ export const jsonApi = {
 
 // ============ 导入 API ============
 
-export type ImportFormat = 'csv' | 'txt' | 'json' | 'xml' | 'sql';
+export type ImportFormat = 'csv' | 'tsv' | 'txt' | 'json' | 'xml' | 'sql';
 
 export const importApi = {
   fromCsv: (profile: ConnectionProfile, database: string, table: string, filePath: string): Promise<ImportResult> =>
@@ -351,13 +494,96 @@ export const importApi = {
     invoke('import_from_jsonl', { profile, database, table, filePath }),
   
   // Unified import with format
-  importTable: (profile: ConnectionProfile, database: string, table: string, filePath: string, format: ImportFormat): Promise<ImportResult> =>
-    invoke('import_table', { profile, database, table, filePath, format }),
+  importTable: (profile: ConnectionProfile, database: string, table: string, filePath: string, format: ImportFormat, options?: ImportOptions): Promise<ImportResult> =>
+    invoke('import_table', { profile, database, table, filePath, format, options: options ?? null }),
+
+  previewRows: (profile: ConnectionProfile, database: string, table: string, filePath: string, format: ImportFormat, limit?: number): Promise<ImportPreviewResult> =>
+    invoke('import_preview_rows', { profile, database, table, filePath, format, limit: limit ?? null }),
+
+  // JSON 顶层为 { 表名: [...] }，按外键依赖顺序逐表导入
+  importMultiTableJson: (profile: ConnectionProfile, database: string, filePath: string, options?: ImportOptions): Promise<TableImportResult[]> =>
+    invoke('import_multi_table_json', { profile, database, filePath, options: options ?? null }),
+
+  cancel: (importId: string): Promise<boolean> =>
+    invoke('import_cancel', { importId }),
 };
 
 // ============ 导出 API ============
 
-export type ExportFormat = 'csv' | 'txt' | 'json' | 'html' | 'xml' | 'sql' | 'jsonl' | 'xlsx';
+export type ExportFormat = 'csv' | 'tsv' | 'txt' | 'json' | 'html' | 'xml' | 'sql' | 'jsonl' | 'xlsx' | 'parquet' | 'markdown';
+
+export interface TableExportOptions {
+  columns?: string[];
+  includeViewDefinition?: boolean;
+  // CREATE VIEW 不带 DEFINER，便于迁移到其他服务器
+  stripViewDefiner?: boolean;
+  stringifyValues?: boolean;
+  spatialFormat?: 'wkt' | 'geojson';
+  // XLSX 增加 Schema 工作表，其他格式另写 .schema.csv
+  includeSchemaSheet?: boolean;
+  // gzip 压缩输出，文件名自动补 .gz
+  compress?: boolean;
+  // 追加到已有文件，仅 csv/tsv/jsonl/sql；json 按 jsonl 追加
+  append?: boolean;
+  // NULL 在文本格式中的写法，如 '\\N'、'NULL'；JSON 未设置时仍为 null
+  nullToken?: string;
+  querySpec?: ExportQuerySpec;
+  xlsx?: XlsxTypeOptions;
+  sql?: SqlExportOptions;
+  // 传入后可用 exportApi.cancel 中途取消，结果 error 为 'cancelled'
+  jobId?: string;
+  binaryEncoding?: BinaryEncoding;
+}
+
+// hex 输出 0x 前缀；SQL 格式下 base64/hex 均写为 0x 字面量
+export type BinaryEncoding = 'utf8Lossy' | 'base64' | 'hex';
+
+export interface SqlExportOptions {
+  addDropTable?: boolean;
+  includeCreate?: boolean;
+}
+
+export interface XlsxTypeOptions {
+  idsAsText?: boolean;
+  nativeDates?: boolean;
+  textColumns?: string[];
+}
+
+export interface ExportQuerySpec {
+  whereClause?: string;
+  orderBy?: string;
+  limit?: number;
+  columns?: string[];
+}
+
+// delimiter/quote 为单个 ASCII 字符，如 ';'；未给出的字段使用默认值
+export interface CsvOptions {
+  delimiter?: string;
+  quote?: string;
+  writeBom?: boolean;
+  lineTerminator?: '\n' | '\r\n' | '\r';
+}
+
+export interface QueryExportOptions {
+  tableName?: string;
+  /** 仅 HTML：内嵌列排序与搜索脚本 */
+  interactive?: boolean;
+  compress?: boolean;
+  append?: boolean;
+  csv?: CsvOptions;
+  nullToken?: string;
+  xlsx?: XlsxTypeOptions;
+  jobId?: string;
+  binaryEncoding?: BinaryEncoding;
+  // 仅 exportQueryToFile：空间列写为 WKT/GeoJSON
+  spatialFormat?: 'wkt' | 'geojson';
+}
+
+// 设置 jobId 后通过 `export-progress:<jobId>` 事件推送，每 10000 行一次，结束时 done 为 true
+export interface ExportProgress {
+  rowsExported: number;
+  done: boolean;
+}
 
 export const exportApi = {
   toCsv: (profile: ConnectionProfile, database: string, table: string, filePath: string): Promise<ExportResult> =>
@@ -367,12 +593,32 @@ export const exportApi = {
     invoke('export_to_jsonl', { profile, database, table, filePath }),
   
   // Unified export with format
-  exportTable: (profile: ConnectionProfile, database: string, table: string, filePath: string, format: ExportFormat): Promise<ExportResult> =>
-    invoke('export_table', { profile, database, table, filePath, format }),
+  exportTable: (profile: ConnectionProfile, database: string, table: string, filePath: string, format: ExportFormat, options?: TableExportOptions): Promise<ExportResult> =>
+    invoke('export_table', { profile, database, table, filePath, format, options }),
+
+  exportTableCsv: (profile: ConnectionProfile, database: string, table: string, filePath: string, csvOptions: CsvOptions, options?: TableExportOptions): Promise<ExportResult> =>
+    invoke('export_table_csv_opts', { profile, database, table, filePath, csvOptions, options }),
+
+  // 取消 options.jobId 对应的导出；任务已结束时返回 false
+  cancel: (jobId: string): Promise<boolean> =>
+    invoke('export_cancel', { jobId }),
+
+  // 在 outDir 下生成 <table>.sql 与 <table>.txt
+  exportTableTab: (profile: ConnectionProfile, database: string, table: string, outDir: string): Promise<TabExportResult> =>
+    invoke('export_table_tab', { profile, database, table, outDir }),
   
   // Export query result with format
-  exportQueryResult: (filePath: string, headers: string[], rows: string[][], format: ExportFormat, tableName?: string): Promise<ExportResult> =>
-    invoke('export_query_result', { filePath, headers, rows, format, tableName }),
+  exportQueryResult: (filePath: string, headers: string[], rows: (string | null)[][], format: ExportFormat, tableName?: string, options?: QueryExportOptions): Promise<ExportResult> =>
+    invoke('export_query_result', { filePath, headers, rows, format, tableName, options }),
+  // XLSX/Parquet 返回 base64
+  exportQueryResultToString: (headers: string[], rows: (string | null)[][], format: ExportFormat, tableName?: string, options?: QueryExportOptions): Promise<string> =>
+    invoke('export_query_result_to_string', { headers, rows, format, tableName, options }),
+
+  // 在后端直接执行查询并写入文件，结果行不经过前端
+  queryToFile: (poolId: number, connId: number, sql: string, filePath: string, format: ExportFormat, options?: QueryExportOptions): Promise<ExportResult> =>
+    invoke('pool_query_to_file', { poolId, connId, sql, filePath, format, options }),
+  exportActiveQuery: (poolId: number, connId: number, sql: string, filePath: string, format: ExportFormat, options?: QueryExportOptions): Promise<ExportResult> =>
+    invoke('export_active_query', { poolId, connId, sql, filePath, format, options }),
 };
 
 // ============ 备份 API ============
@@ -405,6 +651,7 @@ const toRustRestoreRequest = (req: RestoreRequest) => ({
   create_schema: req.createSchema,
   continue_on_error: req.continueOnError ?? false,
   use_transaction: req.useTransaction ?? true,
+  verify_only: req.verifyOnly ?? false,
 });
 
 const toRustIncrementalRequest = (req: IncrementalRequest) => ({
@@ -426,8 +673,12 @@ const fromRustBackupResult = (result: { output_path: string; duration_ms: number
   durationMs: result.duration_ms,
 });
 
-const fromRustRestoreResult = (result: { duration_ms: number }): RestoreResult => ({
+type RustRestoreResult = { duration_ms: number; statement_count: number; verified_only: boolean };
+
+const fromRustRestoreResult = (result: RustRestoreResult): RestoreResult => ({
   durationMs: result.duration_ms,
+  statementCount: result.statement_count,
+  verifiedOnly: result.verified_only,
 });
 
 const fromRustIncrementalResult = (result: { output_file: string; duration_ms: number }): IncrementalResult => ({
@@ -441,7 +692,7 @@ export const backupApi = {
       .then(fromRustBackupResult),
   
   restore: (req: RestoreRequest): Promise<RestoreResult> =>
-    invoke<{ duration_ms: number }>('restore_execute', { req: toRustRestoreRequest(req) })
+    invoke<RustRestoreResult>('restore_execute', { req: toRustRestoreRequest(req) })
       .then(fromRustRestoreResult),
   
   incremental: (req: IncrementalRequest): Promise<IncrementalResult> =>
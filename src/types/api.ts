@@ -12,20 +12,29 @@ export interface ConnectionProfile {
   collation?: string;
   timeout?: number; // 空闲超时（wait_timeout），默认 28800 秒（8小时）
   connectionTimeout?: number; // 连接超时，默认 30 秒
+  readTimeout?: number; // 读取超时（秒），默认不限制
   autoReconnect?: boolean; // 自动重连，默认 false（安全优先）
+  keepaliveInterval?: number; // 心跳间隔（秒），默认 30 秒
   ssl?: boolean;
   sslMode?: 'disabled' | 'preferred' | 'required' | 'verify-ca' | 'verify-identity';
   sslCaPath?: string;
   sslCertPath?: string;
   sslKeyPath?: string;
+  defaultRowLimit?: number;
+  initSql?: string[];
+  envTemplates?: Record<string, string>; // 字段 -> 展开前的 ${VAR} 模板，保存时写回
 }
 
+// 已保存连接可直接以名称引用，由后端解析
+export type ProfileRef = ConnectionProfile | string;
+
 export type FavoriteType = 'SQL_QUERY' | 'CONNECTION_PROFILE' | 'DATABASE_OBJECT';
 
 export interface FavoriteItem {
   id?: string;
   name: string;
   description?: string;
+  author?: string;
   type: FavoriteType;
   content?: string;
   createdTime: number;
@@ -40,6 +49,25 @@ export interface SqlParam {
   value: unknown;
 }
 
+export type FilterOp =
+  | 'eq' | 'ne' | 'lt' | 'le' | 'gt' | 'ge'
+  | 'like' | 'notLike' | 'in' | 'notIn' | 'isNull' | 'isNotNull';
+
+export interface FilterCondition {
+  column: string;
+  op: FilterOp;
+  // in/notIn 传数组，isNull/isNotNull 可省略
+  value?: unknown;
+}
+
+export interface BundleImportResult {
+  version: number;
+  connections: number;
+  favorites: number;
+  settings: number;
+  schedules: number;
+}
+
 export interface UserModel {
   username: string;
   host: string;
@@ -51,6 +79,35 @@ export interface UserModel {
 
 // ============ 连接池类型 ============
 
+// 后端字段为 snake_case
+export interface ConnectionDiagnostics {
+  exists: boolean;
+  current_database: string | null;
+  session_settings: string[];
+  in_transaction: number;
+  has_temp_tables: number;
+  use_count: number;
+  age_secs: number;
+  last_used_secs_ago: number;
+  reconnect_blocked_reason: string | null;
+}
+
+export interface QueryEstimate {
+  estimated_rows: number;
+  // MariaDB 旧版本的计划中没有代价
+  estimated_cost: number | null;
+  full_table_scan: boolean;
+}
+
+export type ActivityKind = 'connect' | 'query' | 'execute' | 'reconnect' | 'keepalivePing' | 'release';
+
+export interface ActivityEvent {
+  // Unix 毫秒时间戳
+  at: number;
+  kind: ActivityKind;
+  detail: string;
+}
+
 export interface PoolStats {
   poolId: number;
   totalConnections: number;
@@ -73,6 +130,8 @@ export interface ConnectionProperties {
   procedure_count?: number | null;
 }
 
+export type BinaryDisplay = 'utf8Lossy' | 'base64' | 'hex';
+
 export interface ColumnMeta {
   name: string;
   label: string;
@@ -100,6 +159,41 @@ export interface TableDetail {
   Comment?: string;
 }
 
+export interface DatabaseOverview {
+  name: string;
+  tableCount: number;
+  sizeBytes: number;
+}
+
+export interface ServerOverview {
+  databases: DatabaseOverview[];
+}
+
+// 索引/外键/触发器列表排序：ordinal 为定义顺序，name 为字母序
+export type ListOrder = 'ordinal' | 'name';
+
+export interface RowKey {
+  column: string;
+  value: string | null;
+}
+
+export interface RowLocator {
+  schema: string;
+  table: string;
+  keys: RowKey[];
+  locator: string;
+  warnings: string[];
+}
+
+export interface TableStorage {
+  table: string;
+  dataLength: number;
+  indexLength: number;
+  dataFree: number;
+  total: number;
+  totalDisplay: string;
+}
+
 export interface ViewDetail {
   Name: string;
   Definition?: string;
@@ -129,19 +223,25 @@ export interface RoutineParam {
   name: string;
   type: string;
   mode?: string;
+  characterSet?: string;
 }
 
 export interface RoutineDetail {
   name: string;
   type: string;
   returnType?: string;
+  returnCharacterSet?: string;
   params: RoutineParam[];
+  deterministic: boolean;
+  sqlDataAccess?: string;
+  securityType?: string;
 }
 
 export interface RoutineParamInfo {
   name: string;
   type: string;
   mode?: string;
+  characterSet?: string;
 }
 
 export interface UserSummary {
@@ -151,6 +251,64 @@ export interface UserSummary {
   status: string;
 }
 
+export interface IndexColumn {
+  name: string;
+  subPart: number | null;
+  collation: string | null;
+}
+
+export interface IndexModel {
+  name: string;
+  unique: boolean;
+  type: string;
+  columns: IndexColumn[];
+  visible: boolean;
+  comment: string;
+}
+
+export interface ColumnModel {
+  name: string;
+  // 重命名时为原列名
+  originalName?: string;
+  // 完整列类型，如 varchar(64)、int unsigned
+  columnType: string;
+  nullable: boolean;
+  defaultValue?: string | null;
+  autoIncrement?: boolean;
+  onUpdate?: string | null;
+  charset?: string | null;
+  collation?: string | null;
+}
+
+export interface ForeignKeyModel {
+  name: string;
+  columns: string[];
+  referencedSchema?: string | null;
+  referencedTable: string;
+  referencedColumns: string[];
+  onUpdate?: string;
+  onDelete?: string;
+}
+
+export interface TableModel {
+  columns: ColumnModel[];
+  indexes?: IndexModel[];
+  foreignKeys?: ForeignKeyModel[];
+}
+
+export interface TableChangePreview {
+  sql: string;
+  // 删除列、收窄类型等可能丢失数据的修改
+  warnings: string[];
+}
+
+export interface UnusedIndex {
+  table: string;
+  indexName: string;
+  columns: string[];
+  unique: boolean;
+}
+
 export interface UserModelPayload {
   username: string;
   host: string;
@@ -159,6 +317,18 @@ export interface UserModelPayload {
   databasePrivileges: Record<string, string[]>;
 }
 
+export interface PrivFilter {
+  privilege?: string;
+  database?: string;
+}
+
+export interface UserPrivilegeSummary {
+  username: string;
+  host: string;
+  serverPrivileges: string[];
+  databasePrivileges: Record<string, string[]>;
+}
+
 // 列、外键、索引、触发器、检查约束使用 Record<string, string>
 export type MetadataRecord = Record<string, string>;
 
@@ -198,6 +368,7 @@ export interface RestoreRequest {
   createSchema: boolean;
   continueOnError?: boolean;
   useTransaction?: boolean;
+  verifyOnly?: boolean;
 }
 
 export interface IncrementalRequest {
@@ -221,6 +392,15 @@ export interface BackupResult {
 
 export interface RestoreResult {
   durationMs: number;
+  statementCount: number;
+  verifiedOnly: boolean;
+}
+
+// restore-progress 事件负载
+export interface RestoreProgress {
+  executed: number;
+  total: number;
+  percentage: number;
 }
 
 export interface IncrementalResult {
@@ -235,14 +415,91 @@ export interface ExportResult {
   rowsExported: number;
   filePath: string;
   durationMs: number;
+  rowsPerSec?: number;
+  bytesPerSec?: number;
+  // 输出文件的 SHA-256（十六进制）
+  checksum?: string;
   error?: string;
 }
 
+// mysqldump --tab 风格：<table>.sql 为建表语句，<table>.txt 为 LOAD DATA 格式数据
+export interface TabExportResult {
+  schemaFile: string;
+  dataFile: string;
+  rowsExported: number;
+  durationMs: number;
+}
+
 export interface ImportResult {
   success: boolean;
   rowsImported: number;
   durationMs: number;
+  rowsPerSec?: number;
+  bytesPerSec?: number;
+  truncationWarnings?: string[];
+  // 文件编码与目标列字符集可能不兼容时的提示
+  charsetWarnings?: string[];
+  // 被取消时事务已回滚，表未发生变化
+  cancelled?: boolean;
   error?: string;
+  // dry-run 时各行的解析错误
+  errors?: string[];
+  // createIfMissing 时自动建表执行的 DDL
+  createdTableDdl?: string;
+}
+
+export interface ImportOptions {
+  strictMode?: boolean;
+  // 传入后可通过 importApi.cancel(importId) 取消该次导入
+  importId?: string;
+  // 字符集不匹配时拒绝导入
+  strictCharset?: boolean;
+  // 主键/唯一键冲突时：报错、跳过或覆盖非键列，默认 insert
+  mode?: ImportMode;
+  // 导入前先 DELETE 清空目标表（同一事务，失败可回滚）
+  truncateBefore?: boolean;
+  // 每批写入行数，默认 500，最小 1
+  batchSize?: number;
+  // 文件表头 → 表列名（仅 CSV/TSV），未列出的表列取 NULL 或默认值
+  columnMap?: Record<string, string>;
+  // CSV/TSV 文件编码，如 gbk、latin1；为空时按 UTF-8
+  encoding?: string;
+  // 文件为 gzip 压缩；.gz 扩展名会自动识别
+  compressed?: boolean;
+  // 只解析校验不写入；rowsImported 为可导入行数，解析失败记入 errors
+  dryRun?: boolean;
+  // Excel 工作表名，默认第一个工作表
+  sheet?: string;
+  // 日期/时间列优先尝试的 chrono 格式，如 %d.%m.%Y、%s
+  datetimeFormats?: string[];
+  // 目标表不存在时按文件推断列类型自动建表
+  createIfMissing?: boolean;
+}
+
+export type ImportMode = 'insert' | 'insertIgnore' | 'upsert';
+
+// 传入 importId 时，导入每写完一批及结束时发送 `import-progress:<importId>` 事件
+export interface ImportProgress {
+  rowsImported: number;
+  done: boolean;
+}
+
+export interface TableImportResult extends ImportResult {
+  table: string;
+}
+
+export interface ImportPreviewResult {
+  columns: string[];
+  rows: unknown[][];
+  warnings: string[];
+}
+
+export interface SchemaWarmProgress {
+  schema: string;
+  stage: 'tables' | 'views' | 'routines';
+  count: number;
+  done: boolean;
+  error?: string | null;
 }
 
 // ============ 前端专用类型 ============
@@ -305,6 +562,7 @@ export interface QueryResultData {
   rows: unknown[][];
   query_time_secs?: number;
   fetch_time_secs?: number;
+  truncated?: boolean;
   source_sql?: string;
   pagination?: {
     page: number;
@@ -323,6 +581,15 @@ export interface MultiQueryResultData {
   fetch_time_secs?: number;
 }
 
+export interface StatementOutcomeData {
+  index: number;
+  kind: 'query' | 'update';
+  result_set: QueryResultData | null;
+  affected_rows: number;
+  last_insert_id: number;
+  duration_ms: number;
+}
+
 export interface ExecResultData {
   affected_rows: number;
   last_insert_id: number;